@@ -166,6 +166,8 @@ impl ChangeSetExt {
 pub struct TransactionOutputExt {
     delta_change_set: DeltaChangeSet,
     output: TransactionOutput,
+    // part of the gas used that was charged for the write set of the transaction.
+    storage_fee_used: u64,
 }
 
 impl TransactionOutputExt {
@@ -173,9 +175,24 @@ impl TransactionOutputExt {
         TransactionOutputExt {
             delta_change_set,
             output,
+            storage_fee_used: 0,
         }
     }
 
+    /// Records the part of the gas used that was charged for the write set of the transaction.
+    pub fn with_storage_fee_used(self, storage_fee_used: u64) -> Self {
+        Self {
+            storage_fee_used,
+            ..self
+        }
+    }
+
+    /// The part of the gas used that was charged for the write set of the transaction, 0 if
+    /// it wasn't recorded.
+    pub fn storage_fee_used(&self) -> u64 {
+        self.storage_fee_used
+    }
+
     pub fn delta_change_set(&self) -> &DeltaChangeSet {
         &self.delta_change_set
     }
@@ -253,6 +270,7 @@ impl From<TransactionOutput> for TransactionOutputExt {
         TransactionOutputExt {
             delta_change_set: DeltaChangeSet::empty(),
             output,
+            storage_fee_used: 0,
         }
     }
 }
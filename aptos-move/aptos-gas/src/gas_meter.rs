@@ -212,6 +212,8 @@ pub struct AptosGasMeter {
     storage_gas_params: StorageGasParameters,
     balance: InternalGas,
     memory_quota: AbstractValueSize,
+    // gas charged for the write set of the transaction, part of the gas used.
+    storage_fee: InternalGas,
 
     should_leak_memory_for_native: bool,
 }
@@ -232,6 +234,7 @@ impl AptosGasMeter {
             storage_gas_params,
             balance,
             memory_quota,
+            storage_fee: 0.into(),
             should_leak_memory_for_native: false,
         }
     }
@@ -241,6 +244,12 @@ impl AptosGasMeter {
             .to_unit_round_down_with_params(&self.gas_params.txn)
     }
 
+    /// The part of the gas used so far that was charged for the write set of the transaction.
+    pub fn storage_fee_used(&self) -> Gas {
+        self.storage_fee
+            .to_unit_round_down_with_params(&self.gas_params.txn)
+    }
+
     #[inline]
     fn charge(&mut self, amount: InternalGas) -> PartialVMResult<()> {
         match self.balance.checked_sub(amount) {
@@ -776,6 +785,9 @@ impl AptosGasMeter {
         ops: impl IntoIterator<Item = (&'a StateKey, &'a WriteOp)>,
     ) -> VMResult<()> {
         let cost = self.storage_gas_params.pricing.calculate_write_set_gas(ops);
-        self.charge(cost).map_err(|e| e.finish(Location::Undefined))
+        self.charge(cost)
+            .map_err(|e| e.finish(Location::Undefined))?;
+        self.storage_fee += cost;
+        Ok(())
     }
 }
@@ -22,6 +22,9 @@ use aptos_aggregator::{
     delta_change_set::DeltaChangeSet,
     transaction::{ChangeSetExt, TransactionOutputExt},
 };
+use aptos_block_executor::pool_sizing::{
+    set_global_pool_threads_once, set_oversubscription_policy_once, OversubscriptionPolicy,
+};
 use aptos_crypto::HashValue;
 use aptos_framework::natives::code::PublishRequest;
 use aptos_gas::{AptosGasMeter, ChangeSetConfigs};
@@ -73,6 +76,7 @@ static EXECUTION_CONCURRENCY_LEVEL: OnceCell<usize> = OnceCell::new();
 static NUM_PROOF_READING_THREADS: OnceCell<usize> = OnceCell::new();
 static PARANOID_TYPE_CHECKS: OnceCell<bool> = OnceCell::new();
 static PROCESSED_TRANSACTIONS_DETAILED_COUNTERS: OnceCell<bool> = OnceCell::new();
+static SPECULATIVE_CONFLICT_LOGS_PER_BLOCK: OnceCell<usize> = OnceCell::new();
 
 /// Remove this once the bundle is removed from the code.
 static MODULE_BUNDLE_DISALLOWED: AtomicBool = AtomicBool::new(true);
@@ -159,6 +163,33 @@ impl AptosVM {
         }
     }
 
+    /// Sets the number of speculative conflicts logged per block when invoked the first time.
+    pub fn set_speculative_conflict_logs_per_block_once(max_logs: usize) {
+        // Only the first call succeeds, due to OnceCell semantics.
+        SPECULATIVE_CONFLICT_LOGS_PER_BLOCK.set(max_logs).ok();
+    }
+
+    /// Sets how the executor pool is sized when it would oversubscribe the CPUs together with
+    /// rayon's global pool. Has to be invoked before the first block is executed.
+    pub fn set_executor_pool_oversubscription_policy_once(policy: OversubscriptionPolicy) {
+        set_oversubscription_policy_once(policy);
+    }
+
+    /// Sets the number of threads of rayon's global pool, so that the executor pool is sized
+    /// with it. Has to be invoked before the first block is executed, otherwise the global pool
+    /// is probed (and started) when the executor pool is sized.
+    pub fn set_global_rayon_pool_threads_once(num_threads: usize) {
+        set_global_pool_threads_once(num_threads);
+    }
+
+    /// Get the number of speculative conflicts logged per block, 0 (disabled) by default.
+    pub fn get_speculative_conflict_logs_per_block() -> usize {
+        match SPECULATIVE_CONFLICT_LOGS_PER_BLOCK.get() {
+            Some(max_logs) => *max_logs,
+            None => 0,
+        }
+    }
+
     pub fn internals(&self) -> AptosVMInternals {
         AptosVMInternals::new(&self.0)
     }
@@ -313,7 +344,8 @@ impl AptosVM {
 
         Ok((
             VMStatus::Executed,
-            TransactionOutputExt::new(delta_change_set, txn_output),
+            TransactionOutputExt::new(delta_change_set, txn_output)
+                .with_storage_fee_used(gas_meter.storage_fee_used().into()),
         ))
     }
 
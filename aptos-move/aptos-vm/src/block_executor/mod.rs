@@ -13,6 +13,8 @@ use crate::{
     AptosVM,
 };
 use aptos_aggregator::{delta_change_set::DeltaOp, transaction::TransactionOutputExt};
+pub use aptos_block_executor::executor::BlockExecutionSummary;
+pub use aptos_block_executor::pool_sizing::OversubscriptionPolicy;
 use aptos_block_executor::{
     errors::Error,
     executor::{BlockExecutor, RAYON_EXEC_POOL},
@@ -27,7 +29,7 @@ use aptos_types::{
     transaction::{Transaction, TransactionOutput, TransactionStatus},
     write_set::{WriteOp, WriteSet, WriteSetMut},
 };
-use move_core_types::vm_status::VMStatus;
+use move_core_types::vm_status::{StatusCode, VMStatus};
 use rayon::prelude::*;
 
 impl BlockExecutorTransaction for PreprocessedTransaction {
@@ -68,6 +70,18 @@ impl BlockExecutorTransactionOutput for AptosTransactionOutput {
             .collect()
     }
 
+    fn gas_used(&self) -> u64 {
+        self.0.txn_output().gas_used()
+    }
+
+    fn execution_gas_used(&self) -> u64 {
+        self.gas_used().saturating_sub(self.storage_fee_used())
+    }
+
+    fn storage_fee_used(&self) -> u64 {
+        self.0.storage_fee_used()
+    }
+
     /// Execution output for transactions that comes after SkipRest signal.
     fn skip_output() -> Self {
         Self(TransactionOutputExt::from(TransactionOutput::new(
@@ -77,6 +91,15 @@ impl BlockExecutorTransactionOutput for AptosTransactionOutput {
             TransactionStatus::Retry,
         )))
     }
+
+    fn incorporate_delta_writes(&mut self, delta_writes: Vec<(StateKey, WriteOp)>) {
+        let output_ext = std::mem::replace(self, Self::skip_output()).into();
+        let storage_fee_used = output_ext.storage_fee_used();
+        self.0 = TransactionOutputExt::from(
+            output_ext.output_with_delta_writes(WriteSetMut::new(delta_writes)),
+        )
+        .with_storage_fee_used(storage_fee_used);
+    }
 }
 
 pub struct BlockAptosVM();
@@ -87,6 +110,17 @@ impl BlockAptosVM {
         state_view: &S,
         concurrency_level: usize,
     ) -> Result<Vec<TransactionOutput>, VMStatus> {
+        Self::execute_block_with_summary(transactions, state_view, concurrency_level)
+            .map(|(outputs, _)| outputs)
+    }
+
+    /// Same as `execute_block`, also returning what the execution of the block amounted to,
+    /// e.g. where the block ended and how contended it was.
+    pub fn execute_block_with_summary<S: StateView + Sync>(
+        transactions: Vec<Transaction>,
+        state_view: &S,
+        concurrency_level: usize,
+    ) -> Result<(Vec<TransactionOutput>, BlockExecutionSummary), VMStatus> {
         let _timer = BLOCK_EXECUTOR_EXECUTE_BLOCK_SECONDS.start_timer();
         // Verify the signatures of all the transactions in parallel.
         // This is time consuming so don't wait and do the checking
@@ -106,13 +140,15 @@ impl BlockAptosVM {
         BLOCK_EXECUTOR_CONCURRENCY.set(concurrency_level as i64);
         let executor = BlockExecutor::<PreprocessedTransaction, AptosExecutorTask<S>, S>::new(
             concurrency_level,
-        );
+            None,
+        )
+        .with_conflict_logging(AptosVM::get_speculative_conflict_logs_per_block());
 
         let ret = executor
-            .execute_block(state_view, signature_verified_block, state_view)
-            .map(|results| {
+            .execute_block_with_summary(state_view, signature_verified_block, state_view)
+            .map(|(results, summary)| {
                 // Process the outputs in parallel, combining delta writes with other writes.
-                RAYON_EXEC_POOL.install(|| {
+                let outputs: Vec<TransactionOutput> = RAYON_EXEC_POOL.install(|| {
                     results
                         .into_par_iter()
                         .map(|(output, delta_writes)| {
@@ -121,15 +157,26 @@ impl BlockAptosVM {
                             .output_with_delta_writes(WriteSetMut::new(delta_writes))
                         })
                         .collect()
-                })
+                });
+                (outputs, summary)
             });
 
         match ret {
-            Ok(outputs) => Ok(outputs),
-            Err(Error::ModulePathReadWrite) => {
+            Ok((outputs, summary)) => Ok((outputs, summary)),
+            Err(Error::ModulePathReadWrite) | Err(Error::TooManyIncarnations) => {
                 unreachable!("[Execution]: Must be handled by sequential fallback")
             },
+            Err(Error::ExecutionCancelled) => {
+                unreachable!("[Execution]: Block execution is never cancelled")
+            },
             Err(Error::UserError(err)) => Err(err),
+            Err(Error::DeltaApplicationFailure(_))
+            | Err(Error::DeltaMaterializationFailure { .. }) => Err(VMStatus::Error(
+                StatusCode::UNKNOWN_INVARIANT_VIOLATION_ERROR,
+            )),
+            Err(Error::StorageReadFailure { .. }) => {
+                Err(VMStatus::Error(StatusCode::STORAGE_ERROR))
+            },
         }
     }
 }
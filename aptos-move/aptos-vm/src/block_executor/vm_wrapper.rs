@@ -69,11 +69,13 @@ impl<'a, S: 'a + StateView + Sync> ExecutorTask for AptosExecutorTask<'a, S> {
         {
             Ok((vm_status, mut output_ext, sender)) => {
                 if materialize_deltas {
+                    let storage_fee_used = output_ext.storage_fee_used();
                     // Keep TransactionOutputExt type for wrapper.
                     output_ext = TransactionOutputExt::new(
                         DeltaChangeSet::empty(),                  // Cleared deltas.
                         output_ext.into_transaction_output(view), // Materialize.
-                    );
+                    )
+                    .with_storage_fee_used(storage_fee_used);
                 }
 
                 if output_ext.txn_output().status().is_discarded() {
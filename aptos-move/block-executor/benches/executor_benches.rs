@@ -0,0 +1,43 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+// Run this bencher via `cargo bench --features fuzzing --bench executor_benches`.
+use aptos_block_executor::test_utils::{mock_data_view, MockBlockExecutor, Workload};
+use criterion::{criterion_group, criterion_main, BatchSize, BenchmarkId, Criterion, Throughput};
+
+const BLOCK_SIZES: [usize; 2] = [1000, 10000];
+const CONCURRENCY_LEVELS: [usize; 3] = [2, 4, 8];
+
+// Throughput of parallel execution of the mock executor, per workload, block size and number of
+// threads. Thread counts above the number of CPUs are skipped.
+fn workload_benches(c: &mut Criterion) {
+    let data_view = mock_data_view();
+    for workload in Workload::ALL {
+        let mut group = c.benchmark_group(workload.name());
+        for block_size in BLOCK_SIZES {
+            group.throughput(Throughput::Elements(block_size as u64));
+            for concurrency_level in CONCURRENCY_LEVELS {
+                if concurrency_level > num_cpus::get() {
+                    continue;
+                }
+                let executor = MockBlockExecutor::new(concurrency_level, None);
+                group.bench_with_input(
+                    BenchmarkId::new(format!("{}_threads", concurrency_level), block_size),
+                    &block_size,
+                    |b, block_size| {
+                        b.iter_batched(
+                            || workload.generate(*block_size),
+                            |block| executor.execute_block((), block, &data_view).unwrap(),
+                            BatchSize::LargeInput,
+                        )
+                    },
+                );
+            }
+        }
+        group.finish();
+    }
+}
+
+criterion_group!(benches, workload_benches);
+
+criterion_main!(benches);
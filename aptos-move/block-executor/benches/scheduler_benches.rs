@@ -2,7 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 // Run this bencher via `cargo bench --features fuzzing`.
-use aptos_parallel_executor::proptest_types::bencher::Bencher;
+use aptos_block_executor::proptest_types::bencher::Bencher;
 use criterion::{criterion_group, criterion_main, Criterion};
 use proptest::prelude::*;
 
@@ -17,6 +17,15 @@ fn random_benches(c: &mut Criterion) {
     });
 }
 
-criterion_group!(benches, random_benches);
+// A small key universe, so that most transactions conflict and commits depend on
+// (re-)validations close to the commit index.
+fn contended_benches(c: &mut Criterion) {
+    c.bench_function("contended_benches", |b| {
+        let bencher = Bencher::<[u8; 32], [u8; 32]>::new(10000, 10);
+        bencher.bench(&any::<[u8; 32]>(), b)
+    });
+}
+
+criterion_group!(benches, random_benches, contended_benches);
 
 criterion_main!(benches);
@@ -0,0 +1,146 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::counters;
+use aptos_metrics_core::IntCounter;
+use aptos_state_view::TStateView;
+use dashmap::{mapref::entry::Entry, DashMap};
+use once_cell::sync::{Lazy, OnceCell};
+use rayon::prelude::*;
+use std::{
+    hash::Hash,
+    sync::{
+        atomic::{AtomicU64, AtomicUsize, Ordering},
+        Arc,
+    },
+};
+
+/// A cached value, read from the base view by the first thread that needs it.
+struct CacheEntry {
+    epoch: u64,
+    value: OnceCell<Option<Vec<u8>>>,
+}
+
+/// A bounded read-through cache of base view (storage) values that can outlive a single block.
+///
+/// The cache does not know when the underlying storage changes: the owner must call
+/// `invalidate` whenever the base view the cache was populated from is superseded (e.g. after
+/// a block is committed). Every entry is tagged with the invalidation epoch it was read in, so
+/// a read racing with an invalidation can never be served afterwards.
+pub struct BaseViewCache<K> {
+    entries: DashMap<K, Arc<CacheEntry>>,
+    epoch: AtomicU64,
+    // number of entries, approximate while racing with an invalidation.
+    size: AtomicUsize,
+    capacity: usize,
+    hits: &'static Lazy<IntCounter>,
+    misses: &'static Lazy<IntCounter>,
+}
+
+impl<K: Hash + Eq + Clone + Send + Sync> BaseViewCache<K> {
+    /// Creates a cache holding at most `capacity` entries. Once full, further reads are
+    /// passed through to the base view without being cached.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            entries: DashMap::new(),
+            epoch: AtomicU64::new(0),
+            size: AtomicUsize::new(0),
+            capacity,
+            hits: &counters::BASE_VIEW_CACHE_HIT_COUNT,
+            misses: &counters::BASE_VIEW_CACHE_MISS_COUNT,
+        }
+    }
+
+    /// An unbounded cache for the reads of a single block, dropped with the block.
+    pub(crate) fn new_for_block() -> Self {
+        Self {
+            hits: &counters::BLOCK_READ_CACHE_HIT_COUNT,
+            misses: &counters::BLOCK_READ_CACHE_MISS_COUNT,
+            ..Self::new(usize::MAX)
+        }
+    }
+
+    /// Drops all the cached values. Values read from the base view before the call (but
+    /// inserted after it) are discarded as well.
+    pub fn invalidate(&self) {
+        self.epoch.fetch_add(1, Ordering::SeqCst);
+        self.entries.clear();
+        self.size.store(0, Ordering::Relaxed);
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Loads the given keys from the base view into the cache, in parallel.
+    pub fn prewarm<S: TStateView<Key = K> + Sync>(&self, keys: &[K], base_view: &S) {
+        keys.par_iter().for_each(|key| {
+            // Errors are not cached, the read will be retried (and fail) during execution.
+            let _ = self.get_state_value(key, base_view);
+        });
+    }
+
+    /// Returns the value for the key, reading it from the base view and caching it on a miss.
+    /// Concurrent misses on the same key wait for each other, so that the key is read from the
+    /// base view once, but the entry is only locked while it is looked up, not while it is read.
+    pub(crate) fn get_state_value<S: TStateView<Key = K>>(
+        &self,
+        key: &K,
+        base_view: &S,
+    ) -> anyhow::Result<Option<Vec<u8>>> {
+        let epoch = self.epoch.load(Ordering::SeqCst);
+        let entry = match self.entries.get(key).map(|entry| entry.clone()) {
+            Some(entry) if entry.epoch == epoch => entry,
+            _ => {
+                if self.size.load(Ordering::Relaxed) >= self.capacity
+                    && !self.entries.contains_key(key)
+                {
+                    // Full, the read is passed through.
+                    self.misses.inc();
+                    return base_view.get_state_value(key);
+                }
+                self.entry(key, epoch)
+            },
+        };
+
+        let mut read = false;
+        // Errors are not cached, the next read of the key tries again.
+        let value = entry.value.get_or_try_init(|| {
+            read = true;
+            base_view.get_state_value(key)
+        })?;
+        if read {
+            self.misses.inc();
+        } else {
+            self.hits.inc();
+        }
+        Ok(value.clone())
+    }
+
+    /// The entry of the key for the epoch, created if missing or from an older epoch.
+    fn entry(&self, key: &K, epoch: u64) -> Arc<CacheEntry> {
+        let new_entry = || {
+            Arc::new(CacheEntry {
+                epoch,
+                value: OnceCell::new(),
+            })
+        };
+        match self.entries.entry(key.clone()) {
+            // Created by another thread in the meantime.
+            Entry::Occupied(entry) if entry.get().epoch == epoch => entry.get().clone(),
+            Entry::Occupied(mut entry) => {
+                let cache_entry = new_entry();
+                entry.insert(cache_entry.clone());
+                cache_entry
+            },
+            Entry::Vacant(entry) => {
+                self.size.fetch_add(1, Ordering::Relaxed);
+                entry.insert(new_entry()).clone()
+            },
+        }
+    }
+}
@@ -0,0 +1,252 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    counters::{
+        BLOCK_EXECUTION_GAS, BLOCK_REEXECUTIONS_BY_POSITION, BLOCK_STORAGE_FEE,
+        BLOCK_WORKER_FINISH_SKEW_SECONDS, COMMITTED_TXN_INCARNATION_COUNT, DEPENDENCY_WAIT_COUNT,
+        REEXECUTIONS_BY_POSITION_COUNT, VALIDATION_FAILURE_COUNT, WORKER_FINISH_SKEW_SECONDS,
+        WORKER_UTILIZATION,
+    },
+    executor::BlockExecutionSummary,
+    scheduler::Incarnation,
+};
+use std::{collections::BTreeMap, time::Duration};
+
+/// Re-executions are bucketed by the decile of the block the transaction is in.
+pub const NUM_POSITION_BUCKETS: usize = 10;
+
+/// Timings of a single worker thread during the parallel execution of a block, along with the
+/// events that slowed it down. Each thread collects its own, and they are merged once the
+/// workers are all done.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub(crate) struct WorkerTimings {
+    /// Time spent executing and validating transactions.
+    pub(crate) busy: Duration,
+    /// Time from the start of the block until the worker first observed that it is done.
+    pub(crate) done_at: Duration,
+    /// Number of times the executions of the worker waited on a dependency.
+    pub(crate) dependency_waits: u64,
+    /// Time spent waiting on dependencies, part of the busy time.
+    pub(crate) dependency_wait: Duration,
+    /// Number of validations of the worker that failed.
+    pub(crate) validation_failures: u64,
+}
+
+impl WorkerTimings {
+    /// Fraction of the time until the worker was done that it spent busy.
+    fn utilization(&self) -> f64 {
+        if self.done_at.is_zero() {
+            return 0.0;
+        }
+        (self.busy.as_secs_f64() / self.done_at.as_secs_f64()).min(1.0)
+    }
+}
+
+/// How unevenly the worker threads finished a block. The finish skew of a worker is how long
+/// after the first worker it observed that the block is done.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct WorkerSkew {
+    pub min_finish_skew: Duration,
+    pub median_finish_skew: Duration,
+    pub max_finish_skew: Duration,
+    /// Fraction of the time until done that each worker spent executing or validating.
+    pub utilization: Vec<f64>,
+}
+
+impl WorkerSkew {
+    pub(crate) fn from_workers(workers: &[WorkerTimings]) -> Self {
+        let mut done_at: Vec<Duration> = workers.iter().map(|worker| worker.done_at).collect();
+        done_at.sort();
+        let first = done_at.first().copied().unwrap_or_default();
+        let skew = |finish: Option<&Duration>| finish.map_or(Duration::ZERO, |d| *d - first);
+
+        Self {
+            min_finish_skew: Duration::ZERO,
+            median_finish_skew: skew(done_at.get(done_at.len() / 2)),
+            max_finish_skew: skew(done_at.last()),
+            utilization: workers.iter().map(WorkerTimings::utilization).collect(),
+        }
+    }
+}
+
+/// Sums the re-executions (incarnations past the first one) of the transactions by their
+/// relative position in the block.
+pub(crate) fn reexecutions_by_position(
+    incarnations: &[Incarnation],
+) -> [u64; NUM_POSITION_BUCKETS] {
+    let mut buckets = [0; NUM_POSITION_BUCKETS];
+    for (idx, incarnation) in incarnations.iter().enumerate() {
+        buckets[idx * NUM_POSITION_BUCKETS / incarnations.len()] += *incarnation as u64;
+    }
+    buckets
+}
+
+/// Number of committed transactions by the incarnation they were committed with, i.e. the
+/// element at index `i` counts the transactions that were executed `i + 1` times.
+pub(crate) fn incarnation_histogram(committed_incarnations: &[Incarnation]) -> Vec<usize> {
+    let mut histogram = vec![];
+    for incarnation in committed_incarnations {
+        if histogram.len() <= *incarnation {
+            histogram.resize(*incarnation + 1, 0);
+        }
+        histogram[*incarnation] += 1;
+    }
+    histogram
+}
+
+/// Metrics of the parallel execution of a single block.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct BlockMetrics {
+    pub worker_skew: WorkerSkew,
+    /// Number of re-executions of the transactions in each decile of the block.
+    pub reexecutions_by_position: [u64; NUM_POSITION_BUCKETS],
+    /// Number of committed transactions by the incarnation they were committed with, see
+    /// `incarnation_histogram`.
+    pub committed_incarnations: Vec<usize>,
+    /// Number of times an execution waited on a dependency.
+    pub dependency_waits: u64,
+    /// Time the workers spent waiting on dependencies, summed over the workers.
+    pub dependency_wait: Duration,
+    /// Number of failed validations, which is at least the number of speculative aborts.
+    pub validation_failures: u64,
+    /// Execution gas used by the committed transactions, what the block gas limit applies to.
+    pub execution_gas: u64,
+    /// Storage fees charged to the committed transactions.
+    pub storage_fee: u64,
+    /// Number of keys written or updated via a delta by the committed transactions, 0 unless
+    /// enabled with `BlockExecutor::with_modified_key_count`.
+    pub modified_keys: usize,
+    /// Concurrency level the block was executed with, after the adjustments of the pool sizing.
+    pub concurrency_level: usize,
+    /// Number of threads of the executor pool.
+    pub exec_pool_threads: usize,
+}
+
+impl BlockMetrics {
+    /// The metrics of the block summarized by the summary, given the final incarnations of its
+    /// transactions and the timings of the workers the summary was made from.
+    pub(crate) fn new(
+        workers: &[WorkerTimings],
+        incarnations: &[Incarnation],
+        summary: &BlockExecutionSummary,
+    ) -> Self {
+        Self {
+            worker_skew: WorkerSkew::from_workers(workers),
+            reexecutions_by_position: reexecutions_by_position(incarnations),
+            committed_incarnations: summary.committed_incarnations.clone(),
+            dependency_waits: summary.dependency_waits,
+            dependency_wait: summary.dependency_wait,
+            validation_failures: summary.validation_failures,
+            execution_gas: summary.execution_gas,
+            storage_fee: summary.storage_fee,
+            modified_keys: 0,
+            concurrency_level: 0,
+            exec_pool_threads: 0,
+        }
+    }
+
+    /// Exports the metrics of the block to prometheus.
+    pub(crate) fn observe(&self, workers: &[WorkerTimings]) {
+        let first = workers
+            .iter()
+            .map(|worker| worker.done_at)
+            .min()
+            .unwrap_or_default();
+        for worker in workers {
+            WORKER_FINISH_SKEW_SECONDS.observe((worker.done_at - first).as_secs_f64());
+            WORKER_UTILIZATION.observe(worker.utilization());
+        }
+        BLOCK_WORKER_FINISH_SKEW_SECONDS.observe(self.worker_skew.max_finish_skew.as_secs_f64());
+
+        for (decile, count) in self.reexecutions_by_position.iter().enumerate() {
+            let decile = decile.to_string();
+            BLOCK_REEXECUTIONS_BY_POSITION
+                .with_label_values(&[&decile])
+                .observe(*count as f64);
+            REEXECUTIONS_BY_POSITION_COUNT
+                .with_label_values(&[&decile])
+                .inc_by(*count);
+        }
+
+        let mut committed_by_incarnations = BTreeMap::new();
+        for (incarnation, count) in self.committed_incarnations.iter().enumerate() {
+            *committed_by_incarnations
+                .entry((incarnation + 1).next_power_of_two())
+                .or_insert(0) += *count as u64;
+        }
+        for (incarnations, count) in committed_by_incarnations {
+            COMMITTED_TXN_INCARNATION_COUNT
+                .with_label_values(&[&incarnations.to_string()])
+                .inc_by(count);
+        }
+        DEPENDENCY_WAIT_COUNT.inc_by(self.dependency_waits);
+        VALIDATION_FAILURE_COUNT.inc_by(self.validation_failures);
+
+        BLOCK_EXECUTION_GAS.observe(self.execution_gas as f64);
+        BLOCK_STORAGE_FEE.observe(self.storage_fee as f64);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn worker(busy_ms: u64, done_at_ms: u64) -> WorkerTimings {
+        WorkerTimings {
+            busy: Duration::from_millis(busy_ms),
+            done_at: Duration::from_millis(done_at_ms),
+            ..WorkerTimings::default()
+        }
+    }
+
+    #[test]
+    fn test_worker_skew() {
+        let skew = WorkerSkew::from_workers(&[
+            worker(40, 100),
+            worker(10, 20),
+            worker(0, 0),
+            worker(30, 30),
+            worker(50, 50),
+        ]);
+        assert_eq!(skew.min_finish_skew, Duration::ZERO);
+        assert_eq!(skew.median_finish_skew, Duration::from_millis(30));
+        assert_eq!(skew.max_finish_skew, Duration::from_millis(100));
+        let expected = [0.4, 0.5, 0.0, 1.0, 1.0];
+        assert_eq!(skew.utilization.len(), expected.len());
+        for (utilization, expected) in skew.utilization.iter().zip(expected) {
+            assert!((utilization - expected).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn test_reexecutions_by_position() {
+        assert_eq!(reexecutions_by_position(&[]), [0; NUM_POSITION_BUCKETS]);
+        // Fewer transactions than buckets
+        assert_eq!(
+            reexecutions_by_position(&[1, 0, 2]),
+            [1, 0, 0, 0, 0, 0, 2, 0, 0, 0]
+        );
+        let mut incarnations = vec![0; 25];
+        incarnations[0] = 1;
+        incarnations[2] = 2;
+        incarnations[3] = 1;
+        incarnations[24] = 5;
+        assert_eq!(
+            reexecutions_by_position(&incarnations),
+            [3, 1, 0, 0, 0, 0, 0, 0, 0, 5]
+        );
+    }
+
+    #[test]
+    fn test_incarnation_histogram() {
+        assert!(incarnation_histogram(&[]).is_empty());
+        assert_eq!(incarnation_histogram(&[0, 0, 0]), vec![3]);
+        assert_eq!(incarnation_histogram(&[0, 3, 1, 0, 3]), vec![2, 1, 0, 2]);
+    }
+
+    #[test]
+    fn test_no_workers() {
+        assert_eq!(WorkerSkew::from_workers(&[]), WorkerSkew::default());
+    }
+}
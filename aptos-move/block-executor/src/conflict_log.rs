@@ -0,0 +1,113 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    scheduler::{Incarnation, TxnIndex},
+    task::KeyDebug,
+};
+use aptos_logger::{info, Schema};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// Explains a speculative abort: the read of `key` by the incarnation of the transaction was
+/// invalidated by the write of the given incarnation of an earlier transaction. The writer is
+/// unknown if the read value got removed, or was resolved from aggregator deltas.
+#[derive(Schema, Clone, Debug, PartialEq, Eq)]
+pub struct SpeculativeConflict {
+    txn_idx: TxnIndex,
+    incarnation: Incarnation,
+    key: String,
+    writer_idx: Option<TxnIndex>,
+    writer_incarnation: Option<Incarnation>,
+}
+
+/// Logs the speculative aborts of a block, up to a limit so that highly contended blocks don't
+/// flood the logs. Logging is disabled with a limit of 0.
+pub(crate) struct ConflictLogger {
+    max_logs: usize,
+    num_logged: AtomicUsize,
+}
+
+impl ConflictLogger {
+    pub(crate) fn new(max_logs: usize) -> Self {
+        Self {
+            max_logs,
+            num_logged: AtomicUsize::new(0),
+        }
+    }
+
+    /// Logs the conflict unless the limit was already reached, in which case the key isn't
+    /// even rendered. Returns the logged record.
+    pub(crate) fn log<K: KeyDebug>(
+        &self,
+        txn_idx: TxnIndex,
+        incarnation: Incarnation,
+        key: &K,
+        writer: Option<(TxnIndex, Option<Incarnation>)>,
+    ) -> Option<SpeculativeConflict> {
+        if self.max_logs == 0 || self.num_logged.fetch_add(1, Ordering::Relaxed) >= self.max_logs {
+            return None;
+        }
+
+        let conflict = SpeculativeConflict {
+            txn_idx,
+            incarnation,
+            key: key.key_debug(),
+            writer_idx: writer.map(|(idx, _)| idx),
+            writer_incarnation: writer.and_then(|(_, incarnation)| incarnation),
+        };
+        info!(conflict.clone(), "[Execution]: Speculative abort");
+        Some(conflict)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct BytesKey(Vec<u8>);
+
+    impl KeyDebug for BytesKey {
+        fn key_debug(&self) -> String {
+            hex::encode(&self.0)
+        }
+    }
+
+    #[test]
+    fn test_conflict_record() {
+        let logger = ConflictLogger::new(10);
+        let conflict = logger
+            .log(7, 2, &BytesKey(vec![0xab, 0x01]), Some((3, Some(1))))
+            .unwrap();
+        assert_eq!(conflict, SpeculativeConflict {
+            txn_idx: 7,
+            incarnation: 2,
+            key: "ab01".to_string(),
+            writer_idx: Some(3),
+            writer_incarnation: Some(1),
+        });
+
+        // Reading an estimate doesn't tell the incarnation of the writer.
+        let conflict = logger
+            .log(8, 0, &BytesKey(vec![]), Some((3, None)))
+            .unwrap();
+        assert_eq!(conflict.writer_idx, Some(3));
+        assert_eq!(conflict.writer_incarnation, None);
+
+        let conflict = logger.log(9, 0, &BytesKey(vec![]), None).unwrap();
+        assert_eq!(conflict.writer_idx, None);
+    }
+
+    #[test]
+    fn test_rate_limit() {
+        let logger = ConflictLogger::new(3);
+        let num_logged = (0..100)
+            .filter_map(|idx| logger.log(idx, 0, &BytesKey(vec![]), None))
+            .count();
+        assert_eq!(num_logged, 3);
+
+        assert_eq!(
+            ConflictLogger::new(0).log(0, 0, &BytesKey(vec![]), None),
+            None
+        );
+    }
+}
@@ -2,7 +2,9 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use aptos_metrics_core::{
-    exponential_buckets, register_histogram, register_int_counter, Histogram, IntCounter,
+    exponential_buckets, register_histogram, register_histogram_vec, register_int_counter,
+    register_int_counter_vec, register_int_gauge, Histogram, HistogramVec, IntCounter,
+    IntCounterVec, IntGauge,
 };
 use once_cell::sync::Lazy;
 
@@ -15,6 +17,24 @@ pub static MODULE_PUBLISHING_FALLBACK_COUNT: Lazy<IntCounter> = Lazy::new(|| {
     .unwrap()
 });
 
+/// Count of times a transaction exceeded the incarnation limit in parallel execution.
+pub static INCARNATION_LIMIT_FALLBACK_COUNT: Lazy<IntCounter> = Lazy::new(|| {
+    register_int_counter!(
+        "aptos_execution_incarnation_limit_fallback_count",
+        "Count times a transaction was re-executed too many times in parallel execution (sequential fallback)"
+    )
+    .unwrap()
+});
+
+/// Count of times the execution of a block in the order of the ordering hook was discarded.
+pub static ORDERED_EXECUTION_FALLBACK_COUNT: Lazy<IntCounter> = Lazy::new(|| {
+    register_int_counter!(
+        "aptos_execution_ordered_execution_fallback_count",
+        "Count times the execution in the order of the ordering hook depended on the order (executed in block order instead)"
+    )
+    .unwrap()
+});
+
 /// Count of speculative transaction re-executions due to a failed validation.
 pub static SPECULATIVE_ABORT_COUNT: Lazy<IntCounter> = Lazy::new(|| {
     register_int_counter!(
@@ -24,6 +44,74 @@ pub static SPECULATIVE_ABORT_COUNT: Lazy<IntCounter> = Lazy::new(|| {
     .unwrap()
 });
 
+/// Count of base view reads served from the cross-block base view cache.
+pub static BASE_VIEW_CACHE_HIT_COUNT: Lazy<IntCounter> = Lazy::new(|| {
+    register_int_counter!(
+        "aptos_execution_base_view_cache_hit_count",
+        "Number of base view reads served from the base view cache"
+    )
+    .unwrap()
+});
+
+/// Count of base view reads that missed the cross-block base view cache.
+pub static BASE_VIEW_CACHE_MISS_COUNT: Lazy<IntCounter> = Lazy::new(|| {
+    register_int_counter!(
+        "aptos_execution_base_view_cache_miss_count",
+        "Number of base view reads that missed the base view cache and went to storage"
+    )
+    .unwrap()
+});
+
+/// Count of base view reads served from the cache of the block in parallel execution.
+pub static BLOCK_READ_CACHE_HIT_COUNT: Lazy<IntCounter> = Lazy::new(|| {
+    register_int_counter!(
+        "aptos_execution_block_read_cache_hit_count",
+        "Number of base view reads served from the read cache of the block"
+    )
+    .unwrap()
+});
+
+/// Count of base view reads that missed the cache of the block in parallel execution.
+pub static BLOCK_READ_CACHE_MISS_COUNT: Lazy<IntCounter> = Lazy::new(|| {
+    register_int_counter!(
+        "aptos_execution_block_read_cache_miss_count",
+        "Number of base view reads that missed the read cache of the block and went to storage"
+    )
+    .unwrap()
+});
+
+/// Count of keys removed from the multi-version data-structure after their last entry got
+/// deleted by a re-execution.
+pub static MVHASHMAP_RECLAIMED_KEY_COUNT: Lazy<IntCounter> = Lazy::new(|| {
+    register_int_counter!(
+        "aptos_execution_mvhashmap_reclaimed_key_count",
+        "Number of keys reclaimed from the multi-version data-structure when emptied by deletes"
+    )
+    .unwrap()
+});
+
+/// Count of speculative re-executions by the decile of the block the transaction is in.
+pub static REEXECUTIONS_BY_POSITION_COUNT: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "aptos_execution_reexecutions_by_position_count",
+        "Number of transaction re-executions in parallel execution, by decile of the block",
+        &["decile"]
+    )
+    .unwrap()
+});
+
+pub static BLOCK_REEXECUTIONS_BY_POSITION: Lazy<HistogramVec> = Lazy::new(|| {
+    register_histogram_vec!(
+        // metric name
+        "aptos_execution_block_reexecutions_by_position",
+        // metric description
+        "Number of transaction re-executions per block in parallel execution, by decile of the block",
+        &["decile"],
+        exponential_buckets(/*start=*/ 1.0, /*factor=*/ 2.0, /*count=*/ 16).unwrap(),
+    )
+    .unwrap()
+});
+
 pub static VM_INIT_SECONDS: Lazy<Histogram> = Lazy::new(|| {
     register_histogram!(
         // metric name
@@ -46,6 +134,28 @@ pub static TASK_VALIDATE_SECONDS: Lazy<Histogram> = Lazy::new(|| {
     .unwrap()
 });
 
+pub static TIME_TO_FIRST_COMMIT_SECONDS: Lazy<Histogram> = Lazy::new(|| {
+    register_histogram!(
+        // metric name
+        "aptos_execution_time_to_first_commit_seconds",
+        // metric description
+        "The time spent in seconds from the start of parallel execution to the first commit",
+        exponential_buckets(/*start=*/ 1e-6, /*factor=*/ 2.0, /*count=*/ 30).unwrap(),
+    )
+    .unwrap()
+});
+
+pub static PARALLEL_EXECUTION_SECONDS: Lazy<Histogram> = Lazy::new(|| {
+    register_histogram!(
+        // metric name
+        "aptos_execution_parallel_execution_seconds",
+        // metric description
+        "The time spent in seconds in parallel execution of a block in Block STM",
+        exponential_buckets(/*start=*/ 1e-6, /*factor=*/ 2.0, /*count=*/ 30).unwrap(),
+    )
+    .unwrap()
+});
+
 pub static TASK_EXECUTE_SECONDS: Lazy<Histogram> = Lazy::new(|| {
     register_histogram!(
         // metric name
@@ -57,6 +167,46 @@ pub static TASK_EXECUTE_SECONDS: Lazy<Histogram> = Lazy::new(|| {
     .unwrap()
 });
 
+/// Count of times an execution waited on a dependency in parallel execution.
+pub static DEPENDENCY_WAIT_COUNT: Lazy<IntCounter> = Lazy::new(|| {
+    register_int_counter!(
+        "aptos_execution_dependency_wait_count",
+        "Number of times an execution waited for a dependency in Block STM"
+    )
+    .unwrap()
+});
+
+/// Count of validations that failed in parallel execution, whether or not they aborted the
+/// transaction (another validation may have already).
+pub static VALIDATION_FAILURE_COUNT: Lazy<IntCounter> = Lazy::new(|| {
+    register_int_counter!(
+        "aptos_execution_validation_failure_count",
+        "Number of failed validations in Block STM"
+    )
+    .unwrap()
+});
+
+/// Count of committed transactions by their number of incarnations, rounded up to a power of two.
+pub static COMMITTED_TXN_INCARNATION_COUNT: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "aptos_execution_committed_txn_incarnation_count",
+        "Number of committed transactions in Block STM, by their number of incarnations rounded \
+         up to a power of two",
+        &["incarnations"]
+    )
+    .unwrap()
+});
+
+/// Length of the committed prefix of the last block that ended early, i.e. at a SkipRest
+/// transaction or at the block gas limit.
+pub static EARLY_HALT_COMMITTED_TXNS: Lazy<IntGauge> = Lazy::new(|| {
+    register_int_gauge!(
+        "aptos_execution_early_halt_committed_txns",
+        "Number of committed transactions of the last block that ended before its last transaction"
+    )
+    .unwrap()
+});
+
 pub static DEPENDENCY_WAIT_SECONDS: Lazy<Histogram> = Lazy::new(|| {
     register_histogram!(
         "aptos_execution_dependency_wait",
@@ -65,3 +215,58 @@ pub static DEPENDENCY_WAIT_SECONDS: Lazy<Histogram> = Lazy::new(|| {
     )
     .unwrap()
 });
+
+pub static WORKER_FINISH_SKEW_SECONDS: Lazy<Histogram> = Lazy::new(|| {
+    register_histogram!(
+        // metric name
+        "aptos_execution_worker_finish_skew_seconds",
+        // metric description
+        "Time in seconds after the first worker thread that each worker observed the end of a block",
+        exponential_buckets(/*start=*/ 1e-6, /*factor=*/ 2.0, /*count=*/ 30).unwrap(),
+    )
+    .unwrap()
+});
+
+pub static BLOCK_WORKER_FINISH_SKEW_SECONDS: Lazy<Histogram> = Lazy::new(|| {
+    register_histogram!(
+        // metric name
+        "aptos_execution_block_worker_finish_skew_seconds",
+        // metric description
+        "Time in seconds between the first and the last worker thread observing the end of a block",
+        exponential_buckets(/*start=*/ 1e-6, /*factor=*/ 2.0, /*count=*/ 30).unwrap(),
+    )
+    .unwrap()
+});
+
+pub static WORKER_UTILIZATION: Lazy<Histogram> = Lazy::new(|| {
+    register_histogram!(
+        // metric name
+        "aptos_execution_worker_utilization",
+        // metric description
+        "Fraction of the time until the end of a block that a worker thread spent executing or validating",
+        vec![0.1, 0.2, 0.3, 0.4, 0.5, 0.6, 0.7, 0.8, 0.9, 1.0],
+    )
+    .unwrap()
+});
+
+pub static BLOCK_EXECUTION_GAS: Lazy<Histogram> = Lazy::new(|| {
+    register_histogram!(
+        // metric name
+        "aptos_execution_block_execution_gas",
+        // metric description
+        "Execution gas used by the committed transactions of a block",
+        exponential_buckets(/*start=*/ 1.0, /*factor=*/ 2.0, /*count=*/ 40).unwrap(),
+    )
+    .unwrap()
+});
+
+pub static BLOCK_STORAGE_FEE: Lazy<Histogram> = Lazy::new(|| {
+    register_histogram!(
+        // metric name
+        "aptos_execution_block_storage_fee",
+        // metric description
+        "Storage fees charged to the committed transactions of a block",
+        exponential_buckets(/*start=*/ 1.0, /*factor=*/ 2.0, /*count=*/ 40).unwrap(),
+    )
+    .unwrap()
+});
@@ -1,6 +1,8 @@
 // Copyright (c) Aptos
 // SPDX-License-Identifier: Apache-2.0
 
+use crate::scheduler::TxnIndex;
+
 #[derive(Debug, PartialEq, Eq)]
 pub enum Error<E> {
     /// The same module access path for module was both read & written during speculative executions.
@@ -12,6 +14,24 @@ pub enum Error<E> {
     /// Execution of a thread yields a non-recoverable error, such error will be propagated back to
     /// the caller.
     UserError(E),
+    /// A delta of the transaction at the given index could not be applied to the latest value
+    /// of the aggregator during sequential execution, i.e. it genuinely over/underflowed (or the
+    /// aggregator does not exist). Re-executing the block would fail the same way.
+    DeltaApplicationFailure(TxnIndex),
+    /// Same as `DeltaApplicationFailure`, for parallel execution: the delta of the committed
+    /// transaction at the index, on the rendered key, could not be applied when materializing
+    /// the deltas of the block as writes.
+    DeltaMaterializationFailure { txn_idx: TxnIndex, key: String },
+    /// Reading the latest value of an aggregator from storage failed while applying a delta of
+    /// the transaction at the index during sequential execution, with the rendered error.
+    StorageReadFailure { txn_idx: TxnIndex, error: String },
+    /// A transaction was aborted more times than the incarnation limit of the executor allows
+    /// during parallel execution, likely livelocked by conflicts. Mitigation requires halting
+    /// the parallel execution and falling back to the sequential execution.
+    TooManyIncarnations,
+    /// The execution of the block was cancelled through a `BlockExecutorHandle` before it
+    /// completed, and its results were discarded.
+    ExecutionCancelled,
 }
 
 pub type Result<T, E> = ::std::result::Result<T, Error<E>>;
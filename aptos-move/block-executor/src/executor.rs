@@ -1,40 +1,255 @@
 // Copyright (c) Aptos
 // SPDX-License-Identifier: Apache-2.0
 
+pub use crate::scheduler::BlockCommitReason;
 use crate::{
+    base_view_cache::BaseViewCache,
+    block_metrics::{incarnation_histogram, BlockMetrics, WorkerTimings},
+    conflict_log::ConflictLogger,
     counters,
-    counters::{TASK_EXECUTE_SECONDS, TASK_VALIDATE_SECONDS, VM_INIT_SECONDS},
+    counters::{
+        PARALLEL_EXECUTION_SECONDS, TASK_EXECUTE_SECONDS, TASK_VALIDATE_SECONDS,
+        TIME_TO_FIRST_COMMIT_SECONDS, VM_INIT_SECONDS,
+    },
     errors::*,
+    ordering::{BlockOrderingHook, ExecutionOrder, OrderedBlock},
     output_delta_resolver::OutputDeltaResolver,
-    scheduler::{Scheduler, SchedulerTask, Version, Wave},
-    task::{ExecutionStatus, ExecutorTask, Transaction, TransactionOutput},
+    pool_sizing::EXEC_POOL_SIZING,
+    scheduler::{
+        Incarnation, Scheduler, SchedulerTask, TxnIndex, Version, Wave,
+        DEFAULT_VALIDATION_PRIORITY_WINDOW,
+    },
+    task::{ExecutionStatus, ExecutorTask, KeyDebug, Transaction, TransactionOutput},
     txn_last_input_output::TxnLastInputOutput,
     view::{LatestView, MVHashMapView},
 };
+use aptos_aggregator::delta_change_set::{deserialize, serialize};
+use aptos_infallible::Mutex;
 use aptos_logger::debug;
 use aptos_mvhashmap::{MVHashMap, MVHashMapError, MVHashMapOutput};
 use aptos_state_view::TStateView;
 use aptos_types::write_set::WriteOp;
 use num_cpus;
 use once_cell::sync::Lazy;
+use rayon::prelude::*;
 use std::{
     collections::btree_map::BTreeMap,
     marker::PhantomData,
-    sync::atomic::{AtomicBool, Ordering},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    time::{Duration, Instant},
 };
 
-pub static RAYON_EXEC_POOL: Lazy<rayon::ThreadPool> = Lazy::new(|| {
-    rayon::ThreadPoolBuilder::new()
-        .num_threads(num_cpus::get())
-        .thread_name(|index| format!("par_exec_{}", index))
-        .build()
-        .unwrap()
+/// The executor pool shared by the block executors of the process, unless given another one.
+pub static RAYON_EXEC_POOL: Lazy<Arc<rayon::ThreadPool>> = Lazy::new(|| {
+    Arc::new(
+        rayon::ThreadPoolBuilder::new()
+            .num_threads(EXEC_POOL_SIZING.exec_pool_threads)
+            .thread_name(|index| format!("par_exec_{}", index))
+            .build()
+            .unwrap(),
+    )
 });
 
-pub struct BlockExecutor<T, E, S> {
+/// Default bound on the incarnations of a transaction in parallel execution, past which the
+/// block is deemed livelocked and executed sequentially instead.
+pub const DEFAULT_MAX_INCARNATIONS: Incarnation = 64;
+
+/// Minimum number of outputs taken by a single task at the end of parallel execution, so that
+/// small blocks aren't split into tasks cheaper than their scheduling.
+const OUTPUT_EXTRACTION_MIN_CHUNK: usize = 256;
+
+/// What the execution of a block amounted to, returned alongside its outputs.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BlockExecutionSummary {
+    /// Number of transactions (from the start of the block) whose outputs are kept, the rest of
+    /// the block getting skip outputs.
+    pub num_committed_txns: usize,
+    /// Execution gas used by the committed transactions, what the block gas limit applies to.
+    pub execution_gas: u64,
+    /// Storage fees charged to the committed transactions.
+    pub storage_fee: u64,
+    /// Number of speculative aborts in this block, 0 when executed sequentially.
+    pub speculative_aborts: u64,
+    /// Highest incarnation any transaction of the block was executed with.
+    pub max_incarnation: Incarnation,
+    /// Number of committed transactions by the incarnation they were committed with, i.e. the
+    /// element at index `i` counts the transactions that were executed `i + 1` times.
+    pub committed_incarnations: Vec<usize>,
+    /// Number of times an execution waited on a dependency, 0 when executed sequentially.
+    pub dependency_waits: u64,
+    /// Time the workers spent waiting on dependencies, summed over the workers.
+    pub dependency_wait: Duration,
+    /// Number of failed validations, which is at least the number of speculative aborts.
+    pub validation_failures: u64,
+    /// Where the block ended.
+    pub commit_reason: BlockCommitReason,
+}
+
+impl BlockExecutionSummary {
+    /// Index of the first transaction of the block that wasn't committed, None if the whole
+    /// block was.
+    pub fn first_uncommitted_txn_idx(&self) -> Option<TxnIndex> {
+        match self.commit_reason {
+            BlockCommitReason::EndOfBlock => None,
+            BlockCommitReason::SkipRest(_)
+            | BlockCommitReason::BlockGasLimitReached(_)
+            | BlockCommitReason::Cancelled => Some(self.num_committed_txns),
+        }
+    }
+}
+
+/// Walks the committed transactions in order and decides where the block ends: right after the
+/// earlier of the first SkipRest transaction and the first transaction at which the accumulated
+/// execution gas reaches the block gas limit (storage fees don't count against it). When both
+/// happen at the same transaction, SkipRest is reported. Parallel and sequential execution share
+/// the tracker so they always agree.
+struct BlockCutTracker {
+    maybe_gas_limit: Option<u64>,
+    accumulated_execution_gas: u64,
+    accumulated_storage_fee: u64,
+    // index of the last transaction in the block, set once the cut (or an abort) is reached.
+    last_txn_idx: Option<TxnIndex>,
+    reason: BlockCommitReason,
+}
+
+impl BlockCutTracker {
+    fn new(maybe_gas_limit: Option<u64>) -> Self {
+        Self {
+            maybe_gas_limit,
+            accumulated_execution_gas: 0,
+            accumulated_storage_fee: 0,
+            last_txn_idx: None,
+            reason: BlockCommitReason::EndOfBlock,
+        }
+    }
+
+    fn is_done(&self) -> bool {
+        self.last_txn_idx.is_some()
+    }
+
+    /// Processes the status of the next committed transaction, returns true if the block ends
+    /// with it. An abort also ends the block (execution then returns the error).
+    fn process<O: TransactionOutput, U>(
+        &mut self,
+        txn_idx: TxnIndex,
+        status: &ExecutionStatus<O, U>,
+    ) -> bool {
+        assert!(!self.is_done(), "Block already ended");
+
+        match status {
+            ExecutionStatus::Success(output) | ExecutionStatus::SkipRest(output) => {
+                self.accumulated_execution_gas = self
+                    .accumulated_execution_gas
+                    .saturating_add(output.execution_gas_used());
+                self.accumulated_storage_fee = self
+                    .accumulated_storage_fee
+                    .saturating_add(output.storage_fee_used());
+            },
+            ExecutionStatus::Abort(_) => {
+                self.last_txn_idx = Some(txn_idx);
+                return true;
+            },
+        }
+
+        let gas_limit_reached = self.maybe_gas_limit.map_or(false, |gas_limit| {
+            self.accumulated_execution_gas >= gas_limit
+        });
+        if matches!(status, ExecutionStatus::SkipRest(_)) {
+            self.reason = BlockCommitReason::SkipRest(txn_idx);
+        } else if gas_limit_reached {
+            self.reason = BlockCommitReason::BlockGasLimitReached(txn_idx);
+        } else {
+            return false;
+        }
+
+        self.last_txn_idx = Some(txn_idx);
+        true
+    }
+
+    /// Where the block ended and the number of transactions (from the start of the block) whose
+    /// outputs are kept, in the form of Scheduler::commit_boundary.
+    fn commit_boundary(&self, num_txns: usize) -> (BlockCommitReason, TxnIndex) {
+        (
+            self.reason,
+            self.last_txn_idx.map_or(num_txns, |idx| idx + 1),
+        )
+    }
+
+    /// Summarizes the block once it ended at the given commit boundary, given the final
+    /// incarnations of its transactions and the timings of the workers (all 0 and none for
+    /// sequential execution). Every speculative abort leads to the next incarnation.
+    fn summary(
+        &self,
+        (commit_reason, num_committed_txns): (BlockCommitReason, TxnIndex),
+        incarnations: &[Incarnation],
+        workers: &[WorkerTimings],
+    ) -> BlockExecutionSummary {
+        BlockExecutionSummary {
+            num_committed_txns,
+            execution_gas: self.accumulated_execution_gas,
+            storage_fee: self.accumulated_storage_fee,
+            speculative_aborts: incarnations.iter().map(|i| *i as u64).sum(),
+            max_incarnation: incarnations.iter().copied().max().unwrap_or(0),
+            committed_incarnations: incarnation_histogram(&incarnations[..num_committed_txns]),
+            dependency_waits: workers.iter().map(|worker| worker.dependency_waits).sum(),
+            dependency_wait: workers.iter().map(|worker| worker.dependency_wait).sum(),
+            validation_failures: workers
+                .iter()
+                .map(|worker| worker.validation_failures)
+                .sum(),
+            commit_reason,
+        }
+    }
+}
+
+/// Cancels the executions of a block executor from another thread, e.g. on shutdown.
+#[derive(Clone, Debug)]
+pub struct BlockExecutorHandle {
+    cancelled: Arc<AtomicBool>,
+}
+
+impl BlockExecutorHandle {
+    /// Cancels the execution in flight, if any, which then returns `Error::ExecutionCancelled`.
+    /// Transactions being executed run to completion, but no new tasks are started. The
+    /// cancellation holds until the next call to `execute_block`, which starts afresh.
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Release);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Acquire)
+    }
+}
+
+pub struct BlockExecutor<T: Transaction, E, S> {
     // number of active concurrent tasks, corresponding to the maximum number of rayon
     // threads that may be concurrently participating in parallel execution.
     concurrency_level: usize,
+    // once the gas used by the committed transactions reaches the limit, the rest of the
+    // block is skipped.
+    maybe_gas_limit: Option<u64>,
+    // optional cache of base view reads, shared across blocks and with prewarming.
+    base_view_cache: Option<Arc<BaseViewCache<T::Key>>>,
+    // whether base view reads go through a cache created for each block in parallel execution,
+    // when there is no cache shared across blocks. Disabled by default.
+    block_read_cache: bool,
+    // maximum number of speculative aborts logged with their conflict per block, 0 disables.
+    max_conflict_logs_per_block: usize,
+    // optional order to execute the transactions of a block in, in parallel execution.
+    ordering_hook: Option<Arc<dyn BlockOrderingHook<T>>>,
+    // pool the work of the executor runs on, including the asynchronous drops.
+    executor_pool: Arc<rayon::ThreadPool>,
+    // set once the execution in flight is cancelled through a handle, reset by execute_block.
+    cancelled: Arc<AtomicBool>,
+    // highest incarnation a transaction may be executed with before parallel execution gives up.
+    max_incarnations: Incarnation,
+    // number of transactions from the commit index whose validations are prioritized.
+    validation_priority_window: usize,
+    // whether the keys modified by the committed transactions are counted in the block metrics.
+    count_modified_keys: bool,
     phantom: PhantomData<(T, E, S)>,
 }
 
@@ -46,37 +261,187 @@ where
 {
     /// The caller needs to ensure that concurrency_level > 1 (0 is illegal and 1 should
     /// be handled by sequential execution) and that concurrency_level <= num_cpus.
-    pub fn new(concurrency_level: usize) -> Self {
+    pub fn new(concurrency_level: usize, maybe_gas_limit: Option<u64>) -> Self {
         assert!(
             concurrency_level > 0 && concurrency_level <= num_cpus::get(),
             "Parallel execution concurrency level {} should be between 1 and number of CPUs",
             concurrency_level
         );
+        Self::from_parts(
+            // Lowered if the executor pool would oversubscribe the CPUs, depending on the policy.
+            EXEC_POOL_SIZING.concurrency_level(concurrency_level),
+            maybe_gas_limit,
+            RAYON_EXEC_POOL.clone(),
+        )
+    }
+
+    /// Same as `new`, but the executor runs on the given pool instead of the pool shared by the
+    /// executors of the process, e.g. so that executors running side by side don't compete for
+    /// the same threads. The concurrency level is bounded by the threads of the pool rather than
+    /// by the CPUs.
+    pub fn new_with_pool(
+        concurrency_level: usize,
+        maybe_gas_limit: Option<u64>,
+        executor_pool: Arc<rayon::ThreadPool>,
+    ) -> Self {
+        assert!(
+            concurrency_level > 0 && concurrency_level <= executor_pool.current_num_threads(),
+            "Parallel execution concurrency level {} should be between 1 and number of threads of the pool",
+            concurrency_level
+        );
+        Self::from_parts(concurrency_level, maybe_gas_limit, executor_pool)
+    }
+
+    fn from_parts(
+        concurrency_level: usize,
+        maybe_gas_limit: Option<u64>,
+        executor_pool: Arc<rayon::ThreadPool>,
+    ) -> Self {
         Self {
             concurrency_level,
+            maybe_gas_limit,
+            base_view_cache: None,
+            block_read_cache: false,
+            max_conflict_logs_per_block: 0,
+            ordering_hook: None,
+            executor_pool,
+            cancelled: Arc::new(AtomicBool::new(false)),
+            max_incarnations: DEFAULT_MAX_INCARNATIONS,
+            validation_priority_window: DEFAULT_VALIDATION_PRIORITY_WINDOW,
+            count_modified_keys: false,
             phantom: PhantomData,
         }
     }
 
+    /// Logs the conflict that caused each speculative abort (key, writer and incarnations), up
+    /// to the given number of aborts per block. Meant for debugging contention.
+    pub fn with_conflict_logging(self, max_conflict_logs_per_block: usize) -> Self {
+        Self {
+            max_conflict_logs_per_block,
+            ..self
+        }
+    }
+
+    /// Whether the base view reads of the transactions of a block go through a cache dropped
+    /// with the block in parallel execution, so that keys read by many transactions are read
+    /// from storage once (disabled by default). Ignored with a base view cache shared across
+    /// blocks, which serves the same purpose.
+    pub fn with_block_read_cache(self, block_read_cache: bool) -> Self {
+        Self {
+            block_read_cache,
+            ..self
+        }
+    }
+
+    /// Bounds the incarnations of a transaction in parallel execution: once a transaction is
+    /// aborted at the given incarnation, the block is executed sequentially instead.
+    pub fn with_max_incarnations(self, max_incarnations: Incarnation) -> Self {
+        Self {
+            max_incarnations,
+            ..self
+        }
+    }
+
+    /// Prioritizes the validations of the given number of transactions from the commit index
+    /// over the validation wavefront in parallel execution, 0 disabling the prioritization.
+    pub fn with_validation_priority_window(self, validation_priority_window: usize) -> Self {
+        Self {
+            validation_priority_window,
+            ..self
+        }
+    }
+
+    /// Whether the keys modified by the committed transactions are counted in the metrics of a
+    /// block in parallel execution (disabled by default). Counting walks all the keys of the
+    /// block.
+    pub fn with_modified_key_count(self, count_modified_keys: bool) -> Self {
+        Self {
+            count_modified_keys,
+            ..self
+        }
+    }
+
+    /// Executes the transactions of each block in the order decided by the hook in parallel
+    /// execution, see `BlockOrderingHook` for what the order has to guarantee.
+    pub fn with_ordering_hook(self, ordering_hook: Arc<dyn BlockOrderingHook<T>>) -> Self {
+        Self {
+            ordering_hook: Some(ordering_hook),
+            ..self
+        }
+    }
+
+    /// Same as `new`, but base view reads go through the provided cache. The cache can be
+    /// shared across blocks (and executors), in which case the caller is responsible for
+    /// invalidating it whenever the base view changes.
+    pub fn new_with_base_view_cache(
+        concurrency_level: usize,
+        maybe_gas_limit: Option<u64>,
+        base_view_cache: Arc<BaseViewCache<T::Key>>,
+    ) -> Self {
+        Self {
+            base_view_cache: Some(base_view_cache),
+            ..Self::new(concurrency_level, maybe_gas_limit)
+        }
+    }
+
+    /// A handle to cancel the executions of this executor from another thread.
+    pub fn executor_handle(&self) -> BlockExecutorHandle {
+        BlockExecutorHandle {
+            cancelled: self.cancelled.clone(),
+        }
+    }
+
+    fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Acquire)
+    }
+
+    /// Asynchronously loads the given keys from the base view into the base view cache, e.g.
+    /// ahead of a block that is expected to contain an epoch change. No-op without a cache.
+    pub fn prewarm(&self, keys: Vec<T::Key>, base_view: Arc<S>)
+    where
+        S: Send + 'static,
+    {
+        if let Some(cache) = &self.base_view_cache {
+            let cache = cache.clone();
+            self.executor_pool
+                .spawn(move || cache.prewarm(&keys, base_view.as_ref()));
+        }
+    }
+
+    /// Invalidates the base view cache, to be called once the base view it was populated
+    /// from is no longer current.
+    pub fn invalidate_base_view_cache(&self) {
+        if let Some(cache) = &self.base_view_cache {
+            cache.invalidate();
+        }
+    }
+
     fn execute(
         &self,
         version: Version,
-        signature_verified_block: &[T],
+        signature_verified_block: &OrderedBlock<T>,
         last_input_output: &TxnLastInputOutput<T::Key, E::Output, E::Error>,
         versioned_data_cache: &MVHashMap<T::Key, T::Value>,
         scheduler: &Scheduler,
         executor: &E,
         base_view: &S,
+        base_view_cache: Option<&BaseViewCache<T::Key>>,
+        timings: &mut WorkerTimings,
     ) -> SchedulerTask {
         let _timer = TASK_EXECUTE_SECONDS.start_timer();
         let (idx_to_execute, incarnation) = version;
-        let txn = &signature_verified_block[idx_to_execute];
+        let txn = signature_verified_block.txn(idx_to_execute);
 
         let speculative_view = MVHashMapView::new(versioned_data_cache, scheduler);
 
         // VM execution.
         let execute_result = executor.execute_transaction(
-            &LatestView::<T, S>::new_mv_view(base_view, &speculative_view, idx_to_execute),
+            &LatestView::<T, S>::new_mv_view(
+                base_view,
+                base_view_cache,
+                &speculative_view,
+                idx_to_execute,
+            ),
             txn,
             idx_to_execute,
             false,
@@ -127,9 +492,15 @@ where
 
         // Remove entries from previous write/delta set that were not overwritten.
         for k in prev_modified_keys {
-            versioned_data_cache.delete(&k, idx_to_execute);
+            if versioned_data_cache.delete(&k, idx_to_execute) {
+                counters::MVHASHMAP_RECLAIMED_KEY_COUNT.inc();
+            }
         }
 
+        let (dependency_waits, dependency_wait) = speculative_view.dependency_waits();
+        timings.dependency_waits += dependency_waits;
+        timings.dependency_wait += dependency_wait;
+
         last_input_output.record(idx_to_execute, speculative_view.take_reads(), result);
         scheduler.finish_execution(idx_to_execute, incarnation, updates_outside)
     }
@@ -141,6 +512,9 @@ where
         last_input_output: &TxnLastInputOutput<T::Key, E::Output, E::Error>,
         versioned_data_cache: &MVHashMap<T::Key, T::Value>,
         scheduler: &Scheduler,
+        conflict_logger: &ConflictLogger,
+        incarnation_limit_reached: &AtomicBool,
+        timings: &mut WorkerTimings,
     ) -> SchedulerTask {
         use MVHashMapError::*;
         use MVHashMapOutput::*;
@@ -151,33 +525,54 @@ where
             .read_set(idx_to_validate)
             .expect("Prior read-set must be recorded");
 
-        let valid = read_set.iter().all(|r| {
-            match versioned_data_cache.read(r.path(), idx_to_validate) {
-                Ok(Version(version, _)) => r.validate_version(version),
-                Ok(Resolved(value)) => r.validate_resolved(value),
-                Err(Dependency(_)) => false, // Dependency implies a validation failure.
-                Err(Unresolved(delta)) => r.validate_unresolved(delta),
-                Err(NotFound) => r.validate_storage(),
+        // The first read that fails validation, along with the version that invalidated it
+        // if known (the incarnation of an estimate isn't).
+        let conflict = read_set.iter().find_map(|r| {
+            let (valid, writer) = match versioned_data_cache.read(r.path(), idx_to_validate) {
+                Ok(Version(version, _)) => (
+                    r.validate_version(version),
+                    Some((version.0, Some(version.1))),
+                ),
+                Ok(Resolved(value)) => (r.validate_resolved(value), None),
+                // Dependency implies a validation failure.
+                Err(Dependency(dep_idx)) => (false, Some((dep_idx, None))),
+                Err(Unresolved(delta)) => (r.validate_unresolved(delta), None),
+                Err(NotFound) => (r.validate_storage(), None),
                 // We successfully validate when read (again) results in a delta application
                 // failure. If the failure is speculative, a later validation will fail due to
                 // a read without this error. However, if the failure is real, passing
-                // validation here allows to avoid infinitely looping and instead panic when
-                // materializing deltas as writes in the final output preparation state. Panic
-                // is also preferrable as it allows testing for this scenario.
-                Err(DeltaApplicationFailure) => r.validate_delta_application_failure(),
-            }
+                // validation here allows to avoid infinitely looping and instead fail the block
+                // with DeltaMaterializationFailure when materializing deltas as writes in the
+                // final output preparation stage.
+                Err(DeltaApplicationFailure) => (r.validate_delta_application_failure(), None),
+            };
+            (!valid).then(|| (r.path(), writer))
         });
+        let valid = conflict.is_none();
+        if !valid {
+            timings.validation_failures += 1;
+        }
 
         let aborted = !valid && scheduler.try_abort(idx_to_validate, incarnation);
 
         if aborted {
             counters::SPECULATIVE_ABORT_COUNT.inc();
+            if let Some((key, writer)) = conflict {
+                conflict_logger.log(idx_to_validate, incarnation, key, writer);
+            }
 
             // Not valid and successfully aborted, mark the latest write/delta sets as estimates.
             for k in last_input_output.modified_keys(idx_to_validate) {
                 versioned_data_cache.mark_estimate(&k, idx_to_validate);
             }
 
+            if incarnation >= self.max_incarnations {
+                // Likely livelocked, the block is executed sequentially instead.
+                incarnation_limit_reached.store(true, Ordering::Relaxed);
+                scheduler.halt();
+                return SchedulerTask::NoTask;
+            }
+
             scheduler.finish_abort(idx_to_validate, incarnation)
         } else {
             scheduler.finish_validation(idx_to_validate, validation_wave);
@@ -188,29 +583,54 @@ where
     fn work_task_with_scope(
         &self,
         executor_arguments: &E::Argument,
-        block: &[T],
+        block: &OrderedBlock<T>,
         last_input_output: &TxnLastInputOutput<T::Key, E::Output, E::Error>,
         versioned_data_cache: &MVHashMap<T::Key, T::Value>,
         scheduler: &Scheduler,
         base_view: &S,
+        base_view_cache: Option<&BaseViewCache<T::Key>>,
+        block_cut: &Mutex<BlockCutTracker>,
+        conflict_logger: &ConflictLogger,
+        incarnation_limit_reached: &AtomicBool,
+        start_time: Instant,
         committing: bool,
-    ) {
+    ) -> WorkerTimings {
         // Make executor for each task. TODO: fast concurrent executor.
         let init_timer = VM_INIT_SECONDS.start_timer();
         let executor = E::init(*executor_arguments);
         drop(init_timer);
 
+        let mut timings = WorkerTimings::default();
         let mut scheduler_task = SchedulerTask::NoTask;
         loop {
             // Only one thread try_commit to avoid contention.
             if committing {
-                // Keep committing txns until there is no more that can be committed now.
-                loop {
-                    if scheduler.try_commit().is_none() {
+                // Keep committing txns until there is no more that can be committed now, or
+                // the block ends, in which case the scheduler is halted so that the txns after
+                // the end of the block are no longer executed or validated.
+                while let Some(txn_idx) = scheduler.try_commit() {
+                    if txn_idx == 0 {
+                        TIME_TO_FIRST_COMMIT_SECONDS.observe(start_time.elapsed().as_secs_f64());
+                    }
+                    let output = last_input_output
+                        .output(txn_idx)
+                        .expect("Output must be recorded before commit");
+                    let mut block_cut = block_cut.lock();
+                    if block_cut.process(txn_idx, output.as_ref()) {
+                        match block_cut.reason {
+                            // Aborted, the execution of the block fails.
+                            BlockCommitReason::EndOfBlock => scheduler.halt(),
+                            reason => scheduler.end_block(reason),
+                        }
                         break;
                     }
                 }
             }
+            let busy_since = matches!(
+                scheduler_task,
+                SchedulerTask::ValidationTask(..) | SchedulerTask::ExecutionTask(_, None)
+            )
+            .then(Instant::now);
             scheduler_task = match scheduler_task {
                 SchedulerTask::ValidationTask(version_to_validate, wave) => self.validate(
                     version_to_validate,
@@ -218,6 +638,9 @@ where
                     last_input_output,
                     versioned_data_cache,
                     scheduler,
+                    conflict_logger,
+                    incarnation_limit_reached,
+                    &mut timings,
                 ),
                 SchedulerTask::ExecutionTask(version_to_execute, None) => self.execute(
                     version_to_execute,
@@ -227,6 +650,8 @@ where
                     scheduler,
                     &executor,
                     base_view,
+                    base_view_cache,
+                    &mut timings,
                 ),
                 SchedulerTask::ExecutionTask(_, Some(condvar)) => {
                     let (lock, cvar) = &*condvar;
@@ -237,12 +662,23 @@ where
 
                     SchedulerTask::NoTask
                 },
-                SchedulerTask::NoTask => scheduler.next_task(committing),
+                SchedulerTask::NoTask => {
+                    if self.is_cancelled() {
+                        // The scheduler is done from then on, for all the workers.
+                        scheduler.end_block(BlockCommitReason::Cancelled);
+                    }
+                    scheduler.next_task(committing)
+                },
                 SchedulerTask::Done => {
+                    timings.done_at = start_time.elapsed();
                     break;
                 },
+            };
+            if let Some(busy_since) = busy_since {
+                timings.busy += busy_since.elapsed();
             }
         }
+        timings
     }
 
     pub(crate) fn execute_transactions_parallel(
@@ -250,50 +686,182 @@ where
         executor_initial_arguments: E::Argument,
         signature_verified_block: &Vec<T>,
         base_view: &S,
-    ) -> Result<Vec<(E::Output, Vec<(T::Key, WriteOp)>)>, E::Error> {
+    ) -> Result<
+        (
+            Vec<(E::Output, Vec<(T::Key, WriteOp)>)>,
+            BlockExecutionSummary,
+        ),
+        E::Error,
+    > {
+        self.execute_transactions_parallel_with_metrics(
+            executor_initial_arguments,
+            signature_verified_block,
+            base_view,
+        )
+        .map(|(outputs, summary, _)| (outputs, summary))
+    }
+
+    /// Same as `execute_transactions_parallel`, also returning the metrics of the block.
+    pub(crate) fn execute_transactions_parallel_with_metrics(
+        &self,
+        executor_initial_arguments: E::Argument,
+        signature_verified_block: &Vec<T>,
+        base_view: &S,
+    ) -> Result<
+        (
+            Vec<(E::Output, Vec<(T::Key, WriteOp)>)>,
+            BlockExecutionSummary,
+            BlockMetrics,
+        ),
+        E::Error,
+    > {
+        self.execute_transactions_parallel_in_order(
+            executor_initial_arguments,
+            signature_verified_block,
+            None,
+            base_view,
+        )
+    }
+
+    /// Executes the transactions in the given order, the outputs and metrics being returned in
+    /// the order of the block. The commit reason of the summary refers to positions in the
+    /// execution order.
+    pub(crate) fn execute_transactions_parallel_in_order(
+        &self,
+        executor_initial_arguments: E::Argument,
+        signature_verified_block: &[T],
+        execution_order: Option<&ExecutionOrder>,
+        base_view: &S,
+    ) -> Result<
+        (
+            Vec<(E::Output, Vec<(T::Key, WriteOp)>)>,
+            BlockExecutionSummary,
+            BlockMetrics,
+        ),
+        E::Error,
+    > {
         assert!(self.concurrency_level > 1, "Must use sequential execution");
+        let _timer = PARALLEL_EXECUTION_SECONDS.start_timer();
+        let start_time = Instant::now();
 
         let versioned_data_cache = MVHashMap::new();
 
         if signature_verified_block.is_empty() {
-            return Ok(vec![]);
+            return Ok((
+                vec![],
+                BlockCutTracker::new(self.maybe_gas_limit).summary(
+                    (BlockCommitReason::EndOfBlock, 0),
+                    &[],
+                    &[],
+                ),
+                BlockMetrics::default(),
+            ));
         }
 
         let num_txns = signature_verified_block.len();
         let last_input_output = TxnLastInputOutput::new(num_txns);
         let committing = AtomicBool::new(true);
-        let scheduler = Scheduler::new(num_txns);
+        let scheduler = Scheduler::new_with_validation_priority_window(
+            num_txns,
+            self.validation_priority_window,
+        );
+        let block_cut = Mutex::new(BlockCutTracker::new(self.maybe_gas_limit));
+        let conflict_logger = ConflictLogger::new(self.max_conflict_logs_per_block);
+        let incarnation_limit_reached = AtomicBool::new(false);
+        let worker_timings = Mutex::new(Vec::with_capacity(self.concurrency_level));
+        let ordered_block = OrderedBlock::new(signature_verified_block, execution_order);
+        // Never outlives the block, as the base view of the next block may differ.
+        let block_read_cache = (self.block_read_cache && self.base_view_cache.is_none())
+            .then(BaseViewCache::new_for_block);
+        let base_view_cache = self
+            .base_view_cache
+            .as_deref()
+            .or(block_read_cache.as_ref());
 
-        RAYON_EXEC_POOL.scope(|s| {
+        self.executor_pool.scope(|s| {
             for _ in 0..self.concurrency_level {
                 s.spawn(|_| {
-                    self.work_task_with_scope(
+                    let timings = self.work_task_with_scope(
                         &executor_initial_arguments,
-                        signature_verified_block,
+                        &ordered_block,
                         &last_input_output,
                         &versioned_data_cache,
                         &scheduler,
                         base_view,
+                        base_view_cache,
+                        &block_cut,
+                        &conflict_logger,
+                        &incarnation_limit_reached,
+                        start_time,
                         committing.swap(false, Ordering::SeqCst),
                     );
+                    worker_timings.lock().push(timings);
                 });
             }
         });
 
-        // TODO: for large block sizes and many cores, extract outputs in parallel.
+        if scheduler.commit_boundary().0 == BlockCommitReason::Cancelled {
+            self.executor_pool.spawn(move || {
+                // Explicit async drops.
+                drop(last_input_output);
+                drop(scheduler);
+                drop(block_read_cache);
+            });
+            return Err(Error::ExecutionCancelled);
+        }
+        if incarnation_limit_reached.into_inner() {
+            counters::INCARNATION_LIMIT_FALLBACK_COUNT.inc();
+            self.executor_pool.spawn(move || {
+                // Explicit async drops.
+                drop(last_input_output);
+                drop(scheduler);
+                drop(block_read_cache);
+            });
+            return Err(Error::TooManyIncarnations);
+        }
+
+        // The outputs are kept up to the end of the block as determined by the commit thread.
+        let block_cut = block_cut.into_inner();
+
+        let worker_timings = worker_timings.into_inner();
+        let incarnations = match execution_order {
+            Some(order) => order.to_block_order(scheduler.incarnations()),
+            None => scheduler.incarnations(),
+        };
+        // The scheduler stopped the commit index where the block ended.
+        let commit_boundary = scheduler.commit_boundary();
+        debug_assert_eq!(commit_boundary, block_cut.commit_boundary(num_txns));
+        let summary = block_cut.summary(commit_boundary, &incarnations, &worker_timings);
+        let num_txns_to_keep = summary.num_committed_txns;
+        if summary.commit_reason != BlockCommitReason::EndOfBlock {
+            counters::EARLY_HALT_COMMITTED_TXNS.set(num_txns_to_keep as i64);
+        }
+
+        let mut block_metrics = BlockMetrics::new(&worker_timings, &incarnations, &summary);
+        block_metrics.concurrency_level = self.concurrency_level;
+        block_metrics.exec_pool_threads = self.executor_pool.current_num_threads();
+        block_metrics.observe(&worker_timings);
+
         let mut final_results = Vec::with_capacity(num_txns);
 
         let maybe_err = if last_input_output.module_publishing_may_race() {
             counters::MODULE_PUBLISHING_FALLBACK_COUNT.inc();
             Some(Error::ModulePathReadWrite)
         } else {
+            // Outputs are taken concurrently, each index by a single task, and collected in
+            // version order. They are then processed in order, like in a sequential loop.
+            let outputs: Vec<_> = self.executor_pool.install(|| {
+                (0..num_txns_to_keep)
+                    .into_par_iter()
+                    .with_min_len(OUTPUT_EXTRACTION_MIN_CHUNK)
+                    .map(|idx| last_input_output.take_output(idx))
+                    .collect()
+            });
             let mut ret = None;
-            for idx in 0..num_txns {
-                match last_input_output.take_output(idx) {
-                    ExecutionStatus::Success(t) => final_results.push(t),
-                    ExecutionStatus::SkipRest(t) => {
-                        final_results.push(t);
-                        break;
+            for output in outputs {
+                match output {
+                    ExecutionStatus::Success(t) | ExecutionStatus::SkipRest(t) => {
+                        final_results.push(t)
                     },
                     ExecutionStatus::Abort(err) => {
                         ret = Some(err);
@@ -304,10 +872,11 @@ where
             ret
         };
 
-        RAYON_EXEC_POOL.spawn(move || {
+        self.executor_pool.spawn(move || {
             // Explicit async drops.
             drop(last_input_output);
             drop(scheduler);
+            drop(block_read_cache);
         });
 
         match maybe_err {
@@ -315,12 +884,33 @@ where
             None => {
                 final_results.resize_with(num_txns, E::Output::skip_output);
                 let delta_resolver: OutputDeltaResolver<T> =
-                    OutputDeltaResolver::new(versioned_data_cache);
-                // TODO: parallelize when necessary.
-                Ok(final_results
-                    .into_iter()
-                    .zip(delta_resolver.resolve(base_view, num_txns).into_iter())
-                    .collect())
+                    OutputDeltaResolver::new(versioned_data_cache, self.executor_pool.clone());
+                if self.count_modified_keys {
+                    block_metrics.modified_keys =
+                        num_txns_to_keep.checked_sub(1).map_or(0, |commit_idx| {
+                            delta_resolver.modified_key_count(commit_idx)
+                        });
+                }
+                // Skipped txns may have been executed, their deltas are not resolved.
+                let delta_writes =
+                    match delta_resolver.resolve(base_view, num_txns, num_txns_to_keep) {
+                        Ok(delta_writes) => delta_writes,
+                        Err((txn_idx, key)) => {
+                            return Err(Error::DeltaMaterializationFailure {
+                                txn_idx,
+                                key: key.key_debug(),
+                            })
+                        },
+                    };
+                let outputs: Vec<_> = final_results.into_iter().zip(delta_writes).collect();
+                Ok((
+                    match execution_order {
+                        Some(order) => order.to_block_order(outputs),
+                        None => outputs,
+                    },
+                    summary,
+                    block_metrics,
+                ))
             },
         }
     }
@@ -330,33 +920,81 @@ where
         executor_arguments: E::Argument,
         signature_verified_block: &[T],
         base_view: &S,
-    ) -> Result<Vec<(E::Output, Vec<(T::Key, WriteOp)>)>, E::Error> {
+    ) -> Result<
+        (
+            Vec<(E::Output, Vec<(T::Key, WriteOp)>)>,
+            BlockExecutionSummary,
+        ),
+        E::Error,
+    > {
         let num_txns = signature_verified_block.len();
         let executor = E::init(executor_arguments);
         let mut data_map = BTreeMap::new();
+        // Latest values of the aggregators updated via deltas. A key written by a transaction is
+        // removed from here, and a key updated via a delta is removed from data_map.
+        let mut aggregator_map = BTreeMap::new();
+        let mut block_cut = BlockCutTracker::new(self.maybe_gas_limit);
 
         let mut ret = Vec::with_capacity(num_txns);
         for (idx, txn) in signature_verified_block.iter().enumerate() {
+            if self.is_cancelled() {
+                return Err(Error::ExecutionCancelled);
+            }
             let res = executor.execute_transaction(
-                &LatestView::<T, S>::new_btree_view(base_view, &data_map, idx),
+                &LatestView::<T, S>::new_btree_view(
+                    base_view,
+                    self.base_view_cache.as_deref(),
+                    &data_map,
+                    &aggregator_map,
+                    idx,
+                ),
                 txn,
                 idx,
-                true,
+                false,
             );
 
-            let must_skip = matches!(res, ExecutionStatus::SkipRest(_));
+            let block_ended = block_cut.process(idx, &res);
 
             match res {
-                ExecutionStatus::Success(output) | ExecutionStatus::SkipRest(output) => {
-                    assert_eq!(
-                        output.get_deltas().len(),
-                        0,
-                        "Sequential execution must materialize deltas"
-                    );
+                ExecutionStatus::Success(mut output) | ExecutionStatus::SkipRest(mut output) => {
                     // Apply the writes.
                     for (ap, write_op) in output.get_writes().into_iter() {
+                        aggregator_map.remove(&ap);
                         data_map.insert(ap, write_op);
                     }
+
+                    // Apply the deltas right away, so that the following transactions read the
+                    // aggregator values, and materialize them as writes in the output.
+                    let deltas = output.get_deltas();
+                    if !deltas.is_empty() {
+                        let mut delta_writes = Vec::with_capacity(deltas.len());
+                        for (key, delta) in deltas {
+                            let latest_value = LatestView::<T, S>::new_btree_view(
+                                base_view,
+                                self.base_view_cache.as_deref(),
+                                &data_map,
+                                &aggregator_map,
+                                idx,
+                            )
+                            .get_state_value(&key)
+                            .map_err(|err| {
+                                Error::StorageReadFailure {
+                                    txn_idx: idx,
+                                    error: format!("{:?}", err),
+                                }
+                            })?;
+                            // Deleted, nothing to apply the delta to.
+                            let aggregator_value = latest_value
+                                .and_then(|bytes| delta.apply_to(deserialize(&bytes)).ok())
+                                .ok_or(Error::DeltaApplicationFailure(idx))?;
+
+                            data_map.remove(&key);
+                            aggregator_map.insert(key.clone(), aggregator_value);
+                            delta_writes
+                                .push((key, WriteOp::Modification(serialize(&aggregator_value))));
+                        }
+                        output.incorporate_delta_writes(delta_writes);
+                    }
                     ret.push(output);
                 },
                 ExecutionStatus::Abort(err) => {
@@ -365,13 +1003,61 @@ where
                 },
             }
 
-            if must_skip {
+            if block_ended {
                 break;
             }
         }
 
         ret.resize_with(num_txns, E::Output::skip_output);
-        Ok(ret.into_iter().map(|out| (out, vec![])).collect())
+        Ok((
+            ret.into_iter().map(|out| (out, vec![])).collect(),
+            block_cut.summary(block_cut.commit_boundary(num_txns), &vec![0; num_txns], &[]),
+        ))
+    }
+
+    /// Executes the block in the order decided by the ordering hook, if any. None if there's no
+    /// hook, the order is the order of the block already, or the results depend on the order,
+    /// in which case the block has to be executed in its order.
+    fn execute_transactions_parallel_ordered(
+        &self,
+        executor_arguments: E::Argument,
+        signature_verified_block: &[T],
+        base_view: &S,
+    ) -> Option<
+        Result<
+            (
+                Vec<(E::Output, Vec<(T::Key, WriteOp)>)>,
+                BlockExecutionSummary,
+            ),
+            E::Error,
+        >,
+    > {
+        let order = ExecutionOrder::new(
+            self.ordering_hook
+                .as_ref()?
+                .execution_order(signature_verified_block),
+            signature_verified_block.len(),
+        )?;
+        match self.execute_transactions_parallel_in_order(
+            executor_arguments,
+            signature_verified_block,
+            Some(&order),
+            base_view,
+        ) {
+            Ok((outputs, summary, _)) if summary.commit_reason == BlockCommitReason::EndOfBlock => {
+                Some(Ok((outputs, summary)))
+            },
+            // Falls back to sequential execution regardless of the order.
+            Err(Error::ModulePathReadWrite) => Some(Err(Error::ModulePathReadWrite)),
+            Err(Error::TooManyIncarnations) => Some(Err(Error::TooManyIncarnations)),
+            Err(Error::ExecutionCancelled) => Some(Err(Error::ExecutionCancelled)),
+            // Skipping the rest of the block, reaching the gas limit and failing all depend on
+            // the order.
+            Ok(_) | Err(_) => {
+                counters::ORDERED_EXECUTION_FALLBACK_COUNT.inc();
+                None
+            },
+        }
     }
 
     pub fn execute_block(
@@ -380,12 +1066,40 @@ where
         signature_verified_block: Vec<T>,
         base_view: &S,
     ) -> Result<Vec<(E::Output, Vec<(T::Key, WriteOp)>)>, E::Error> {
+        self.execute_block_with_summary(executor_arguments, signature_verified_block, base_view)
+            .map(|(outputs, _)| outputs)
+    }
+
+    /// Same as `execute_block`, also returning the summary of the execution of the block (by
+    /// the sequential fallback, if the block ended up executed sequentially).
+    pub fn execute_block_with_summary(
+        &self,
+        executor_arguments: E::Argument,
+        signature_verified_block: Vec<T>,
+        base_view: &S,
+    ) -> Result<
+        (
+            Vec<(E::Output, Vec<(T::Key, WriteOp)>)>,
+            BlockExecutionSummary,
+        ),
+        E::Error,
+    > {
+        // A cancellation only applies to the execution it was meant for.
+        self.cancelled.store(false, Ordering::Release);
+
         let mut ret = if self.concurrency_level > 1 {
-            self.execute_transactions_parallel(
+            self.execute_transactions_parallel_ordered(
                 executor_arguments,
                 &signature_verified_block,
                 base_view,
             )
+            .unwrap_or_else(|| {
+                self.execute_transactions_parallel(
+                    executor_arguments,
+                    &signature_verified_block,
+                    base_view,
+                )
+            })
         } else {
             self.execute_transactions_sequential(
                 executor_arguments,
@@ -394,8 +1108,13 @@ where
             )
         };
 
-        if matches!(ret, Err(Error::ModulePathReadWrite)) {
-            debug!("[Execution]: Module read & written, sequential fallback");
+        let fallback_reason = match &ret {
+            Err(Error::ModulePathReadWrite) => Some("Module read & written"),
+            Err(Error::TooManyIncarnations) => Some("Incarnation limit reached"),
+            _ => None,
+        };
+        if let Some(reason) = fallback_reason {
+            debug!("[Execution]: {}, sequential fallback", reason);
 
             ret = self.execute_transactions_sequential(
                 executor_arguments,
@@ -404,7 +1123,7 @@ where
             )
         }
 
-        RAYON_EXEC_POOL.spawn(move || {
+        self.executor_pool.spawn(move || {
             // Explicit async drops.
             drop(signature_verified_block);
         });
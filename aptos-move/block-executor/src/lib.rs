@@ -135,14 +135,23 @@ and threads that perform these tasks can already detect validation failures
 due to the ESTIMATE markers on memory locations, instead of waiting for a
 subsequent incarnation to finish.
 **/
+pub mod base_view_cache;
+pub mod block_metrics;
+pub mod conflict_log;
 pub mod counters;
 pub mod errors;
 pub mod executor;
+pub mod ordering;
 pub mod output_delta_resolver;
+pub mod pool_sizing;
 #[cfg(any(test, feature = "fuzzing"))]
 pub mod proptest_types;
 mod scheduler;
 pub mod task;
+#[cfg(any(test, feature = "fuzzing"))]
+pub mod test_kit;
+#[cfg(any(test, feature = "fuzzing"))]
+pub mod test_utils;
 mod txn_last_input_output;
 #[cfg(test)]
 mod unit_tests;
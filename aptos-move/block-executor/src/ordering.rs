@@ -0,0 +1,167 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::scheduler::TxnIndex;
+use aptos_types::account_address::AccountAddress;
+use std::collections::HashMap;
+
+/// Decides the order the transactions of a block are executed in by the parallel executor, e.g.
+/// to group the transactions that are likely to conflict and cut down on aborts. The outputs are
+/// still returned in the order of the block.
+///
+/// The order must keep the relative order of any two transactions that may conflict, the
+/// executor has no way to check it. Executions in another order that skip the rest of the
+/// block, reach the gas limit or fail are discarded and the block is executed in its order
+/// instead, as these depend on the order.
+pub trait BlockOrderingHook<T>: Send + Sync {
+    /// A permutation of the indices of the block, the transaction at `order[i]` being the i-th
+    /// one executed. Anything else is ignored, and the block executed in its order.
+    fn execution_order(&self, block: &[T]) -> Vec<TxnIndex>;
+}
+
+/// Groups the transactions of the same sender together, in the order their senders first
+/// appear in the block, keeping the order of the transactions of each sender. Transactions
+/// without a sender, e.g. block metadata, stay where they are and no transaction is moved
+/// across them.
+///
+/// Only transactions of the same sender are assumed to conflict. This does not hold in general:
+/// when a transaction writes the resources of another sender, e.g. a payment to an account that
+/// then spends the funds, the transactions of the recipient may be moved before it, and the
+/// outputs differ from the execution in the order of the block (which the executor can't
+/// detect). It is only safe for blocks whose transactions only write the resources of their
+/// sender.
+pub struct SenderGrouping<F> {
+    sender: F,
+}
+
+impl<F> SenderGrouping<F> {
+    pub fn new(sender: F) -> Self {
+        Self { sender }
+    }
+}
+
+impl<T, F> BlockOrderingHook<T> for SenderGrouping<F>
+where
+    F: Fn(&T) -> Option<AccountAddress> + Send + Sync,
+{
+    fn execution_order(&self, block: &[T]) -> Vec<TxnIndex> {
+        let mut order = Vec::with_capacity(block.len());
+        let mut groups: Vec<Vec<TxnIndex>> = vec![];
+        let mut group_by_sender: HashMap<AccountAddress, usize> = HashMap::new();
+        for (idx, txn) in block.iter().enumerate() {
+            match (self.sender)(txn) {
+                Some(sender) => {
+                    let group = *group_by_sender.entry(sender).or_insert_with(|| {
+                        groups.push(vec![]);
+                        groups.len() - 1
+                    });
+                    groups[group].push(idx);
+                },
+                None => {
+                    order.extend(groups.drain(..).flatten());
+                    group_by_sender.clear();
+                    order.push(idx);
+                },
+            }
+        }
+        order.extend(groups.into_iter().flatten());
+        order
+    }
+}
+
+/// Translates between the positions in the execution order and the indices in the block.
+pub(crate) struct ExecutionOrder {
+    order: Vec<TxnIndex>,
+}
+
+impl ExecutionOrder {
+    /// None if the order is not a permutation of the indices of the block, or is the order of
+    /// the block already.
+    pub(crate) fn new(order: Vec<TxnIndex>, num_txns: usize) -> Option<Self> {
+        if order.len() != num_txns || order.iter().enumerate().all(|(pos, idx)| pos == *idx) {
+            return None;
+        }
+        let mut seen = vec![false; num_txns];
+        for idx in &order {
+            if *idx >= num_txns || std::mem::replace(&mut seen[*idx], true) {
+                return None;
+            }
+        }
+        Some(Self { order })
+    }
+
+    /// Index in the block of the transaction executed at the position.
+    pub(crate) fn original_idx(&self, position: TxnIndex) -> TxnIndex {
+        self.order[position]
+    }
+
+    /// Reorders values by position in the execution order into the order of the block.
+    pub(crate) fn to_block_order<X>(&self, by_position: Vec<X>) -> Vec<X> {
+        let mut by_idx: Vec<_> = self.order.iter().copied().zip(by_position).collect();
+        by_idx.sort_unstable_by_key(|(idx, _)| *idx);
+        by_idx.into_iter().map(|(_, value)| value).collect()
+    }
+}
+
+/// The transactions of a block in execution order.
+pub(crate) struct OrderedBlock<'a, T> {
+    block: &'a [T],
+    order: Option<&'a ExecutionOrder>,
+}
+
+impl<'a, T> OrderedBlock<'a, T> {
+    pub(crate) fn new(block: &'a [T], order: Option<&'a ExecutionOrder>) -> Self {
+        Self { block, order }
+    }
+
+    /// The transaction executed at the position.
+    pub(crate) fn txn(&self, position: TxnIndex) -> &'a T {
+        match self.order {
+            Some(order) => &self.block[order.original_idx(position)],
+            None => &self.block[position],
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sender(byte: u8) -> Option<AccountAddress> {
+        Some(AccountAddress::new([byte; AccountAddress::LENGTH]))
+    }
+
+    #[test]
+    fn test_sender_grouping() {
+        let hook = SenderGrouping::new(|txn: &Option<AccountAddress>| *txn);
+        // Block metadata, then a, b, a, c, b, then a state checkpoint and b, a.
+        let block = vec![
+            None,
+            sender(1),
+            sender(2),
+            sender(1),
+            sender(3),
+            sender(2),
+            None,
+            sender(2),
+            sender(1),
+        ];
+        assert_eq!(hook.execution_order(&block), vec![0, 1, 3, 2, 5, 4, 6, 7, 8]);
+        assert!(hook.execution_order(&[]).is_empty());
+    }
+
+    #[test]
+    fn test_execution_order() {
+        assert!(ExecutionOrder::new(vec![0, 1, 2], 3).is_none());
+        assert!(ExecutionOrder::new(vec![1, 0], 3).is_none());
+        assert!(ExecutionOrder::new(vec![1, 1, 0], 3).is_none());
+        assert!(ExecutionOrder::new(vec![3, 1, 0], 3).is_none());
+
+        let order = ExecutionOrder::new(vec![2, 0, 1], 3).unwrap();
+        let block = ["a", "b", "c"];
+        let ordered = OrderedBlock::new(&block, Some(&order));
+        let executed: Vec<_> = (0..3).map(|position| *ordered.txn(position)).collect();
+        assert_eq!(executed, vec!["c", "a", "b"]);
+        assert_eq!(order.to_block_order(executed), block.to_vec());
+    }
+}
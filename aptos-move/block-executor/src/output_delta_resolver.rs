@@ -1,68 +1,194 @@
 // Copyright (c) Aptos
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::{executor::RAYON_EXEC_POOL, task::Transaction};
+use crate::task::Transaction;
 use aptos_aggregator::delta_change_set::{deserialize, serialize};
-use aptos_mvhashmap::{EntryCell, MVHashMap};
+use aptos_mvhashmap::{EntryCell, MVHashMap, TxnIndex};
 use aptos_state_view::TStateView;
 use aptos_types::write_set::{TransactionWrite, WriteOp};
+use rayon::prelude::*;
+use std::sync::Arc;
+
+/// Below this many keys with deltas, they are resolved on the calling thread.
+const PARALLEL_RESOLUTION_MIN_KEYS: usize = 8;
+
+/// The materialized deltas of a key, with the index of their transaction, or the index of the
+/// first transaction whose delta failed to apply.
+type ResolvedKey = Result<Vec<(TxnIndex, WriteOp)>, TxnIndex>;
 
 pub(crate) struct OutputDeltaResolver<T: Transaction> {
     versioned_outputs: MVHashMap<T::Key, T::Value>,
+    // Pool of the executor, which the outputs are dropped on once resolved.
+    executor_pool: Arc<rayon::ThreadPool>,
 }
 
 impl<T: Transaction> OutputDeltaResolver<T> {
-    pub fn new(versioned_outputs: MVHashMap<T::Key, T::Value>) -> Self {
-        Self { versioned_outputs }
+    pub fn new(
+        versioned_outputs: MVHashMap<T::Key, T::Value>,
+        executor_pool: Arc<rayon::ThreadPool>,
+    ) -> Self {
+        Self {
+            versioned_outputs,
+            executor_pool,
+        }
+    }
+
+    /// Number of keys written or updated via a delta by the transactions up to and including
+    /// 'commit_idx', i.e. the keys of the state delta of the committed prefix.
+    pub(crate) fn modified_key_count(&self, commit_idx: TxnIndex) -> usize {
+        self.versioned_outputs.num_modified_keys_up_to(commit_idx)
     }
 
     /// Takes Self, vector of all involved aggregator keys (each with at least one
     /// delta to resolve in the output), resolved values from storage for each key,
     /// and blocksize, and returns a Vec of materialized deltas per transaction index.
+    /// Only the deltas of the first 'num_committed' transactions are materialized. If one of
+    /// them can't be applied, i.e. it genuinely over/underflows (or the aggregator does not
+    /// exist), returns the earliest such transaction along with the key instead.
+    /// Keys are resolved on the executor pool when there are enough of them.
     pub(crate) fn resolve(
+        self,
+        base_view: &(impl TStateView<Key = T::Key> + Sync),
+        block_size: usize,
+        num_committed: usize,
+    ) -> Result<Vec<Vec<(T::Key, WriteOp)>>, (TxnIndex, T::Key)> {
+        let keys = self.versioned_outputs.aggregator_keys();
+        let resolved = if keys.len() < PARALLEL_RESOLUTION_MIN_KEYS {
+            self.resolve_keys_sequential(keys, base_view, num_committed)
+        } else {
+            self.resolve_keys_parallel(keys, base_view, num_committed)
+        };
+        self.materialize(resolved, block_size)
+    }
+
+    /// Same as `resolve`, one key after the other on the calling thread.
+    #[cfg(test)]
+    pub(crate) fn resolve_sequential(
         self,
         base_view: &impl TStateView<Key = T::Key>,
         block_size: usize,
-    ) -> Vec<Vec<(T::Key, WriteOp)>> {
-        let mut ret: Vec<Vec<(T::Key, WriteOp)>> = vec![vec![]; block_size];
+        num_committed: usize,
+    ) -> Result<Vec<Vec<(T::Key, WriteOp)>>, (TxnIndex, T::Key)> {
+        let keys = self.versioned_outputs.aggregator_keys();
+        let resolved = self.resolve_keys_sequential(keys, base_view, num_committed);
+        self.materialize(resolved, block_size)
+    }
+
+    /// Same as `resolve`, the keys being spread across the executor pool.
+    #[cfg(test)]
+    pub(crate) fn resolve_parallel(
+        self,
+        base_view: &(impl TStateView<Key = T::Key> + Sync),
+        block_size: usize,
+        num_committed: usize,
+    ) -> Result<Vec<Vec<(T::Key, WriteOp)>>, (TxnIndex, T::Key)> {
+        let keys = self.versioned_outputs.aggregator_keys();
+        let resolved = self.resolve_keys_parallel(keys, base_view, num_committed);
+        self.materialize(resolved, block_size)
+    }
 
-        // TODO: with more deltas, re-use executor threads and process in parallel.
-        for key in self.versioned_outputs.aggregator_keys() {
-            let mut latest_value: Option<u128> = base_view
-                .get_state_value(&key)
-                .ok() // Was anything found in storage
-                .and_then(|value| value.map(|bytes| deserialize(&bytes)));
-
-            let indexed_entries = self
-                .versioned_outputs
-                .entry_map_for_key(&key)
-                .expect("No entries found for the provided key");
-            for (idx, entry) in indexed_entries.iter() {
-                match &entry.cell {
-                    EntryCell::Write(_, data) => {
-                        latest_value = data.extract_raw_bytes().map(|bytes| deserialize(&bytes))
-                    },
-                    EntryCell::Delta(delta) => {
-                        // Apply to the latest value and store in outputs.
-                        let aggregator_value = delta
-                            .apply_to(
-                                latest_value
-                                    .expect("Failed to apply delta to (non-existent) aggregator"),
-                            )
-                            .expect("Failed to apply aggregator delta output");
-
-                        ret[*idx].push((
-                            key.clone(),
-                            WriteOp::Modification(serialize(&aggregator_value)),
-                        ));
-                        latest_value = Some(aggregator_value);
-                    },
-                }
+    fn resolve_keys_sequential(
+        &self,
+        keys: Vec<T::Key>,
+        base_view: &impl TStateView<Key = T::Key>,
+        num_committed: usize,
+    ) -> Vec<(T::Key, ResolvedKey)> {
+        keys.into_iter()
+            .map(|key| {
+                let resolved = self.resolve_key(&key, base_view, num_committed);
+                (key, resolved)
+            })
+            .collect()
+    }
+
+    /// The keys are independent, but the entries of each key are resolved in version order by
+    /// a single task. The results are collected in the order of the keys.
+    fn resolve_keys_parallel(
+        &self,
+        keys: Vec<T::Key>,
+        base_view: &(impl TStateView<Key = T::Key> + Sync),
+        num_committed: usize,
+    ) -> Vec<(T::Key, ResolvedKey)> {
+        self.executor_pool.install(|| {
+            keys.into_par_iter()
+                .map(|key| {
+                    let resolved = self.resolve_key(&key, base_view, num_committed);
+                    (key, resolved)
+                })
+                .collect()
+        })
+    }
+
+    /// Applies the deltas of the first 'num_committed' transactions to the key in version
+    /// order, starting from the value in storage, and taking the writes in between into
+    /// account.
+    fn resolve_key(
+        &self,
+        key: &T::Key,
+        base_view: &impl TStateView<Key = T::Key>,
+        num_committed: usize,
+    ) -> ResolvedKey {
+        let mut latest_value: Option<u128> = base_view
+            .get_state_value(key)
+            .ok() // Was anything found in storage
+            .and_then(|value| value.map(|bytes| deserialize(&bytes)));
+
+        let indexed_entries = self
+            .versioned_outputs
+            .entry_map_for_key(key)
+            .expect("No entries found for the provided key");
+        let mut writes = vec![];
+        for (idx, entry) in indexed_entries.range(..num_committed) {
+            match &entry.cell {
+                EntryCell::Write(_, data) => {
+                    latest_value = data.extract_raw_bytes().map(|bytes| deserialize(&bytes))
+                },
+                EntryCell::Delta(delta) => {
+                    // Apply to the latest value and store in outputs.
+                    let aggregator_value = latest_value
+                        .and_then(|value| delta.apply_to(value).ok())
+                        .ok_or(*idx)?;
+
+                    writes.push((*idx, WriteOp::Modification(serialize(&aggregator_value))));
+                    latest_value = Some(aggregator_value);
+                },
             }
         }
+        Ok(writes)
+    }
 
-        RAYON_EXEC_POOL.spawn(move || drop(self));
+    /// Assembles the writes of the resolved keys per transaction, in the order of the keys.
+    fn materialize(
+        self,
+        resolved: Vec<(T::Key, ResolvedKey)>,
+        block_size: usize,
+    ) -> Result<Vec<Vec<(T::Key, WriteOp)>>, (TxnIndex, T::Key)> {
+        let mut ret: Vec<Vec<(T::Key, WriteOp)>> = vec![vec![]; block_size];
+        let mut failure: Option<(TxnIndex, T::Key)> = None;
+        for (key, resolved) in resolved {
+            match resolved {
+                Ok(writes) => {
+                    for (idx, write) in writes {
+                        ret[idx].push((key.clone(), write));
+                    }
+                },
+                Err(idx) => {
+                    if failure
+                        .as_ref()
+                        .map_or(true, |(fail_idx, _)| idx < *fail_idx)
+                    {
+                        failure = Some((idx, key));
+                    }
+                },
+            }
+        }
+
+        let executor_pool = self.executor_pool.clone();
+        executor_pool.spawn(move || drop(self));
 
-        ret
+        match failure {
+            Some(failure) => Err(failure),
+            None => Ok(ret),
+        }
     }
 }
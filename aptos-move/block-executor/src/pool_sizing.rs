@@ -0,0 +1,202 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use aptos_logger::warn;
+use once_cell::sync::{Lazy, OnceCell};
+
+static OVERSUBSCRIPTION_POLICY: OnceCell<OversubscriptionPolicy> = OnceCell::new();
+static GLOBAL_POOL_THREADS: OnceCell<usize> = OnceCell::new();
+
+/// Sizing of the executor pool, decided once when it is first used.
+pub static EXEC_POOL_SIZING: Lazy<PoolSizing> = Lazy::new(|| {
+    let sizing = PoolSizing::new(
+        num_cpus::get(),
+        num_cpus::get(),
+        global_pool_threads(),
+        OVERSUBSCRIPTION_POLICY.get().copied().unwrap_or_default(),
+    );
+    if sizing.is_oversubscribed() {
+        warn!(
+            num_cpus = sizing.num_cpus,
+            global_pool_threads = sizing.global_pool_threads,
+            exec_pool_threads = sizing.exec_pool_threads,
+            max_concurrency_level = sizing.max_concurrency_level,
+            policy = ?sizing.policy,
+            "[Execution]: The executor pool and rayon's global pool have more threads than CPUs"
+        );
+    }
+    sizing
+});
+
+/// Sets how the executor pool is sized when it and rayon's global pool together have more
+/// threads than CPUs. Only the first call succeeds, and only if it happens before the executor
+/// pool is first used.
+pub fn set_oversubscription_policy_once(policy: OversubscriptionPolicy) {
+    OVERSUBSCRIPTION_POLICY.set(policy).ok();
+}
+
+/// Sets the number of threads of rayon's global pool, for processes that use it next to the
+/// executor pool. Only the first call succeeds, and only if it happens before the executor pool
+/// is first used. Without it, the global pool is probed, which starts it if it wasn't already.
+pub fn set_global_pool_threads_once(num_threads: usize) {
+    GLOBAL_POOL_THREADS.set(num_threads).ok();
+}
+
+/// The number of threads of rayon's global pool, as set, or as rayon reports it otherwise.
+fn global_pool_threads() -> usize {
+    GLOBAL_POOL_THREADS
+        .get()
+        .copied()
+        .unwrap_or_else(rayon::current_num_threads)
+}
+
+/// What to do when the executor pool and rayon's global pool together have more threads than
+/// CPUs. The executor is then considered to have the CPUs not taken by the global pool, but
+/// never less than half of them, as the global pool is mostly idle during block execution.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum OversubscriptionPolicy {
+    /// Only log a warning, the executor pool gets a thread per CPU.
+    #[default]
+    Warn,
+    /// Create the executor pool with only the CPUs available to the executor, which also bounds
+    /// the concurrency level.
+    ShrinkPool,
+    /// Keep a thread per CPU in the executor pool, but bound the concurrency level by the CPUs
+    /// available to the executor.
+    LowerConcurrency,
+}
+
+/// The effective sizes of the executor pool and of the parallel execution.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PoolSizing {
+    pub num_cpus: usize,
+    /// Number of threads of the executor pool, if not shrunk.
+    pub requested_exec_pool_threads: usize,
+    pub global_pool_threads: usize,
+    pub policy: OversubscriptionPolicy,
+    /// Number of threads of the executor pool.
+    pub exec_pool_threads: usize,
+    /// Bound on the concurrency level of the parallel executor.
+    pub max_concurrency_level: usize,
+}
+
+impl PoolSizing {
+    pub fn new(
+        num_cpus: usize,
+        requested_exec_pool_threads: usize,
+        global_pool_threads: usize,
+        policy: OversubscriptionPolicy,
+    ) -> Self {
+        let requested = requested_exec_pool_threads;
+        let available = num_cpus
+            .saturating_sub(global_pool_threads)
+            .max(num_cpus / 2)
+            .max(1)
+            .min(requested);
+        let oversubscribed = Self::oversubscribes(num_cpus, requested, global_pool_threads);
+        let (exec_pool_threads, max_concurrency_level) = match policy {
+            _ if !oversubscribed => (requested, requested),
+            OversubscriptionPolicy::Warn => (requested, requested),
+            OversubscriptionPolicy::ShrinkPool => (available, available),
+            OversubscriptionPolicy::LowerConcurrency => (requested, available),
+        };
+        Self {
+            num_cpus,
+            requested_exec_pool_threads,
+            global_pool_threads,
+            policy,
+            exec_pool_threads,
+            max_concurrency_level,
+        }
+    }
+
+    /// Whether the requested executor pool would oversubscribe the CPUs.
+    pub fn is_oversubscribed(&self) -> bool {
+        Self::oversubscribes(
+            self.num_cpus,
+            self.requested_exec_pool_threads,
+            self.global_pool_threads,
+        )
+    }
+
+    fn oversubscribes(
+        num_cpus: usize,
+        exec_pool_threads: usize,
+        global_pool_threads: usize,
+    ) -> bool {
+        exec_pool_threads + global_pool_threads > num_cpus
+    }
+
+    /// The requested concurrency level, lowered to the bound if needed.
+    pub fn concurrency_level(&self, concurrency_level: usize) -> usize {
+        concurrency_level.min(self.max_concurrency_level)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unset_global_pool_threads() {
+        // Nothing in the tests sets the threads of the global pool, so rayon is asked.
+        assert_eq!(global_pool_threads(), rayon::current_num_threads());
+    }
+
+    #[test]
+    fn test_oversubscribed_sizing() {
+        let num_cpus = 16;
+        // Another component uses a pool with a thread per CPU, like rayon's default.
+        let sizing = |policy| PoolSizing::new(num_cpus, num_cpus, num_cpus, policy);
+
+        let warn = sizing(OversubscriptionPolicy::Warn);
+        assert!(warn.is_oversubscribed());
+        assert_eq!(warn.exec_pool_threads, 16);
+        assert_eq!(warn.concurrency_level(16), 16);
+
+        // The executor is considered to have at least half of the CPUs.
+        let shrink = sizing(OversubscriptionPolicy::ShrinkPool);
+        assert_eq!(shrink.exec_pool_threads, 8);
+        assert_eq!(shrink.concurrency_level(16), 8);
+        assert_eq!(shrink.concurrency_level(4), 4);
+
+        let lower = sizing(OversubscriptionPolicy::LowerConcurrency);
+        assert_eq!(lower.exec_pool_threads, 16);
+        assert_eq!(lower.concurrency_level(16), 8);
+    }
+
+    #[test]
+    fn test_small_global_pool() {
+        // The executor gets the CPUs the global pool doesn't take.
+        let sizing = PoolSizing::new(16, 16, 4, OversubscriptionPolicy::ShrinkPool);
+        assert!(sizing.is_oversubscribed());
+        assert_eq!(sizing.exec_pool_threads, 12);
+        assert_eq!(sizing.concurrency_level(16), 12);
+
+        // A single CPU is never shrunk below a thread.
+        let sizing = PoolSizing::new(1, 1, 1, OversubscriptionPolicy::ShrinkPool);
+        assert_eq!(sizing.exec_pool_threads, 1);
+        assert_eq!(sizing.concurrency_level(1), 1);
+    }
+
+    #[test]
+    fn test_not_oversubscribed() {
+        for policy in [
+            OversubscriptionPolicy::Warn,
+            OversubscriptionPolicy::ShrinkPool,
+            OversubscriptionPolicy::LowerConcurrency,
+        ] {
+            // The global pool isn't used.
+            let sizing = PoolSizing::new(8, 8, 0, policy);
+            assert!(!sizing.is_oversubscribed());
+            assert_eq!(sizing.exec_pool_threads, 8);
+            assert_eq!(sizing.concurrency_level(8), 8);
+
+            // The two pools together fit in the CPUs.
+            let sizing = PoolSizing::new(16, 12, 4, policy);
+            assert!(!sizing.is_oversubscribed());
+            assert_eq!(sizing.exec_pool_threads, 12);
+            assert_eq!(sizing.concurrency_level(16), 12);
+        }
+    }
+}
@@ -116,9 +116,9 @@ where
             Transaction<KeyType<K>, ValueType<V>>,
             Task<KeyType<K>, ValueType<V>>,
             EmptyDataView<KeyType<K>, ValueType<V>>,
-        >::new(num_cpus::get())
+        >::new(num_cpus::get(), None)
         .execute_transactions_parallel((), &self.transactions, &data_view)
-        .map(|zipped| zipped.into_iter().map(|(res, _)| res).collect());
+        .map(|(zipped, _)| zipped.into_iter().map(|(res, _)| res).collect());
 
         self.expected_output.assert_output(&output);
     }
@@ -5,9 +5,16 @@ use crate::{
     errors::Error,
     executor::BlockExecutor,
     proptest_types::types::{
-        DeltaDataView, EmptyDataView, ExpectedOutput, KeyType, Task, Transaction, TransactionGen,
-        TransactionGenParams, ValueType,
+        DeltaDataView, EmptyDataView, ExpectedOutput, KeyType, Output, Task, Transaction,
+        TransactionGen, TransactionGenParams, ValueType, STORAGE_AGGREGATOR_VALUE,
     },
+    task::{KeyDebug, ModulePath, TransactionOutput},
+};
+use aptos_aggregator::delta_change_set::{delta_add, delta_sub};
+use aptos_state_view::{StateViewId, TStateView};
+use aptos_types::{
+    state_store::state_storage_usage::StateStorageUsage,
+    write_set::{TransactionWrite, WriteOp},
 };
 use claims::assert_ok;
 use num_cpus;
@@ -18,7 +25,13 @@ use proptest::{
     strategy::{Strategy, ValueTree},
     test_runner::TestRunner,
 };
-use std::{fmt::Debug, hash::Hash, marker::PhantomData};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt::Debug,
+    hash::Hash,
+    marker::PhantomData,
+    sync::{atomic::AtomicUsize, Arc},
+};
 
 fn run_transactions<K, V>(
     key_universe: &[K],
@@ -54,9 +67,9 @@ fn run_transactions<K, V>(
             Transaction<KeyType<K>, ValueType<V>>,
             Task<KeyType<K>, ValueType<V>>,
             EmptyDataView<KeyType<K>, ValueType<V>>,
-        >::new(num_cpus::get())
+        >::new(num_cpus::get(), None)
         .execute_transactions_parallel((), &transactions, &data_view)
-        .map(|zipped| zipped.into_iter().map(|(res, _)| res).collect());
+        .map(|(zipped, _)| zipped.into_iter().map(|(res, _)| res).collect());
 
         if module_access.0 && module_access.1 {
             assert_eq!(output.unwrap_err(), Error::ModulePathReadWrite);
@@ -180,9 +193,9 @@ fn deltas_writes_mixed() {
             Transaction<KeyType<[u8; 32]>, ValueType<[u8; 32]>>,
             Task<KeyType<[u8; 32]>, ValueType<[u8; 32]>>,
             DeltaDataView<KeyType<[u8; 32]>, ValueType<[u8; 32]>>,
-        >::new(num_cpus::get())
+        >::new(num_cpus::get(), None)
         .execute_transactions_parallel((), &transactions, &data_view)
-        .map(|zipped| zipped.into_iter().map(|(res, _)| res).collect());
+        .map(|(zipped, _)| zipped.into_iter().map(|(res, _)| res).collect());
 
         let baseline = ExpectedOutput::generate_baseline(&transactions, None);
         baseline.assert_output(&output);
@@ -221,9 +234,10 @@ fn deltas_resolver() {
             Transaction<KeyType<[u8; 32]>, ValueType<[u8; 32]>>,
             Task<KeyType<[u8; 32]>, ValueType<[u8; 32]>>,
             DeltaDataView<KeyType<[u8; 32]>, ValueType<[u8; 32]>>,
-        >::new(num_cpus::get())
+        >::new(num_cpus::get(), None)
         .execute_transactions_parallel((), &transactions, &data_view)
         .unwrap()
+        .0
         .into_iter()
         .unzip();
 
@@ -232,6 +246,227 @@ fn deltas_resolver() {
     }
 }
 
+/// Applies the writes and the materialized deltas of the outputs in order.
+fn final_state<K, V>(
+    outputs: Vec<(Output<K, V>, Vec<(K, WriteOp)>)>,
+) -> BTreeMap<K, Option<Vec<u8>>>
+where
+    K: PartialOrd + Ord + Send + Sync + Clone + Hash + Eq + ModulePath + KeyDebug + 'static,
+    V: Send + Sync + Debug + Clone + TransactionWrite + 'static,
+{
+    let mut state = BTreeMap::new();
+    for (output, delta_writes) in outputs {
+        for (k, v) in output.get_writes() {
+            state.insert(k, v.extract_raw_bytes());
+        }
+        for (k, write_op) in output
+            .incorporated_delta_writes()
+            .iter()
+            .cloned()
+            .chain(delta_writes)
+        {
+            state.insert(k, write_op.extract_raw_bytes());
+        }
+    }
+    state
+}
+
+#[test]
+fn deltas_sequential_matches_parallel() {
+    let mut runner = TestRunner::default();
+    let num_txns = 1000;
+
+    let universe = vec(any::<[u8; 32]>(), 50)
+        .new_tree(&mut runner)
+        .expect("creating a new value should succeed")
+        .current();
+    // Static transactions, so that both modes execute the same writes and deltas.
+    let transaction_gen = vec(
+        any_with::<TransactionGen<[u8; 32]>>(TransactionGenParams::default()),
+        num_txns,
+    )
+    .new_tree(&mut runner)
+    .expect("creating a new value should succeed")
+    .current();
+
+    // Most of the keys are aggregators, no deletes as the resolver can't apply deltas to them.
+    let transactions: Vec<_> = transaction_gen
+        .into_iter()
+        .map(|txn_gen| txn_gen.materialize_with_deltas(&universe, 5, false))
+        .collect();
+
+    let data_view = DeltaDataView::<KeyType<[u8; 32]>, ValueType<[u8; 32]>> {
+        phantom: PhantomData,
+    };
+    let executor = BlockExecutor::<
+        Transaction<KeyType<[u8; 32]>, ValueType<[u8; 32]>>,
+        Task<KeyType<[u8; 32]>, ValueType<[u8; 32]>>,
+        DeltaDataView<KeyType<[u8; 32]>, ValueType<[u8; 32]>>,
+    >::new(num_cpus::get(), None);
+
+    let sequential = executor
+        .execute_transactions_sequential((), &transactions, &data_view)
+        .map(|(zipped, _)| zipped);
+    let baseline = ExpectedOutput::generate_baseline(&transactions, None);
+    if let ExpectedOutput::DeltaFailure(fail_idx, _) = baseline {
+        assert_eq!(
+            sequential.unwrap_err(),
+            Error::DeltaApplicationFailure(fail_idx)
+        );
+        return;
+    }
+    let (outputs, delta_writes): (Vec<_>, Vec<_>) = sequential.unwrap().into_iter().unzip();
+    assert!(outputs.iter().all(|output| output.get_deltas().is_empty()));
+    assert!(delta_writes.iter().all(Vec::is_empty));
+    // The reads of the transactions must observe the deltas applied by earlier ones.
+    let outputs = Ok(outputs);
+    baseline.assert_output(&outputs);
+    let sequential = outputs.unwrap().into_iter().zip(delta_writes).collect();
+
+    let parallel = executor
+        .execute_transactions_parallel((), &transactions, &data_view)
+        .unwrap()
+        .0;
+    assert_eq!(final_state(sequential), final_state(parallel));
+}
+
+#[test]
+fn modified_keys_match_committed_outputs() {
+    let mut runner = TestRunner::default();
+    let num_txns = 1000;
+
+    let data_view = DeltaDataView::<KeyType<[u8; 32]>, ValueType<[u8; 32]>> {
+        phantom: PhantomData,
+    };
+    let executor = BlockExecutor::<
+        Transaction<KeyType<[u8; 32]>, ValueType<[u8; 32]>>,
+        Task<KeyType<[u8; 32]>, ValueType<[u8; 32]>>,
+        DeltaDataView<KeyType<[u8; 32]>, ValueType<[u8; 32]>>,
+    >::new(num_cpus::get(), None)
+    .with_modified_key_count(true);
+
+    for _ in 0..10 {
+        let universe = vec(any::<[u8; 32]>(), 50)
+            .new_tree(&mut runner)
+            .expect("creating a new value should succeed")
+            .current();
+        // Dynamic transactions, so that re-executions delete the entries of previous incarnations.
+        let transaction_gen = vec(
+            any_with::<TransactionGen<[u8; 32]>>(TransactionGenParams::new_dynamic()),
+            num_txns,
+        )
+        .new_tree(&mut runner)
+        .expect("creating a new value should succeed")
+        .current();
+        let skip_rest_idx = any::<Index>()
+            .new_tree(&mut runner)
+            .expect("creating a new value should succeed")
+            .current()
+            .index(num_txns);
+
+        let mut transactions: Vec<_> = transaction_gen
+            .into_iter()
+            .map(|txn_gen| txn_gen.materialize_with_deltas(&universe, 15, false))
+            .collect();
+        // The transactions after it are executed speculatively, but not committed.
+        transactions[skip_rest_idx] = Transaction::SkipRest;
+
+        let (outputs, _, metrics) = executor
+            .execute_transactions_parallel_with_metrics((), &transactions, &data_view)
+            .unwrap();
+        let committed_keys: BTreeSet<_> = outputs[..=skip_rest_idx]
+            .iter()
+            .flat_map(|(output, _)| {
+                output
+                    .get_writes()
+                    .into_iter()
+                    .map(|(k, _)| k)
+                    .chain(output.get_deltas().into_iter().map(|(k, _)| k))
+            })
+            .collect();
+        assert_eq!(metrics.modified_keys, committed_keys.len());
+    }
+}
+
+#[test]
+fn sequential_delta_application_failure() {
+    let key = KeyType([0u8; 32], false);
+    let transactions = vec![
+        Transaction::Write {
+            incarnation: Arc::new(AtomicUsize::new(0)),
+            writes_and_deltas: vec![(vec![], vec![(key, delta_add(5, u128::MAX))])],
+            reads: vec![vec![key]],
+        },
+        Transaction::Write {
+            incarnation: Arc::new(AtomicUsize::new(0)),
+            // Underflows, the aggregator holds STORAGE_AGGREGATOR_VALUE + 5.
+            writes_and_deltas: vec![(
+                vec![],
+                vec![(key, delta_sub(STORAGE_AGGREGATOR_VALUE + 6, u128::MAX))],
+            )],
+            reads: vec![vec![key]],
+        },
+    ];
+    let data_view = DeltaDataView::<KeyType<[u8; 32]>, ValueType<[u8; 32]>> {
+        phantom: PhantomData,
+    };
+
+    let output = BlockExecutor::<
+        Transaction<KeyType<[u8; 32]>, ValueType<[u8; 32]>>,
+        Task<KeyType<[u8; 32]>, ValueType<[u8; 32]>>,
+        DeltaDataView<KeyType<[u8; 32]>, ValueType<[u8; 32]>>,
+    >::new(1, None)
+    .execute_transactions_sequential((), &transactions, &data_view);
+    assert_eq!(output.unwrap_err(), Error::DeltaApplicationFailure(1));
+}
+
+/// Fails every read from storage.
+struct FailingDataView;
+
+impl TStateView for FailingDataView {
+    type Key = KeyType<[u8; 32]>;
+
+    fn get_state_value(&self, _: &Self::Key) -> anyhow::Result<Option<Vec<u8>>> {
+        anyhow::bail!("storage unavailable")
+    }
+
+    fn id(&self) -> StateViewId {
+        StateViewId::Miscellaneous
+    }
+
+    fn is_genesis(&self) -> bool {
+        unreachable!();
+    }
+
+    fn get_usage(&self) -> anyhow::Result<StateStorageUsage> {
+        unreachable!();
+    }
+}
+
+#[test]
+fn sequential_delta_storage_read_failure() {
+    // The delta is applied to the value in storage, which fails to be read.
+    let transactions = vec![Transaction::Write {
+        incarnation: Arc::new(AtomicUsize::new(0)),
+        writes_and_deltas: vec![(
+            vec![],
+            vec![(KeyType([0u8; 32], false), delta_add(5, u128::MAX))],
+        )],
+        reads: vec![vec![]],
+    }];
+
+    let output = BlockExecutor::<
+        Transaction<KeyType<[u8; 32]>, ValueType<[u8; 32]>>,
+        Task<KeyType<[u8; 32]>, ValueType<[u8; 32]>>,
+        FailingDataView,
+    >::new(1, None)
+    .execute_transactions_sequential((), &transactions, &FailingDataView);
+    assert!(matches!(
+        output.unwrap_err(),
+        Error::StorageReadFailure { txn_idx: 0, .. }
+    ));
+}
+
 #[test]
 fn dynamic_read_writes_contended() {
     let mut runner = TestRunner::default();
@@ -358,7 +593,7 @@ fn publishing_fixed_params() {
         Transaction<KeyType<[u8; 32]>, ValueType<[u8; 32]>>,
         Task<KeyType<[u8; 32]>, ValueType<[u8; 32]>>,
         DeltaDataView<KeyType<[u8; 32]>, ValueType<[u8; 32]>>,
-    >::new(num_cpus::get())
+    >::new(num_cpus::get(), None)
     .execute_transactions_parallel((), &transactions, &data_view);
     assert_ok!(output);
 
@@ -394,7 +629,7 @@ fn publishing_fixed_params() {
             Transaction<KeyType<[u8; 32]>, ValueType<[u8; 32]>>,
             Task<KeyType<[u8; 32]>, ValueType<[u8; 32]>>,
             DeltaDataView<KeyType<[u8; 32]>, ValueType<[u8; 32]>>,
-        >::new(num_cpus::get())
+        >::new(num_cpus::get(), None)
         .execute_transactions_parallel((), &transactions, &data_view);
 
         assert_eq!(output.unwrap_err(), Error::ModulePathReadWrite);
@@ -5,7 +5,7 @@ use crate::{
     errors::{Error, Result},
     scheduler::TxnIndex,
     task::{
-        ExecutionStatus, ExecutorTask, ModulePath, Transaction as TransactionType,
+        ExecutionStatus, ExecutorTask, KeyDebug, ModulePath, Transaction as TransactionType,
         TransactionOutput,
     },
 };
@@ -39,10 +39,18 @@ use std::{
 // most 100 in the tests.
 pub(crate) const STORAGE_AGGREGATOR_VALUE: u128 = 100001;
 
-pub(crate) struct DeltaDataView<K, V> {
+pub struct DeltaDataView<K, V> {
     pub(crate) phantom: PhantomData<(K, V)>,
 }
 
+impl<K, V> Default for DeltaDataView<K, V> {
+    fn default() -> Self {
+        Self {
+            phantom: PhantomData,
+        }
+    }
+}
+
 impl<K, V> TStateView for DeltaDataView<K, V>
 where
     K: PartialOrd + Ord + Send + Sync + Clone + Hash + Eq + ModulePath + 'static,
@@ -132,6 +140,12 @@ impl<K: Hash + Clone + Debug + Eq + PartialOrd + Ord> ModulePath for KeyType<K>
     }
 }
 
+impl<K: Hash + Clone + Debug + Eq + PartialOrd + Ord> KeyDebug for KeyType<K> {
+    fn key_debug(&self) -> String {
+        format!("{:?}", self.0)
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Arbitrary)]
 pub struct ValueType<V: Into<Vec<u8>> + Debug + Clone + Eq + Arbitrary>(
     /// Wrapping the types used for testing to add TransactionWrite trait implementation (below).
@@ -382,7 +396,7 @@ impl<V: Into<Vec<u8>> + Arbitrary + Clone + Debug + Eq + Sync + Send> Transactio
 
 impl<K, V> TransactionType for Transaction<K, V>
 where
-    K: PartialOrd + Ord + Send + Sync + Clone + Hash + Eq + ModulePath + 'static,
+    K: PartialOrd + Ord + Send + Sync + Clone + Hash + Eq + ModulePath + KeyDebug + 'static,
     V: Debug + Send + Sync + Debug + Clone + TransactionWrite + 'static,
 {
     type Key = K;
@@ -404,7 +418,7 @@ impl<K, V> Task<K, V> {
 
 impl<K, V> ExecutorTask for Task<K, V>
 where
-    K: PartialOrd + Ord + Send + Sync + Clone + Hash + Eq + ModulePath + 'static,
+    K: PartialOrd + Ord + Send + Sync + Clone + Hash + Eq + ModulePath + KeyDebug + 'static,
     V: Send + Sync + Debug + Clone + TransactionWrite + 'static,
 {
     type Argument = ();
@@ -447,20 +461,45 @@ where
                     writes_and_deltas[write_idx].0.clone(),
                     writes_and_deltas[write_idx].1.clone(),
                     reads_result,
+                    1,
+                    vec![],
                 ))
             },
-            Transaction::SkipRest => ExecutionStatus::SkipRest(Output(vec![], vec![], vec![])),
+            Transaction::SkipRest => {
+                ExecutionStatus::SkipRest(Output(vec![], vec![], vec![], 1, vec![]))
+            },
             Transaction::Abort => ExecutionStatus::Abort(txn_idx),
         }
     }
 }
 
-#[derive(Debug)]
-pub struct Output<K, V>(Vec<(K, V)>, Vec<(K, DeltaOp)>, Vec<Option<Vec<u8>>>);
+/// Writes, deltas, read results and execution gas used by a transaction (1 per executed
+/// transaction), and the writes its deltas got materialized to by sequential execution. Every
+/// write is charged a storage fee of 1 on top of the execution gas.
+#[derive(Debug, PartialEq)]
+pub struct Output<K, V>(
+    Vec<(K, V)>,
+    Vec<(K, DeltaOp)>,
+    Vec<Option<Vec<u8>>>,
+    u64,
+    Vec<(K, WriteOp)>,
+);
+
+impl<K, V> Output<K, V> {
+    /// What the transaction read, in order.
+    pub(crate) fn read_results(&self) -> &[Option<Vec<u8>>] {
+        &self.2
+    }
+
+    /// Writes incorporated by sequential execution in place of the deltas.
+    pub(crate) fn incorporated_delta_writes(&self) -> &[(K, WriteOp)] {
+        &self.4
+    }
+}
 
 impl<K, V> TransactionOutput for Output<K, V>
 where
-    K: PartialOrd + Ord + Send + Sync + Clone + Hash + Eq + ModulePath + 'static,
+    K: PartialOrd + Ord + Send + Sync + Clone + Hash + Eq + ModulePath + KeyDebug + 'static,
     V: Send + Sync + Debug + Clone + TransactionWrite + 'static,
 {
     type Txn = Transaction<K, V>;
@@ -473,8 +512,26 @@ where
         self.1.clone()
     }
 
+    fn gas_used(&self) -> u64 {
+        self.execution_gas_used() + self.storage_fee_used()
+    }
+
+    fn execution_gas_used(&self) -> u64 {
+        self.3
+    }
+
+    fn storage_fee_used(&self) -> u64 {
+        self.0.len() as u64
+    }
+
     fn skip_output() -> Self {
-        Self(vec![], vec![], vec![])
+        Self(vec![], vec![], vec![], 0, vec![])
+    }
+
+    fn incorporate_delta_writes(&mut self, delta_writes: Vec<(K, WriteOp)>) {
+        assert_eq!(self.1.len(), delta_writes.len());
+        self.1.clear();
+        self.4 = delta_writes;
     }
 }
 
@@ -640,14 +697,20 @@ impl<V: Debug + Clone + PartialEq + Eq + TransactionWrite> ExpectedOutput<V> {
                     .iter()
                     .take(*skip_at)
                     .zip(expected_results.iter())
-                    .for_each(|(Output(_, _, result), expected_results)| {
+                    .for_each(|(Output(_, _, result, _, _), expected_results)| {
                         Self::check_result(expected_results, result)
                     });
 
                 results
                     .iter()
                     .skip(*skip_at)
-                    .for_each(|Output(_, _, result)| assert!(result.is_empty()))
+                    .for_each(|Output(_, _, result, _, _)| assert!(result.is_empty()))
+            },
+            (
+                Self::DeltaFailure(fail_idx, _),
+                Err(Error::DeltaMaterializationFailure { txn_idx, .. }),
+            ) => {
+                assert_eq!(fail_idx, txn_idx);
             },
             (Self::DeltaFailure(fail_idx, expected_results), Ok(results)) => {
                 // Check_result asserts internally, so no need to return a bool.
@@ -655,14 +718,14 @@ impl<V: Debug + Clone + PartialEq + Eq + TransactionWrite> ExpectedOutput<V> {
                     .iter()
                     .take(*fail_idx)
                     .zip(expected_results.iter())
-                    .for_each(|(Output(_, _, result), expected_results)| {
+                    .for_each(|(Output(_, _, result, _, _), expected_results)| {
                         Self::check_result(expected_results, result)
                     });
             },
             (Self::Success(expected_results), Ok(results)) => results
                 .iter()
                 .zip(expected_results.iter())
-                .for_each(|(Output(_, _, result), expected_result)| {
+                .for_each(|(Output(_, _, result, _, _), expected_result)| {
                     Self::check_result(expected_result, result);
                 }),
             _ => panic!("Incomparable execution outcomes"),
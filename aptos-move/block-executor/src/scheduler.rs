@@ -5,7 +5,7 @@ use aptos_infallible::Mutex;
 use crossbeam::utils::CachePadded;
 use parking_lot::{RwLock, RwLockUpgradableReadGuard};
 use std::{
-    cmp::max,
+    cmp::{max, min},
     hint,
     ops::DerefMut,
     sync::{
@@ -16,6 +16,9 @@ use std::{
 
 const TXN_IDX_MASK: u64 = (1 << 32) - 1;
 
+/// Default number of transactions from the commit index whose validations are prioritized.
+pub const DEFAULT_VALIDATION_PRIORITY_WINDOW: usize = 4;
+
 // Type aliases.
 pub type TxnIndex = usize;
 pub type Incarnation = usize;
@@ -23,6 +26,20 @@ pub type Wave = u32;
 pub type Version = (TxnIndex, Incarnation);
 type DependencyCondvar = Arc<(Mutex<bool>, Condvar)>;
 
+/// Describes where the execution of a block ended.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BlockCommitReason {
+    /// All the transactions in the block were committed.
+    EndOfBlock,
+    /// The transaction at the index returned SkipRest, the rest of the block was skipped.
+    SkipRest(TxnIndex),
+    /// The accumulated gas reached the block gas limit at the transaction at the index, the
+    /// rest of the block was skipped.
+    BlockGasLimitReached(TxnIndex),
+    /// The execution was cancelled through an executor handle, the outputs are discarded.
+    Cancelled,
+}
+
 /// A holder for potential task returned from the Scheduler. ExecutionTask and ValidationTask
 /// each contain a version of transaction that must be executed or validated, respectively.
 /// NoTask holds no task (similar None if we wrapped tasks in Option), and Done implies that
@@ -187,8 +204,20 @@ pub struct Scheduler {
     /// be successful in order to commit the next transaction.
     commit_state: Mutex<(TxnIndex, Wave)>,
 
-    /// Shared marker that is set when a thread detects that all txns can be committed.
+    /// Shared marker that is set when a thread detects that all txns can be committed, or when
+    /// the scheduler is halted.
     done_marker: AtomicBool,
+    /// Why the commit index stopped before the end of the block, if it did (see end_block).
+    early_end: Mutex<Option<BlockCommitReason>>,
+
+    /// Number of transactions starting at the commit index, whose validation is prioritized
+    /// over the validation wavefront (0 disables the prioritization).
+    validation_priority_window: usize,
+    /// Mirrors the commit index in commit_state, so it can be read without taking the lock.
+    commit_idx_hint: AtomicUsize,
+    /// Packed (index, wave) of the last prioritized validation, so that threads do not
+    /// dispatch the same prioritized validation more than once.
+    priority_validation_marker: AtomicU64,
 
     /// An index i maps to indices of other transactions that depend on transaction i, i.e. they
     /// should be re-executed once transaction i's next incarnation finishes.
@@ -200,12 +229,26 @@ pub struct Scheduler {
 /// Public Interfaces for the Scheduler
 impl Scheduler {
     pub fn new(num_txns: usize) -> Self {
+        Self::new_with_validation_priority_window(num_txns, 0)
+    }
+
+    /// Creates a scheduler that, before following the validation wavefront, validates the
+    /// transactions within validation_priority_window of the commit index (see
+    /// try_validate_near_commit_idx).
+    pub fn new_with_validation_priority_window(
+        num_txns: usize,
+        validation_priority_window: usize,
+    ) -> Self {
         Self {
             num_txns,
             execution_idx: AtomicUsize::new(0),
             validation_idx: AtomicU64::new(0),
             commit_state: Mutex::new((0, 0)),
             done_marker: AtomicBool::new(false),
+            early_end: Mutex::new(None),
+            validation_priority_window,
+            commit_idx_hint: AtomicUsize::new(0),
+            priority_validation_marker: AtomicU64::new(u64::MAX),
             txn_dependency: (0..num_txns)
                 .map(|_| CachePadded::new(Mutex::new(Vec::new())))
                 .collect(),
@@ -221,8 +264,13 @@ impl Scheduler {
     }
 
     /// If successful, returns Some(TxnIndex), the index of committed transaction.
-    /// The current implementation has one dedicated thread to try_commit.
+    /// The current implementation has one dedicated thread to try_commit. Nothing is committed
+    /// once the scheduler is halted.
     pub fn try_commit(&self) -> Option<TxnIndex> {
+        if self.done() {
+            return None;
+        }
+
         let mut commit_state_mutex = self.commit_state.lock();
         let commit_state = commit_state_mutex.deref_mut();
         let (commit_idx, commit_wave) = (&mut commit_state.0, &mut commit_state.1);
@@ -252,6 +300,7 @@ impl Scheduler {
                             // Can commit.
                             *status_write = ExecutionStatus::Committed(incarnation);
                             *commit_idx += 1;
+                            self.commit_idx_hint.store(*commit_idx, Ordering::Release);
                             return Some(*commit_idx - 1);
                         }
                     }
@@ -261,6 +310,54 @@ impl Scheduler {
         None
     }
 
+    /// The latest incarnation of each transaction, i.e. how many times it was re-executed. Meant
+    /// to be called once the execution of the block is done.
+    pub fn incarnations(&self) -> Vec<Incarnation> {
+        self.txn_status
+            .iter()
+            .map(|status| match &*status.0.read() {
+                ExecutionStatus::ReadyToExecute(incarnation, _)
+                | ExecutionStatus::Executing(incarnation)
+                | ExecutionStatus::Suspended(incarnation, _)
+                | ExecutionStatus::Executed(incarnation)
+                | ExecutionStatus::Committed(incarnation)
+                | ExecutionStatus::Aborting(incarnation) => *incarnation,
+            })
+            .collect()
+    }
+
+    /// Stops the scheduler before all txns are committed: no more tasks are created, and the
+    /// txns suspended on a dependency are woken up, their executions then reading whatever they
+    /// find. Only the results of the txns committed before the halt are meaningful.
+    pub fn halt(&self) {
+        self.done_marker.store(true, Ordering::SeqCst);
+        for status in &self.txn_status {
+            if let ExecutionStatus::Suspended(_, dep_condvar)
+            | ExecutionStatus::ReadyToExecute(_, Some(dep_condvar)) = &*status.0.read()
+            {
+                Self::resolve_dependency(dep_condvar);
+            }
+        }
+    }
+
+    /// Ends the block at the last committed txn for the given reason: the txns after it are not
+    /// committed, and the scheduler is halted.
+    pub fn end_block(&self, reason: BlockCommitReason) {
+        *self.early_end.lock() = Some(reason);
+        self.halt();
+    }
+
+    /// Why the commit index stopped, along with the index of the first txn that wasn't
+    /// committed (the number of txns if all were). Meant to be called once the execution of the
+    /// block is done.
+    pub fn commit_boundary(&self) -> (BlockCommitReason, TxnIndex) {
+        let reason = self
+            .early_end
+            .lock()
+            .unwrap_or(BlockCommitReason::EndOfBlock);
+        (reason, self.commit_state.lock().0)
+    }
+
     #[cfg(test)]
     /// Return the TxnIndex and Wave of current commit index
     pub fn commit_state(&self) -> (usize, u32) {
@@ -296,6 +393,10 @@ impl Scheduler {
                 return SchedulerTask::Done;
             }
 
+            if let Some((version_to_validate, wave)) = self.try_validate_near_commit_idx() {
+                return SchedulerTask::ValidationTask(version_to_validate, wave);
+            }
+
             let (idx_to_validate, _) =
                 Self::unpack_validation_idx(self.validation_idx.load(Ordering::Acquire));
             let idx_to_execute = self.execution_idx.load(Ordering::Acquire);
@@ -368,6 +469,12 @@ impl Scheduler {
             stored_deps.push(txn_idx);
         }
 
+        if self.done() {
+            // Halted concurrently, possibly before the suspension was visible to halt, in which
+            // case nobody else would wake the caller up.
+            Self::resolve_dependency(&dep_condvar);
+        }
+
         Some(dep_condvar)
     }
 
@@ -588,6 +695,52 @@ impl Scheduler {
             .map(|incarnation| ((idx_to_validate, incarnation), wave))
     }
 
+    /// Successful validations of the transactions right at the commit index are what allows
+    /// the commit index to progress, so these are prioritized over arbitrary higher-index
+    /// validations: returns a validation task for the lowest transaction within
+    /// validation_priority_window of the commit index that is executed but not validated at
+    /// the current wave. As with the validation task returned from finish_execution, it is
+    /// safe to validate an executed transaction at the current wave at any point, and since
+    /// the order of validations is arbitrary, the prioritization does not affect correctness.
+    fn try_validate_near_commit_idx(&self) -> Option<(Version, Wave)> {
+        if self.validation_priority_window == 0 {
+            return None;
+        }
+
+        let (_, wave) = Self::unpack_validation_idx(self.validation_idx.load(Ordering::Acquire));
+        let commit_idx = self.commit_idx_hint.load(Ordering::Acquire);
+        let end_idx = min(
+            commit_idx.saturating_add(self.validation_priority_window),
+            self.num_txns,
+        );
+
+        for txn_idx in commit_idx..end_idx {
+            let validated = self.txn_status[txn_idx]
+                .1
+                .read()
+                .maybe_max_validated_wave
+                .map_or(false, |validated_wave| validated_wave >= wave);
+            if validated {
+                continue;
+            }
+
+            // A transaction that is not executed can't be committed, and neither can any of
+            // the following ones, so there is no point in looking further.
+            let incarnation = self.is_executed(txn_idx, false)?;
+            let marker = (txn_idx as u64) | ((wave as u64) << 32);
+            if self
+                .priority_validation_marker
+                .swap(marker, Ordering::SeqCst)
+                == marker
+            {
+                // Already dispatched by another thread.
+                return None;
+            }
+            return Some(((txn_idx, incarnation), wave));
+        }
+        None
+    }
+
     /// Grab an index to try and execute next (by fetch-and-incrementing execution_idx).
     /// - If the index is out of bounds, return None (and invoke a check of whethre
     /// all txns can be committed).
@@ -654,8 +807,16 @@ impl Scheduler {
         *status = ExecutionStatus::ReadyToExecute(incarnation + 1, None);
     }
 
-    /// Checks whether the done marker is set. The marker can only be set by 'try_commit'.
-    fn done(&self) -> bool {
+    /// Checks whether the done marker is set. The marker can only be set by 'try_commit' or
+    /// 'halt'.
+    pub fn done(&self) -> bool {
         self.done_marker.load(Ordering::Acquire)
     }
+
+    /// Wakes up the thread waiting on the condition variable of a dependency.
+    fn resolve_dependency(dep_condvar: &DependencyCondvar) {
+        let (lock, cvar) = &**dep_condvar;
+        *lock.lock() = true;
+        cvar.notify_one();
+    }
 }
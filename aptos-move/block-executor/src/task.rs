@@ -6,7 +6,7 @@ use aptos_state_view::TStateView;
 use aptos_types::{
     access_path::AccessPath,
     state_store::state_key::{StateKey, StateKeyInner},
-    write_set::TransactionWrite,
+    write_set::{TransactionWrite, WriteOp},
 };
 use std::{fmt::Debug, hash::Hash};
 
@@ -38,10 +38,21 @@ impl ModulePath for StateKey {
     }
 }
 
+/// Renders keys in developer facing logs, e.g. the ones explaining speculative aborts.
+pub trait KeyDebug {
+    fn key_debug(&self) -> String;
+}
+
+impl KeyDebug for StateKey {
+    fn key_debug(&self) -> String {
+        format!("{:?}", self)
+    }
+}
+
 /// Trait that defines a transaction that could be parallel executed by the scheduler. Each
 /// transaction will write to a key value storage as their side effect.
 pub trait Transaction: Sync + Send + 'static {
-    type Key: PartialOrd + Ord + Send + Sync + Clone + Hash + Eq + ModulePath;
+    type Key: PartialOrd + Ord + Send + Sync + Clone + Hash + Eq + ModulePath + KeyDebug;
     type Value: Send + Sync + TransactionWrite;
 }
 
@@ -96,6 +107,28 @@ pub trait TransactionOutput: Send + Sync {
     /// Get the deltas of a transaction from its output.
     fn get_deltas(&self) -> Vec<(<Self::Txn as Transaction>::Key, DeltaOp)>;
 
+    /// Get the total gas charged for the transaction.
+    fn gas_used(&self) -> u64;
+
+    /// Get the part of the charged gas that pays for the execution, counted against the block
+    /// gas limit. Defaults to all of it, for gas schedules that don't charge storage separately.
+    fn execution_gas_used(&self) -> u64 {
+        self.gas_used()
+    }
+
+    /// Get the part of the charged gas that pays for the storage deposits of the transaction.
+    fn storage_fee_used(&self) -> u64 {
+        0
+    }
+
     /// Execution output for transactions that comes after SkipRest signal.
     fn skip_output() -> Self;
+
+    /// Replaces the deltas of the output with the given writes, one per delta, holding the
+    /// materialized aggregator values. Used by sequential execution, which applies deltas as
+    /// it goes instead of resolving them after the block (see OutputDeltaResolver).
+    fn incorporate_delta_writes(
+        &mut self,
+        delta_writes: Vec<(<Self::Txn as Transaction>::Key, WriteOp)>,
+    );
 }
@@ -0,0 +1,642 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+//! Conformance suite for `ExecutorTask` implementations, e.g. alternative VMs or simulators, to
+//! check that they behave the way Block-STM expects before being plugged into the executor. An
+//! implementation describes how to build a few kinds of transactions via `ConformanceTask`, and
+//! `run_executor_conformance` executes blocks of them in parallel and sequentially and reports
+//! every violated property. The mock transactions of the crate's own tests pass the suite, see
+//! `MockTask`.
+
+pub use crate::test_utils::{MockDataView, MockKey, MockTransaction, MockValue};
+use crate::{
+    errors::Error,
+    executor::BlockExecutor,
+    proptest_types::types::{Task, Transaction as MockTxn},
+    task::{ExecutionStatus, ExecutorTask, ModulePath, Transaction, TransactionOutput},
+    test_utils,
+};
+use aptos_aggregator::delta_change_set::{delta_add, serialize, DeltaOp};
+use aptos_state_view::{StateViewId, TStateView};
+use aptos_types::{
+    state_store::state_storage_usage::StateStorageUsage,
+    write_set::{TransactionWrite, WriteOp},
+};
+use std::{
+    collections::HashMap,
+    fmt,
+    hash::Hash,
+    sync::{atomic::AtomicUsize, Arc, Mutex},
+};
+
+pub type Key<X> = <<X as ExecutorTask>::Txn as Transaction>::Key;
+pub type Value<X> = <<X as ExecutorTask>::Txn as Transaction>::Value;
+pub type MockTask = Task<MockKey, MockValue>;
+
+type Outputs<X> = Vec<(<X as ExecutorTask>::Output, Vec<(Key<X>, WriteOp)>)>;
+
+/// Value of the aggregator in storage, before the deltas of the blocks.
+const STORAGE_AGGREGATOR_VALUE: u128 = 100;
+/// Number of transactions of the contended block.
+const CONTENDED_BLOCK_SIZE: usize = 100;
+/// Contended blocks are executed this many times, as conflicts depend on the interleaving.
+const CONTENDED_RUNS: usize = 5;
+
+/// How an `ExecutorTask` implementation builds the transactions the suite executes. All of them
+/// must be deterministic, and can be built and executed any number of times.
+pub trait ConformanceTask: ExecutorTask + Sized {
+    fn argument() -> Self::Argument;
+
+    /// A key that is not a module path, distinct for distinct indices.
+    fn key(idx: usize) -> Key<Self>;
+
+    /// A key that is a module path, distinct for distinct indices and from the other keys.
+    fn module_key(idx: usize) -> Key<Self>;
+
+    /// A value that isn't a deletion, whose bytes are distinct for distinct indices.
+    fn value(idx: usize) -> Value<Self>;
+
+    /// A transaction that reads the keys in order through the view, then writes the values and
+    /// applies the deltas.
+    fn transaction(
+        reads: Vec<Key<Self>>,
+        writes: Vec<(Key<Self>, Value<Self>)>,
+        deltas: Vec<(Key<Self>, DeltaOp)>,
+    ) -> Self::Txn;
+
+    /// A transaction that succeeds and skips the rest of the block.
+    fn skip_rest_transaction() -> Self::Txn;
+
+    /// A transaction that fails with an error, aborting the block.
+    fn abort_transaction() -> Self::Txn;
+
+    /// What a transaction built by `transaction` read, in the order of its reads, None for a
+    /// key without value.
+    fn read_results(output: &Self::Output) -> Vec<Option<Vec<u8>>>;
+}
+
+/// The properties of the contract between Block-STM and `ExecutorTask` checked by the suite.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Property {
+    /// Reads go through the view the transaction is executed with, so that the executor records
+    /// them and validates them later.
+    ReadsThroughView,
+    /// Reads see the writes of the earlier transactions of the block.
+    WritesVisible,
+    /// In a block where every transaction reads and writes the same key, reads see the write of
+    /// the previous transaction, however the executor interleaves, aborts and re-executes them.
+    /// This relies on the writes of aborted incarnations, marked as estimates, turning the reads
+    /// of later transactions into dependencies.
+    DependenciesRespected,
+    /// Aggregator updates are reported as deltas, not writes, and reads see them applied.
+    DeltaSemantics,
+    /// A transaction failing with an error fails the block with it.
+    AbortsPropagate,
+    /// The outputs of the transactions after a skip are `skip_output`, which has no effects.
+    SkipOutput,
+    /// Module keys, and only them, are module paths, and a block both reading and writing a
+    /// module makes parallel execution fall back.
+    ModulePaths,
+}
+
+/// A property violated by an implementation, along with what went wrong.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ConformanceViolation {
+    pub property: Property,
+    pub message: String,
+}
+
+impl fmt::Display for ConformanceViolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?}: {}", self.property, self.message)
+    }
+}
+
+/// Checks every property, returning the violated ones.
+pub fn run_executor_conformance<X: ConformanceTask>() -> Result<(), Vec<ConformanceViolation>> {
+    let checks: [(Property, fn() -> Result<(), String>); 7] = [
+        (Property::ReadsThroughView, check_reads_through_view::<X>),
+        (Property::WritesVisible, check_writes_visible::<X>),
+        (
+            Property::DependenciesRespected,
+            check_dependencies_respected::<X>,
+        ),
+        (Property::DeltaSemantics, check_delta_semantics::<X>),
+        (Property::AbortsPropagate, check_aborts_propagate::<X>),
+        (Property::SkipOutput, check_skip_output::<X>),
+        (Property::ModulePaths, check_module_paths::<X>),
+    ];
+    let violations: Vec<_> = checks
+        .iter()
+        .filter_map(|(property, check)| {
+            check().err().map(|message| ConformanceViolation {
+                property: *property,
+                message,
+            })
+        })
+        .collect();
+    if violations.is_empty() {
+        Ok(())
+    } else {
+        Err(violations)
+    }
+}
+
+/// Storage for the suite, which also records the keys read from it.
+pub struct ConformanceView<K> {
+    data: HashMap<K, Vec<u8>>,
+    reads: Mutex<Vec<K>>,
+}
+
+impl<K: Clone + Eq + Hash> ConformanceView<K> {
+    fn new(data: HashMap<K, Vec<u8>>) -> Self {
+        Self {
+            data,
+            reads: Mutex::new(vec![]),
+        }
+    }
+
+    fn reads(&self) -> Vec<K> {
+        self.reads.lock().unwrap().clone()
+    }
+}
+
+impl<K: Clone + Eq + Hash> TStateView for ConformanceView<K> {
+    type Key = K;
+
+    fn id(&self) -> StateViewId {
+        StateViewId::Miscellaneous
+    }
+
+    fn get_state_value(&self, state_key: &K) -> anyhow::Result<Option<Vec<u8>>> {
+        self.reads.lock().unwrap().push(state_key.clone());
+        Ok(self.data.get(state_key).cloned())
+    }
+
+    fn is_genesis(&self) -> bool {
+        false
+    }
+
+    fn get_usage(&self) -> anyhow::Result<StateStorageUsage> {
+        Ok(StateStorageUsage::new_untracked())
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+enum Mode {
+    Parallel,
+    Sequential,
+}
+
+const MODES: [Mode; 2] = [Mode::Parallel, Mode::Sequential];
+
+fn execute<X: ConformanceTask>(
+    mode: Mode,
+    block: Vec<X::Txn>,
+    view: &ConformanceView<Key<X>>,
+) -> Result<Outputs<X>, Error<X::Error>> {
+    let concurrency_level = match mode {
+        Mode::Parallel => num_cpus::get().min(4),
+        Mode::Sequential => 1,
+    };
+    let executor =
+        BlockExecutor::<X::Txn, X, ConformanceView<Key<X>>>::new(concurrency_level, None);
+    let result = match mode {
+        Mode::Parallel => executor.execute_transactions_parallel(X::argument(), &block, view),
+        Mode::Sequential => executor.execute_transactions_sequential(X::argument(), &block, view),
+    };
+    result.map(|(outputs, _)| outputs)
+}
+
+/// Executes a block that is expected to succeed.
+fn execute_ok<X: ConformanceTask>(
+    mode: Mode,
+    block: Vec<X::Txn>,
+    view: &ConformanceView<Key<X>>,
+) -> Result<Outputs<X>, String> {
+    let num_txns = block.len();
+    let outputs = execute::<X>(mode, block, view)
+        .map_err(|err| format!("{:?} execution failed with {}", mode, describe(&err)))?;
+    if outputs.len() != num_txns {
+        return Err(format!(
+            "{:?} execution returned {} outputs for {} transactions",
+            mode,
+            outputs.len(),
+            num_txns
+        ));
+    }
+    Ok(outputs)
+}
+
+fn describe<E>(err: &Error<E>) -> String {
+    match err {
+        Error::ModulePathReadWrite => "a module read and written".to_string(),
+        Error::UserError(_) => "an error of a transaction".to_string(),
+        Error::DeltaApplicationFailure(idx) => {
+            format!("a delta of transaction {} failing to apply", idx)
+        },
+        Error::DeltaMaterializationFailure { txn_idx, key } => {
+            format!(
+                "a delta of transaction {} on {} failing to apply",
+                txn_idx, key
+            )
+        },
+        Error::StorageReadFailure { txn_idx, error } => {
+            format!(
+                "a storage read of transaction {} failing with {}",
+                txn_idx, error
+            )
+        },
+        Error::TooManyIncarnations => "a transaction re-executed too many times".to_string(),
+        Error::ExecutionCancelled => "the execution being cancelled".to_string(),
+    }
+}
+
+fn bytes<X: ConformanceTask>(idx: usize) -> Option<Vec<u8>> {
+    X::value(idx).extract_raw_bytes()
+}
+
+/// Checks that the transaction read the expected values, in order.
+fn check_reads<X: ConformanceTask>(
+    mode: Mode,
+    txn_idx: usize,
+    output: &X::Output,
+    expected: &[Option<Vec<u8>>],
+) -> Result<(), String> {
+    let reads = X::read_results(output);
+    if reads != expected {
+        return Err(format!(
+            "{:?} execution: transaction {} read {:?}, expected {:?}",
+            mode, txn_idx, reads, expected
+        ));
+    }
+    Ok(())
+}
+
+fn check_reads_through_view<X: ConformanceTask>() -> Result<(), String> {
+    let keys: Vec<_> = (0..3).map(X::key).collect();
+    let view = ConformanceView::new(
+        keys.iter()
+            .enumerate()
+            .filter_map(|(idx, key)| bytes::<X>(idx).map(|bytes| (key.clone(), bytes)))
+            .collect(),
+    );
+    let txn = X::transaction(keys.clone(), vec![], vec![]);
+    let output = match X::init(X::argument()).execute_transaction(&view, &txn, 0, false) {
+        ExecutionStatus::Success(output) => output,
+        _ => return Err("a transaction only reading did not succeed".to_string()),
+    };
+
+    let reads = view.reads();
+    if let Some(missing) = keys.iter().position(|key| !reads.contains(key)) {
+        return Err(format!(
+            "read {} of the transaction did not go through the view, so the executor can't \
+             validate it",
+            missing
+        ));
+    }
+    let expected: Vec<_> = (0..keys.len()).map(bytes::<X>).collect();
+    check_reads::<X>(Mode::Sequential, 0, &output, &expected)
+}
+
+fn check_writes_visible<X: ConformanceTask>() -> Result<(), String> {
+    // Transaction i reads key i - 1 and writes key i, the last one reads all the keys.
+    let num_txns = 8;
+    let block = || {
+        let mut block: Vec<_> = (0..num_txns)
+            .map(|idx| {
+                let reads = idx.checked_sub(1).map(X::key).into_iter().collect();
+                X::transaction(reads, vec![(X::key(idx), X::value(idx))], vec![])
+            })
+            .collect();
+        block.push(X::transaction(
+            (0..num_txns).map(X::key).collect(),
+            vec![],
+            vec![],
+        ));
+        block
+    };
+
+    for mode in MODES {
+        let outputs = execute_ok::<X>(mode, block(), &ConformanceView::new(HashMap::new()))?;
+        check_reads::<X>(mode, 0, &outputs[0].0, &[])?;
+        for (idx, (output, _)) in outputs.iter().enumerate().take(num_txns).skip(1) {
+            check_reads::<X>(mode, idx, output, &[bytes::<X>(idx - 1)])?;
+        }
+        let expected: Vec<_> = (0..num_txns).map(bytes::<X>).collect();
+        check_reads::<X>(mode, num_txns, &outputs[num_txns].0, &expected)?;
+    }
+    Ok(())
+}
+
+fn check_dependencies_respected<X: ConformanceTask>() -> Result<(), String> {
+    let hot_key = X::key(0);
+    for _ in 0..CONTENDED_RUNS {
+        let block = (0..CONTENDED_BLOCK_SIZE)
+            .map(|idx| {
+                X::transaction(
+                    vec![hot_key.clone()],
+                    vec![(hot_key.clone(), X::value(idx))],
+                    vec![],
+                )
+            })
+            .collect();
+        let outputs =
+            execute_ok::<X>(Mode::Parallel, block, &ConformanceView::new(HashMap::new()))?;
+        for (idx, (output, _)) in outputs.iter().enumerate() {
+            let expected = idx.checked_sub(1).and_then(bytes::<X>);
+            check_reads::<X>(Mode::Parallel, idx, output, &[expected])?;
+        }
+    }
+    Ok(())
+}
+
+fn check_delta_semantics<X: ConformanceTask>() -> Result<(), String> {
+    let aggregator = X::key(0);
+    let view = || {
+        ConformanceView::new(HashMap::from([(
+            aggregator.clone(),
+            serialize(&STORAGE_AGGREGATOR_VALUE),
+        )]))
+    };
+    let block = || {
+        vec![
+            X::transaction(
+                vec![],
+                vec![],
+                vec![(aggregator.clone(), delta_add(5, u128::MAX))],
+            ),
+            X::transaction(
+                vec![],
+                vec![],
+                vec![(aggregator.clone(), delta_add(3, u128::MAX))],
+            ),
+            X::transaction(vec![aggregator.clone()], vec![], vec![]),
+        ]
+    };
+
+    for mode in MODES {
+        let outputs = execute_ok::<X>(mode, block(), &view())?;
+        for (idx, (output, _)) in outputs.iter().take(2).enumerate() {
+            if output
+                .get_writes()
+                .iter()
+                .any(|(key, _)| key == &aggregator)
+            {
+                return Err(format!(
+                    "{:?} execution: transaction {} reported its delta as a write",
+                    mode, idx
+                ));
+            }
+        }
+        check_reads::<X>(
+            mode,
+            2,
+            &outputs[2].0,
+            &[Some(serialize(&(STORAGE_AGGREGATOR_VALUE + 8)))],
+        )?;
+
+        // Parallel execution materializes the deltas after the block.
+        if let Mode::Parallel = mode {
+            for (idx, expected) in [(0, 105), (1, 108)] {
+                let (output, delta_writes) = &outputs[idx];
+                if output.get_deltas().len() != 1 {
+                    return Err(format!(
+                        "transaction {} reported {} deltas, expected 1",
+                        idx,
+                        output.get_deltas().len()
+                    ));
+                }
+                let materialized: Vec<_> = delta_writes
+                    .iter()
+                    .map(|(_, write)| write.extract_raw_bytes())
+                    .collect();
+                if materialized != vec![Some(serialize(&expected))] {
+                    return Err(format!(
+                        "the delta of transaction {} materialized to {:?}, expected {}",
+                        idx, materialized, expected
+                    ));
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+fn check_aborts_propagate<X: ConformanceTask>() -> Result<(), String> {
+    let block = || {
+        vec![
+            X::transaction(vec![], vec![(X::key(0), X::value(0))], vec![]),
+            X::abort_transaction(),
+            X::transaction(vec![X::key(0)], vec![(X::key(1), X::value(1))], vec![]),
+        ]
+    };
+
+    for mode in MODES {
+        match execute::<X>(mode, block(), &ConformanceView::new(HashMap::new())) {
+            Err(Error::UserError(_)) => {},
+            Ok(_) => {
+                return Err(format!(
+                    "{:?} execution of a block with a failing transaction succeeded",
+                    mode
+                ))
+            },
+            Err(err) => {
+                return Err(format!(
+                    "{:?} execution of a block with a failing transaction failed with {}",
+                    mode,
+                    describe(&err)
+                ))
+            },
+        }
+    }
+    Ok(())
+}
+
+fn check_skip_output<X: ConformanceTask>() -> Result<(), String> {
+    let no_effects = |output: &X::Output| {
+        output.get_writes().is_empty() && output.get_deltas().is_empty() && output.gas_used() == 0
+    };
+    if !no_effects(&X::Output::skip_output()) {
+        return Err("skip_output has writes, deltas or gas".to_string());
+    }
+
+    let block = || {
+        vec![
+            X::transaction(vec![], vec![(X::key(0), X::value(0))], vec![]),
+            X::skip_rest_transaction(),
+            X::transaction(vec![X::key(0)], vec![(X::key(1), X::value(1))], vec![]),
+            X::transaction(vec![], vec![], vec![(X::key(2), delta_add(1, u128::MAX))]),
+        ]
+    };
+    for mode in MODES {
+        let outputs = execute_ok::<X>(mode, block(), &ConformanceView::new(HashMap::new()))?;
+        if let Some(idx) = (2..outputs.len()).find(|idx| !no_effects(&outputs[*idx].0)) {
+            return Err(format!(
+                "{:?} execution: transaction {} after the skip has effects",
+                mode, idx
+            ));
+        }
+    }
+    Ok(())
+}
+
+fn check_module_paths<X: ConformanceTask>() -> Result<(), String> {
+    if let Some(idx) = (0..4).find(|idx| X::module_key(*idx).module_path().is_none()) {
+        return Err(format!("module key {} is not a module path", idx));
+    }
+    if let Some(idx) = (0..4).find(|idx| X::key(*idx).module_path().is_some()) {
+        return Err(format!("key {} is a module path", idx));
+    }
+
+    let module = X::module_key(0);
+    let block = vec![
+        X::transaction(vec![], vec![(module.clone(), X::value(0))], vec![]),
+        X::transaction(vec![module], vec![], vec![]),
+    ];
+    match execute::<X>(Mode::Parallel, block, &ConformanceView::new(HashMap::new())) {
+        Err(Error::ModulePathReadWrite) => Ok(()),
+        Ok(_) => Err(
+            "parallel execution of a block reading and writing a module succeeded, \
+                      instead of falling back"
+                .to_string(),
+        ),
+        Err(err) => Err(format!(
+            "parallel execution of a block reading and writing a module failed with {}",
+            describe(&err)
+        )),
+    }
+}
+
+impl ConformanceTask for MockTask {
+    fn argument() {}
+
+    fn key(idx: usize) -> MockKey {
+        test_utils::account(idx)
+    }
+
+    fn module_key(idx: usize) -> MockKey {
+        test_utils::module(idx)
+    }
+
+    fn value(idx: usize) -> MockValue {
+        test_utils::value(idx)
+    }
+
+    fn transaction(
+        reads: Vec<MockKey>,
+        writes: Vec<(MockKey, MockValue)>,
+        deltas: Vec<(MockKey, DeltaOp)>,
+    ) -> MockTransaction {
+        MockTxn::Write {
+            incarnation: Arc::new(AtomicUsize::new(0)),
+            writes_and_deltas: vec![(writes, deltas)],
+            reads: vec![reads],
+        }
+    }
+
+    fn skip_rest_transaction() -> MockTransaction {
+        MockTxn::SkipRest
+    }
+
+    fn abort_transaction() -> MockTransaction {
+        MockTxn::Abort
+    }
+
+    fn read_results(output: &Self::Output) -> Vec<Option<Vec<u8>>> {
+        output.read_results().to_vec()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::proptest_types::types::EmptyDataView;
+    use std::marker::PhantomData;
+
+    #[test]
+    fn test_mock_task_conforms() {
+        if let Err(violations) = run_executor_conformance::<MockTask>() {
+            let violations: Vec<_> = violations.iter().map(ToString::to_string).collect();
+            panic!("{}", violations.join("\n"));
+        }
+    }
+
+    /// Executes the mock transactions against empty storage rather than the view it is given,
+    /// i.e. the executor never sees the reads.
+    struct ForgetfulTask(MockTask);
+
+    impl ExecutorTask for ForgetfulTask {
+        type Argument = ();
+        type Error = usize;
+        type Output = <MockTask as ExecutorTask>::Output;
+        type Txn = MockTransaction;
+
+        fn init(_argument: ()) -> Self {
+            Self(MockTask::new())
+        }
+
+        fn execute_transaction(
+            &self,
+            _view: &impl TStateView<Key = MockKey>,
+            txn: &MockTransaction,
+            txn_idx: usize,
+            materialize_deltas: bool,
+        ) -> ExecutionStatus<Self::Output, usize> {
+            let view = EmptyDataView::<MockKey, MockValue> {
+                phantom: PhantomData,
+            };
+            self.0
+                .execute_transaction(&view, txn, txn_idx, materialize_deltas)
+        }
+    }
+
+    impl ConformanceTask for ForgetfulTask {
+        fn argument() {}
+
+        fn key(idx: usize) -> MockKey {
+            MockTask::key(idx)
+        }
+
+        fn module_key(idx: usize) -> MockKey {
+            MockTask::module_key(idx)
+        }
+
+        fn value(idx: usize) -> MockValue {
+            MockTask::value(idx)
+        }
+
+        fn transaction(
+            reads: Vec<MockKey>,
+            writes: Vec<(MockKey, MockValue)>,
+            deltas: Vec<(MockKey, DeltaOp)>,
+        ) -> MockTransaction {
+            MockTask::transaction(reads, writes, deltas)
+        }
+
+        fn skip_rest_transaction() -> MockTransaction {
+            MockTask::skip_rest_transaction()
+        }
+
+        fn abort_transaction() -> MockTransaction {
+            MockTask::abort_transaction()
+        }
+
+        fn read_results(output: &Self::Output) -> Vec<Option<Vec<u8>>> {
+            MockTask::read_results(output)
+        }
+    }
+
+    #[test]
+    fn test_unrecorded_reads_detected() {
+        let violations = run_executor_conformance::<ForgetfulTask>().unwrap_err();
+        let properties: Vec<_> = violations.iter().map(|v| v.property).collect();
+        assert!(properties.contains(&Property::ReadsThroughView));
+        assert!(properties.contains(&Property::WritesVisible));
+        assert!(properties.contains(&Property::ModulePaths));
+        // Every violation says what went wrong.
+        assert!(violations[0].to_string().starts_with(
+            "ReadsThroughView: read 0 of the transaction did not go through the view"
+        ));
+    }
+}
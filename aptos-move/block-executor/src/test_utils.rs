@@ -0,0 +1,189 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+//! Deterministic block generators for the mock executor, modeling the workloads that matter for
+//! parallel execution performance. Shared by the benchmarks and the tests.
+
+use crate::{
+    executor::BlockExecutor,
+    proptest_types::types::{DeltaDataView, KeyType, Task, Transaction, ValueType},
+};
+use aptos_aggregator::delta_change_set::{delta_add, DeltaOp};
+use std::sync::{atomic::AtomicUsize, Arc};
+
+pub type MockKey = KeyType<[u8; 32]>;
+pub type MockValue = ValueType<[u8; 32]>;
+pub type MockTransaction = Transaction<MockKey, MockValue>;
+pub type MockBlockExecutor = BlockExecutor<MockTransaction, Task<MockKey, MockValue>, MockDataView>;
+pub type MockDataView = DeltaDataView<MockKey, MockValue>;
+
+/// Number of hot accounts in the contended workload.
+const NUM_HOT_ACCOUNTS: usize = 4;
+/// In the module publishing workload, one transaction out of this many publishes a module.
+const MODULE_PUBLISHING_PERIOD: usize = 50;
+/// Number of aggregators in the workload spreading deltas over many of them.
+const NUM_AGGREGATORS: usize = 64;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Workload {
+    /// Transfers between distinct pairs of accounts, i.e. no conflicts at all.
+    P2PTransfers,
+    /// Every transaction transfers from its own account to one of a few hot accounts.
+    HotAccountContention,
+    /// Transfers between distinct accounts that also update a shared aggregator (e.g. the
+    /// total supply), which doesn't conflict thanks to deltas.
+    AggregatorHeavy,
+    /// Transfers between distinct accounts that also update one of many aggregators, so that
+    /// there are many keys whose deltas are resolved after the block.
+    ManyAggregators,
+    /// Every transaction reads the output of the previous one, i.e. no parallelism at all.
+    DependencyChain,
+    /// Transfers, with a module published every once in a while. Modules are never read by
+    /// the block, so execution doesn't fall back to sequential.
+    ModulePublishingMixed,
+}
+
+impl Workload {
+    pub const ALL: [Workload; 6] = [
+        Workload::P2PTransfers,
+        Workload::HotAccountContention,
+        Workload::AggregatorHeavy,
+        Workload::ManyAggregators,
+        Workload::DependencyChain,
+        Workload::ModulePublishingMixed,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Workload::P2PTransfers => "p2p_transfers",
+            Workload::HotAccountContention => "hot_account_contention",
+            Workload::AggregatorHeavy => "aggregator_heavy",
+            Workload::ManyAggregators => "many_aggregators",
+            Workload::DependencyChain => "dependency_chain",
+            Workload::ModulePublishingMixed => "module_publishing_mixed",
+        }
+    }
+
+    /// Generates a block of the given size. Transactions keep track of their incarnations, so
+    /// a block must not be executed more than once.
+    pub fn generate(&self, block_size: usize) -> Vec<MockTransaction> {
+        (0..block_size)
+            .map(|idx| match self {
+                Workload::P2PTransfers => transfer(account(2 * idx), account(2 * idx + 1), idx),
+                Workload::HotAccountContention => transfer(
+                    account(NUM_HOT_ACCOUNTS + idx),
+                    account(idx % NUM_HOT_ACCOUNTS),
+                    idx,
+                ),
+                Workload::AggregatorHeavy => {
+                    let (sender, receiver) = (account(2 * idx), account(2 * idx + 1));
+                    mock_transaction(
+                        vec![sender, receiver],
+                        vec![sender, receiver],
+                        vec![(aggregator(0), delta_add(1, u128::MAX))],
+                        idx,
+                    )
+                },
+                Workload::ManyAggregators => {
+                    let (sender, receiver) = (account(2 * idx), account(2 * idx + 1));
+                    mock_transaction(
+                        vec![sender, receiver],
+                        vec![sender, receiver],
+                        vec![(aggregator(idx % NUM_AGGREGATORS), delta_add(1, u128::MAX))],
+                        idx,
+                    )
+                },
+                // Only the next account is written, so the next transaction reads it.
+                Workload::DependencyChain => {
+                    mock_transaction(vec![account(idx)], vec![account(idx + 1)], vec![], idx)
+                },
+                Workload::ModulePublishingMixed => {
+                    if idx % MODULE_PUBLISHING_PERIOD == 0 {
+                        mock_transaction(vec![], vec![module(idx)], vec![], idx)
+                    } else {
+                        transfer(account(2 * idx), account(2 * idx + 1), idx)
+                    }
+                },
+            })
+            .collect()
+    }
+}
+
+pub fn mock_data_view() -> MockDataView {
+    DeltaDataView::default()
+}
+
+fn key(prefix: u8, idx: usize) -> [u8; 32] {
+    let mut key = [0u8; 32];
+    key[0] = prefix;
+    key[1..9].copy_from_slice(&(idx as u64).to_le_bytes());
+    key
+}
+
+pub(crate) fn account(idx: usize) -> MockKey {
+    KeyType(key(0, idx), false)
+}
+
+fn aggregator(idx: usize) -> MockKey {
+    KeyType(key(1, idx), false)
+}
+
+pub(crate) fn module(idx: usize) -> MockKey {
+    KeyType(key(2, idx), true)
+}
+
+pub(crate) fn value(idx: usize) -> MockValue {
+    ValueType(key(3, idx), true)
+}
+
+/// A static transaction with the given reads, writes (of a value derived from the index) and
+/// deltas.
+fn mock_transaction(
+    reads: Vec<MockKey>,
+    writes: Vec<MockKey>,
+    deltas: Vec<(MockKey, DeltaOp)>,
+    idx: usize,
+) -> MockTransaction {
+    Transaction::Write {
+        incarnation: Arc::new(AtomicUsize::new(0)),
+        writes_and_deltas: vec![(
+            writes.into_iter().map(|key| (key, value(idx))).collect(),
+            deltas,
+        )],
+        reads: vec![reads],
+    }
+}
+
+/// Reads both accounts and writes both of them.
+fn transfer(sender: MockKey, receiver: MockKey, idx: usize) -> MockTransaction {
+    mock_transaction(
+        vec![sender, receiver],
+        vec![sender, receiver],
+        vec![],
+        idx,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::proptest_types::types::ExpectedOutput;
+
+    #[test]
+    fn test_workloads_match_baseline() {
+        let data_view = mock_data_view();
+        for workload in Workload::ALL {
+            let transactions = workload.generate(500);
+            let (outputs, delta_writes): (Vec<_>, Vec<_>) =
+                MockBlockExecutor::new(num_cpus::get(), None)
+                    .execute_transactions_parallel((), &transactions, &data_view)
+                    .unwrap()
+                    .0
+                    .into_iter()
+                    .unzip();
+
+            let baseline = ExpectedOutput::generate_baseline(&transactions, Some(delta_writes));
+            baseline.assert_output(&Ok(outputs));
+        }
+    }
+}
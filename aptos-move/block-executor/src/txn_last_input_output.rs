@@ -231,6 +231,12 @@ impl<K: ModulePath, T: TransactionOutput, E: Send + Clone> TxnLastInputOutput<K,
         }
     }
 
+    // Returns the recorded output without taking it, e.g. to inspect a committed txn. The
+    // reference must be dropped before the output is taken.
+    pub fn output(&self, txn_idx: TxnIndex) -> Option<Arc<TxnOutput<T, E>>> {
+        self.outputs[txn_idx].load_full()
+    }
+
     // Must be executed after parallel execution is done, grabs outputs. Will panic if
     // other outstanding references to the recorded outputs exist.
     pub fn take_output(&self, txn_idx: TxnIndex) -> ExecutionStatus<T, Error<E>> {
@@ -2,34 +2,55 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
-    executor::BlockExecutor,
-    proptest_types::types::{DeltaDataView, ExpectedOutput, KeyType, Task, Transaction, ValueType},
+    base_view_cache::BaseViewCache,
+    counters::{
+        INCARNATION_LIMIT_FALLBACK_COUNT, MODULE_PUBLISHING_FALLBACK_COUNT,
+        ORDERED_EXECUTION_FALLBACK_COUNT,
+    },
+    errors::Error,
+    executor::{BlockCommitReason, BlockExecutionSummary, BlockExecutor},
+    ordering::{BlockOrderingHook, SenderGrouping},
+    output_delta_resolver::OutputDeltaResolver,
+    proptest_types::types::{
+        DeltaDataView, ExpectedOutput, KeyType, Output, Task, Transaction, ValueType,
+        STORAGE_AGGREGATOR_VALUE,
+    },
     scheduler::{Scheduler, SchedulerTask},
-    task::ModulePath,
+    task::{KeyDebug, ModulePath, TransactionOutput},
+};
+use aptos_aggregator::delta_change_set::{delta_add, delta_sub, serialize, DeltaOp, DeltaUpdate};
+use aptos_mvhashmap::MVHashMap;
+use aptos_state_view::{StateViewId, TStateView};
+use aptos_types::{
+    account_address::AccountAddress, state_store::state_storage_usage::StateStorageUsage,
+    write_set::TransactionWrite,
 };
-use aptos_aggregator::delta_change_set::{delta_add, delta_sub, DeltaOp, DeltaUpdate};
-use aptos_types::write_set::TransactionWrite;
 use rand::random;
 use std::{
+    collections::HashMap,
     fmt::Debug,
     hash::Hash,
     marker::PhantomData,
-    sync::{atomic::AtomicUsize, Arc},
+    sync::{atomic::AtomicUsize, Arc, Condvar, Mutex},
+    thread,
+    time::Duration,
 };
 
 fn run_and_assert<K, V>(transactions: Vec<Transaction<K, V>>)
 where
-    K: PartialOrd + Ord + Send + Sync + Clone + Hash + Eq + ModulePath + 'static,
+    K: PartialOrd + Ord + Send + Sync + Clone + Hash + Eq + ModulePath + KeyDebug + 'static,
     V: Send + Sync + Debug + Clone + Eq + TransactionWrite + 'static,
 {
     let data_view = DeltaDataView::<K, V> {
         phantom: PhantomData,
     };
 
-    let output =
-        BlockExecutor::<Transaction<K, V>, Task<K, V>, DeltaDataView<K, V>>::new(num_cpus::get())
-            .execute_transactions_parallel((), &transactions, &data_view)
-            .map(|zipped| zipped.into_iter().map(|(res, _)| res).collect());
+    let output = BlockExecutor::<Transaction<K, V>, Task<K, V>, DeltaDataView<K, V>>::new(
+        num_cpus::get(),
+        None,
+    )
+    .execute_transactions_parallel((), &transactions, &data_view)
+    .map(|(zipped, _)| zipped.into_iter().map(|(res, _)| res).collect());
 
     let baseline = ExpectedOutput::generate_baseline(&transactions, None);
 
@@ -74,6 +95,67 @@ fn delta_counters() {
     run_and_assert(transactions)
 }
 
+#[test]
+fn delta_materialization_failure() {
+    let key = KeyType(random::<[u8; 32]>(), false);
+    // The aggregator is at STORAGE_AGGREGATOR_VALUE in storage, so the delta of the transaction
+    // at index 3 genuinely overflows the limit.
+    let limit = STORAGE_AGGREGATOR_VALUE + 8;
+    let transactions: Vec<_> = (0..5)
+        .map(|idx| {
+            let deltas = if idx == 2 || idx == 3 {
+                vec![(key, delta_add(5, limit))]
+            } else {
+                vec![]
+            };
+            Transaction::Write {
+                incarnation: Arc::new(AtomicUsize::new(0)),
+                reads: vec![vec![]],
+                writes_and_deltas: vec![(
+                    vec![(
+                        KeyType(random::<[u8; 32]>(), false),
+                        ValueType(random::<[u8; 32]>(), true),
+                    )],
+                    deltas,
+                )],
+            }
+        })
+        .collect();
+    let data_view = DeltaDataView::<KeyType<[u8; 32]>, ValueType<[u8; 32]>> {
+        phantom: PhantomData,
+    };
+
+    assert_eq!(
+        GasExecutor::new(num_cpus::get(), None)
+            .execute_transactions_parallel((), &transactions, &data_view)
+            .unwrap_err(),
+        Error::DeltaMaterializationFailure {
+            txn_idx: 3,
+            key: key.key_debug(),
+        }
+    );
+    assert_eq!(
+        GasExecutor::new(1, None)
+            .execute_transactions_sequential((), &transactions, &data_view)
+            .unwrap_err(),
+        Error::DeltaApplicationFailure(3)
+    );
+
+    // Past the end of the block, the delta doesn't matter.
+    let (outputs, summary) = GasExecutor::new(num_cpus::get(), Some(3))
+        .execute_transactions_parallel((), &transactions, &data_view)
+        .unwrap();
+    assert_eq!(
+        summary.commit_reason,
+        BlockCommitReason::BlockGasLimitReached(2)
+    );
+    let delta_writes: Vec<_> = outputs
+        .into_iter()
+        .map(|(_, writes)| writes.len())
+        .collect();
+    assert_eq!(delta_writes, vec![0, 0, 1, 0, 0]);
+}
+
 #[test]
 fn delta_chains() {
     let mut transactions = vec![];
@@ -140,6 +222,142 @@ fn cycle_transactions() {
     run_and_assert(transactions)
 }
 
+fn pool(num_threads: usize) -> Arc<rayon::ThreadPool> {
+    Arc::new(
+        rayon::ThreadPoolBuilder::new()
+            .num_threads(num_threads)
+            .build()
+            .unwrap(),
+    )
+}
+
+/// A transaction reading the given keys, then writing a random value to each of the given keys.
+fn read_write_txn(
+    reads: Vec<KeyType<[u8; 32]>>,
+    writes: &[KeyType<[u8; 32]>],
+) -> Transaction<KeyType<[u8; 32]>, ValueType<[u8; 32]>> {
+    Transaction::Write {
+        incarnation: Arc::new(AtomicUsize::new(0)),
+        reads: vec![reads],
+        writes_and_deltas: vec![(
+            writes
+                .iter()
+                .map(|key| (*key, ValueType(random::<[u8; 32]>(), true)))
+                .collect(),
+            vec![],
+        )],
+    }
+}
+
+/// A transaction slow to execute (it reads slow keys) writing the hot key, followed by one
+/// reading the hot key, which in parallel likely executes before the write and gets aborted.
+fn slow_write_then_read() -> Vec<Transaction<KeyType<[u8; 32]>, ValueType<[u8; 32]>>> {
+    let hot_key = KeyType(random::<[u8; 32]>(), false);
+    let slow_keys: Vec<_> = (0..20)
+        .map(|_| KeyType(random::<[u8; 32]>(), false))
+        .collect();
+    vec![
+        read_write_txn(slow_keys, &[hot_key]),
+        read_write_txn(vec![hot_key], &[]),
+    ]
+}
+
+/// A transaction writing the hot key, followed by `num_readers` transactions reading it. The
+/// returned view holds the first transaction back until one of the others read the hot
+/// key from storage, so in parallel that one is always aborted.
+fn gated_write_then_reads(
+    num_readers: usize,
+) -> (
+    Vec<Transaction<KeyType<[u8; 32]>, ValueType<[u8; 32]>>>,
+    CountingDataView,
+) {
+    let hot_key = KeyType(random::<[u8; 32]>(), false);
+    let gate_key = KeyType(random::<[u8; 32]>(), false);
+    let transactions = std::iter::once(read_write_txn(vec![gate_key], &[hot_key]))
+        .chain((0..num_readers).map(|_| read_write_txn(vec![hot_key], &[])))
+        .collect();
+    (transactions, CountingDataView::with_gate(gate_key, hot_key))
+}
+
+/// Transactions all reading a few keys and writing one of them, so that each depends on the
+/// ones right before it.
+fn contended_transactions(
+    num_txns: usize,
+) -> Vec<Transaction<KeyType<[u8; 32]>, ValueType<Vec<u8>>>> {
+    let keys: Vec<_> = (0..4)
+        .map(|_| KeyType(random::<[u8; 32]>(), false))
+        .collect();
+    (0..num_txns)
+        .map(|idx| Transaction::Write {
+            incarnation: Arc::new(AtomicUsize::new(0)),
+            reads: vec![keys.clone()],
+            writes_and_deltas: vec![(vec![(keys[idx % keys.len()], random_value(false))], vec![])],
+        })
+        .collect()
+}
+
+fn run_on_pool_and_assert(
+    transactions: &Vec<Transaction<KeyType<[u8; 32]>, ValueType<Vec<u8>>>>,
+    executor_pool: Arc<rayon::ThreadPool>,
+) {
+    let data_view = DeltaDataView::<KeyType<[u8; 32]>, ValueType<Vec<u8>>> {
+        phantom: PhantomData,
+    };
+    let output = BlockExecutor::<
+        Transaction<KeyType<[u8; 32]>, ValueType<Vec<u8>>>,
+        Task<KeyType<[u8; 32]>, ValueType<Vec<u8>>>,
+        DeltaDataView<KeyType<[u8; 32]>, ValueType<Vec<u8>>>,
+    >::new_with_pool(executor_pool.current_num_threads(), None, executor_pool)
+    .execute_transactions_parallel((), transactions, &data_view)
+    .map(|(zipped, _)| zipped.into_iter().map(|(res, _)| res).collect());
+
+    ExpectedOutput::generate_baseline(transactions, None).assert_output(&output);
+}
+
+#[test]
+fn executor_on_custom_pool() {
+    run_on_pool_and_assert(&contended_transactions(200), pool(2));
+}
+
+#[test]
+fn executors_on_separate_pools() {
+    let handles: Vec<_> = (0..2)
+        .map(|_| {
+            thread::spawn(|| {
+                let executor_pool = pool(2);
+                for _ in 0..10 {
+                    run_on_pool_and_assert(&contended_transactions(200), executor_pool.clone());
+                }
+            })
+        })
+        .collect();
+    for handle in handles {
+        handle.join().unwrap();
+    }
+}
+
+#[test]
+fn large_block_outputs_in_order() {
+    // Enough transactions for the outputs to be taken by many tasks.
+    run_on_pool_and_assert(&contended_transactions(10_000), pool(4));
+}
+
+#[test]
+fn large_block_skip_rest() {
+    let mut transactions = contended_transactions(10_000);
+    transactions[7_000] = Transaction::SkipRest;
+    transactions[9_000] = Transaction::SkipRest;
+    run_on_pool_and_assert(&transactions, pool(4));
+}
+
+#[test]
+fn large_block_first_abort() {
+    let mut transactions = contended_transactions(10_000);
+    transactions[3_000] = Transaction::Abort;
+    transactions[6_000] = Transaction::Abort;
+    run_on_pool_and_assert(&transactions, pool(4));
+}
+
 const NUM_BLOCKS: u64 = 10;
 const TXN_PER_BLOCK: u64 = 100;
 
@@ -238,6 +456,455 @@ fn early_skips() {
     run_and_assert(transactions)
 }
 
+/// A slow base view that records how many times each key was read.
+#[derive(Default)]
+struct CountingDataView {
+    reads: Mutex<HashMap<KeyType<[u8; 32]>, usize>>,
+    read: Condvar,
+    // Reads of the first key wait until the second one was read, see `with_gate`.
+    gate: Option<(KeyType<[u8; 32]>, KeyType<[u8; 32]>)>,
+}
+
+impl CountingDataView {
+    /// Reads of `gate_key` wait until `awaited_key` was read (or a few seconds passed, for a
+    /// broken test to fail rather than hang), to order the execution of transactions.
+    fn with_gate(gate_key: KeyType<[u8; 32]>, awaited_key: KeyType<[u8; 32]>) -> Self {
+        Self {
+            gate: Some((gate_key, awaited_key)),
+            ..Self::default()
+        }
+    }
+
+    fn num_reads(&self, keys: &[KeyType<[u8; 32]>]) -> usize {
+        let reads = self.reads.lock().unwrap();
+        keys.iter()
+            .map(|k| reads.get(k).copied().unwrap_or(0))
+            .sum()
+    }
+}
+
+impl TStateView for CountingDataView {
+    type Key = KeyType<[u8; 32]>;
+
+    fn get_state_value(&self, key: &Self::Key) -> anyhow::Result<Option<Vec<u8>>> {
+        let mut reads = self.reads.lock().unwrap();
+        if let Some((gate_key, awaited_key)) = &self.gate {
+            if key == gate_key {
+                reads = self
+                    .read
+                    .wait_timeout_while(reads, Duration::from_secs(10), |reads| {
+                        !reads.contains_key(awaited_key)
+                    })
+                    .unwrap()
+                    .0;
+            }
+        }
+        *reads.entry(*key).or_insert(0) += 1;
+        drop(reads);
+        self.read.notify_all();
+        thread::sleep(Duration::from_millis(1));
+        Ok(Some(key.0.to_vec()))
+    }
+
+    fn id(&self) -> StateViewId {
+        StateViewId::Miscellaneous
+    }
+
+    fn is_genesis(&self) -> bool {
+        unreachable!();
+    }
+
+    fn get_usage(&self) -> anyhow::Result<StateStorageUsage> {
+        unreachable!();
+    }
+}
+
+type CountingExecutor = BlockExecutor<
+    Transaction<KeyType<[u8; 32]>, ValueType<[u8; 32]>>,
+    Task<KeyType<[u8; 32]>, ValueType<[u8; 32]>>,
+    CountingDataView,
+>;
+
+/// Every transaction reads all the (never written) `hot_keys` and writes its own key.
+fn hot_read_transactions(
+    hot_keys: &[KeyType<[u8; 32]>],
+) -> Vec<Transaction<KeyType<[u8; 32]>, ValueType<[u8; 32]>>> {
+    (0..TXN_PER_BLOCK)
+        .map(|_| read_write_txn(hot_keys.to_vec(), &[KeyType(random::<[u8; 32]>(), false)]))
+        .collect()
+}
+
+#[test]
+fn prewarmed_keys_not_read_during_execution() {
+    let hot_keys: Vec<_> = (0..10)
+        .map(|_| KeyType(random::<[u8; 32]>(), false))
+        .collect();
+    let transactions = hot_read_transactions(&hot_keys);
+    let data_view = CountingDataView::default();
+    let cache = Arc::new(BaseViewCache::new(1000));
+
+    cache.prewarm(&hot_keys, &data_view);
+    assert_eq!(data_view.num_reads(&hot_keys), hot_keys.len());
+
+    for concurrency_level in [1, num_cpus::get()] {
+        let executor =
+            CountingExecutor::new_with_base_view_cache(concurrency_level, None, cache.clone());
+        executor
+            .execute_block((), transactions.clone(), &data_view)
+            .unwrap();
+        // All the reads of the hot keys were served by the cache.
+        assert_eq!(data_view.num_reads(&hot_keys), hot_keys.len());
+    }
+
+    // After invalidation, the hot keys have to be read from the base view again.
+    let executor = CountingExecutor::new_with_base_view_cache(num_cpus::get(), None, cache.clone());
+    executor.invalidate_base_view_cache();
+    assert!(cache.is_empty());
+    executor
+        .execute_block((), transactions, &data_view)
+        .unwrap();
+    assert!(data_view.num_reads(&hot_keys) > hot_keys.len());
+}
+
+#[test]
+fn block_read_cache() {
+    let hot_key = KeyType(random::<[u8; 32]>(), false);
+    let transactions = hot_read_transactions(&[hot_key]);
+    let data_view = CountingDataView::default();
+
+    let executor = CountingExecutor::new(num_cpus::get(), None).with_block_read_cache(true);
+    let outputs = executor
+        .execute_transactions_parallel((), &transactions, &data_view)
+        .unwrap()
+        .0;
+    assert_eq!(outputs.len(), transactions.len());
+    // Every transaction read the hot key, from storage once.
+    assert_eq!(data_view.num_reads(&[hot_key]), 1);
+
+    // The cache doesn't outlive the block.
+    executor
+        .execute_transactions_parallel((), &transactions, &data_view)
+        .unwrap();
+    assert_eq!(data_view.num_reads(&[hot_key]), 2);
+
+    // Without it (the default), the hot key is read by every transaction.
+    CountingExecutor::new(num_cpus::get(), None)
+        .execute_transactions_parallel((), &transactions, &data_view)
+        .unwrap();
+    assert!(data_view.num_reads(&[hot_key]) >= 2 + transactions.len());
+}
+
+#[test]
+fn async_prewarm() {
+    let hot_keys: Vec<_> = (0..10)
+        .map(|_| KeyType(random::<[u8; 32]>(), false))
+        .collect();
+    let data_view = Arc::new(CountingDataView::default());
+    let cache = Arc::new(BaseViewCache::new(1000));
+    let executor = CountingExecutor::new_with_base_view_cache(num_cpus::get(), None, cache.clone());
+
+    executor.prewarm(hot_keys.clone(), data_view.clone());
+    while cache.len() < hot_keys.len() {
+        thread::sleep(Duration::from_millis(1));
+    }
+    assert_eq!(data_view.num_reads(&hot_keys), hot_keys.len());
+}
+
+#[test]
+fn imbalanced_block_worker_skew() {
+    // All the transactions are trivial, except for the last one which reads many slow keys.
+    let slow_keys: Vec<_> = (0..100)
+        .map(|_| KeyType(random::<[u8; 32]>(), false))
+        .collect();
+    let mut transactions = hot_read_transactions(&[]);
+    transactions.extend(hot_read_transactions(&slow_keys).into_iter().take(1));
+    let data_view = CountingDataView::default();
+
+    let (_, _, metrics) = CountingExecutor::new(num_cpus::get(), None)
+        .execute_transactions_parallel_with_metrics((), &transactions, &data_view)
+        .unwrap();
+    let skew = metrics.worker_skew;
+    assert_eq!(skew.utilization.len(), num_cpus::get());
+    assert_eq!(skew.min_finish_skew, Duration::ZERO);
+    assert!(skew.median_finish_skew <= skew.max_finish_skew);
+    assert!(
+        skew.utilization.iter().all(|u| (0.0..=1.0).contains(u)),
+        "{:?}",
+        skew
+    );
+    // Some worker executed the slow transaction.
+    assert!(skew.utilization.iter().any(|u| *u > 0.0), "{:?}", skew);
+}
+
+#[test]
+fn reexecutions_bucketed_by_position() {
+    // Transactions are independent, except for those of the last decile that read the hot key
+    // written by the first of them. That one is held back until another read the hot key, which
+    // then gets re-executed.
+    let hot_key = KeyType(random::<[u8; 32]>(), false);
+    let gate_key = KeyType(random::<[u8; 32]>(), false);
+    let mut transactions = hot_read_transactions(&[]);
+    let num_txns = transactions.len();
+    let first_hot_txn = num_txns * 9 / 10;
+    for (idx, txn) in transactions.iter_mut().enumerate().skip(first_hot_txn) {
+        *txn = if idx == first_hot_txn {
+            read_write_txn(vec![gate_key], &[hot_key])
+        } else {
+            read_write_txn(vec![hot_key], &[])
+        };
+    }
+    let data_view = CountingDataView::with_gate(gate_key, hot_key);
+
+    let (_, _, metrics) = CountingExecutor::new_with_pool(4, None, pool(4))
+        .execute_transactions_parallel_with_metrics((), &transactions, &data_view)
+        .unwrap();
+    let buckets = metrics.reexecutions_by_position;
+    assert_eq!(buckets[..9], [0; 9]);
+    assert!(buckets[9] > 0, "{:?}", buckets);
+}
+
+#[test]
+fn summary_with_speculative_abort() {
+    let (transactions, gated_view) = gated_write_then_reads(1);
+    let data_view = CountingDataView::default();
+
+    let (sequential, summary) = CountingExecutor::new(1, None)
+        .execute_transactions_sequential((), &transactions, &data_view)
+        .unwrap();
+    assert_eq!(summary.speculative_aborts, 0);
+    assert_eq!(summary.max_incarnation, 0);
+
+    let (parallel, summary) = CountingExecutor::new_with_pool(2, None, pool(2))
+        .execute_transactions_parallel((), &transactions, &gated_view)
+        .unwrap();
+    assert_eq!(parallel, sequential);
+    assert_eq!(summary.num_committed_txns, 2);
+    assert_eq!(summary.execution_gas, 2);
+    assert_eq!(summary.storage_fee, 1);
+    assert_eq!(summary.commit_reason, BlockCommitReason::EndOfBlock);
+    assert_eq!(summary.first_uncommitted_txn_idx(), None);
+    // The second transaction read the hot key before it was written, and is aborted once.
+    assert_eq!(summary.speculative_aborts, 1, "{:?}", summary);
+    assert_eq!(summary.max_incarnation, 1);
+}
+
+#[test]
+fn cancel_in_flight_execution() {
+    // Every transaction reads slow keys, then reads and writes the hot key, so the block takes
+    // seconds and the transactions wait on each other.
+    let hot_key = KeyType(random::<[u8; 32]>(), false);
+    let slow_keys: Vec<_> = (0..10_000)
+        .map(|_| KeyType(random::<[u8; 32]>(), false))
+        .collect();
+    let transactions: Vec<_> = slow_keys
+        .chunks(10)
+        .map(|keys| {
+            let mut reads = keys.to_vec();
+            reads.push(hot_key);
+            read_write_txn(reads, &[hot_key])
+        })
+        .collect();
+    let data_view = CountingDataView::default();
+    let executor = CountingExecutor::new_with_pool(4, None, pool(4));
+    let handle = executor.executor_handle();
+
+    let canceller = thread::spawn(move || {
+        thread::sleep(Duration::from_millis(50));
+        handle.cancel();
+    });
+    let result = executor.execute_transactions_parallel((), &transactions, &data_view);
+    canceller.join().unwrap();
+    assert_eq!(result.unwrap_err(), Error::ExecutionCancelled);
+    // The execution stopped before reaching the end of the block.
+    assert_eq!(data_view.num_reads(&slow_keys[slow_keys.len() - 10..]), 0);
+
+    // The cancellation holds until the next block.
+    assert!(executor.executor_handle().is_cancelled());
+    assert_eq!(
+        executor
+            .execute_transactions_sequential((), &transactions, &data_view)
+            .unwrap_err(),
+        Error::ExecutionCancelled
+    );
+    let outputs = executor
+        .execute_block((), transactions[..10].to_vec(), &data_view)
+        .unwrap();
+    assert_eq!(outputs.len(), 10);
+    assert!(!executor.executor_handle().is_cancelled());
+}
+
+/// Transactions all reading and then writing the same key, the first of them being slow to
+/// execute, so that the others execute before its write and get aborted.
+fn conflicting_transactions(
+    num_txns: usize,
+) -> Vec<Transaction<KeyType<[u8; 32]>, ValueType<[u8; 32]>>> {
+    let hot_key = KeyType(random::<[u8; 32]>(), false);
+    (0..num_txns)
+        .map(|idx| {
+            let mut reads = vec![hot_key];
+            if idx == 0 {
+                reads.extend((0..20).map(|_| KeyType(random::<[u8; 32]>(), false)));
+            }
+            read_write_txn(reads, &[hot_key])
+        })
+        .collect()
+}
+
+#[test]
+fn block_metrics_of_conflicting_block() {
+    let data_view = CountingDataView::default();
+    for transactions in [slow_write_then_read(), conflicting_transactions(50)] {
+        let (_, summary, metrics) = CountingExecutor::new_with_pool(4, None, pool(4))
+            .execute_transactions_parallel_with_metrics((), &transactions, &data_view)
+            .unwrap();
+        // Every transaction is committed once, with its last incarnation.
+        assert_eq!(
+            summary.committed_incarnations.iter().sum::<usize>(),
+            transactions.len()
+        );
+        assert_eq!(
+            summary.committed_incarnations.len(),
+            summary.max_incarnation + 1
+        );
+        assert!(summary.validation_failures >= summary.speculative_aborts);
+        assert_eq!(metrics.committed_incarnations, summary.committed_incarnations);
+        assert_eq!(metrics.dependency_waits, summary.dependency_waits);
+        assert_eq!(metrics.dependency_wait, summary.dependency_wait);
+        assert_eq!(metrics.validation_failures, summary.validation_failures);
+    }
+
+    // Sequential execution has every transaction committed with its first incarnation.
+    let transactions = conflicting_transactions(50);
+    let (_, summary) = CountingExecutor::new(1, None)
+        .execute_transactions_sequential((), &transactions, &data_view)
+        .unwrap();
+    assert_eq!(summary.committed_incarnations, vec![transactions.len()]);
+    assert_eq!(summary.dependency_waits, 0);
+    assert_eq!(summary.validation_failures, 0);
+}
+
+#[test]
+fn incarnation_limit_fallback() {
+    // The first abort of the reader reaches the limit.
+    let (transactions, gated_view) = gated_write_then_reads(10);
+    let data_view = CountingDataView::default();
+    let executor = CountingExecutor::new_with_pool(4, None, pool(4)).with_max_incarnations(0);
+
+    let fallbacks = INCARNATION_LIMIT_FALLBACK_COUNT.get();
+    assert_eq!(
+        executor
+            .execute_transactions_parallel((), &transactions, &gated_view)
+            .unwrap_err(),
+        Error::TooManyIncarnations
+    );
+    assert!(INCARNATION_LIMIT_FALLBACK_COUNT.get() > fallbacks);
+
+    // The block is executed sequentially instead.
+    let (sequential, _) = CountingExecutor::new(1, None)
+        .execute_transactions_sequential((), &transactions, &data_view)
+        .unwrap();
+    assert_eq!(
+        executor
+            .execute_block((), transactions.clone(), &data_view)
+            .unwrap(),
+        sequential
+    );
+
+    // Within the default limit, the block is executed in parallel.
+    let (transactions, gated_view) = gated_write_then_reads(10);
+    let (sequential, _) = CountingExecutor::new(1, None)
+        .execute_transactions_sequential((), &transactions, &data_view)
+        .unwrap();
+    let (parallel, summary) = CountingExecutor::new_with_pool(4, None, pool(4))
+        .execute_transactions_parallel((), &transactions, &gated_view)
+        .unwrap();
+    assert!(summary.speculative_aborts > 0);
+    assert_eq!(parallel, sequential);
+}
+
+#[test]
+fn validation_priority_window_matches_sequential() {
+    let transactions = conflicting_transactions(100);
+    let data_view = CountingDataView::default();
+    let (sequential, _) = CountingExecutor::new(1, None)
+        .execute_transactions_sequential((), &transactions, &data_view)
+        .unwrap();
+
+    for window in [0, 1, 100] {
+        let (parallel, _) = CountingExecutor::new_with_pool(4, None, pool(4))
+            .with_validation_priority_window(window)
+            .execute_transactions_parallel((), &transactions, &data_view)
+            .unwrap();
+        assert_eq!(parallel, sequential);
+    }
+}
+
+#[test]
+fn module_publishing_fallback_in_execute_block() {
+    // The first transaction publishes a module that a later one reads, among transactions
+    // reading and writing their own keys.
+    let module = KeyType(random::<[u8; 32]>(), true);
+    let mut transactions: Vec<_> = (0..100)
+        .map(|_| {
+            let key = KeyType(random::<[u8; 32]>(), false);
+            read_write_txn(vec![key], &[key])
+        })
+        .collect();
+    transactions[0] = read_write_txn(vec![], &[module]);
+    transactions[50] = read_write_txn(vec![module], &[]);
+    let data_view = CountingDataView::default();
+    let executor = CountingExecutor::new_with_pool(4, None, pool(4));
+
+    let fallbacks = MODULE_PUBLISHING_FALLBACK_COUNT.get();
+    assert_eq!(
+        executor
+            .execute_transactions_parallel((), &transactions, &data_view)
+            .unwrap_err(),
+        Error::ModulePathReadWrite
+    );
+    assert!(MODULE_PUBLISHING_FALLBACK_COUNT.get() > fallbacks);
+
+    // The block is executed sequentially instead, the same as with a concurrency level of 1.
+    let (sequential, _) = CountingExecutor::new(1, None)
+        .execute_transactions_sequential((), &transactions, &data_view)
+        .unwrap();
+    assert_eq!(
+        executor
+            .execute_block((), transactions.clone(), &data_view)
+            .unwrap(),
+        sequential
+    );
+    assert_eq!(
+        CountingExecutor::new(1, None)
+            .execute_block((), transactions, &data_view)
+            .unwrap(),
+        sequential
+    );
+}
+
+#[test]
+fn base_view_cache_bounded() {
+    let keys: Vec<_> = (0..10)
+        .map(|_| KeyType(random::<[u8; 32]>(), false))
+        .collect();
+    let data_view = CountingDataView::default();
+    let cache = BaseViewCache::new(5);
+
+    for key in &keys {
+        cache.get_state_value(key, &data_view).unwrap();
+    }
+    assert_eq!(cache.len(), 5);
+
+    // Keys that didn't fit are still served, but always from the base view.
+    for key in &keys {
+        assert_eq!(
+            cache.get_state_value(key, &data_view).unwrap(),
+            Some(key.0.to_vec())
+        );
+    }
+    assert_eq!(data_view.num_reads(&keys), 15);
+}
+
 #[test]
 fn scheduler_tasks() {
     let s = Scheduler::new(6);
@@ -630,6 +1297,75 @@ fn test_rolling_commit_wave() {
     assert!(matches!(s.next_task(false), SchedulerTask::Done));
 }
 
+#[test]
+fn test_no_commit_after_halt() {
+    let s = Scheduler::new(2);
+
+    assert!(matches!(
+        s.next_task(false),
+        SchedulerTask::ExecutionTask((0, 0), None)
+    ));
+    assert!(matches!(
+        s.finish_execution(0, 0, false),
+        SchedulerTask::NoTask
+    ));
+    s.finish_validation(0, 0);
+    assert_eq!(s.try_commit(), Some(0));
+
+    assert!(matches!(
+        s.next_task(false),
+        SchedulerTask::ExecutionTask((1, 0), None)
+    ));
+    s.finish_execution(1, 0, false);
+    s.finish_validation(1, 0);
+
+    // txn 1 could be committed, but the block ended at txn 0.
+    s.halt();
+    assert!(s.try_commit().is_none());
+    assert!(matches!(s.commit_state(), (1, 0)));
+    assert!(matches!(s.next_task(false), SchedulerTask::Done));
+    assert_eq!(s.commit_boundary(), (BlockCommitReason::EndOfBlock, 1));
+}
+
+#[test]
+fn test_commit_boundary() {
+    let s = Scheduler::new(2);
+    for txn_idx in 0..2 {
+        assert!(matches!(
+            s.next_task(false),
+            SchedulerTask::ExecutionTask((idx, 0), None) if idx == txn_idx
+        ));
+        s.finish_execution(txn_idx, 0, false);
+        s.finish_validation(txn_idx, 0);
+        assert_eq!(s.try_commit(), Some(txn_idx));
+    }
+    assert!(s.try_commit().is_none());
+    assert_eq!(s.commit_boundary(), (BlockCommitReason::EndOfBlock, 2));
+
+    // The block ends at txn 0, txn 1 is left uncommitted.
+    let s = Scheduler::new(2);
+    assert!(matches!(
+        s.next_task(false),
+        SchedulerTask::ExecutionTask((0, 0), None)
+    ));
+    s.finish_execution(0, 0, false);
+    s.finish_validation(0, 0);
+    assert_eq!(s.try_commit(), Some(0));
+    s.end_block(BlockCommitReason::BlockGasLimitReached(0));
+    assert!(s.try_commit().is_none());
+    assert!(matches!(s.next_task(false), SchedulerTask::Done));
+    assert_eq!(
+        s.commit_boundary(),
+        (BlockCommitReason::BlockGasLimitReached(0), 1)
+    );
+
+    // A cancellation stops the scheduler before anything is committed.
+    let s = Scheduler::new(2);
+    s.end_block(BlockCommitReason::Cancelled);
+    assert!(matches!(s.next_task(false), SchedulerTask::Done));
+    assert_eq!(s.commit_boundary(), (BlockCommitReason::Cancelled, 0));
+}
+
 #[test]
 fn test_rolling_commit_wave_update() {
     let s = Scheduler::new(2);
@@ -691,3 +1427,437 @@ fn test_rolling_commit_wave_update() {
     assert!(s.try_commit().is_none());
     assert!(matches!(s.next_task(false), SchedulerTask::Done));
 }
+
+#[test]
+fn scheduler_priority_validation() {
+    for window in [0, 2] {
+        let s = Scheduler::new_with_validation_priority_window(3, window);
+
+        for i in 0..3 {
+            assert!(matches!(
+                s.next_task(false),
+                SchedulerTask::ExecutionTask((j, 0), None) if i == j
+            ));
+        }
+
+        // Validation index is at 2, so txn 2 is validated by the wavefront.
+        assert!(matches!(
+            s.finish_execution(2, 0, false),
+            SchedulerTask::NoTask
+        ));
+        // The validation tasks returned for txns 1 and 0 are dropped (as if the threads
+        // that got them were slow), only the prioritized validations may pick them up.
+        assert!(matches!(
+            s.finish_execution(1, 0, false),
+            SchedulerTask::ValidationTask((1, 0), 0)
+        ));
+        assert!(matches!(
+            s.next_task(false),
+            SchedulerTask::ValidationTask((2, 0), 0)
+        ));
+        assert!(matches!(
+            s.finish_execution(0, 0, false),
+            SchedulerTask::ValidationTask((0, 0), 0)
+        ));
+
+        if window == 0 {
+            assert!(matches!(s.next_task(false), SchedulerTask::NoTask));
+            continue;
+        }
+
+        // The txn at the commit index gets validated first, and only once.
+        assert!(matches!(
+            s.next_task(false),
+            SchedulerTask::ValidationTask((0, 0), 0)
+        ));
+        assert!(matches!(s.next_task(false), SchedulerTask::NoTask));
+        s.finish_validation(0, 0);
+        assert!(matches!(
+            s.next_task(false),
+            SchedulerTask::ValidationTask((1, 0), 0)
+        ));
+        s.finish_validation(1, 0);
+        s.finish_validation(2, 0);
+
+        // All validations happened and all txns get committed.
+        for i in 0..3 {
+            assert_eq!(s.try_commit(), Some(i));
+        }
+        assert!(matches!(s.next_task(false), SchedulerTask::NoTask));
+        assert_eq!(s.try_commit(), None);
+        assert!(matches!(s.next_task(false), SchedulerTask::Done));
+    }
+}
+
+type GasExecutor = BlockExecutor<
+    Transaction<KeyType<[u8; 32]>, ValueType<[u8; 32]>>,
+    Task<KeyType<[u8; 32]>, ValueType<[u8; 32]>>,
+    DeltaDataView<KeyType<[u8; 32]>, ValueType<[u8; 32]>>,
+>;
+
+#[test]
+fn skip_rest_and_gas_limit_cut() {
+    let num_txns = 20;
+    // A small universe, so that transactions conflict and get re-executed.
+    let universe: Vec<_> = (0..4)
+        .map(|_| KeyType(random::<[u8; 32]>(), false))
+        .collect();
+    let data_view = DeltaDataView::<KeyType<[u8; 32]>, ValueType<[u8; 32]>> {
+        phantom: PhantomData,
+    };
+
+    // Every executed transaction uses 1 gas, so the gas limit is reached at index limit - 1.
+    // Covers SkipRest before, at and after the gas cut, as well as only one (or none) of them.
+    for maybe_skip_at in std::iter::once(None).chain((0..num_txns).map(Some)) {
+        for maybe_gas_limit in std::iter::once(None).chain((1..=num_txns as u64 + 1).map(Some)) {
+            let transactions: Vec<_> = (0..num_txns)
+                .map(|idx| {
+                    if maybe_skip_at == Some(idx) {
+                        return Transaction::SkipRest;
+                    }
+                    read_write_txn(
+                        vec![universe[idx % 4], universe[(idx + 1) % 4]],
+                        &[universe[idx % 4]],
+                    )
+                })
+                .collect();
+
+            let gas_cut = maybe_gas_limit
+                .map(|gas_limit| gas_limit as usize - 1)
+                .filter(|idx| *idx < num_txns);
+            let expected_reason = match (maybe_skip_at, gas_cut) {
+                (Some(skip_at), Some(gas_cut)) if skip_at <= gas_cut => {
+                    BlockCommitReason::SkipRest(skip_at)
+                },
+                (_, Some(gas_cut)) => BlockCommitReason::BlockGasLimitReached(gas_cut),
+                (Some(skip_at), None) => BlockCommitReason::SkipRest(skip_at),
+                (None, None) => BlockCommitReason::EndOfBlock,
+            };
+            let num_kept = match expected_reason {
+                BlockCommitReason::EndOfBlock => num_txns,
+                BlockCommitReason::SkipRest(idx) | BlockCommitReason::BlockGasLimitReached(idx) => {
+                    idx + 1
+                },
+                BlockCommitReason::Cancelled => unreachable!(),
+            };
+
+            let (parallel_output, parallel_summary) =
+                GasExecutor::new(num_cpus::get(), maybe_gas_limit)
+                    .execute_transactions_parallel((), &transactions, &data_view)
+                    .unwrap();
+            let (sequential_output, sequential_summary) = GasExecutor::new(1, maybe_gas_limit)
+                .execute_transactions_sequential((), &transactions, &data_view)
+                .unwrap();
+
+            assert_eq!(parallel_summary.commit_reason, expected_reason);
+            assert_eq!(sequential_summary.commit_reason, expected_reason);
+
+            let parallel_output: Vec<_> = parallel_output.into_iter().map(|(o, _)| o).collect();
+            let sequential_output: Vec<_> = sequential_output.into_iter().map(|(o, _)| o).collect();
+            assert_eq!(parallel_output, sequential_output);
+            // Only the transactions up to the cut are kept, the rest get the skip output.
+            assert!(parallel_output
+                .iter()
+                .enumerate()
+                .all(|(idx, output)| output.execution_gas_used() == (idx < num_kept) as u64));
+        }
+    }
+}
+
+#[test]
+fn gas_limit_counts_execution_gas_only() {
+    let num_txns = 10;
+    let num_writes = 3;
+    let data_view = DeltaDataView::<KeyType<[u8; 32]>, ValueType<[u8; 32]>> {
+        phantom: PhantomData,
+    };
+    // Every transaction uses 1 execution gas, plus a storage fee of 1 per write.
+    let transactions: Vec<_> = (0..num_txns)
+        .map(|_| {
+            let writes: Vec<_> = (0..num_writes)
+                .map(|_| KeyType(random::<[u8; 32]>(), false))
+                .collect();
+            read_write_txn(vec![], &writes)
+        })
+        .collect();
+
+    // Counting the total gas would cut the block at the second transaction already.
+    let gas_limit = 5;
+    let (parallel_output, parallel_summary, metrics) =
+        GasExecutor::new(num_cpus::get(), Some(gas_limit))
+            .execute_transactions_parallel_with_metrics((), &transactions, &data_view)
+            .unwrap();
+    let (sequential_output, sequential_summary) = GasExecutor::new(1, Some(gas_limit))
+        .execute_transactions_sequential((), &transactions, &data_view)
+        .unwrap();
+
+    assert_eq!(
+        parallel_summary.commit_reason,
+        BlockCommitReason::BlockGasLimitReached(4)
+    );
+    assert_eq!(
+        sequential_summary.commit_reason,
+        parallel_summary.commit_reason
+    );
+    let parallel_output: Vec<_> = parallel_output.into_iter().map(|(o, _)| o).collect();
+    let sequential_output: Vec<_> = sequential_output.into_iter().map(|(o, _)| o).collect();
+    assert_eq!(parallel_output, sequential_output);
+    assert_eq!(parallel_output[4].gas_used(), 1 + num_writes as u64);
+    assert_eq!(parallel_output[5].gas_used(), 0);
+
+    assert_eq!(metrics.execution_gas, gas_limit);
+    assert_eq!(metrics.storage_fee, gas_limit * num_writes as u64);
+}
+
+#[test]
+fn execute_block_gas_limit_same_prefix() {
+    // A small universe, so that transactions conflict and get re-executed in parallel.
+    let universe: Vec<_> = (0..4)
+        .map(|_| KeyType(random::<[u8; 32]>(), false))
+        .collect();
+    let data_view = DeltaDataView::<KeyType<[u8; 32]>, ValueType<[u8; 32]>> {
+        phantom: PhantomData,
+    };
+    let transactions: Vec<_> = (0..100)
+        .map(|idx| {
+            read_write_txn(
+                vec![universe[idx % 4], universe[(idx + 1) % 4]],
+                &[universe[idx % 4]],
+            )
+        })
+        .collect();
+
+    // Whichever mode the executor picks, the block is cut at the same transaction.
+    let gas_limit = 17;
+    let sequential: Vec<_> = GasExecutor::new(1, Some(gas_limit))
+        .execute_block((), transactions.clone(), &data_view)
+        .unwrap()
+        .into_iter()
+        .map(|(output, _)| output)
+        .collect();
+    let parallel: Vec<_> = GasExecutor::new(num_cpus::get(), Some(gas_limit))
+        .execute_block((), transactions, &data_view)
+        .unwrap()
+        .into_iter()
+        .map(|(output, _)| output)
+        .collect();
+    assert_eq!(parallel, sequential);
+    assert!(parallel
+        .iter()
+        .enumerate()
+        .all(|(idx, output)| output.execution_gas_used() == (idx < gas_limit as usize) as u64));
+}
+
+#[test]
+fn summary_at_gas_limit() {
+    let data_view = DeltaDataView::<KeyType<[u8; 32]>, ValueType<[u8; 32]>> {
+        phantom: PhantomData,
+    };
+    // Independent transactions, each using 1 execution gas and writing its own key.
+    let transactions: Vec<_> = (0..10)
+        .map(|_| read_write_txn(vec![], &[KeyType(random::<[u8; 32]>(), false)]))
+        .collect();
+
+    let (_, parallel_summary) = GasExecutor::new(num_cpus::get(), Some(3))
+        .execute_transactions_parallel((), &transactions, &data_view)
+        .unwrap();
+    let (_, sequential_summary) = GasExecutor::new(1, Some(3))
+        .execute_transactions_sequential((), &transactions, &data_view)
+        .unwrap();
+
+    let expected = BlockExecutionSummary {
+        num_committed_txns: 3,
+        execution_gas: 3,
+        storage_fee: 3,
+        speculative_aborts: 0,
+        max_incarnation: 0,
+        committed_incarnations: vec![3],
+        dependency_waits: 0,
+        dependency_wait: Duration::ZERO,
+        validation_failures: 0,
+        commit_reason: BlockCommitReason::BlockGasLimitReached(2),
+    };
+    assert_eq!(parallel_summary, expected);
+    assert_eq!(sequential_summary, expected);
+    assert_eq!(expected.first_uncommitted_txn_idx(), Some(3));
+
+    for concurrency_level in [1, num_cpus::get()] {
+        let (outputs, summary) = GasExecutor::new(concurrency_level, Some(3))
+            .execute_block_with_summary((), transactions.clone(), &data_view)
+            .unwrap();
+        assert_eq!(outputs.len(), transactions.len());
+        assert_eq!(summary, expected);
+    }
+}
+
+type OrderedTransaction = Transaction<KeyType<[u8; 32]>, ValueType<[u8; 32]>>;
+
+/// The sender of a transaction is the key of its first write, without writes it has none.
+fn sender_grouping() -> Arc<dyn BlockOrderingHook<OrderedTransaction>> {
+    Arc::new(SenderGrouping::new(|txn: &OrderedTransaction| match txn {
+        Transaction::Write {
+            writes_and_deltas, ..
+        } => writes_and_deltas[0]
+            .0
+            .first()
+            .map(|(key, _)| AccountAddress::new(key.0)),
+        _ => None,
+    }))
+}
+
+/// Transactions of interleaved senders, each reading and writing the keys of its sender only,
+/// with a transaction reading the keys of all the senders every so often.
+fn interleaved_senders_block(num_txns: usize) -> Vec<OrderedTransaction> {
+    let senders: Vec<[u8; 32]> = (0..8).map(|_| random::<[u8; 32]>()).collect();
+    (0..num_txns)
+        .map(|idx| {
+            if idx % 50 == 25 {
+                return read_write_txn(senders.iter().map(|s| KeyType(*s, false)).collect(), &[]);
+            }
+            let sender = senders[random::<usize>() % senders.len()];
+            let mut second_key = sender;
+            second_key[0] ^= 1;
+            let keys = [KeyType(sender, false), KeyType(second_key, false)];
+            read_write_txn(keys.to_vec(), &keys)
+        })
+        .collect()
+}
+
+fn execute_block(
+    transactions: &[OrderedTransaction],
+    ordering_hook: Option<Arc<dyn BlockOrderingHook<OrderedTransaction>>>,
+) -> Vec<Output<KeyType<[u8; 32]>, ValueType<[u8; 32]>>> {
+    let data_view = DeltaDataView::<KeyType<[u8; 32]>, ValueType<[u8; 32]>> {
+        phantom: PhantomData,
+    };
+    let executor = BlockExecutor::<
+        OrderedTransaction,
+        Task<KeyType<[u8; 32]>, ValueType<[u8; 32]>>,
+        DeltaDataView<KeyType<[u8; 32]>, ValueType<[u8; 32]>>,
+    >::new(num_cpus::get(), None);
+    let executor = match ordering_hook {
+        Some(hook) => executor.with_ordering_hook(hook),
+        None => executor,
+    };
+    executor
+        .execute_block((), transactions.to_vec(), &data_view)
+        .unwrap()
+        .into_iter()
+        .map(|(output, _)| output)
+        .collect()
+}
+
+#[test]
+fn sender_grouping_matches_block_order() {
+    for _ in 0..10 {
+        let transactions = interleaved_senders_block(500);
+        let grouped = execute_block(&transactions, Some(sender_grouping()));
+        assert_eq!(grouped, execute_block(&transactions, None));
+        ExpectedOutput::generate_baseline(&transactions, None).assert_output(&Ok(grouped));
+    }
+}
+
+#[test]
+fn ordering_hook_skip_rest_in_block_order() {
+    let mut transactions = interleaved_senders_block(500);
+    transactions[300] = Transaction::SkipRest;
+
+    let fallbacks = ORDERED_EXECUTION_FALLBACK_COUNT.get();
+    let grouped = execute_block(&transactions, Some(sender_grouping()));
+    assert_eq!(grouped, execute_block(&transactions, None));
+    ExpectedOutput::generate_baseline(&transactions, None).assert_output(&Ok(grouped));
+    if num_cpus::get() > 1 {
+        assert!(ORDERED_EXECUTION_FALLBACK_COUNT.get() > fallbacks);
+    }
+}
+
+#[test]
+fn sender_grouping_cross_sender_conflict_diverges() {
+    if num_cpus::get() == 1 {
+        // Executed sequentially, in the order of the block.
+        return;
+    }
+    let (a, b) = (
+        KeyType(random::<[u8; 32]>(), false),
+        KeyType(random::<[u8; 32]>(), false),
+    );
+    let transactions = vec![
+        read_write_txn(vec![b], &[b]),
+        // a pays b.
+        read_write_txn(vec![a, b], &[a, b]),
+        // b spends what a paid, but is grouped with the first transaction of b, before it.
+        read_write_txn(vec![b], &[b]),
+    ];
+    let grouped = execute_block(&transactions, Some(sender_grouping()));
+    let in_block_order = execute_block(&transactions, None);
+    assert_eq!(grouped[..2], in_block_order[..2]);
+    assert_ne!(grouped[2], in_block_order[2]);
+    ExpectedOutput::generate_baseline(&transactions, None).assert_output(&Ok(in_block_order));
+}
+
+struct InvalidOrder;
+
+impl BlockOrderingHook<OrderedTransaction> for InvalidOrder {
+    fn execution_order(&self, block: &[OrderedTransaction]) -> Vec<usize> {
+        vec![0; block.len()]
+    }
+}
+
+#[test]
+fn invalid_order_ignored() {
+    let transactions = interleaved_senders_block(100);
+    let outputs = execute_block(&transactions, Some(Arc::new(InvalidOrder)));
+    ExpectedOutput::generate_baseline(&transactions, None).assert_output(&Ok(outputs));
+}
+
+/// The same randomized writes and deltas over many aggregators, in two separate maps.
+fn random_delta_outputs(
+    num_keys: usize,
+    block_size: usize,
+) -> [MVHashMap<KeyType<[u8; 32]>, ValueType<Vec<u8>>>; 2] {
+    let maps = [MVHashMap::new(), MVHashMap::new()];
+    for _ in 0..num_keys {
+        let key = KeyType(random::<[u8; 32]>(), false);
+        for idx in 0..block_size {
+            match random::<u8>() % 4 {
+                0 => {
+                    let value = ValueType(serialize(&(random::<u32>() as u128)), true);
+                    for map in &maps {
+                        map.add_write(&key, (idx, 0), value.clone());
+                    }
+                },
+                1 | 2 => {
+                    let delta = delta_add(random::<u32>() as u128, u128::MAX);
+                    for map in &maps {
+                        map.add_delta(&key, idx, delta);
+                    }
+                },
+                _ => {},
+            }
+        }
+    }
+    maps
+}
+
+#[test]
+fn parallel_delta_resolution_matches_sequential() {
+    type T = Transaction<KeyType<[u8; 32]>, ValueType<Vec<u8>>>;
+    let data_view = DeltaDataView::<KeyType<[u8; 32]>, ValueType<Vec<u8>>> {
+        phantom: PhantomData,
+    };
+    let block_size = 300;
+
+    for num_committed in [block_size, block_size / 2] {
+        let [sequential, parallel] = random_delta_outputs(50, block_size);
+        let sequential = OutputDeltaResolver::<T>::new(sequential, pool(1))
+            .resolve_sequential(&data_view, block_size, num_committed)
+            .unwrap();
+        let parallel = OutputDeltaResolver::<T>::new(parallel, pool(4))
+            .resolve_parallel(&data_view, block_size, num_committed)
+            .unwrap();
+
+        assert_eq!(sequential, parallel);
+        assert!(sequential[num_committed..].iter().all(Vec::is_empty));
+        assert!(sequential.iter().any(|writes| writes.len() > 1));
+    }
+}
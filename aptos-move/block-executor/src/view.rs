@@ -2,6 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
+    base_view_cache::BaseViewCache,
     counters,
     scheduler::{Scheduler, TxnIndex},
     task::{ModulePath, Transaction},
@@ -17,7 +18,13 @@ use aptos_types::{
     write_set::TransactionWrite,
 };
 use move_binary_format::errors::Location;
-use std::{cell::RefCell, collections::BTreeMap, hash::Hash, sync::Arc};
+use std::{
+    cell::{Cell, RefCell},
+    collections::BTreeMap,
+    hash::Hash,
+    sync::Arc,
+    time::{Duration, Instant},
+};
 
 /// Resolved and serialized data for WriteOps, None means deletion.
 pub type ResolvedData = Option<Vec<u8>>;
@@ -33,6 +40,8 @@ pub(crate) struct MVHashMapView<'a, K, V> {
     versioned_map: &'a MVHashMap<K, V>,
     scheduler: &'a Scheduler,
     captured_reads: RefCell<Vec<ReadDescriptor<K>>>,
+    // Number of times the execution waited on a dependency, and for how long overall.
+    dependency_waits: Cell<(u64, Duration)>,
 }
 
 /// A struct which describes the result of the read from the proxy. The client
@@ -60,6 +69,7 @@ impl<
             versioned_map,
             scheduler,
             captured_reads: RefCell::new(Vec::new()),
+            dependency_waits: Cell::new((0, Duration::ZERO)),
         }
     }
 
@@ -68,6 +78,11 @@ impl<
         self.captured_reads.take()
     }
 
+    /// Number of times the execution waited on a dependency, and the time spent waiting.
+    pub(crate) fn dependency_waits(&self) -> (u64, Duration) {
+        self.dependency_waits.get()
+    }
+
     /// Captures a read from the VM execution.
     fn read(&self, key: &K, txn_idx: TxnIndex) -> ReadResult<V> {
         use MVHashMapError::*;
@@ -101,10 +116,16 @@ impl<
                     return ReadResult::Unresolved(delta);
                 },
                 Err(Dependency(dep_idx)) => {
+                    if self.scheduler.done() {
+                        // Halted, the execution is discarded anyway and must not wait on a
+                        // dependency that may never be resolved.
+                        return ReadResult::None;
+                    }
                     // `self.txn_idx` estimated to depend on a write from `dep_idx`.
                     match self.scheduler.wait_for_dependency(txn_idx, dep_idx) {
                         Some(dep_condition) => {
                             let _timer = counters::DEPENDENCY_WAIT_SECONDS.start_timer();
+                            let wait_start = Instant::now();
                             // Wait on a condition variable corresponding to the encountered
                             // read dependency. Once the dep_idx finishes re-execution, scheduler
                             // will mark the dependency as resolved, and then the txn_idx will be
@@ -124,6 +145,9 @@ impl<
                             while !*dep_resolved {
                                 dep_resolved = cvar.wait(dep_resolved).unwrap();
                             }
+                            let (waits, waited) = self.dependency_waits.get();
+                            self.dependency_waits
+                                .set((waits + 1, waited + wait_start.elapsed()));
                         },
                         None => continue,
                     }
@@ -144,11 +168,14 @@ impl<
 
 enum ViewMapKind<'a, T: Transaction> {
     MultiVersion(&'a MVHashMapView<'a, T::Key, T::Value>),
-    BTree(&'a BTreeMap<T::Key, T::Value>),
+    /// Latest writes and latest values of aggregators updated via deltas. A key is never
+    /// present in both maps.
+    BTree(&'a BTreeMap<T::Key, T::Value>, &'a BTreeMap<T::Key, u128>),
 }
 
 pub(crate) struct LatestView<'a, T: Transaction, S: TStateView<Key = T::Key>> {
     base_view: &'a S,
+    base_view_cache: Option<&'a BaseViewCache<T::Key>>,
     latest_view: ViewMapKind<'a, T>,
     txn_idx: TxnIndex,
 }
@@ -156,11 +183,13 @@ pub(crate) struct LatestView<'a, T: Transaction, S: TStateView<Key = T::Key>> {
 impl<'a, T: Transaction, S: TStateView<Key = T::Key>> LatestView<'a, T, S> {
     pub(crate) fn new_mv_view(
         base_view: &'a S,
+        base_view_cache: Option<&'a BaseViewCache<T::Key>>,
         map: &'a MVHashMapView<'a, T::Key, T::Value>,
         txn_idx: TxnIndex,
     ) -> LatestView<'a, T, S> {
         LatestView {
             base_view,
+            base_view_cache,
             latest_view: ViewMapKind::MultiVersion(map),
             txn_idx,
         }
@@ -168,15 +197,26 @@ impl<'a, T: Transaction, S: TStateView<Key = T::Key>> LatestView<'a, T, S> {
 
     pub(crate) fn new_btree_view(
         base_view: &'a S,
+        base_view_cache: Option<&'a BaseViewCache<T::Key>>,
         map: &'a BTreeMap<T::Key, T::Value>,
+        aggregator_map: &'a BTreeMap<T::Key, u128>,
         txn_idx: TxnIndex,
     ) -> LatestView<'a, T, S> {
         LatestView {
             base_view,
-            latest_view: ViewMapKind::BTree(map),
+            base_view_cache,
+            latest_view: ViewMapKind::BTree(map, aggregator_map),
             txn_idx,
         }
     }
+
+    /// Reads from the base view, going through the base view cache if there is one.
+    fn get_base_state_value(&self, state_key: &T::Key) -> anyhow::Result<Option<Vec<u8>>> {
+        match self.base_view_cache {
+            Some(cache) => cache.get_state_value(state_key, self.base_view),
+            None => self.base_view.get_state_value(state_key),
+        }
+    }
 }
 
 impl<'a, T: Transaction, S: TStateView<Key = T::Key>> TStateView for LatestView<'a, T, S> {
@@ -189,8 +229,7 @@ impl<'a, T: Transaction, S: TStateView<Key = T::Key>> TStateView for LatestView<
                 ReadResult::U128(v) => Ok(Some(serialize(&v))),
                 ReadResult::Unresolved(delta) => {
                     let from_storage = self
-                        .base_view
-                        .get_state_value(state_key)?
+                        .get_base_state_value(state_key)?
                         .map_or(Err(VMStatus::Error(StatusCode::STORAGE_ERROR)), |bytes| {
                             Ok(deserialize(&bytes))
                         })?;
@@ -199,12 +238,16 @@ impl<'a, T: Transaction, S: TStateView<Key = T::Key>> TStateView for LatestView<
                         .map_err(|pe| pe.finish(Location::Undefined).into_vm_status())?;
                     Ok(Some(serialize(&result)))
                 },
-                ReadResult::None => self.base_view.get_state_value(state_key),
+                ReadResult::None => self.get_base_state_value(state_key),
             },
-            ViewMapKind::BTree(map) => map.get(state_key).map_or_else(
+            ViewMapKind::BTree(map, aggregator_map) => map.get(state_key).map_or_else(
                 || {
+                    if let Some(value) = aggregator_map.get(state_key) {
+                        return Ok(Some(serialize(value)));
+                    }
+
                     // let ret =
-                    self.base_view.get_state_value(state_key)
+                    self.get_base_state_value(state_key)
 
                     // TODO: common treatment with the above case.
                     // TODO: enable below when logging isn't a circular dependency.
@@ -197,11 +197,58 @@ impl<K: Hash + Clone + Eq, V: TransactionWrite> MVHashMap<K, V> {
     }
 
     /// Delete an entry from transaction 'txn_idx' at access path 'key'. Will panic
-    /// if the access path has never been written before.
-    pub fn delete(&self, key: &K, txn_idx: TxnIndex) {
-        // TODO: investigate logical deletion.
-        let mut v = self.data.get_mut(key).expect("Path must exist");
-        v.versioned_map.remove(&txn_idx);
+    /// if the access path has never been written before. When the last entry at the access
+    /// path is deleted, the access path itself is reclaimed and true is returned, unless it
+    /// holds an aggregator, whose entries are needed to resolve the deltas after the block.
+    pub fn delete(&self, key: &K, txn_idx: TxnIndex) -> bool {
+        {
+            let mut v = self.data.get_mut(key).expect("Path must exist");
+            v.versioned_map.remove(&txn_idx);
+            if !v.versioned_map.is_empty() || v.contains_delta {
+                return false;
+            }
+        }
+
+        // The shard lock was released above, so the access path may have been written to since.
+        // Removal holds the lock while checking again, so it never races with other accesses.
+        self.data
+            .remove_if(key, |_, v| v.versioned_map.is_empty() && !v.contains_delta)
+            .is_some()
+    }
+
+    /// Number of access paths with entries in the data-structure.
+    pub fn num_keys(&self) -> usize {
+        self.data.len()
+    }
+
+    /// Iterates the access paths with at least one write or delta from a transaction at index
+    /// 'commit_idx' or lower. Entries deleted by re-executions are gone from the BTreeMaps, so
+    /// their access paths are only included if they still hold other entries in the prefix.
+    /// The map is not locked as a whole, DashMap only holds the lock of the shard being iterated.
+    pub fn modified_keys_up_to(&self, commit_idx: TxnIndex) -> impl Iterator<Item = K> + '_ {
+        self.data.iter().filter_map(move |entry| {
+            entry
+                .value()
+                .versioned_map
+                .range(..=commit_idx)
+                .next()
+                .map(|_| entry.key().clone())
+        })
+    }
+
+    /// Number of access paths `modified_keys_up_to` iterates, without cloning them.
+    pub fn num_modified_keys_up_to(&self, commit_idx: TxnIndex) -> usize {
+        self.data
+            .iter()
+            .filter(|entry| {
+                entry
+                    .value()
+                    .versioned_map
+                    .range(..=commit_idx)
+                    .next()
+                    .is_some()
+            })
+            .count()
     }
 
     /// Read entry from transaction 'txn_idx' at access path 'key'.
@@ -165,3 +165,85 @@ fn create_write_read_placeholder_struct() {
     let r_31 = mvtbl.read(&ap2, 31);
     assert_eq!(Err(DeltaApplicationFailure), r_31);
 }
+
+#[test]
+fn delete_reclaims_empty_keys() {
+    let ap1 = b"/foo/b".to_vec();
+    let ap2 = b"/foo/c".to_vec();
+
+    let mvtbl = MVHashMap::new();
+    mvtbl.add_write(&ap1, (5, 0), value_for(5, 0));
+    mvtbl.add_write(&ap1, (7, 0), value_for(7, 0));
+    mvtbl.add_write(&ap2, (5, 0), value_for(5, 0));
+    mvtbl.add_delta(&ap2, 6, add_for(6, 1000));
+    assert_eq!(mvtbl.num_keys(), 2);
+
+    // The key is only reclaimed with its last entry.
+    assert!(!mvtbl.delete(&ap1, 5));
+    assert!(mvtbl.delete(&ap1, 7));
+    assert_eq!(mvtbl.num_keys(), 1);
+    assert_eq!(mvtbl.read(&ap1, 10), Err(MVHashMapError::NotFound));
+
+    // Writing again after the key was reclaimed starts from scratch.
+    mvtbl.add_write(&ap1, (3, 1), value_for(3, 1));
+    assert_eq!(
+        mvtbl.read(&ap1, 10),
+        Ok(MVHashMapOutput::Version((3, 1), arc_value_for(3, 1)))
+    );
+
+    // Aggregator keys stay around to resolve their deltas after the block.
+    assert!(!mvtbl.delete(&ap2, 5));
+    assert!(!mvtbl.delete(&ap2, 6));
+    assert_eq!(mvtbl.num_keys(), 2);
+    assert_eq!(mvtbl.aggregator_keys(), vec![ap2.clone()]);
+    assert!(mvtbl.entry_map_for_key(&ap2).unwrap().is_empty());
+}
+
+#[test]
+fn wide_aborted_writers_bounded_size() {
+    const NUM_TXNS: usize = 16;
+    const WIDE_WRITE_SET_SIZE: usize = 1000;
+    const NUM_INCARNATIONS: usize = 10;
+
+    let key = |txn_idx: usize, i: usize| format!("/wide/{}/{}", txn_idx, i).into_bytes();
+    let mvtbl = MVHashMap::new();
+
+    // Every transaction repeatedly writes a wide write set, then gets aborted and re-executes,
+    // only writing a single key of it and deleting the rest, while the others read concurrently.
+    rayon::scope(|s| {
+        for txn_idx in 0..NUM_TXNS {
+            let mvtbl = &mvtbl;
+            s.spawn(move |_| {
+                for incarnation in 0..NUM_INCARNATIONS {
+                    for i in 0..WIDE_WRITE_SET_SIZE {
+                        mvtbl.add_write(
+                            &key(txn_idx, i),
+                            (txn_idx, 2 * incarnation),
+                            value_for(txn_idx, incarnation),
+                        );
+                        let _ = mvtbl.read(&key((txn_idx + 1) % NUM_TXNS, i), NUM_TXNS);
+                    }
+                    mvtbl.add_write(
+                        &key(txn_idx, 0),
+                        (txn_idx, 2 * incarnation + 1),
+                        value_for(txn_idx, incarnation),
+                    );
+                    for i in 1..WIDE_WRITE_SET_SIZE {
+                        assert!(mvtbl.delete(&key(txn_idx, i), txn_idx));
+                    }
+                }
+            });
+        }
+    });
+
+    assert_eq!(mvtbl.num_keys(), NUM_TXNS);
+    for txn_idx in 0..NUM_TXNS {
+        assert_eq!(
+            mvtbl.read(&key(txn_idx, 0), NUM_TXNS),
+            Ok(MVHashMapOutput::Version(
+                (txn_idx, 2 * NUM_INCARNATIONS - 1),
+                arc_value_for(txn_idx, NUM_INCARNATIONS - 1)
+            ))
+        );
+    }
+}
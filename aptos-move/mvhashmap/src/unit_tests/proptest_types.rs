@@ -9,7 +9,7 @@ use aptos_aggregator::{
 use aptos_types::write_set::TransactionWrite;
 use proptest::{collection::vec, prelude::*, sample::Index, strategy::Strategy};
 use std::{
-    collections::{BTreeMap, HashMap},
+    collections::{BTreeMap, BTreeSet, HashMap},
     fmt::Debug,
     hash::Hash,
     sync::atomic::{AtomicUsize, Ordering},
@@ -283,6 +283,68 @@ where
     Ok(())
 }
 
+/// Re-executes every transaction once per incarnation, writing or updating via a delta the keys
+/// of the incarnation and deleting the keys of the previous incarnation it did not write again,
+/// as the parallel executor does. The keys of a transaction are those of its last incarnation.
+fn run_and_assert_modified_keys<K>(
+    universe: Vec<K>,
+    incarnations: Vec<Vec<Vec<(Index, bool)>>>,
+    commit_idx: Index,
+) -> Result<(), TestCaseError>
+where
+    K: Send + Clone + Hash + Eq + Ord + Sync + Debug,
+{
+    let transactions: Vec<Vec<BTreeMap<K, bool>>> = incarnations
+        .into_iter()
+        .map(|incarnations| {
+            incarnations
+                .into_iter()
+                .map(|keys| {
+                    keys.into_iter()
+                        .map(|(idx, is_delta)| (idx.get(&universe).clone(), is_delta))
+                        .collect()
+                })
+                .collect()
+        })
+        .collect();
+    let map = MVHashMap::<K, Value<Vec<u8>>>::new();
+
+    rayon::scope(|s| {
+        for (txn_idx, incarnations) in transactions.iter().enumerate() {
+            let map = &map;
+            s.spawn(move |_| {
+                let mut prev_keys = BTreeMap::new();
+                for (incarnation, keys) in incarnations.iter().enumerate() {
+                    for (key, is_delta) in keys {
+                        if *is_delta {
+                            map.add_delta(key, txn_idx, delta_add(1, u128::MAX));
+                        } else {
+                            map.add_write(key, (txn_idx, incarnation), Value(Some(vec![1])));
+                        }
+                        prev_keys.remove(key);
+                    }
+                    for key in prev_keys.keys() {
+                        map.delete(key, txn_idx);
+                    }
+                    prev_keys = keys.clone();
+                }
+            });
+        }
+    });
+
+    let commit_idx = commit_idx.index(transactions.len());
+    let expected: BTreeSet<K> = transactions[..=commit_idx]
+        .iter()
+        .filter_map(|incarnations| incarnations.last())
+        .flat_map(|keys| keys.keys().cloned())
+        .collect();
+    let modified: Vec<K> = map.modified_keys_up_to(commit_idx).collect();
+    prop_assert_eq!(modified.len(), expected.len());
+    prop_assert_eq!(map.num_modified_keys_up_to(commit_idx), expected.len());
+    prop_assert_eq!(modified.into_iter().collect::<BTreeSet<_>>(), expected);
+    Ok(())
+}
+
 proptest! {
     #[test]
     fn single_key_proptest(
@@ -307,4 +369,13 @@ proptest! {
     ) {
         run_and_assert(universe, transactions)?;
     }
+
+    #[test]
+    fn modified_keys_proptest(
+        universe in vec(any::<[u8; 32]>(), 20),
+        incarnations in vec(vec(vec((any::<Index>(), any::<bool>()), 0..5), 1..4), 100),
+        commit_idx in any::<Index>(),
+    ) {
+        run_and_assert_modified_keys(universe, incarnations, commit_idx)?;
+    }
 }
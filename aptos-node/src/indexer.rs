@@ -23,6 +23,51 @@ pub fn bootstrap_indexer(
     }
 }
 
+/// Runs the indexer checks against the configured database and the node's storage, printing
+/// the report. Returns whether all of them passed.
+#[cfg(feature = "indexer")]
+pub fn check_indexer(node_config: &NodeConfig) -> Result<bool, anyhow::Error> {
+    use crate::utils;
+    use anyhow::anyhow;
+    use aptos_db::AptosDB;
+    use aptos_indexer::runtime::check;
+    use aptos_storage_interface::DbReaderWriter;
+    use futures::channel::mpsc;
+
+    // Opened read only so that this can run next to the node
+    let aptos_db = AptosDB::open(
+        &node_config.storage.dir(),
+        true, /* readonly */
+        node_config.storage.storage_pruner_config,
+        node_config.storage.rocksdb_configs,
+        node_config.storage.enable_indexer,
+        node_config.storage.buffered_state_target_items,
+        node_config.storage.max_num_nodes_per_lru_cache_shard,
+    )
+    .map_err(|err| anyhow!("DB failed to open {}", err))?;
+    let db_rw = DbReaderWriter::new(aptos_db);
+    let chain_id = utils::fetch_chain_id(&db_rw)?;
+
+    // Nothing is ever submitted to mempool by the checks
+    let (mp_client_sender, _mp_client_receiver) = mpsc::channel(1);
+    let report = check(node_config, chain_id, db_rw.reader, mp_client_sender)?;
+    println!("{}", report);
+    Ok(report.passed())
+}
+
+/// Prints the query patterns recorded by the indexer that no index of its database serves
+#[cfg(feature = "indexer")]
+pub fn index_advisor(node_config: &NodeConfig) -> Result<(), anyhow::Error> {
+    let suggestions = aptos_indexer::runtime::index_advisor(&node_config.indexer)?;
+    if suggestions.is_empty() {
+        println!("Every recorded query pattern is served by an index");
+    }
+    for suggestion in suggestions {
+        println!("{}", suggestion);
+    }
+    Ok(())
+}
+
 #[cfg(not(feature = "indexer"))]
 pub fn bootstrap_indexer(
     _node_config: &NodeConfig,
@@ -32,3 +77,17 @@ pub fn bootstrap_indexer(
 ) -> Result<Option<Runtime>, anyhow::Error> {
     Ok(None)
 }
+
+#[cfg(not(feature = "indexer"))]
+pub fn check_indexer(_node_config: &NodeConfig) -> Result<bool, anyhow::Error> {
+    Err(anyhow::anyhow!(
+        "The indexer can't be checked, aptos-node was built without the indexer feature"
+    ))
+}
+
+#[cfg(not(feature = "indexer"))]
+pub fn index_advisor(_node_config: &NodeConfig) -> Result<(), anyhow::Error> {
+    Err(anyhow::anyhow!(
+        "No indexes can be advised on, aptos-node was built without the indexer feature"
+    ))
+}
@@ -21,7 +21,7 @@ use aptos_framework::ReleaseBundle;
 use aptos_logger::{prelude::*, telemetry_log_writer::TelemetryLog, Level, LoggerFilterUpdater};
 use aptos_state_sync_driver::driver_factory::StateSyncRuntimes;
 use aptos_types::chain_id::ChainId;
-use clap::Parser;
+use clap::{Parser, Subcommand};
 use futures::channel::mpsc;
 use hex::FromHex;
 use rand::{rngs::StdRng, SeedableRng};
@@ -75,6 +75,22 @@ pub struct AptosNodeArgs {
     /// only commit a block when there are user transactions in mempool.
     #[clap(long, requires("test"))]
     lazy: bool,
+
+    #[clap(subcommand)]
+    command: Option<AptosNodeCommand>,
+}
+
+#[derive(Clone, Debug, Subcommand)]
+enum AptosNodeCommand {
+    /// Validate the indexer config, database and chain without starting the node.
+    ///
+    /// Prints a report of every check and exits with a non-zero code if any of them failed.
+    CheckIndexer,
+    /// Suggest indexes for the queries of the indexer that no index serves.
+    ///
+    /// Reads the statistics the indexer writes to its `query_stats_path` and cross-references
+    /// them with the indexes of its database, printing the most frequent patterns first.
+    IndexAdvisor,
 }
 
 impl AptosNodeArgs {
@@ -122,6 +138,19 @@ impl AptosNodeArgs {
                     error
                 )
             });
+
+            match self.command {
+                Some(AptosNodeCommand::CheckIndexer) => {
+                    let passed =
+                        indexer::check_indexer(&config).expect("Indexer checks should run");
+                    std::process::exit(if passed { 0 } else { 1 });
+                },
+                Some(AptosNodeCommand::IndexAdvisor) => {
+                    indexer::index_advisor(&config).expect("Index advisor should run");
+                    return;
+                },
+                None => {},
+            }
             println!("Using node config {:?}", &config);
 
             // Start the node
@@ -52,7 +52,7 @@ pub fn bootstrap_api_and_indexer(
     let indexer_grpc = bootstrap_indexer_grpc(
         node_config,
         chain_id,
-        aptos_db.clone(),
+        vec![aptos_db.clone()],
         mempool_client_sender.clone(),
     );
 
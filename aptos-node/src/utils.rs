@@ -2,13 +2,13 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use anyhow::anyhow;
-use aptos_config::config::NodeConfig;
+use aptos_config::config::{ExecutorPoolOversubscription, NodeConfig};
 use aptos_state_view::account_with_state_view::AsAccountWithStateView;
 use aptos_storage_interface::{state_view::LatestDbStateCheckpointView, DbReaderWriter};
 use aptos_types::{
     account_config::CORE_CODE_ADDRESS, account_view::AccountView, chain_id::ChainId,
 };
-use aptos_vm::AptosVM;
+use aptos_vm::{block_executor::OversubscriptionPolicy, AptosVM};
 
 /// Error message to display when non-production features are enabled
 pub const ERROR_MSG_BAD_FEATURE_FLAGS: &str = r#"
@@ -29,6 +29,8 @@ pub fn create_global_rayon_pool(create_global_rayon_pool: bool) {
             .thread_name(|index| format!("rayon-global-{}", index))
             .build_global()
             .expect("Failed to build rayon global thread pool.");
+        // The global pool runs next to the executor pool, which is sized accordingly.
+        AptosVM::set_global_rayon_pool_threads_once(rayon::current_num_threads());
     }
 }
 
@@ -53,6 +55,18 @@ pub fn set_aptos_vm_configurations(node_config: &NodeConfig) {
     AptosVM::set_num_proof_reading_threads_once(
         node_config.execution.num_proof_reading_threads as usize,
     );
+    AptosVM::set_speculative_conflict_logs_per_block_once(
+        node_config.execution.speculative_conflict_logs_per_block,
+    );
+    AptosVM::set_executor_pool_oversubscription_policy_once(
+        match node_config.execution.executor_pool_oversubscription {
+            ExecutorPoolOversubscription::Warn => OversubscriptionPolicy::Warn,
+            ExecutorPoolOversubscription::ShrinkPool => OversubscriptionPolicy::ShrinkPool,
+            ExecutorPoolOversubscription::LowerConcurrency => {
+                OversubscriptionPolicy::LowerConcurrency
+            },
+        },
+    );
 
     if node_config
         .execution
@@ -23,6 +23,24 @@ pub struct ExecutionConfig {
     pub paranoid_type_verification: bool,
     pub paranoid_hot_potato_verification: bool,
     pub processed_transactions_detailed_counters: bool,
+    /// Number of speculative aborts per block logged with the conflict that caused them, for
+    /// debugging contention in parallel execution. Disabled with 0.
+    pub speculative_conflict_logs_per_block: usize,
+    /// What to do when the executor thread pool and rayon's global pool together have more
+    /// threads than CPUs.
+    pub executor_pool_oversubscription: ExecutorPoolOversubscription,
+}
+
+#[derive(Clone, Copy, Debug, Default, Deserialize, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ExecutorPoolOversubscription {
+    /// Only log a warning
+    #[default]
+    Warn,
+    /// Shrink the executor pool to the CPUs left by the global pool, but at least half of them
+    ShrinkPool,
+    /// Keep the executor pool, but lower the concurrency level to the CPUs left by the global pool
+    LowerConcurrency,
 }
 
 impl std::fmt::Debug for ExecutionConfig {
@@ -52,6 +70,8 @@ impl Default for ExecutionConfig {
             paranoid_type_verification: true,
             paranoid_hot_potato_verification: true,
             processed_transactions_detailed_counters: false,
+            speculative_conflict_logs_per_block: 0,
+            executor_pool_oversubscription: ExecutorPoolOversubscription::default(),
         }
     }
 }
@@ -7,6 +7,9 @@ pub const DEFAULT_BATCH_SIZE: u16 = 500;
 pub const DEFAULT_FETCH_TASKS: u8 = 5;
 pub const DEFAULT_PROCESSOR_TASKS: u8 = 5;
 pub const DEFAULT_EMIT_EVERY: u64 = 1000;
+pub const DEFAULT_PARQUET_MAX_ROWS: usize = 100_000;
+pub const DEFAULT_PARQUET_MAX_BUFFER_SECS: u64 = 300;
+pub const DEFAULT_FAN_OUT_QUEUE_SIZE: usize = 10;
 
 #[derive(Clone, Debug, Default, Deserialize, PartialEq, Eq, Serialize)]
 #[serde(default, deny_unknown_fields)]
@@ -26,12 +29,40 @@ pub struct IndexerConfig {
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub processor: Option<String>,
 
-    /// If set, will ignore database contents and start processing from the specified version.
+    /// Other processors to run along with `processor`, on the same batches. Every batch is read
+    /// once and queued for each processor, which processes its batches in order, one at a time.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub fan_out_processors: Vec<String>,
+
+    /// How many batches are queued for each processor at most, when there are
+    /// `fan_out_processors`. Reading batches pauses while the queue of a processor is full.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fan_out_queue_size: Option<usize>,
+
+    /// The version to start processing from if the processor has never processed any version,
+    /// otherwise it resumes after the last version it processed, unless `force_starting_version`
+    /// is set. Always used along with `ending_version`.
     /// This will not delete any database contents, just transactions as it reprocesses them.
     /// Alternatively can set the `STARTING_VERSION` env var
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub starting_version: Option<u64>,
 
+    /// If set, will ignore the last version processed in the database and start processing from
+    /// `starting_version`, which the processor then resumes from
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub force_starting_version: Option<bool>,
+
+    /// If set, only processes versions up to this one and stops once they are processed, e.g. to
+    /// backfill a range of versions along with `starting_version`. Refuses to start if versions
+    /// of the range were already processed, unless `overwrite_processed_versions` is set.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ending_version: Option<u64>,
+
+    /// If set, a range with an `ending_version` may overlap versions already processed, whose
+    /// rows are then overwritten.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub overwrite_processed_versions: Option<bool>,
+
     ///////////////////
     ///////////////////
     ///////////////////
@@ -69,6 +100,48 @@ pub struct IndexerConfig {
     /// Which address does the ans contract live at. Only available for token_processor. If null, disable ANS indexing
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub ans_contract_address: Option<String>,
+
+    /// Versions that will not be processed. Emergency bypass for versions that block ingestion,
+    /// skipped versions are recorded in `processor_statuses` with reason "operator_skip"
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub skip_versions: Vec<u64>,
+
+    /// Inclusive ranges of versions that will not be processed, see `skip_versions`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub skip_ranges: Vec<(u64, u64)>,
+
+    /// If set, batches are cut on block boundaries so that a block is always processed in a
+    /// single database transaction and the processed version always ends a block. A block with
+    /// more than `batch_size` transactions is processed as a batch of its own.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub align_batches_to_blocks: Option<bool>,
+
+    /// If set, versions missing before a batch are fetched again. Otherwise the indexer stops,
+    /// which is the default. Either way the gap is logged and counted.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub allow_gap_recovery: Option<bool>,
+
+    /// If set, records how the query helpers access the tables and writes the statistics to
+    /// this file every minute, for `aptos-node index-advisor` to suggest missing indexes from.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub query_stats_path: Option<String>,
+
+    /// Local directory the parquet processors write their Parquet files to, required by them
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parquet_dir: Option<String>,
+
+    /// If set, the Parquet files are also copied to this directory once written, e.g. a mounted
+    /// bucket of the data lake
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parquet_upload_dir: Option<String>,
+
+    /// How many rows of a table the parquet processors buffer before writing them to a file
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parquet_max_rows: Option<usize>,
+
+    /// How many seconds the parquet processors buffer rows at most before writing them to a file
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parquet_max_buffer_secs: Option<u64>,
 }
 
 pub fn env_or_default<T: std::str::FromStr>(
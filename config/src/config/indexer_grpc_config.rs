@@ -2,6 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
 
 #[derive(Clone, Debug, Default, Deserialize, PartialEq, Eq, Serialize)]
 #[serde(default, deny_unknown_fields)]
@@ -22,4 +23,113 @@ pub struct IndexerGrpcConfig {
     /// Number of transactions returned in a single stream response
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub output_batch_size: Option<u16>,
+
+    /// Responses buffered for a stream before sending to it waits on the client to read them.
+    /// Defaults to 35.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub transaction_channel_size: Option<usize>,
+
+    /// Seconds a stream waits on the client to read a response before terminating it with a
+    /// SLOW_CONSUMER status, so that a stalled client doesn't hold on to the stream forever.
+    /// Defaults to 60 seconds.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub slow_consumer_timeout_secs: Option<u64>,
+
+    /// Which stream lifecycle events are logged
+    pub stream_events_verbosity: StreamEventsVerbosity,
+
+    /// Only every n-th batch of a stream is logged when batch events are enabled
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stream_events_batch_sample_rate: Option<u64>,
+
+    /// Interval in seconds at which HTTP/2 pings are sent on idle connections. Keeps streams
+    /// that are waiting on new transactions alive across load balancers that drop idle
+    /// connections, so it should be below their idle timeout. Defaults to 30 seconds.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub http2_keepalive_interval_secs: Option<u64>,
+
+    /// Seconds to wait for a keepalive ping to be acknowledged before closing the connection.
+    /// Defaults to 20 seconds.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub http2_keepalive_timeout_secs: Option<u64>,
+
+    /// HTTP/2 flow control window of a connection, in bytes. Responses are several MB each, so
+    /// the default of 32MB is much larger than the 64KB of the spec to avoid stalling on acks.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub initial_connection_window_size: Option<u32>,
+
+    /// HTTP/2 flow control window of a single stream, in bytes. Defaults to 16MB.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub initial_stream_window_size: Option<u32>,
+
+    /// Largest HTTP/2 frame accepted by the server, in bytes. Must be between 16KB and 16MB - 1
+    /// as per the spec. Defaults to 1MB.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_frame_size: Option<u32>,
+
+    /// Clients that only support an older transaction schema version than the node's are
+    /// rejected with FAILED_PRECONDITION if set, instead of being streamed transactions stripped
+    /// down to their version.
+    pub reject_schema_downgrades: bool,
+
+    /// Compresses the responses with gzip for clients that accept it if set, and packs the
+    /// transactions of a batch into as few responses of `output_batch_size` as possible so that
+    /// compression isn't defeated by small messages. Off by default, compressing only costs CPU
+    /// for consumers on the same network.
+    pub compression_enabled: bool,
+
+    /// Content replaced by a marker with its hash before it is streamed, for operators that
+    /// can't export it off-box. Either a field selector, "user_transaction.payload.script.code"
+    /// or "user_transaction.payload.module_bundle.modules", or "event:<type pattern>" to redact
+    /// the data of matching events, e.g. "event:0x1::coin::*". Advertised in the init status.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub redaction_rules: Vec<String>,
+
+    /// Serves everything on the address over TLS if set, instead of plaintext. The node doesn't
+    /// start if the certificates or the key can't be loaded.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tls: Option<IndexerGrpcTlsConfig>,
+
+    /// Serves the gRPC health service along with the stream if set. The stream is reported as
+    /// serving once the ledger can be read, and as not serving whenever it didn't advance for
+    /// `health_staleness_secs`, so that load balancers stop routing to a stuck node. Defaults to
+    /// true.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub health_check_enabled: Option<bool>,
+
+    /// Seconds without a new ledger version after which the stream is reported as not serving.
+    /// Defaults to 30 seconds.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub health_staleness_secs: Option<u64>,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Eq, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct IndexerGrpcTlsConfig {
+    /// PEM encoded certificate chain of the server
+    pub cert_path: PathBuf,
+
+    /// PEM encoded private key of the server
+    pub key_path: PathBuf,
+
+    /// PEM encoded CA certificate of the clients. Clients have to present a certificate signed
+    /// by it if set, i.e. mutual TLS, and are rejected during the handshake otherwise.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub client_ca_cert_path: Option<PathBuf>,
+}
+
+/// Bounds of the HTTP/2 SETTINGS_MAX_FRAME_SIZE, see RFC 7540 section 6.5.2.
+pub const MIN_HTTP2_FRAME_SIZE: u32 = 1 << 14;
+pub const MAX_HTTP2_FRAME_SIZE: u32 = (1 << 24) - 1;
+
+#[derive(Clone, Copy, Debug, Default, Deserialize, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StreamEventsVerbosity {
+    /// No stream events are logged
+    Disabled,
+    /// Stream connects and disconnects are logged
+    #[default]
+    Lifecycle,
+    /// Every (sampled) batch sent is logged as well
+    Batches,
 }
@@ -362,8 +362,13 @@ impl NodeConfig {
             },
         };
 
+        self.indexer.overwrite_processed_versions =
+            self.indexer.overwrite_processed_versions.or(Some(false));
         self.indexer.skip_migrations = self.indexer.skip_migrations.or(Some(false));
         self.indexer.check_chain_id = self.indexer.check_chain_id.or(Some(true));
+        self.indexer.align_batches_to_blocks = self.indexer.align_batches_to_blocks.or(Some(false));
+        self.indexer.allow_gap_recovery = self.indexer.allow_gap_recovery.or(Some(false));
+        self.indexer.force_starting_version = self.indexer.force_starting_version.or(Some(false));
         self.indexer.batch_size = default_if_zero(
             self.indexer.batch_size.map(|v| v as u64),
             DEFAULT_BATCH_SIZE as u64,
@@ -382,6 +387,19 @@ impl NodeConfig {
             self.indexer.gap_lookback_versions.or(Some(1_500_000)),
             None,
         );
+        self.indexer.fan_out_queue_size = self
+            .indexer
+            .fan_out_queue_size
+            .filter(|size| *size > 0)
+            .or(Some(DEFAULT_FAN_OUT_QUEUE_SIZE));
+        self.indexer.parquet_max_rows = self
+            .indexer
+            .parquet_max_rows
+            .or(Some(DEFAULT_PARQUET_MAX_ROWS));
+        self.indexer.parquet_max_buffer_secs = default_if_zero(
+            self.indexer.parquet_max_buffer_secs,
+            DEFAULT_PARQUET_MAX_BUFFER_SECS,
+        );
 
         Ok(self)
     }
@@ -406,6 +424,67 @@ impl NodeConfig {
 
         self.indexer_grpc.output_batch_size = self.indexer_grpc.output_batch_size.or(Some(100));
 
+        self.indexer_grpc.transaction_channel_size =
+            self.indexer_grpc.transaction_channel_size.or(Some(35));
+
+        self.indexer_grpc.slow_consumer_timeout_secs =
+            self.indexer_grpc.slow_consumer_timeout_secs.or(Some(60));
+
+        self.indexer_grpc.health_check_enabled =
+            self.indexer_grpc.health_check_enabled.or(Some(true));
+
+        self.indexer_grpc.health_staleness_secs =
+            self.indexer_grpc.health_staleness_secs.or(Some(30));
+
+        self.indexer_grpc.stream_events_batch_sample_rate = self
+            .indexer_grpc
+            .stream_events_batch_sample_rate
+            .or(Some(10));
+
+        self.indexer_grpc.http2_keepalive_interval_secs =
+            self.indexer_grpc.http2_keepalive_interval_secs.or(Some(30));
+
+        self.indexer_grpc.http2_keepalive_timeout_secs =
+            self.indexer_grpc.http2_keepalive_timeout_secs.or(Some(20));
+
+        self.indexer_grpc.initial_connection_window_size = self
+            .indexer_grpc
+            .initial_connection_window_size
+            .or(Some(32 * 1024 * 1024));
+
+        self.indexer_grpc.initial_stream_window_size = self
+            .indexer_grpc
+            .initial_stream_window_size
+            .or(Some(16 * 1024 * 1024));
+
+        self.indexer_grpc.max_frame_size = self.indexer_grpc.max_frame_size.or(Some(1024 * 1024));
+
+        let max_frame_size = self.indexer_grpc.max_frame_size.unwrap();
+        invariant(
+            (MIN_HTTP2_FRAME_SIZE..=MAX_HTTP2_FRAME_SIZE).contains(&max_frame_size),
+            format!(
+                "indexer_grpc.max_frame_size must be between {} and {}, got {}",
+                MIN_HTTP2_FRAME_SIZE, MAX_HTTP2_FRAME_SIZE, max_frame_size
+            ),
+        )?;
+
+        invariant(
+            self.indexer_grpc.transaction_channel_size != Some(0),
+            "indexer_grpc.transaction_channel_size must be positive".into(),
+        )?;
+
+        invariant(
+            self.indexer_grpc.health_staleness_secs != Some(0),
+            "indexer_grpc.health_staleness_secs must be positive".into(),
+        )?;
+
+        if let Some(tls) = &self.indexer_grpc.tls {
+            invariant(
+                !tls.cert_path.as_os_str().is_empty() && !tls.key_path.as_os_str().is_empty(),
+                "indexer_grpc.tls requires both a cert_path and a key_path".into(),
+            )?;
+        }
+
         Ok(self)
     }
 
@@ -643,4 +722,99 @@ mod test {
             Err(Error::InvariantViolation(_))
         ));
     }
+
+    #[test]
+    fn validate_indexer_grpc_max_frame_size() {
+        let mut config = NodeConfig::default_for_public_full_node();
+        config.indexer_grpc.enabled = true;
+        let config = config.validate_indexer_grpc_configs().unwrap();
+        assert_eq!(config.indexer_grpc.max_frame_size, Some(1024 * 1024));
+
+        let mut config = NodeConfig::default_for_public_full_node();
+        config.indexer_grpc.enabled = true;
+        config.indexer_grpc.max_frame_size = Some(MAX_HTTP2_FRAME_SIZE + 1);
+        assert!(matches!(
+            config.validate_indexer_grpc_configs(),
+            Err(Error::InvariantViolation(_))
+        ));
+    }
+
+    #[test]
+    fn validate_indexer_grpc_transaction_channel_size() {
+        let mut config = NodeConfig::default_for_public_full_node();
+        config.indexer_grpc.enabled = true;
+        let config = config.validate_indexer_grpc_configs().unwrap();
+        assert_eq!(config.indexer_grpc.transaction_channel_size, Some(35));
+        assert_eq!(config.indexer_grpc.slow_consumer_timeout_secs, Some(60));
+
+        let mut config = NodeConfig::default_for_public_full_node();
+        config.indexer_grpc.enabled = true;
+        config.indexer_grpc.transaction_channel_size = Some(0);
+        assert!(matches!(
+            config.validate_indexer_grpc_configs(),
+            Err(Error::InvariantViolation(_))
+        ));
+    }
+
+    #[test]
+    fn validate_indexer_grpc_health_check() {
+        let mut config = NodeConfig::default_for_public_full_node();
+        config.indexer_grpc.enabled = true;
+        let config = config.validate_indexer_grpc_configs().unwrap();
+        assert_eq!(config.indexer_grpc.health_check_enabled, Some(true));
+        assert_eq!(config.indexer_grpc.health_staleness_secs, Some(30));
+
+        let mut config = NodeConfig::default_for_public_full_node();
+        config.indexer_grpc.enabled = true;
+        config.indexer_grpc.health_staleness_secs = Some(0);
+        assert!(matches!(
+            config.validate_indexer_grpc_configs(),
+            Err(Error::InvariantViolation(_))
+        ));
+    }
+
+    #[test]
+    fn validate_indexer_grpc_tls() {
+        let config: IndexerGrpcConfig = serde_yaml::from_str(
+            r#"
+            enabled: true
+            tls:
+                cert_path: /opt/aptos/indexer/server.pem
+                key_path: /opt/aptos/indexer/server.key
+                client_ca_cert_path: /opt/aptos/indexer/ca.pem
+            "#,
+        )
+        .unwrap();
+        assert_eq!(
+            config.tls,
+            Some(IndexerGrpcTlsConfig {
+                cert_path: PathBuf::from("/opt/aptos/indexer/server.pem"),
+                key_path: PathBuf::from("/opt/aptos/indexer/server.key"),
+                client_ca_cert_path: Some(PathBuf::from("/opt/aptos/indexer/ca.pem")),
+            })
+        );
+        // Plaintext without it, and the client CA is optional
+        assert_eq!(
+            serde_yaml::from_str::<IndexerGrpcConfig>("enabled: true")
+                .unwrap()
+                .tls,
+            None
+        );
+        let tls: IndexerGrpcTlsConfig =
+            serde_yaml::from_str("{cert_path: server.pem, key_path: server.key}").unwrap();
+        assert_eq!(tls.client_ca_cert_path, None);
+        assert!(serde_yaml::from_str::<IndexerGrpcTlsConfig>("cert_path: server.pem").is_err());
+
+        let mut config = NodeConfig::default_for_public_full_node();
+        config.indexer_grpc.enabled = true;
+        config.indexer_grpc.tls = Some(IndexerGrpcTlsConfig {
+            cert_path: PathBuf::from("server.pem"),
+            key_path: PathBuf::new(),
+            client_ca_cert_path: None,
+        });
+        assert!(matches!(
+            config.validate_indexer_grpc_configs(),
+            Err(Error::InvariantViolation(_))
+        ));
+    }
 }
@@ -3,3 +3,14 @@
 
 mod pb;
 pub use pb::aptos::*;
+
+/// Version of the `aptos.transaction.v1` schema the stream data is encoded with. Bump it whenever
+/// fields are added to the transaction protos, and teach the fullnode to strip them for older
+/// clients.
+///
+/// 1: Initial schema.
+/// 2: `type_str` of `Event`, `WriteResource` and `DeleteResource`.
+pub const TRANSACTION_SCHEMA_VERSION: u32 = 2;
+
+/// Oldest transaction schema version the stream can be downgraded to.
+pub const MIN_TRANSACTION_SCHEMA_VERSION: u32 = 1;
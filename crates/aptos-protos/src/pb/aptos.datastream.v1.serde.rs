@@ -13,10 +13,50 @@ impl serde::Serialize for RawDatastreamRequest {
         if self.starting_version != 0 {
             len += 1;
         }
+        if self.max_supported_schema_version.is_some() {
+            len += 1;
+        }
+        if self.resume_token.is_some() {
+            len += 1;
+        }
+        if self.strict_resume {
+            len += 1;
+        }
+        if self.ending_version.is_some() {
+            len += 1;
+        }
+        if !self.transaction_types.is_empty() {
+            len += 1;
+        }
+        if !self.entry_function_address_prefixes.is_empty() {
+            len += 1;
+        }
         let mut struct_ser = serializer.serialize_struct("aptos.datastream.v1.RawDatastreamRequest", len)?;
         if self.starting_version != 0 {
             struct_ser.serialize_field("startingVersion", ToString::to_string(&self.starting_version).as_str())?;
         }
+        if let Some(v) = self.max_supported_schema_version.as_ref() {
+            struct_ser.serialize_field("maxSupportedSchemaVersion", v)?;
+        }
+        if let Some(v) = self.resume_token.as_ref() {
+            struct_ser.serialize_field("resumeToken", v)?;
+        }
+        if self.strict_resume {
+            struct_ser.serialize_field("strictResume", &self.strict_resume)?;
+        }
+        if let Some(v) = self.ending_version.as_ref() {
+            struct_ser.serialize_field("endingVersion", ToString::to_string(&v).as_str())?;
+        }
+        if !self.transaction_types.is_empty() {
+            let v = self.transaction_types.iter().cloned().map(|v| {
+                raw_datastream_request::TransactionType::from_i32(v)
+                    .ok_or_else(|| serde::ser::Error::custom(format!("Invalid variant {}", v)))
+                }).collect::<Result<Vec<_>, _>>()?;
+            struct_ser.serialize_field("transactionTypes", &v)?;
+        }
+        if !self.entry_function_address_prefixes.is_empty() {
+            struct_ser.serialize_field("entryFunctionAddressPrefixes", &self.entry_function_address_prefixes)?;
+        }
         struct_ser.end()
     }
 }
@@ -28,11 +68,23 @@ impl<'de> serde::Deserialize<'de> for RawDatastreamRequest {
     {
         const FIELDS: &[&str] = &[
             "startingVersion",
+            "maxSupportedSchemaVersion",
+            "resumeToken",
+            "strictResume",
+            "endingVersion",
+            "transactionTypes",
+            "entryFunctionAddressPrefixes",
         ];
 
         #[allow(clippy::enum_variant_names)]
         enum GeneratedField {
             StartingVersion,
+            MaxSupportedSchemaVersion,
+            ResumeToken,
+            StrictResume,
+            EndingVersion,
+            TransactionTypes,
+            EntryFunctionAddressPrefixes,
         }
         impl<'de> serde::Deserialize<'de> for GeneratedField {
             fn deserialize<D>(deserializer: D) -> std::result::Result<GeneratedField, D::Error>
@@ -55,6 +107,12 @@ impl<'de> serde::Deserialize<'de> for RawDatastreamRequest {
                     {
                         match value {
                             "startingVersion" => Ok(GeneratedField::StartingVersion),
+                            "maxSupportedSchemaVersion" => Ok(GeneratedField::MaxSupportedSchemaVersion),
+                            "resumeToken" => Ok(GeneratedField::ResumeToken),
+                            "strictResume" => Ok(GeneratedField::StrictResume),
+                            "endingVersion" => Ok(GeneratedField::EndingVersion),
+                            "transactionTypes" => Ok(GeneratedField::TransactionTypes),
+                            "entryFunctionAddressPrefixes" => Ok(GeneratedField::EntryFunctionAddressPrefixes),
                             _ => Err(serde::de::Error::unknown_field(value, FIELDS)),
                         }
                     }
@@ -75,6 +133,12 @@ impl<'de> serde::Deserialize<'de> for RawDatastreamRequest {
                     V: serde::de::MapAccess<'de>,
             {
                 let mut starting_version__ = None;
+                let mut max_supported_schema_version__ = None;
+                let mut resume_token__ = None;
+                let mut strict_resume__ = None;
+                let mut ending_version__ = None;
+                let mut transaction_types__ = None;
+                let mut entry_function_address_prefixes__ = None;
                 while let Some(k) = map.next_key()? {
                     match k {
                         GeneratedField::StartingVersion => {
@@ -85,16 +149,141 @@ impl<'de> serde::Deserialize<'de> for RawDatastreamRequest {
                                 map.next_value::<::pbjson::private::NumberDeserialize<_>>()?.0
                             );
                         }
+                        GeneratedField::MaxSupportedSchemaVersion => {
+                            if max_supported_schema_version__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("maxSupportedSchemaVersion"));
+                            }
+                            max_supported_schema_version__ = Some(
+                                map.next_value::<::pbjson::private::NumberDeserialize<_>>()?.0
+                            );
+                        }
+                        GeneratedField::ResumeToken => {
+                            if resume_token__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("resumeToken"));
+                            }
+                            resume_token__ = map.next_value()?;
+                        }
+                        GeneratedField::StrictResume => {
+                            if strict_resume__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("strictResume"));
+                            }
+                            strict_resume__ = Some(map.next_value()?);
+                        }
+                        GeneratedField::EndingVersion => {
+                            if ending_version__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("endingVersion"));
+                            }
+                            ending_version__ = Some(
+                                map.next_value::<::pbjson::private::NumberDeserialize<_>>()?.0
+                            );
+                        }
+                        GeneratedField::TransactionTypes => {
+                            if transaction_types__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("transactionTypes"));
+                            }
+                            transaction_types__ = Some(map.next_value::<Vec<raw_datastream_request::TransactionType>>()?.into_iter().map(|x| x as i32).collect());
+                        }
+                        GeneratedField::EntryFunctionAddressPrefixes => {
+                            if entry_function_address_prefixes__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("entryFunctionAddressPrefixes"));
+                            }
+                            entry_function_address_prefixes__ = Some(map.next_value()?);
+                        }
                     }
                 }
                 Ok(RawDatastreamRequest {
                     starting_version: starting_version__.unwrap_or_default(),
+                    max_supported_schema_version: max_supported_schema_version__,
+                    resume_token: resume_token__,
+                    strict_resume: strict_resume__.unwrap_or_default(),
+                    ending_version: ending_version__,
+                    transaction_types: transaction_types__.unwrap_or_default(),
+                    entry_function_address_prefixes: entry_function_address_prefixes__.unwrap_or_default(),
                 })
             }
         }
         deserializer.deserialize_struct("aptos.datastream.v1.RawDatastreamRequest", FIELDS, GeneratedVisitor)
     }
 }
+impl serde::Serialize for raw_datastream_request::TransactionType {
+    #[allow(deprecated)]
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        let variant = match self {
+            Self::Genesis => "GENESIS",
+            Self::BlockMetadata => "BLOCK_METADATA",
+            Self::StateCheckpoint => "STATE_CHECKPOINT",
+            Self::User => "USER",
+        };
+        serializer.serialize_str(variant)
+    }
+}
+impl<'de> serde::Deserialize<'de> for raw_datastream_request::TransactionType {
+    #[allow(deprecated)]
+    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        const FIELDS: &[&str] = &[
+            "GENESIS",
+            "BLOCK_METADATA",
+            "STATE_CHECKPOINT",
+            "USER",
+        ];
+
+        struct GeneratedVisitor;
+
+        impl<'de> serde::de::Visitor<'de> for GeneratedVisitor {
+            type Value = raw_datastream_request::TransactionType;
+
+            fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                write!(formatter, "expected one of: {:?}", &FIELDS)
+            }
+
+            fn visit_i64<E>(self, v: i64) -> std::result::Result<Self::Value, E>
+            where
+                E: serde::de::Error,
+            {
+                use std::convert::TryFrom;
+                i32::try_from(v)
+                    .ok()
+                    .and_then(raw_datastream_request::TransactionType::from_i32)
+                    .ok_or_else(|| {
+                        serde::de::Error::invalid_value(serde::de::Unexpected::Signed(v), &self)
+                    })
+            }
+
+            fn visit_u64<E>(self, v: u64) -> std::result::Result<Self::Value, E>
+            where
+                E: serde::de::Error,
+            {
+                use std::convert::TryFrom;
+                i32::try_from(v)
+                    .ok()
+                    .and_then(raw_datastream_request::TransactionType::from_i32)
+                    .ok_or_else(|| {
+                        serde::de::Error::invalid_value(serde::de::Unexpected::Unsigned(v), &self)
+                    })
+            }
+
+            fn visit_str<E>(self, value: &str) -> std::result::Result<Self::Value, E>
+            where
+                E: serde::de::Error,
+            {
+                match value {
+                    "GENESIS" => Ok(raw_datastream_request::TransactionType::Genesis),
+                    "BLOCK_METADATA" => Ok(raw_datastream_request::TransactionType::BlockMetadata),
+                    "STATE_CHECKPOINT" => Ok(raw_datastream_request::TransactionType::StateCheckpoint),
+                    "USER" => Ok(raw_datastream_request::TransactionType::User),
+                    _ => Err(serde::de::Error::unknown_variant(value, FIELDS)),
+                }
+            }
+        }
+        deserializer.deserialize_any(GeneratedVisitor)
+    }
+}
 impl serde::Serialize for RawDatastreamResponse {
     #[allow(deprecated)]
     fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
@@ -311,6 +500,18 @@ impl serde::Serialize for StreamStatus {
         if self.end_version.is_some() {
             len += 1;
         }
+        if self.schema_version.is_some() {
+            len += 1;
+        }
+        if self.resume_token.is_some() {
+            len += 1;
+        }
+        if !self.redactions.is_empty() {
+            len += 1;
+        }
+        if self.error_message.is_some() {
+            len += 1;
+        }
         let mut struct_ser = serializer.serialize_struct("aptos.datastream.v1.StreamStatus", len)?;
         if self.r#type != 0 {
             let v = stream_status::StatusType::from_i32(self.r#type)
@@ -323,6 +524,18 @@ impl serde::Serialize for StreamStatus {
         if let Some(v) = self.end_version.as_ref() {
             struct_ser.serialize_field("endVersion", ToString::to_string(&v).as_str())?;
         }
+        if let Some(v) = self.schema_version.as_ref() {
+            struct_ser.serialize_field("schemaVersion", v)?;
+        }
+        if let Some(v) = self.resume_token.as_ref() {
+            struct_ser.serialize_field("resumeToken", v)?;
+        }
+        if !self.redactions.is_empty() {
+            struct_ser.serialize_field("redactions", &self.redactions)?;
+        }
+        if let Some(v) = self.error_message.as_ref() {
+            struct_ser.serialize_field("errorMessage", v)?;
+        }
         struct_ser.end()
     }
 }
@@ -336,6 +549,10 @@ impl<'de> serde::Deserialize<'de> for StreamStatus {
             "type",
             "startVersion",
             "endVersion",
+            "schemaVersion",
+            "resumeToken",
+            "redactions",
+            "errorMessage",
         ];
 
         #[allow(clippy::enum_variant_names)]
@@ -343,6 +560,10 @@ impl<'de> serde::Deserialize<'de> for StreamStatus {
             Type,
             StartVersion,
             EndVersion,
+            SchemaVersion,
+            ResumeToken,
+            Redactions,
+            ErrorMessage,
         }
         impl<'de> serde::Deserialize<'de> for GeneratedField {
             fn deserialize<D>(deserializer: D) -> std::result::Result<GeneratedField, D::Error>
@@ -367,6 +588,10 @@ impl<'de> serde::Deserialize<'de> for StreamStatus {
                             "type" => Ok(GeneratedField::Type),
                             "startVersion" => Ok(GeneratedField::StartVersion),
                             "endVersion" => Ok(GeneratedField::EndVersion),
+                            "schemaVersion" => Ok(GeneratedField::SchemaVersion),
+                            "resumeToken" => Ok(GeneratedField::ResumeToken),
+                            "redactions" => Ok(GeneratedField::Redactions),
+                            "errorMessage" => Ok(GeneratedField::ErrorMessage),
                             _ => Err(serde::de::Error::unknown_field(value, FIELDS)),
                         }
                     }
@@ -389,6 +614,10 @@ impl<'de> serde::Deserialize<'de> for StreamStatus {
                 let mut r#type__ = None;
                 let mut start_version__ = None;
                 let mut end_version__ = None;
+                let mut schema_version__ = None;
+                let mut resume_token__ = None;
+                let mut redactions__ = None;
+                let mut error_message__ = None;
                 while let Some(k) = map.next_key()? {
                     match k {
                         GeneratedField::Type => {
@@ -413,12 +642,42 @@ impl<'de> serde::Deserialize<'de> for StreamStatus {
                                 map.next_value::<::pbjson::private::NumberDeserialize<_>>()?.0
                             );
                         }
+                        GeneratedField::SchemaVersion => {
+                            if schema_version__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("schemaVersion"));
+                            }
+                            schema_version__ = Some(
+                                map.next_value::<::pbjson::private::NumberDeserialize<_>>()?.0
+                            );
+                        }
+                        GeneratedField::ResumeToken => {
+                            if resume_token__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("resumeToken"));
+                            }
+                            resume_token__ = map.next_value()?;
+                        }
+                        GeneratedField::Redactions => {
+                            if redactions__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("redactions"));
+                            }
+                            redactions__ = Some(map.next_value()?);
+                        }
+                        GeneratedField::ErrorMessage => {
+                            if error_message__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("errorMessage"));
+                            }
+                            error_message__ = map.next_value()?;
+                        }
                     }
                 }
                 Ok(StreamStatus {
                     r#type: r#type__.unwrap_or_default(),
                     start_version: start_version__.unwrap_or_default(),
                     end_version: end_version__,
+                    schema_version: schema_version__,
+                    resume_token: resume_token__,
+                    redactions: redactions__.unwrap_or_default(),
+                    error_message: error_message__,
                 })
             }
         }
@@ -434,6 +693,9 @@ impl serde::Serialize for stream_status::StatusType {
         let variant = match self {
             Self::Init => "INIT",
             Self::BatchEnd => "BATCH_END",
+            Self::Completed => "COMPLETED",
+            Self::SlowConsumer => "SLOW_CONSUMER",
+            Self::Error => "ERROR",
         };
         serializer.serialize_str(variant)
     }
@@ -447,6 +709,9 @@ impl<'de> serde::Deserialize<'de> for stream_status::StatusType {
         const FIELDS: &[&str] = &[
             "INIT",
             "BATCH_END",
+            "COMPLETED",
+            "SLOW_CONSUMER",
+            "ERROR",
         ];
 
         struct GeneratedVisitor;
@@ -491,6 +756,9 @@ impl<'de> serde::Deserialize<'de> for stream_status::StatusType {
                 match value {
                     "INIT" => Ok(stream_status::StatusType::Init),
                     "BATCH_END" => Ok(stream_status::StatusType::BatchEnd),
+                    "COMPLETED" => Ok(stream_status::StatusType::Completed),
+                    "SLOW_CONSUMER" => Ok(stream_status::StatusType::SlowConsumer),
+                    "ERROR" => Ok(stream_status::StatusType::Error),
                     _ => Err(serde::de::Error::unknown_variant(value, FIELDS)),
                 }
             }
@@ -716,3 +984,301 @@ impl<'de> serde::Deserialize<'de> for TransactionsOutput {
         deserializer.deserialize_struct("aptos.datastream.v1.TransactionsOutput", FIELDS, GeneratedVisitor)
     }
 }
+impl serde::Serialize for WatchChainStatusRequest {
+    #[allow(deprecated)]
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        use serde::ser::SerializeStruct;
+        let mut len = 0;
+        if self.every_versions.is_some() {
+            len += 1;
+        }
+        if self.every_millis.is_some() {
+            len += 1;
+        }
+        let mut struct_ser = serializer.serialize_struct("aptos.datastream.v1.WatchChainStatusRequest", len)?;
+        if let Some(v) = self.every_versions.as_ref() {
+            struct_ser.serialize_field("everyVersions", ToString::to_string(&v).as_str())?;
+        }
+        if let Some(v) = self.every_millis.as_ref() {
+            struct_ser.serialize_field("everyMillis", ToString::to_string(&v).as_str())?;
+        }
+        struct_ser.end()
+    }
+}
+impl<'de> serde::Deserialize<'de> for WatchChainStatusRequest {
+    #[allow(deprecated)]
+    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        const FIELDS: &[&str] = &[
+            "everyVersions",
+            "everyMillis",
+        ];
+
+        #[allow(clippy::enum_variant_names)]
+        enum GeneratedField {
+            EveryVersions,
+            EveryMillis,
+        }
+        impl<'de> serde::Deserialize<'de> for GeneratedField {
+            fn deserialize<D>(deserializer: D) -> std::result::Result<GeneratedField, D::Error>
+            where
+                D: serde::Deserializer<'de>,
+            {
+                struct GeneratedVisitor;
+
+                impl<'de> serde::de::Visitor<'de> for GeneratedVisitor {
+                    type Value = GeneratedField;
+
+                    fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                        write!(formatter, "expected one of: {:?}", &FIELDS)
+                    }
+
+                    #[allow(unused_variables)]
+                    fn visit_str<E>(self, value: &str) -> std::result::Result<GeneratedField, E>
+                    where
+                        E: serde::de::Error,
+                    {
+                        match value {
+                            "everyVersions" => Ok(GeneratedField::EveryVersions),
+                            "everyMillis" => Ok(GeneratedField::EveryMillis),
+                            _ => Err(serde::de::Error::unknown_field(value, FIELDS)),
+                        }
+                    }
+                }
+                deserializer.deserialize_identifier(GeneratedVisitor)
+            }
+        }
+        struct GeneratedVisitor;
+        impl<'de> serde::de::Visitor<'de> for GeneratedVisitor {
+            type Value = WatchChainStatusRequest;
+
+            fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                formatter.write_str("struct aptos.datastream.v1.WatchChainStatusRequest")
+            }
+
+            fn visit_map<V>(self, mut map: V) -> std::result::Result<WatchChainStatusRequest, V::Error>
+                where
+                    V: serde::de::MapAccess<'de>,
+            {
+                let mut every_versions__ = None;
+                let mut every_millis__ = None;
+                while let Some(k) = map.next_key()? {
+                    match k {
+                        GeneratedField::EveryVersions => {
+                            if every_versions__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("everyVersions"));
+                            }
+                            every_versions__ = Some(
+                                map.next_value::<::pbjson::private::NumberDeserialize<_>>()?.0
+                            );
+                        }
+                        GeneratedField::EveryMillis => {
+                            if every_millis__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("everyMillis"));
+                            }
+                            every_millis__ = Some(
+                                map.next_value::<::pbjson::private::NumberDeserialize<_>>()?.0
+                            );
+                        }
+                    }
+                }
+                Ok(WatchChainStatusRequest {
+                    every_versions: every_versions__,
+                    every_millis: every_millis__,
+                })
+            }
+        }
+        deserializer.deserialize_struct("aptos.datastream.v1.WatchChainStatusRequest", FIELDS, GeneratedVisitor)
+    }
+}
+impl serde::Serialize for WatchChainStatusResponse {
+    #[allow(deprecated)]
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        use serde::ser::SerializeStruct;
+        let mut len = 0;
+        if self.chain_id != 0 {
+            len += 1;
+        }
+        if self.latest_version != 0 {
+            len += 1;
+        }
+        if self.epoch != 0 {
+            len += 1;
+        }
+        if self.block_height != 0 {
+            len += 1;
+        }
+        if self.timestamp.is_some() {
+            len += 1;
+        }
+        if self.oldest_available_version != 0 {
+            len += 1;
+        }
+        let mut struct_ser = serializer.serialize_struct("aptos.datastream.v1.WatchChainStatusResponse", len)?;
+        if self.chain_id != 0 {
+            struct_ser.serialize_field("chainId", &self.chain_id)?;
+        }
+        if self.latest_version != 0 {
+            struct_ser.serialize_field("latestVersion", ToString::to_string(&self.latest_version).as_str())?;
+        }
+        if self.epoch != 0 {
+            struct_ser.serialize_field("epoch", ToString::to_string(&self.epoch).as_str())?;
+        }
+        if self.block_height != 0 {
+            struct_ser.serialize_field("blockHeight", ToString::to_string(&self.block_height).as_str())?;
+        }
+        if let Some(v) = self.timestamp.as_ref() {
+            struct_ser.serialize_field("timestamp", v)?;
+        }
+        if self.oldest_available_version != 0 {
+            struct_ser.serialize_field("oldestAvailableVersion", ToString::to_string(&self.oldest_available_version).as_str())?;
+        }
+        struct_ser.end()
+    }
+}
+impl<'de> serde::Deserialize<'de> for WatchChainStatusResponse {
+    #[allow(deprecated)]
+    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        const FIELDS: &[&str] = &[
+            "chainId",
+            "latestVersion",
+            "epoch",
+            "blockHeight",
+            "timestamp",
+            "oldestAvailableVersion",
+        ];
+
+        #[allow(clippy::enum_variant_names)]
+        enum GeneratedField {
+            ChainId,
+            LatestVersion,
+            Epoch,
+            BlockHeight,
+            Timestamp,
+            OldestAvailableVersion,
+        }
+        impl<'de> serde::Deserialize<'de> for GeneratedField {
+            fn deserialize<D>(deserializer: D) -> std::result::Result<GeneratedField, D::Error>
+            where
+                D: serde::Deserializer<'de>,
+            {
+                struct GeneratedVisitor;
+
+                impl<'de> serde::de::Visitor<'de> for GeneratedVisitor {
+                    type Value = GeneratedField;
+
+                    fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                        write!(formatter, "expected one of: {:?}", &FIELDS)
+                    }
+
+                    #[allow(unused_variables)]
+                    fn visit_str<E>(self, value: &str) -> std::result::Result<GeneratedField, E>
+                    where
+                        E: serde::de::Error,
+                    {
+                        match value {
+                            "chainId" => Ok(GeneratedField::ChainId),
+                            "latestVersion" => Ok(GeneratedField::LatestVersion),
+                            "epoch" => Ok(GeneratedField::Epoch),
+                            "blockHeight" => Ok(GeneratedField::BlockHeight),
+                            "timestamp" => Ok(GeneratedField::Timestamp),
+                            "oldestAvailableVersion" => Ok(GeneratedField::OldestAvailableVersion),
+                            _ => Err(serde::de::Error::unknown_field(value, FIELDS)),
+                        }
+                    }
+                }
+                deserializer.deserialize_identifier(GeneratedVisitor)
+            }
+        }
+        struct GeneratedVisitor;
+        impl<'de> serde::de::Visitor<'de> for GeneratedVisitor {
+            type Value = WatchChainStatusResponse;
+
+            fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                formatter.write_str("struct aptos.datastream.v1.WatchChainStatusResponse")
+            }
+
+            fn visit_map<V>(self, mut map: V) -> std::result::Result<WatchChainStatusResponse, V::Error>
+                where
+                    V: serde::de::MapAccess<'de>,
+            {
+                let mut chain_id__ = None;
+                let mut latest_version__ = None;
+                let mut epoch__ = None;
+                let mut block_height__ = None;
+                let mut timestamp__ = None;
+                let mut oldest_available_version__ = None;
+                while let Some(k) = map.next_key()? {
+                    match k {
+                        GeneratedField::ChainId => {
+                            if chain_id__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("chainId"));
+                            }
+                            chain_id__ = Some(
+                                map.next_value::<::pbjson::private::NumberDeserialize<_>>()?.0
+                            );
+                        }
+                        GeneratedField::LatestVersion => {
+                            if latest_version__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("latestVersion"));
+                            }
+                            latest_version__ = Some(
+                                map.next_value::<::pbjson::private::NumberDeserialize<_>>()?.0
+                            );
+                        }
+                        GeneratedField::Epoch => {
+                            if epoch__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("epoch"));
+                            }
+                            epoch__ = Some(
+                                map.next_value::<::pbjson::private::NumberDeserialize<_>>()?.0
+                            );
+                        }
+                        GeneratedField::BlockHeight => {
+                            if block_height__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("blockHeight"));
+                            }
+                            block_height__ = Some(
+                                map.next_value::<::pbjson::private::NumberDeserialize<_>>()?.0
+                            );
+                        }
+                        GeneratedField::Timestamp => {
+                            if timestamp__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("timestamp"));
+                            }
+                            timestamp__ = Some(map.next_value()?);
+                        }
+                        GeneratedField::OldestAvailableVersion => {
+                            if oldest_available_version__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("oldestAvailableVersion"));
+                            }
+                            oldest_available_version__ = Some(
+                                map.next_value::<::pbjson::private::NumberDeserialize<_>>()?.0
+                            );
+                        }
+                    }
+                }
+                Ok(WatchChainStatusResponse {
+                    chain_id: chain_id__.unwrap_or_default(),
+                    latest_version: latest_version__.unwrap_or_default(),
+                    epoch: epoch__.unwrap_or_default(),
+                    block_height: block_height__.unwrap_or_default(),
+                    timestamp: timestamp__,
+                    oldest_available_version: oldest_available_version__.unwrap_or_default(),
+                })
+            }
+        }
+        deserializer.deserialize_struct("aptos.datastream.v1.WatchChainStatusResponse", FIELDS, GeneratedVisitor)
+    }
+}
@@ -95,6 +95,29 @@ pub mod indexer_stream_client {
             );
             self.inner.server_streaming(request.into_request(), path, codec).await
         }
+        /// Watchers too slow to keep up are terminated with RESOURCE_EXHAUSTED.
+        pub async fn watch_chain_status(
+            &mut self,
+            request: impl tonic::IntoRequest<super::WatchChainStatusRequest>,
+        ) -> Result<
+            tonic::Response<tonic::codec::Streaming<super::WatchChainStatusResponse>>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/aptos.datastream.v1.IndexerStream/WatchChainStatus",
+            );
+            self.inner.server_streaming(request.into_request(), path, codec).await
+        }
     }
 }
 /// Generated server implementations.
@@ -115,6 +138,17 @@ pub mod indexer_stream_server {
             &self,
             request: tonic::Request<super::RawDatastreamRequest>,
         ) -> Result<tonic::Response<Self::RawDatastreamStream>, tonic::Status>;
+        ///Server streaming response type for the WatchChainStatus method.
+        type WatchChainStatusStream: futures_core::Stream<
+                Item = Result<super::WatchChainStatusResponse, tonic::Status>,
+            >
+            + Send
+            + 'static;
+        /// Watchers too slow to keep up are terminated with RESOURCE_EXHAUSTED.
+        async fn watch_chain_status(
+            &self,
+            request: tonic::Request<super::WatchChainStatusRequest>,
+        ) -> Result<tonic::Response<Self::WatchChainStatusStream>, tonic::Status>;
     }
     ///
     #[derive(Debug)]
@@ -217,6 +251,48 @@ pub mod indexer_stream_server {
                     };
                     Box::pin(fut)
                 }
+                "/aptos.datastream.v1.IndexerStream/WatchChainStatus" => {
+                    #[allow(non_camel_case_types)]
+                    struct WatchChainStatusSvc<T: IndexerStream>(pub Arc<T>);
+                    impl<
+                        T: IndexerStream,
+                    > tonic::server::ServerStreamingService<
+                        super::WatchChainStatusRequest,
+                    > for WatchChainStatusSvc<T> {
+                        type Response = super::WatchChainStatusResponse;
+                        type ResponseStream = T::WatchChainStatusStream;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::ResponseStream>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::WatchChainStatusRequest>,
+                        ) -> Self::Future {
+                            let inner = self.0.clone();
+                            let fut = async move {
+                                (*inner).watch_chain_status(request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = WatchChainStatusSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            );
+                        let res = grpc.server_streaming(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
                 _ => {
                     Box::pin(async move {
                         Ok(
@@ -410,8 +410,8 @@ pub struct EntryFunctionPayload {
 }
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct MoveScriptBytecode {
-    #[prost(bytes="vec", tag="1")]
-    pub bytecode: ::prost::alloc::vec::Vec<u8>,
+    #[prost(bytes="bytes", tag="1")]
+    pub bytecode: ::prost::bytes::Bytes,
     #[prost(message, optional, tag="2")]
     pub abi: ::core::option::Option<MoveFunction>,
 }
@@ -431,8 +431,8 @@ pub struct ModuleBundlePayload {
 }
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct MoveModuleBytecode {
-    #[prost(bytes="vec", tag="1")]
-    pub bytecode: ::prost::alloc::vec::Vec<u8>,
+    #[prost(bytes="bytes", tag="1")]
+    pub bytecode: ::prost::bytes::Bytes,
     #[prost(message, optional, tag="2")]
     pub abi: ::core::option::Option<MoveModule>,
 }
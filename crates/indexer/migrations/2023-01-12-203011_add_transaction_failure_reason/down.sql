@@ -0,0 +1,7 @@
+-- This file should undo anything in `up.sql`
+DROP INDEX IF EXISTS txn_failure_index;
+ALTER TABLE transactions
+DROP COLUMN IF EXISTS failure_category,
+  DROP COLUMN IF EXISTS abort_module_address,
+  DROP COLUMN IF EXISTS abort_module_name,
+  DROP COLUMN IF EXISTS abort_code;
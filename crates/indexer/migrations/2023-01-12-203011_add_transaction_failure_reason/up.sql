@@ -0,0 +1,8 @@
+-- Your SQL goes here
+-- Parsed from vm_status, only set for failed transactions
+ALTER TABLE transactions
+ADD COLUMN IF NOT EXISTS failure_category VARCHAR(50),
+  ADD COLUMN IF NOT EXISTS abort_module_address VARCHAR(66),
+  ADD COLUMN IF NOT EXISTS abort_module_name VARCHAR(255),
+  ADD COLUMN IF NOT EXISTS abort_code NUMERIC;
+CREATE INDEX IF NOT EXISTS txn_failure_index ON transactions (failure_category, abort_module_address, abort_module_name);
@@ -0,0 +1,17 @@
+-- Your SQL goes here
+-- One row per block, derived from its block metadata transaction. last_version is null until
+-- the end of the block is indexed.
+CREATE TABLE IF NOT EXISTS blocks (
+  block_height BIGINT UNIQUE PRIMARY KEY NOT NULL,
+  block_hash VARCHAR(66) NOT NULL,
+  first_version BIGINT NOT NULL,
+  last_version BIGINT,
+  "timestamp" TIMESTAMP NOT NULL,
+  epoch BIGINT NOT NULL,
+  proposer VARCHAR(66) NOT NULL,
+  -- Default time columns
+  inserted_at TIMESTAMP NOT NULL DEFAULT NOW()
+);
+CREATE INDEX IF NOT EXISTS block_first_version_index ON blocks (first_version);
+CREATE INDEX IF NOT EXISTS block_timestamp_index ON blocks ("timestamp");
+CREATE INDEX IF NOT EXISTS block_insat_index ON blocks (inserted_at);
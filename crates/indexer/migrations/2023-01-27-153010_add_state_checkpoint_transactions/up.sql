@@ -0,0 +1,16 @@
+-- Your SQL goes here
+-- Details of the state checkpoint transactions, so that every version of the transactions table
+-- has its row in one of the transaction type tables, except for genesis
+CREATE TABLE IF NOT EXISTS state_checkpoint_transactions (
+  version BIGINT UNIQUE PRIMARY KEY NOT NULL,
+  block_height BIGINT NOT NULL,
+  state_checkpoint_hash VARCHAR(66),
+  epoch BIGINT NOT NULL,
+  "timestamp" TIMESTAMP NOT NULL,
+  -- Default time columns
+  inserted_at TIMESTAMP NOT NULL DEFAULT NOW(),
+  -- Constraints
+  CONSTRAINT fk_versions FOREIGN KEY (version) REFERENCES transactions (version)
+);
+CREATE INDEX IF NOT EXISTS sct_bh_index ON state_checkpoint_transactions (block_height);
+CREATE INDEX IF NOT EXISTS sct_insat_index ON state_checkpoint_transactions (inserted_at);
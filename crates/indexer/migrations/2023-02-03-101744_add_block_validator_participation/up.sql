@@ -0,0 +1,19 @@
+-- Your SQL goes here
+-- One row per validator of the epoch and block, expanded from the previous block votes bitvec and
+-- the failed proposer indices of the block metadata transaction. validator_address is null when
+-- the validator set of the epoch wasn't indexed.
+CREATE TABLE IF NOT EXISTS block_validator_participation (
+  block_height BIGINT NOT NULL,
+  validator_index BIGINT NOT NULL,
+  version BIGINT NOT NULL,
+  epoch BIGINT NOT NULL,
+  validator_address VARCHAR(66),
+  voted BOOLEAN NOT NULL,
+  failed_to_propose BOOLEAN NOT NULL,
+  -- Default time columns
+  inserted_at TIMESTAMP NOT NULL DEFAULT NOW(),
+  -- Constraints
+  PRIMARY KEY (block_height, validator_index)
+);
+CREATE INDEX IF NOT EXISTS bvp_epoch_addr_index ON block_validator_participation (epoch, validator_address);
+CREATE INDEX IF NOT EXISTS bvp_insat_index ON block_validator_participation (inserted_at);
@@ -0,0 +1,5 @@
+-- Your SQL goes here
+-- The error the processor stopped on after its last successful version, cleared once it gets
+-- past it
+ALTER TABLE processor_status
+ADD COLUMN IF NOT EXISTS last_error TEXT;
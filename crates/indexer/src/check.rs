@@ -0,0 +1,451 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+//! Checks that the indexer is set up correctly without indexing anything, so that a typo in the
+//! database url or a missing migration shows up at deploy time rather than on the first batch.
+//! Exposed through `aptos-node --config <path> check-indexer`.
+
+use crate::{
+    database::{new_db_pool, PgPoolConnection},
+    indexer::{
+        fetcher::TransactionFetcherTrait,
+        skip_list::VersionSkipList,
+        tailer::{pending_migrations, verify_chain_id},
+    },
+    models::ledger_info::LedgerInfo,
+    processors::Processor,
+};
+use anyhow::{anyhow, bail, ensure, Result};
+use aptos_config::config::IndexerConfig;
+use serde::Serialize;
+use std::{
+    fmt,
+    panic::{catch_unwind, AssertUnwindSafe},
+};
+
+pub const CONFIG_CHECK: &str = "config";
+pub const DATABASE_CHECK: &str = "database";
+pub const MIGRATIONS_CHECK: &str = "migrations";
+pub const UPSTREAM_CHECK: &str = "upstream";
+pub const CHAIN_ID_CHECK: &str = "chain_id";
+
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+pub struct CheckResult {
+    pub name: &'static str,
+    pub passed: bool,
+    /// What was found if the check passed, otherwise what went wrong
+    pub detail: String,
+}
+
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct CheckReport {
+    pub checks: Vec<CheckResult>,
+}
+
+impl CheckReport {
+    /// Whether every check passed
+    pub fn passed(&self) -> bool {
+        self.checks.iter().all(|check| check.passed)
+    }
+
+    pub fn get(&self, name: &str) -> Option<&CheckResult> {
+        self.checks.iter().find(|check| check.name == name)
+    }
+
+    fn record<T>(&mut self, name: &'static str, result: Result<(T, String)>) -> Option<T> {
+        match result {
+            Ok((value, detail)) => {
+                self.checks.push(CheckResult {
+                    name,
+                    passed: true,
+                    detail,
+                });
+                Some(value)
+            },
+            Err(err) => {
+                self.checks.push(CheckResult {
+                    name,
+                    passed: false,
+                    detail: format!("{:#}", err),
+                });
+                None
+            },
+        }
+    }
+}
+
+impl fmt::Display for CheckReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for check in &self.checks {
+            let status = if check.passed { "PASS" } else { "FAIL" };
+            writeln!(f, "[{}] {}: {}", status, check.name, check.detail)?;
+        }
+        write!(
+            f,
+            "{}/{} checks passed",
+            self.checks.iter().filter(|check| check.passed).count(),
+            self.checks.len()
+        )
+    }
+}
+
+/// Runs every check, going as far as possible even if some of them fail. The fetcher is the
+/// upstream the indexer would read transactions from.
+pub fn run_checks(
+    config: &IndexerConfig,
+    fetcher: &mut dyn TransactionFetcherTrait,
+) -> CheckReport {
+    let mut report = CheckReport::default();
+    report.record(CONFIG_CHECK, check_config(config));
+
+    let mut conn = report.record(DATABASE_CHECK, connect(config));
+    match conn.as_mut() {
+        Some(conn) => {
+            report.record(MIGRATIONS_CHECK, check_migrations(conn));
+        },
+        None => {
+            report.record::<()>(MIGRATIONS_CHECK, Err(anyhow!("No database connection")));
+        },
+    }
+
+    let upstream_chain_id = report.record(UPSTREAM_CHECK, check_upstream(fetcher));
+    let result = match (conn.as_mut(), upstream_chain_id) {
+        _ if config.check_chain_id == Some(false) => {
+            Ok(((), "Skipped, check_chain_id is disabled".to_string()))
+        },
+        (Some(conn), Some(upstream_chain_id)) => check_chain_id(conn, upstream_chain_id),
+        (None, _) => Err(anyhow!("No database connection")),
+        (_, None) => Err(anyhow!("No upstream chain id")),
+    };
+    report.record(CHAIN_ID_CHECK, result);
+    report
+}
+
+fn check_config(config: &IndexerConfig) -> Result<((), String)> {
+    ensure!(config.enabled, "The indexer is not enabled");
+    let processor_name = config
+        .processor
+        .as_ref()
+        .ok_or_else(|| anyhow!("No processor is set"))?;
+    if Processor::try_from_string(processor_name).is_none() {
+        bail!("Processor unsupported {}", processor_name);
+    }
+    let mut processor_names = vec![processor_name];
+    for fan_out_processor in &config.fan_out_processors {
+        if Processor::try_from_string(fan_out_processor).is_none() {
+            bail!("Processor unsupported {}", fan_out_processor);
+        }
+        ensure!(
+            !processor_names.contains(&fan_out_processor),
+            "Processor {} is run more than once",
+            fan_out_processor
+        );
+        processor_names.push(fan_out_processor);
+    }
+    for (start, end) in &config.skip_ranges {
+        ensure!(start <= end, "Invalid skip range [{}, {}]", start, end);
+    }
+    if let (Some(start), Some(end)) = (config.starting_version, config.ending_version) {
+        ensure!(
+            start <= end,
+            "Starting version {} is after the ending version {}",
+            start,
+            end
+        );
+    }
+    if config.force_starting_version == Some(true) {
+        ensure!(
+            config.starting_version.is_some(),
+            "force_starting_version is set without a starting_version"
+        );
+    }
+    let skip_list = VersionSkipList::new(&config.skip_versions, &config.skip_ranges);
+    let mut detail = format!("Processor {}", processor_name);
+    if !config.fan_out_processors.is_empty() {
+        detail = format!(
+            "{}, along with {}",
+            detail,
+            config.fan_out_processors.join(", ")
+        );
+    }
+    if let Some(end) = config.ending_version {
+        detail = format!("{}, stopping after version {}", detail, end);
+    }
+    if !skip_list.is_empty() {
+        detail = format!("{}, skipping versions {}", detail, skip_list);
+    }
+    Ok(((), detail))
+}
+
+fn connect(config: &IndexerConfig) -> Result<(PgPoolConnection, String)> {
+    let db_uri = config
+        .postgres_uri
+        .as_ref()
+        .ok_or_else(|| anyhow!("No postgres uri is set"))?;
+    let conn = new_db_pool(db_uri)?.get()?;
+    Ok((conn, "Connected".to_string()))
+}
+
+fn check_migrations(conn: &mut PgPoolConnection) -> Result<((), String)> {
+    let pending = pending_migrations(conn)?;
+    ensure!(
+        pending.is_empty(),
+        "{} pending migrations: {}",
+        pending.len(),
+        pending.join(", ")
+    );
+    Ok(((), "All migrations applied".to_string()))
+}
+
+fn check_upstream(fetcher: &mut dyn TransactionFetcherTrait) -> Result<(i64, String)> {
+    // The production fetcher panics when the ledger info can't be read
+    let ledger_info = catch_unwind(AssertUnwindSafe(|| fetcher.fetch_ledger_info()))
+        .map_err(|_| anyhow!("Failed to fetch the ledger info"))?;
+    Ok((
+        ledger_info.chain_id as i64,
+        format!(
+            "Chain {} at version {}",
+            ledger_info.chain_id, ledger_info.ledger_version
+        ),
+    ))
+}
+
+fn check_chain_id(conn: &mut PgPoolConnection, upstream_chain_id: i64) -> Result<((), String)> {
+    let existing_chain_id = LedgerInfo::get(conn)?.map(|li| li.chain_id);
+    verify_chain_id(existing_chain_id, upstream_chain_id)?;
+    let detail = match existing_chain_id {
+        Some(chain_id) => format!("Existing data is for chain {}", chain_id),
+        None => "No existing data".to_string(),
+    };
+    Ok(((), detail))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        indexer::tailer::{test::wipe_database, MIGRATIONS},
+        schema::ledger_infos,
+    };
+    use aptos_api_types::{LedgerInfo as APILedgerInfo, Transaction, U64};
+    use diesel::RunQueryDsl;
+    use diesel_migrations::MigrationHarness;
+
+    struct FakeUpstream {
+        chain_id: Option<u8>,
+    }
+
+    #[async_trait::async_trait]
+    impl TransactionFetcherTrait for FakeUpstream {
+        async fn fetch_next_batch(&mut self) -> Vec<Transaction> {
+            unreachable!("Checks never fetch transactions")
+        }
+
+        fn fetch_ledger_info(&mut self) -> APILedgerInfo {
+            APILedgerInfo {
+                chain_id: self.chain_id.expect("Upstream is down"),
+                epoch: U64::from(0),
+                ledger_version: U64::from(100),
+                ledger_timestamp: U64::from(0),
+                oldest_ledger_version: U64::from(0),
+                oldest_block_height: U64::from(0),
+                block_height: U64::from(0),
+            }
+        }
+
+        async fn set_version(&mut self, _version: u64) {
+            unreachable!("Checks never fetch transactions")
+        }
+
+        async fn start(&mut self) {
+            unreachable!("Checks never fetch transactions")
+        }
+    }
+
+    fn test_config() -> IndexerConfig {
+        IndexerConfig {
+            enabled: true,
+            postgres_uri: std::env::var("INDEXER_DATABASE_URL").ok(),
+            processor: Some("default_processor".to_string()),
+            check_chain_id: Some(true),
+            ..Default::default()
+        }
+    }
+
+    fn setup_database(chain_id: Option<i64>) -> PgPoolConnection {
+        let mut conn = new_db_pool(&std::env::var("INDEXER_DATABASE_URL").unwrap())
+            .unwrap()
+            .get()
+            .unwrap();
+        wipe_database(&mut conn);
+        conn.run_pending_migrations(MIGRATIONS).unwrap();
+        if let Some(chain_id) = chain_id {
+            diesel::insert_into(ledger_infos::table)
+                .values(LedgerInfo { chain_id })
+                .execute(&mut conn)
+                .unwrap();
+        }
+        conn
+    }
+
+    fn failed_checks(report: &CheckReport) -> Vec<&'static str> {
+        report
+            .checks
+            .iter()
+            .filter(|check| !check.passed)
+            .map(|check| check.name)
+            .collect()
+    }
+
+    #[test]
+    fn test_invalid_config() {
+        let config = IndexerConfig {
+            processor: Some("defualt_processor".to_string()),
+            postgres_uri: None,
+            ..test_config()
+        };
+        let report = run_checks(&config, &mut FakeUpstream { chain_id: Some(4) });
+        assert!(!report.passed());
+        assert_eq!(
+            failed_checks(&report),
+            vec![
+                CONFIG_CHECK,
+                DATABASE_CHECK,
+                MIGRATIONS_CHECK,
+                CHAIN_ID_CHECK
+            ]
+        );
+        assert_eq!(
+            report.get(CONFIG_CHECK).unwrap().detail,
+            "Processor unsupported defualt_processor"
+        );
+        assert_eq!(
+            report.get(UPSTREAM_CHECK).unwrap().detail,
+            "Chain 4 at version 100"
+        );
+        assert!(report
+            .to_string()
+            .ends_with("[PASS] upstream: Chain 4 at version 100\n[FAIL] chain_id: No database connection\n1/5 checks passed"));
+    }
+
+    #[test]
+    fn test_backfill_config() {
+        let config = IndexerConfig {
+            starting_version: Some(100),
+            ending_version: Some(200),
+            ..test_config()
+        };
+        assert_eq!(
+            check_config(&config).unwrap().1,
+            "Processor default_processor, stopping after version 200"
+        );
+        let err = check_config(&IndexerConfig {
+            ending_version: Some(99),
+            ..config.clone()
+        })
+        .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Starting version 100 is after the ending version 99"
+        );
+
+        // Every processor can overwrite the rows it wrote
+        assert!(check_config(&IndexerConfig {
+            processor: Some("coin_processor".to_string()),
+            overwrite_processed_versions: Some(true),
+            ..config
+        })
+        .is_ok());
+    }
+
+    #[test]
+    fn test_forced_starting_version_config() {
+        let config = IndexerConfig {
+            force_starting_version: Some(true),
+            ..test_config()
+        };
+        assert_eq!(
+            check_config(&config).unwrap_err().to_string(),
+            "force_starting_version is set without a starting_version"
+        );
+        assert!(check_config(&IndexerConfig {
+            starting_version: Some(100),
+            ..config
+        })
+        .is_ok());
+    }
+
+    #[test]
+    fn test_fan_out_config() {
+        let config = IndexerConfig {
+            fan_out_processors: vec!["coin_processor".to_string(), "stake_processor".to_string()],
+            ..test_config()
+        };
+        assert_eq!(
+            check_config(&config).unwrap().1,
+            "Processor default_processor, along with coin_processor, stake_processor"
+        );
+        assert_eq!(
+            check_config(&IndexerConfig {
+                fan_out_processors: vec!["default_processor".to_string()],
+                ..config.clone()
+            })
+            .unwrap_err()
+            .to_string(),
+            "Processor default_processor is run more than once"
+        );
+        assert!(check_config(&IndexerConfig {
+            fan_out_processors: vec!["coin_procesor".to_string()],
+            ..config
+        })
+        .is_err());
+    }
+
+    #[test]
+    fn test_healthy_setup() {
+        if crate::should_skip_pg_tests() {
+            return;
+        }
+        setup_database(Some(4));
+
+        let report = run_checks(&test_config(), &mut FakeUpstream { chain_id: Some(4) });
+        assert!(report.passed(), "{}", report);
+        assert_eq!(report.checks.len(), 5);
+        assert_eq!(
+            report.get(CHAIN_ID_CHECK).unwrap().detail,
+            "Existing data is for chain 4"
+        );
+    }
+
+    #[test]
+    fn test_broken_setups() {
+        if crate::should_skip_pg_tests() {
+            return;
+        }
+
+        // Indexing another chain than the existing data, with the upstream down
+        setup_database(Some(4));
+        let report = run_checks(&test_config(), &mut FakeUpstream { chain_id: Some(1) });
+        assert_eq!(failed_checks(&report), vec![CHAIN_ID_CHECK]);
+        assert_eq!(
+            report.get(CHAIN_ID_CHECK).unwrap().detail,
+            "Wrong chain detected! Trying to index chain 1 now but existing data is for chain 4"
+        );
+        let report = run_checks(&test_config(), &mut FakeUpstream { chain_id: None });
+        assert_eq!(failed_checks(&report), vec![UPSTREAM_CHECK, CHAIN_ID_CHECK]);
+
+        // Migrations not applied
+        let mut conn = setup_database(None);
+        wipe_database(&mut conn);
+        let report = run_checks(&test_config(), &mut FakeUpstream { chain_id: Some(4) });
+        assert_eq!(
+            failed_checks(&report),
+            vec![MIGRATIONS_CHECK, CHAIN_ID_CHECK]
+        );
+        assert!(report
+            .get(MIGRATIONS_CHECK)
+            .unwrap()
+            .detail
+            .contains("pending migrations"));
+    }
+}
@@ -37,6 +37,26 @@ pub static PROCESSOR_SUCCESSES: Lazy<IntCounterVec> = Lazy::new(|| {
     .unwrap()
 });
 
+/// Number of versions skipped by a given processor because the operator configured them to be
+pub static PROCESSOR_OPERATOR_SKIPS: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "indexer_processor_operator_skip_count",
+        "Number of versions skipped by a given processor because of the operator skip list",
+        &["processor_name"]
+    )
+    .unwrap()
+});
+
+/// Number of gaps a given processor found between the versions it processed and the next batch
+pub static PROCESSOR_VERSION_GAPS: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "indexer_processor_version_gap_count",
+        "Number of gaps a given processor found before a batch",
+        &["processor_name"]
+    )
+    .unwrap()
+});
+
 /// Number of times the connection pool has timed out when trying to get a connection
 pub static UNABLE_TO_GET_CONNECTION: Lazy<IntCounter> = Lazy::new(|| {
     register_int_counter!(
@@ -82,3 +102,23 @@ pub static LATEST_PROCESSED_VERSION: Lazy<IntGaugeVec> = Lazy::new(|| {
     )
     .unwrap()
 });
+
+/// Number of batches waiting in the queue of a processor of the fan-out
+pub static PROCESSOR_QUEUE_DEPTH: Lazy<IntGaugeVec> = Lazy::new(|| {
+    register_int_gauge_vec!(
+        "indexer_processor_queue_depth",
+        "Number of batches waiting in the queue of a processor of the fan-out",
+        &["processor_name"]
+    )
+    .unwrap()
+});
+
+/// Number of times the fan-out paused reading upstream because the queue of a processor was full
+pub static PROCESSOR_QUEUE_FULL_COUNT: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "indexer_processor_queue_full_count",
+        "Number of times the fan-out paused reading upstream because the queue of a processor was full",
+        &["processor_name"]
+    )
+    .unwrap()
+});
@@ -0,0 +1,230 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+//! Ordering of the per-table inserts of a batch
+use anyhow::{bail, Result};
+use diesel::{PgConnection, QueryResult};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+/// Which tables have to be written before which within a batch, e.g. so that the foreign keys
+/// some deployments add from events to transactions hold at all times. Processors register the
+/// tables they write to along with the tables they depend on.
+#[derive(Clone, Debug, Default)]
+pub struct TableDependencies {
+    // table -> tables it depends on
+    dependencies: BTreeMap<&'static str, BTreeSet<&'static str>>,
+}
+
+impl TableDependencies {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers the table, to be written after the given tables. Registering a table again adds
+    /// to its dependencies.
+    pub fn register(mut self, table: &'static str, depends_on: &[&'static str]) -> Self {
+        for dependency in depends_on {
+            self.dependencies.entry(dependency).or_default();
+        }
+        self.dependencies
+            .entry(table)
+            .or_default()
+            .extend(depends_on.iter().copied());
+        self
+    }
+
+    /// The tables in stages, each table depending on tables of earlier stages only. Tables of the
+    /// same stage are independent of each other, so they can be written in any order or in
+    /// parallel. Fails if the dependencies have a cycle.
+    pub fn stages(&self) -> Result<Vec<Vec<&'static str>>> {
+        let mut remaining = self.dependencies.clone();
+        let mut stages: Vec<Vec<&'static str>> = vec![];
+        while !remaining.is_empty() {
+            let stage: Vec<_> = remaining
+                .iter()
+                .filter(|(_, dependencies)| dependencies.is_empty())
+                .map(|(table, _)| *table)
+                .collect();
+            if stage.is_empty() {
+                bail!(
+                    "Cyclic dependencies between tables {:?}",
+                    remaining.keys().collect::<Vec<_>>()
+                );
+            }
+            for table in &stage {
+                remaining.remove(table);
+            }
+            for dependencies in remaining.values_mut() {
+                for table in &stage {
+                    dependencies.remove(table);
+                }
+            }
+            stages.push(stage);
+        }
+        Ok(stages)
+    }
+
+    /// The tables in the order they are written in
+    pub fn write_order(&self) -> Result<Vec<&'static str>> {
+        Ok(self.stages()?.into_iter().flatten().collect())
+    }
+}
+
+type TableWrite<'a> = Box<dyn FnOnce(&mut PgConnection) -> QueryResult<()> + 'a>;
+
+/// Collects the inserts of a batch by table, and runs them in an order that respects the
+/// dependencies between the tables, whatever the order they were added in.
+pub struct BatchWriter<'a> {
+    write_order: Vec<&'static str>,
+    writes: HashMap<&'static str, TableWrite<'a>>,
+}
+
+impl<'a> BatchWriter<'a> {
+    /// Panics if the dependencies have a cycle, which would be a bug of the processor.
+    pub fn new(dependencies: &TableDependencies) -> Self {
+        Self {
+            write_order: dependencies
+                .write_order()
+                .expect("Table dependencies must not have cycles"),
+            writes: HashMap::new(),
+        }
+    }
+
+    /// Adds the insert of the table, which has to be registered in the dependencies.
+    pub fn add(
+        &mut self,
+        table: &'static str,
+        write: impl FnOnce(&mut PgConnection) -> QueryResult<()> + 'a,
+    ) -> &mut Self {
+        assert!(
+            self.write_order.contains(&table),
+            "Table {} has no registered dependencies",
+            table
+        );
+        assert!(
+            self.writes.insert(table, Box::new(write)).is_none(),
+            "Table {} is written twice",
+            table
+        );
+        self
+    }
+
+    /// The tables written so far, in the order they are written in
+    pub fn tables(&self) -> Vec<&'static str> {
+        self.write_order
+            .iter()
+            .filter(|table| self.writes.contains_key(*table))
+            .copied()
+            .collect()
+    }
+
+    pub fn execute(mut self, conn: &mut PgConnection) -> QueryResult<()> {
+        for table in &self.write_order {
+            if let Some(write) = self.writes.remove(table) {
+                write(conn)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::indexer::tailer::test::setup_indexer;
+    use aptos_indexer_test_fixtures::{
+        load_transaction, BLOCK_METADATA_TRANSACTION, GENESIS_TRANSACTION, USER_TRANSACTION,
+    };
+    use diesel::RunQueryDsl;
+
+    fn dependencies() -> TableDependencies {
+        TableDependencies::new()
+            .register("events", &["transactions"])
+            .register("write_set_changes", &["transactions"])
+            .register("move_resources", &["write_set_changes"])
+            .register("signatures", &["user_transactions"])
+            .register("user_transactions", &["transactions"])
+            .register("current_table_items", &[])
+    }
+
+    #[test]
+    fn test_stages() {
+        assert_eq!(dependencies().stages().unwrap(), vec![
+            vec!["current_table_items", "transactions"],
+            vec!["events", "user_transactions", "write_set_changes"],
+            vec!["move_resources", "signatures"],
+        ]);
+    }
+
+    #[test]
+    fn test_extending_dependencies() {
+        // Another processor depending on tables of the first one
+        let dependencies = dependencies()
+            .register("blocks", &[])
+            .register("transactions", &["blocks"]);
+        assert_eq!(dependencies.write_order().unwrap(), vec![
+            "blocks",
+            "current_table_items",
+            "transactions",
+            "events",
+            "user_transactions",
+            "write_set_changes",
+            "move_resources",
+            "signatures",
+        ]);
+    }
+
+    #[test]
+    fn test_cycle() {
+        let dependencies = dependencies().register("transactions", &["signatures"]);
+        assert!(dependencies.stages().is_err());
+    }
+
+    #[test]
+    fn test_writes_ordered_by_dependencies() {
+        let dependencies = dependencies();
+        let mut writer = BatchWriter::new(&dependencies);
+        for table in ["signatures", "events", "transactions", "user_transactions"] {
+            writer.add(table, |_| Ok(()));
+        }
+        assert_eq!(
+            writer.tables(),
+            vec!["transactions", "events", "user_transactions", "signatures"]
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_unregistered_table() {
+        BatchWriter::new(&dependencies()).add("coin_activities", |_| Ok(()));
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_foreign_keys_hold() {
+        if crate::should_skip_pg_tests() {
+            return;
+        }
+        let (conn_pool, tailer) = setup_indexer().unwrap();
+        for command in [
+            "ALTER TABLE user_transactions ADD FOREIGN KEY (version) REFERENCES transactions (version)",
+            "ALTER TABLE signatures ADD FOREIGN KEY (transaction_version) REFERENCES user_transactions (version)",
+            "ALTER TABLE events ADD FOREIGN KEY (transaction_version) REFERENCES transactions (version)",
+            "ALTER TABLE write_set_changes ADD FOREIGN KEY (transaction_version) REFERENCES transactions (version)",
+            "ALTER TABLE move_resources ADD FOREIGN KEY (transaction_version, write_set_change_index) REFERENCES write_set_changes (transaction_version, index)",
+        ] {
+            diesel::sql_query(command)
+                .execute(&mut conn_pool.get().unwrap())
+                .unwrap();
+        }
+
+        tailer
+            .processor
+            .process_transactions_with_status(vec![
+                load_transaction(GENESIS_TRANSACTION),
+                load_transaction(BLOCK_METADATA_TRANSACTION),
+                load_transaction(USER_TRANSACTION),
+            ])
+            .await
+            .unwrap();
+    }
+}
@@ -0,0 +1,286 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::indexer::fetcher::TransactionFetcherTrait;
+use aptos_api_types::{LedgerInfo, Transaction};
+use aptos_logger::warn;
+use std::{collections::VecDeque, sync::Arc};
+use tokio::sync::Mutex;
+
+/// Re-chunks the batches of another fetcher so that they only ever contain whole blocks, which
+/// keeps every block within a single database transaction. Transactions of a block that isn't
+/// complete yet are held back until the rest of it has been fetched.
+pub struct BlockAlignedFetcher {
+    inner: Arc<Mutex<dyn TransactionFetcherTrait>>,
+    max_batch_size: usize,
+    pending: VecDeque<Transaction>,
+}
+
+impl BlockAlignedFetcher {
+    /// Batches hold as many whole blocks as fit in `max_batch_size` transactions, except for
+    /// blocks larger than that which are processed on their own
+    pub fn new(inner: Arc<Mutex<dyn TransactionFetcherTrait>>, max_batch_size: usize) -> Self {
+        Self {
+            inner,
+            max_batch_size: max_batch_size.max(1),
+            pending: VecDeque::new(),
+        }
+    }
+
+    /// Whether the transaction at the given index of the pending transactions is the last one of
+    /// its block. Blocks end with a state checkpoint, and genesis is a block of its own, but
+    /// checking for the start of the next block as well doesn't rely on that.
+    fn ends_block(&self, index: usize) -> bool {
+        match &self.pending[index] {
+            Transaction::StateCheckpointTransaction(_) | Transaction::GenesisTransaction(_) => true,
+            _ => matches!(
+                self.pending.get(index + 1),
+                Some(Transaction::BlockMetadataTransaction(_))
+            ),
+        }
+    }
+
+    /// Takes the longest run of whole blocks from the pending transactions that fits in a batch
+    fn take_whole_blocks(&mut self) -> Option<Vec<Transaction>> {
+        let mut batch_size = 0;
+        for index in 0..self.pending.len() {
+            if !self.ends_block(index) {
+                continue;
+            }
+            let block_end = index + 1;
+            if block_end > self.max_batch_size {
+                if batch_size == 0 {
+                    warn!(
+                        num_transactions = block_end,
+                        max_batch_size = self.max_batch_size,
+                        "Block is larger than the batch size, processing it alone"
+                    );
+                    batch_size = block_end;
+                }
+                break;
+            }
+            batch_size = block_end;
+        }
+
+        if batch_size == 0 {
+            None
+        } else {
+            Some(self.pending.drain(..batch_size).collect())
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl TransactionFetcherTrait for BlockAlignedFetcher {
+    async fn fetch_next_batch(&mut self) -> Vec<Transaction> {
+        loop {
+            if let Some(batch) = self.take_whole_blocks() {
+                return batch;
+            }
+            let transactions = self.inner.lock().await.fetch_next_batch().await;
+            // Caught up, the rest of the block isn't available yet
+            if transactions.is_empty() {
+                return vec![];
+            }
+            self.pending.extend(transactions);
+        }
+    }
+
+    fn fetch_ledger_info(&mut self) -> LedgerInfo {
+        self.inner
+            .try_lock()
+            .expect("Inner fetcher is only used through the block aligned fetcher")
+            .fetch_ledger_info()
+    }
+
+    async fn set_version(&mut self, version: u64) {
+        self.pending.clear();
+        self.inner.lock().await.set_version(version).await;
+    }
+
+    async fn start(&mut self) {
+        self.inner.lock().await.start().await;
+    }
+
+    async fn restart(&mut self, version: u64) {
+        self.pending.clear();
+        self.inner.lock().await.restart(version).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aptos_api_types::U64;
+    use aptos_indexer_test_fixtures::{
+        load_transaction, BLOCK_METADATA_TRANSACTION, GENESIS_TRANSACTION,
+        STATE_CHECKPOINT_TRANSACTION, USER_TRANSACTION,
+    };
+
+    /// Hands out the given batches in order, then nothing
+    struct FakeStream {
+        batches: VecDeque<Vec<Transaction>>,
+    }
+
+    #[async_trait::async_trait]
+    impl TransactionFetcherTrait for FakeStream {
+        async fn fetch_next_batch(&mut self) -> Vec<Transaction> {
+            self.batches.pop_front().unwrap_or_default()
+        }
+
+        fn fetch_ledger_info(&mut self) -> LedgerInfo {
+            unreachable!()
+        }
+
+        async fn set_version(&mut self, _version: u64) {}
+
+        async fn start(&mut self) {}
+    }
+
+    fn with_version(mut txn: Transaction, version: u64) -> Transaction {
+        let info = match &mut txn {
+            Transaction::UserTransaction(txn) => &mut txn.info,
+            Transaction::GenesisTransaction(txn) => &mut txn.info,
+            Transaction::BlockMetadataTransaction(txn) => &mut txn.info,
+            Transaction::StateCheckpointTransaction(txn) => &mut txn.info,
+            Transaction::PendingTransaction(_) => unreachable!(),
+        };
+        info.version = U64::from(version);
+        txn
+    }
+
+    /// Genesis, then blocks with the given numbers of user transactions, each starting with block
+    /// metadata and ending with a state checkpoint. Returns the transactions along with the last
+    /// version of each block.
+    fn chain(num_user_txns: &[usize]) -> (Vec<Transaction>, Vec<u64>) {
+        let mut txns = vec![load_transaction(GENESIS_TRANSACTION)];
+        let mut block_ends = vec![0];
+        for num in num_user_txns {
+            txns.push(load_transaction(BLOCK_METADATA_TRANSACTION));
+            for _ in 0..*num {
+                txns.push(load_transaction(USER_TRANSACTION));
+            }
+            txns.push(load_transaction(STATE_CHECKPOINT_TRANSACTION));
+            block_ends.push(txns.len() as u64 - 1);
+        }
+        let txns = txns
+            .into_iter()
+            .enumerate()
+            .map(|(version, txn)| with_version(txn, version as u64))
+            .collect();
+        (txns, block_ends)
+    }
+
+    /// Splits the transactions into stream batches of the given sizes, in a loop
+    fn split(txns: Vec<Transaction>, sizes: &[usize]) -> VecDeque<Vec<Transaction>> {
+        let mut txns = txns.into_iter().peekable();
+        let mut batches = VecDeque::new();
+        for size in sizes.iter().cycle() {
+            if txns.peek().is_none() {
+                break;
+            }
+            batches.push_back(txns.by_ref().take(*size).collect());
+        }
+        batches
+    }
+
+    /// Fetches batches until caught up, returning the versions of each
+    async fn fetch_all(fetcher: &mut BlockAlignedFetcher) -> Vec<Vec<u64>> {
+        let mut batches = vec![];
+        loop {
+            let batch = fetcher.fetch_next_batch().await;
+            if batch.is_empty() {
+                return batches;
+            }
+            batches.push(batch.iter().map(|txn| txn.version().unwrap()).collect());
+        }
+    }
+
+    fn fetcher(
+        batches: VecDeque<Vec<Transaction>>,
+        max_batch_size: usize,
+    ) -> (BlockAlignedFetcher, Arc<Mutex<FakeStream>>) {
+        let stream = Arc::new(Mutex::new(FakeStream { batches }));
+        (
+            BlockAlignedFetcher::new(stream.clone(), max_batch_size),
+            stream,
+        )
+    }
+
+    #[tokio::test]
+    async fn test_batches_only_contain_whole_blocks() {
+        let (txns, block_ends) = chain(&[3, 0, 5, 1, 2, 4]);
+        let num_txns = txns.len() as u64;
+        // Stream batches cutting through blocks at every possible place
+        let (mut fetcher, _) = fetcher(split(txns, &[4, 1, 7, 3]), 10);
+        let batches = fetch_all(&mut fetcher).await;
+
+        // Nothing is lost or reordered
+        let versions: Vec<u64> = batches.iter().flatten().copied().collect();
+        assert_eq!(versions, (0..num_txns).collect::<Vec<_>>());
+        for batch in &batches {
+            assert!(batch.len() <= 10);
+            // Watermarks land on block ends, and the batch starts right after one
+            let last = *batch.last().unwrap();
+            assert!(block_ends.contains(&last), "{:?}", batch);
+            let first = batch[0];
+            assert!(
+                first == 0 || block_ends.contains(&(first - 1)),
+                "{:?}",
+                batch
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn test_whole_blocks_packed_up_to_max_batch_size() {
+        // Blocks of 5, 2, 7, 3, 4 and 6 transactions after genesis
+        let (txns, _) = chain(&[3, 0, 5, 1, 2, 4]);
+        let (mut fetcher, _) = fetcher(VecDeque::from(vec![txns]), 10);
+        let batches = fetch_all(&mut fetcher).await;
+
+        let lengths: Vec<usize> = batches.iter().map(|batch| batch.len()).collect();
+        assert_eq!(lengths, vec![8, 10, 10]);
+    }
+
+    #[tokio::test]
+    async fn test_large_block_processed_alone() {
+        let (txns, block_ends) = chain(&[1, 12, 1]);
+        let (mut fetcher, _) = fetcher(split(txns, &[3]), 5);
+        let batches = fetch_all(&mut fetcher).await;
+
+        // The block of 14 transactions is larger than the batch size
+        let lengths: Vec<usize> = batches.iter().map(|batch| batch.len()).collect();
+        assert_eq!(lengths, vec![1, 3, 14, 3]);
+        let ends: Vec<u64> = batches.iter().map(|batch| *batch.last().unwrap()).collect();
+        assert_eq!(ends, block_ends);
+    }
+
+    #[tokio::test]
+    async fn test_incomplete_block_held_back() {
+        let (txns, block_ends) = chain(&[2, 2]);
+        assert_eq!(block_ends, vec![0, 4, 8]);
+        // The stream stops in the middle of the second block
+        let mut batches = split(txns, &[6]);
+        let rest = batches.pop_back().unwrap();
+        let (mut fetcher, stream) = fetcher(batches, 100);
+
+        assert_eq!(fetch_all(&mut fetcher).await, vec![vec![0, 1, 2, 3, 4]]);
+
+        // Until the rest of the block arrives
+        stream.lock().await.batches.push_back(rest);
+        assert_eq!(fetch_all(&mut fetcher).await, vec![vec![5, 6, 7, 8]]);
+    }
+
+    #[tokio::test]
+    async fn test_set_version_drops_held_back_transactions() {
+        let (txns, _) = chain(&[2]);
+        let (mut fetcher, stream) = fetcher(split(txns, &[3]), 100);
+        assert_eq!(fetcher.fetch_next_batch().await.len(), 1);
+        assert!(!fetcher.pending.is_empty());
+
+        fetcher.set_version(10).await;
+        assert!(fetcher.pending.is_empty());
+        assert_eq!(stream.lock().await.batches.len(), 1);
+    }
+}
@@ -13,6 +13,8 @@ pub enum TransactionProcessingError {
     ConnectionPoolError(ErrorWithVersionAndName),
     /// Could not commit the transaction
     TransactionCommitError(ErrorWithVersionAndName),
+    /// The batch doesn't follow the versions processed so far
+    InvalidBatchError(ErrorWithVersionAndName),
 }
 
 impl TransactionProcessingError {
@@ -20,6 +22,7 @@ impl TransactionProcessingError {
         match self {
             TransactionProcessingError::ConnectionPoolError(ewv) => ewv,
             TransactionProcessingError::TransactionCommitError(ewv) => ewv,
+            TransactionProcessingError::InvalidBatchError(ewv) => ewv,
         }
     }
 }
@@ -0,0 +1,196 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::counters::{PROCESSOR_QUEUE_DEPTH, PROCESSOR_QUEUE_FULL_COUNT};
+use anyhow::{bail, Result};
+use tokio::sync::mpsc::{self, error::TrySendError};
+
+/// Hands every batch read upstream to each of the enabled processors, through a bounded queue
+/// per processor. A slow processor only holds the others back once its queue is full, as the
+/// reading upstream then pauses until it makes room. Every processor gets every batch, in the
+/// order they were read in.
+pub struct FanOut<T> {
+    queues: Vec<(String, mpsc::Sender<T>)>,
+}
+
+/// The receiving end of the queue of a single processor
+pub struct ProcessorQueue<T> {
+    processor_name: String,
+    receiver: mpsc::Receiver<T>,
+}
+
+/// Creates the fan-out to the given processors, each with a queue of `capacity` batches.
+pub fn fan_out<T: Clone>(
+    processor_names: &[String],
+    capacity: usize,
+) -> (FanOut<T>, Vec<ProcessorQueue<T>>) {
+    let mut queues = vec![];
+    let mut receivers = vec![];
+    for processor_name in processor_names {
+        let (sender, receiver) = mpsc::channel(capacity);
+        PROCESSOR_QUEUE_DEPTH
+            .with_label_values(&[processor_name])
+            .set(0);
+        queues.push((processor_name.clone(), sender));
+        receivers.push(ProcessorQueue {
+            processor_name: processor_name.clone(),
+            receiver,
+        });
+    }
+    (FanOut { queues }, receivers)
+}
+
+impl<T: Clone> FanOut<T> {
+    /// Queues the batch for every processor, waiting for the processors with a full queue to
+    /// make room. Fails if a processor stopped consuming its queue altogether.
+    pub async fn send(&self, batch: T) -> Result<()> {
+        for (processor_name, sender) in &self.queues {
+            // Counted before it's sent so that the processor never sees the depth go negative,
+            // but not while waiting for room, as the batch isn't in the queue yet
+            let depth = PROCESSOR_QUEUE_DEPTH.with_label_values(&[processor_name]);
+            depth.inc();
+            let sent = match sender.try_send(batch.clone()) {
+                Ok(()) => Ok(()),
+                Err(TrySendError::Full(batch)) => {
+                    depth.dec();
+                    PROCESSOR_QUEUE_FULL_COUNT
+                        .with_label_values(&[processor_name])
+                        .inc();
+                    let sent = sender.send(batch).await.map_err(|_| ());
+                    if sent.is_ok() {
+                        depth.inc();
+                    }
+                    sent
+                },
+                Err(TrySendError::Closed(_)) => {
+                    depth.dec();
+                    Err(())
+                },
+            };
+            if sent.is_err() {
+                bail!("Processor {} stopped consuming its queue", processor_name);
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<T> ProcessorQueue<T> {
+    pub fn processor_name(&self) -> &str {
+        &self.processor_name
+    }
+
+    /// The next batch, or None once the fan-out is dropped and the queue drained
+    pub async fn recv(&mut self) -> Option<T> {
+        let batch = self.receiver.recv().await;
+        if batch.is_some() {
+            PROCESSOR_QUEUE_DEPTH
+                .with_label_values(&[&self.processor_name])
+                .dec();
+        }
+        batch
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    };
+    use tokio::sync::oneshot;
+
+    const CAPACITY: usize = 2;
+    const NUM_BATCHES: usize = 10;
+
+    fn depth(processor_name: &str) -> i64 {
+        PROCESSOR_QUEUE_DEPTH
+            .with_label_values(&[processor_name])
+            .get()
+    }
+
+    #[tokio::test]
+    async fn test_slow_processor_pauses_upstream() {
+        let names: Vec<String> = ["fan_out_slow", "fan_out_fast_a", "fan_out_fast_b"]
+            .iter()
+            .map(|name| name.to_string())
+            .collect();
+        let (fan_out, queues) = fan_out::<usize>(&names, CAPACITY);
+        let mut queues = queues.into_iter();
+        let mut slow = queues.next().unwrap();
+        let full_count = || {
+            PROCESSOR_QUEUE_FULL_COUNT
+                .with_label_values(&["fan_out_slow"])
+                .get()
+        };
+        let full_count_before = full_count();
+
+        let sent = Arc::new(AtomicUsize::new(0));
+        let sent_upstream = sent.clone();
+        let upstream = tokio::spawn(async move {
+            for batch in 0..NUM_BATCHES {
+                fan_out.send(batch).await.unwrap();
+                sent_upstream.fetch_add(1, Ordering::SeqCst);
+            }
+        });
+
+        // The fast processors report every batch they consumed
+        let (consumed, mut consumed_rx) = mpsc::unbounded_channel();
+        let received: Vec<_> = queues
+            .map(|mut queue| {
+                let consumed = consumed.clone();
+                tokio::spawn(async move {
+                    let mut batches = vec![];
+                    while let Some(batch) = queue.recv().await {
+                        consumed.send(batch).unwrap();
+                        batches.push(batch);
+                    }
+                    batches
+                })
+            })
+            .collect();
+        let (release, released) = oneshot::channel::<()>();
+        let slow = tokio::spawn(async move {
+            released.await.unwrap();
+            let mut batches = vec![];
+            while let Some(batch) = slow.recv().await {
+                batches.push(batch);
+            }
+            batches
+        });
+
+        // Upstream pauses once the slow queue is full, and the other processors consume every
+        // batch sent before that
+        while full_count() == full_count_before {
+            tokio::task::yield_now().await;
+        }
+        for _ in 0..2 * CAPACITY {
+            assert!(consumed_rx.recv().await.unwrap() < CAPACITY);
+        }
+        assert_eq!(sent.load(Ordering::SeqCst), CAPACITY);
+        assert_eq!(depth("fan_out_slow"), CAPACITY as i64);
+        assert_eq!(depth("fan_out_fast_a"), 0);
+        assert_eq!(depth("fan_out_fast_b"), 0);
+
+        release.send(()).unwrap();
+        upstream.await.unwrap();
+        // Nothing lost nor reordered
+        let expected: Vec<_> = (0..NUM_BATCHES).collect();
+        assert_eq!(slow.await.unwrap(), expected);
+        for received in received {
+            assert_eq!(received.await.unwrap(), expected);
+        }
+        for name in &names {
+            assert_eq!(depth(name), 0);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_stopped_processor() {
+        let names = vec!["fan_out_stopped".to_string()];
+        let (fan_out, queues) = fan_out::<usize>(&names, CAPACITY);
+        drop(queues);
+        assert!(fan_out.send(0).await.is_err());
+    }
+}
@@ -452,6 +452,19 @@ impl TransactionFetcherTrait for TransactionFetcher {
         self.starting_version = version;
     }
 
+    async fn restart(&mut self, version: u64) {
+        if let Some(fetcher_handle) = self.fetcher_handle.take() {
+            fetcher_handle.abort();
+        }
+        // Drops the batches fetched from the previous version
+        let (transactions_sender, transaction_receiver) =
+            mpsc::channel::<Vec<Transaction>>(self.options.max_pending_batches);
+        self.transactions_sender = Some(transactions_sender);
+        self.transaction_receiver = transaction_receiver;
+        self.starting_version = version;
+        self.start().await;
+    }
+
     async fn start(&mut self) {
         if self.fetcher_handle.is_some() {
             panic!("TransactionFetcher already started!");
@@ -480,4 +493,9 @@ pub trait TransactionFetcherTrait: Send + Sync {
     async fn set_version(&mut self, version: u64);
 
     async fn start(&mut self);
+
+    /// Fetches from the version on again, dropping the batches not fetched yet
+    async fn restart(&mut self, version: u64) {
+        self.set_version(version).await;
+    }
 }
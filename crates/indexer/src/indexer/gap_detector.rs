@@ -0,0 +1,181 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use aptos_api_types::Transaction;
+use std::fmt;
+
+/// Why a batch can't be processed after the versions seen so far
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum BatchError {
+    /// Versions `start` to `end` are missing before the batch
+    Gap { start: u64, end: u64 },
+    /// The versions of the batch don't follow each other, `version` comes after `previous`
+    NotContiguous { previous: u64, version: u64 },
+}
+
+impl fmt::Display for BatchError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            BatchError::Gap { start, end } => {
+                write!(f, "Versions {} to {} are missing", start, end)
+            },
+            BatchError::NotContiguous { previous, version } => write!(
+                f,
+                "Version {} follows version {} within the batch",
+                version, previous
+            ),
+        }
+    }
+}
+
+/// Makes sure that the batches handed to the processor follow each other without gaps, e.g. if
+/// the upstream restarted from the wrong version
+#[derive(Debug, Default)]
+pub struct GapDetector {
+    /// The version the next batch has to start at, the first batch is trusted if not set
+    next_version: Option<u64>,
+}
+
+impl GapDetector {
+    pub fn set_next_version(&mut self, version: u64) {
+        self.next_version = Some(version);
+    }
+
+    pub fn next_version(&self) -> Option<u64> {
+        self.next_version
+    }
+
+    /// Checks that the batch is contiguous and follows the versions seen so far, dropping the
+    /// versions that were already seen. The versions seen so far are unchanged on error.
+    pub fn check(
+        &mut self,
+        transactions: Vec<Transaction>,
+    ) -> Result<Vec<Transaction>, BatchError> {
+        let versions: Vec<u64> = transactions
+            .iter()
+            .map(|txn| txn.version().unwrap())
+            .collect();
+        for pair in versions.windows(2) {
+            if pair[1] != pair[0] + 1 {
+                return Err(BatchError::NotContiguous {
+                    previous: pair[0],
+                    version: pair[1],
+                });
+            }
+        }
+        let (first, last) = match (versions.first(), versions.last()) {
+            (Some(first), Some(last)) => (*first, *last),
+            _ => return Ok(transactions),
+        };
+        let next_version = self.next_version.unwrap_or(first);
+        if first > next_version {
+            return Err(BatchError::Gap {
+                start: next_version,
+                end: first - 1,
+            });
+        }
+        self.next_version = Some(std::cmp::max(next_version, last + 1));
+        Ok(transactions
+            .into_iter()
+            .skip((next_version - first) as usize)
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aptos_api_types::U64;
+    use aptos_indexer_test_fixtures::{load_transaction, STATE_CHECKPOINT_TRANSACTION};
+
+    fn batch(versions: impl IntoIterator<Item = u64>) -> Vec<Transaction> {
+        versions
+            .into_iter()
+            .map(|version| {
+                let mut txn = load_transaction(STATE_CHECKPOINT_TRANSACTION);
+                if let Transaction::StateCheckpointTransaction(ref mut sct) = txn {
+                    sct.info.version = U64::from(version);
+                }
+                txn
+            })
+            .collect()
+    }
+
+    fn versions(transactions: &[Transaction]) -> Vec<u64> {
+        transactions
+            .iter()
+            .map(|txn| txn.version().unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn test_contiguous_batches() {
+        let mut detector = GapDetector::default();
+        detector.set_next_version(10);
+        assert_eq!(
+            versions(&detector.check(batch(10..15)).unwrap()),
+            vec![10, 11, 12, 13, 14]
+        );
+        assert!(detector.check(vec![]).unwrap().is_empty());
+        assert_eq!(
+            versions(&detector.check(batch(15..17)).unwrap()),
+            vec![15, 16]
+        );
+        assert_eq!(detector.next_version(), Some(17));
+    }
+
+    #[test]
+    fn test_first_batch_trusted() {
+        let mut detector = GapDetector::default();
+        assert_eq!(detector.check(batch(42..44)).unwrap().len(), 2);
+        assert_eq!(detector.next_version(), Some(44));
+    }
+
+    #[test]
+    fn test_gap() {
+        let mut detector = GapDetector::default();
+        detector.set_next_version(10);
+        assert_eq!(
+            detector.check(batch(15..20)).unwrap_err(),
+            BatchError::Gap { start: 10, end: 14 }
+        );
+        // Nothing is considered seen, so the missing versions can still be processed
+        assert_eq!(detector.next_version(), Some(10));
+        assert_eq!(detector.check(batch(10..20)).unwrap().len(), 10);
+    }
+
+    #[test]
+    fn test_overlap_deduplicated() {
+        let mut detector = GapDetector::default();
+        detector.set_next_version(10);
+        detector.check(batch(10..15)).unwrap();
+        assert_eq!(
+            versions(&detector.check(batch(12..17)).unwrap()),
+            vec![15, 16]
+        );
+        // A batch seen entirely already leaves nothing to process
+        assert!(detector.check(batch(11..14)).unwrap().is_empty());
+        assert_eq!(detector.next_version(), Some(17));
+    }
+
+    #[test]
+    fn test_out_of_order_batch() {
+        let mut detector = GapDetector::default();
+        detector.set_next_version(10);
+        assert_eq!(
+            detector.check(batch([10, 12, 11])).unwrap_err(),
+            BatchError::NotContiguous {
+                previous: 10,
+                version: 12
+            }
+        );
+        assert_eq!(
+            detector.check(batch([10, 11, 11])).unwrap_err(),
+            BatchError::NotContiguous {
+                previous: 11,
+                version: 11
+            }
+        );
+        assert_eq!(detector.next_version(), Some(10));
+    }
+}
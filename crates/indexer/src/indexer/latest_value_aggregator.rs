@@ -0,0 +1,179 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use std::{
+    collections::{hash_map::Entry, HashMap},
+    hash::Hash,
+};
+
+/// Implemented by models backing a `current_*` table, i.e. a table that only keeps the latest
+/// state for each primary key.
+pub trait CurrentStateModel {
+    fn last_transaction_version(&self) -> i64;
+}
+
+/// Collapses all the updates to a `current_*` table within a batch so that only the value with
+/// the highest version is written for each primary key. Hot rows (e.g. a market account doing
+/// many transfers) would otherwise be rewritten by postgres once per update.
+pub struct LatestValueAggregator<K, V> {
+    values: HashMap<K, V>,
+    num_updates: usize,
+}
+
+impl<K, V> LatestValueAggregator<K, V>
+where
+    K: Hash + Eq + Ord,
+    V: CurrentStateModel,
+{
+    pub fn new() -> Self {
+        Self {
+            values: HashMap::new(),
+            num_updates: 0,
+        }
+    }
+
+    /// Records an update for `key`, replacing the current value only if the update is at least
+    /// as recent. Ties go to the later update, matching the order writes happen within a txn.
+    pub fn insert(&mut self, key: K, value: V) {
+        self.num_updates += 1;
+        match self.values.entry(key) {
+            Entry::Occupied(mut entry) => {
+                if value.last_transaction_version() >= entry.get().last_transaction_version() {
+                    entry.insert(value);
+                }
+            },
+            Entry::Vacant(entry) => {
+                entry.insert(value);
+            },
+        }
+    }
+
+    /// Number of updates recorded, including the ones that got collapsed.
+    pub fn num_updates(&self) -> usize {
+        self.num_updates
+    }
+
+    /// Number of distinct primary keys, i.e. the number of rows that will be upserted.
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    /// Returns the latest values sorted by primary key in order to avoid postgres deadlock since
+    /// we're doing multi threaded db writes
+    pub fn into_sorted_values(self) -> Vec<V> {
+        let mut values = self.values.into_iter().collect::<Vec<(K, V)>>();
+        values.sort_by(|a, b| a.0.cmp(&b.0));
+        values.into_iter().map(|(_, value)| value).collect()
+    }
+}
+
+impl<K, V> Default for LatestValueAggregator<K, V>
+where
+    K: Hash + Eq + Ord,
+    V: CurrentStateModel,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K, V> Extend<(K, V)> for LatestValueAggregator<K, V>
+where
+    K: Hash + Eq + Ord,
+    V: CurrentStateModel,
+{
+    fn extend<I: IntoIterator<Item = (K, V)>>(&mut self, iter: I) {
+        for (key, value) in iter {
+            self.insert(key, value);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeMap;
+
+    #[derive(Clone, Debug, PartialEq, Eq)]
+    struct Balance {
+        owner: String,
+        amount: u64,
+        version: i64,
+    }
+
+    impl CurrentStateModel for Balance {
+        fn last_transaction_version(&self) -> i64 {
+            self.version
+        }
+    }
+
+    fn updates(num_updates: usize, num_keys: usize) -> Vec<(String, Balance)> {
+        (0..num_updates)
+            .map(|i| {
+                let owner = format!("0x{:064x}", i % num_keys);
+                let balance = Balance {
+                    owner: owner.clone(),
+                    amount: (i * 7 % 1000) as u64,
+                    version: i as i64,
+                };
+                (owner, balance)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_collapses_updates_per_key() {
+        let mut aggregator = LatestValueAggregator::new();
+        aggregator.extend(updates(10_000, 100));
+
+        assert_eq!(aggregator.num_updates(), 10_000);
+        assert_eq!(aggregator.len(), 100);
+        assert_eq!(aggregator.num_updates() / aggregator.len(), 100);
+    }
+
+    #[test]
+    fn test_matches_unaggregated_application() {
+        let updates = updates(10_000, 100);
+
+        // Apply every update one by one, the same as upserting each of them would.
+        let mut table = BTreeMap::new();
+        for (key, value) in updates.clone() {
+            let replace = match table.get(&key) {
+                Some(existing) => value.version >= existing.version,
+                None => true,
+            };
+            if replace {
+                table.insert(key, value);
+            }
+        }
+
+        let mut aggregator = LatestValueAggregator::new();
+        aggregator.extend(updates);
+        assert_eq!(
+            aggregator.into_sorted_values(),
+            table.into_values().collect::<Vec<Balance>>()
+        );
+    }
+
+    #[test]
+    fn test_keeps_highest_version() {
+        let old = Balance {
+            owner: "0x1".to_string(),
+            amount: 1,
+            version: 5,
+        };
+        let new = Balance {
+            owner: "0x1".to_string(),
+            amount: 2,
+            version: 10,
+        };
+        let mut aggregator = LatestValueAggregator::new();
+        aggregator.insert(new.owner.clone(), new.clone());
+        aggregator.insert(old.owner.clone(), old);
+        assert_eq!(aggregator.into_sorted_values(), vec![new]);
+    }
+}
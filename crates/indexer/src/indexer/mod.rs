@@ -1,8 +1,13 @@
 // Copyright (c) Aptos
 // SPDX-License-Identifier: Apache-2.0
 
+pub mod block_aligned_fetcher;
 pub mod errors;
+pub mod fan_out;
 pub mod fetcher;
+pub mod gap_detector;
+pub mod latest_value_aggregator;
 pub mod processing_result;
+pub mod skip_list;
 pub mod tailer;
 pub mod transaction_processor;
@@ -1,11 +1,15 @@
 // Copyright (c) Aptos
 // SPDX-License-Identifier: Apache-2.0
 
+use std::collections::BTreeMap;
+
 #[derive(Debug)]
 pub struct ProcessingResult {
     pub name: &'static str,
     pub start_version: u64,
     pub end_version: u64,
+    /// Rows written by table, for the processors reporting them
+    pub rows_written: BTreeMap<&'static str, u64>,
 }
 
 impl ProcessingResult {
@@ -14,6 +18,12 @@ impl ProcessingResult {
             name,
             start_version,
             end_version,
+            rows_written: BTreeMap::new(),
         }
     }
+
+    pub fn with_rows_written(mut self, table: &'static str, rows: usize) -> Self {
+        *self.rows_written.entry(table).or_default() += rows as u64;
+        self
+    }
 }
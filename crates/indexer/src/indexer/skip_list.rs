@@ -0,0 +1,85 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use std::{collections::BTreeSet, fmt};
+
+/// Reason recorded in `processor_statuses.details` for versions skipped by the operator
+pub const OPERATOR_SKIP_REASON: &str = "operator_skip";
+
+/// Versions the operator asked us not to process, e.g. because they trigger a conversion bug
+/// and block ingestion until a fix ships.
+#[derive(Clone, Debug, Default)]
+pub struct VersionSkipList {
+    versions: BTreeSet<u64>,
+    /// Inclusive ranges
+    ranges: Vec<(u64, u64)>,
+}
+
+impl VersionSkipList {
+    pub fn new(versions: &[u64], ranges: &[(u64, u64)]) -> Self {
+        for (start, end) in ranges {
+            assert!(
+                start <= end,
+                "Invalid skip range {}-{}, start must not be after end",
+                start,
+                end
+            );
+        }
+        Self {
+            versions: versions.iter().copied().collect(),
+            ranges: ranges.to_vec(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.versions.is_empty() && self.ranges.is_empty()
+    }
+
+    pub fn contains(&self, version: u64) -> bool {
+        self.versions.contains(&version)
+            || self
+                .ranges
+                .iter()
+                .any(|(start, end)| *start <= version && version <= *end)
+    }
+}
+
+impl fmt::Display for VersionSkipList {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let versions = self.versions.iter().map(|version| version.to_string());
+        let ranges = self
+            .ranges
+            .iter()
+            .map(|(start, end)| format!("{}-{}", start, end));
+        let skips: Vec<String> = versions.chain(ranges).collect();
+        write!(f, "{}", skips.join(", "))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_contains() {
+        let skip_list = VersionSkipList::new(&[5, 42], &[(100, 102)]);
+        assert!(!skip_list.is_empty());
+        for version in [5, 42, 100, 101, 102] {
+            assert!(skip_list.contains(version));
+        }
+        for version in [0, 4, 6, 99, 103] {
+            assert!(!skip_list.contains(version));
+        }
+        assert_eq!(skip_list.to_string(), "5, 42, 100-102");
+
+        let empty = VersionSkipList::default();
+        assert!(empty.is_empty());
+        assert!(!empty.contains(0));
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_invalid_range() {
+        VersionSkipList::new(&[], &[(10, 9)]);
+    }
+}
@@ -1,27 +1,33 @@
 // Copyright (c) Aptos
 // SPDX-License-Identifier: Apache-2.0
 use crate::{
-    database::{execute_with_better_error, PgDbPool},
+    counters::{PROCESSOR_OPERATOR_SKIPS, PROCESSOR_VERSION_GAPS},
+    database::{execute_with_better_error, PgDbPool, PgPoolConnection},
     indexer::{
+        block_aligned_fetcher::BlockAlignedFetcher,
         errors::TransactionProcessingError,
         fetcher::{TransactionFetcher, TransactionFetcherOptions, TransactionFetcherTrait},
+        gap_detector::{BatchError, GapDetector},
         processing_result::ProcessingResult,
+        skip_list::{VersionSkipList, OPERATOR_SKIP_REASON},
         transaction_processor::TransactionProcessor,
     },
     models::{
         ledger_info::LedgerInfo,
         processor_status::{ProcessorStatusV2, ProcessorStatusV2Query},
+        processor_statuses::ProcessorStatusModel,
     },
     schema::{ledger_infos, processor_status},
 };
-use anyhow::{ensure, Context, Result};
+use anyhow::{anyhow, bail, ensure, Context, Result};
 use aptos_api::context::Context as ApiContext;
-use aptos_logger::{debug, info};
+use aptos_api_types::Transaction;
+use aptos_logger::{debug, error, info, warn};
 use chrono::ParseError;
 use diesel::{
     pg::upsert::excluded,
     sql_query,
-    sql_types::{BigInt, Text},
+    sql_types::{BigInt, Nullable, Text},
     ExpressionMethods, RunQueryDsl,
 };
 use diesel_migrations::{EmbeddedMigrations, MigrationHarness};
@@ -30,11 +36,19 @@ use tokio::{sync::Mutex, task::JoinHandle};
 
 pub const MIGRATIONS: EmbeddedMigrations = embed_migrations!();
 
+/// How many times in a row the missing versions are fetched again before giving up
+const MAX_GAP_RECOVERIES: usize = 3;
+
 #[derive(Clone)]
 pub struct Tailer {
     pub transaction_fetcher: Arc<Mutex<dyn TransactionFetcherTrait>>,
     processor: Arc<dyn TransactionProcessor>,
     connection_pool: PgDbPool,
+    skip_list: Arc<VersionSkipList>,
+    ending_version: Option<u64>,
+    // Only ever locked while the fetcher is, so that batches are checked in order
+    gap_detector: Arc<std::sync::Mutex<GapDetector>>,
+    allow_gap_recovery: bool,
 }
 
 impl Tailer {
@@ -51,9 +65,51 @@ impl Tailer {
             transaction_fetcher: Arc::new(Mutex::new(transaction_fetcher)),
             connection_pool,
             processor,
+            skip_list: Arc::new(VersionSkipList::default()),
+            ending_version: None,
+            gap_detector: Arc::new(std::sync::Mutex::new(GapDetector::default())),
+            allow_gap_recovery: false,
         })
     }
 
+    /// Versions in the skip list are never handed to the processor
+    pub fn with_skip_list(mut self, skip_list: VersionSkipList) -> Self {
+        self.skip_list = Arc::new(skip_list);
+        self
+    }
+
+    /// Versions after `ending_version` are never handed to the processor, the batch that reaches
+    /// it is cut right after it
+    pub fn with_ending_version(mut self, ending_version: u64) -> Self {
+        self.ending_version = Some(ending_version);
+        self
+    }
+
+    /// Fetches the missing versions again when a batch doesn't follow the versions processed so
+    /// far, instead of failing the batch
+    pub fn with_gap_recovery(mut self) -> Self {
+        self.allow_gap_recovery = true;
+        self
+    }
+
+    /// A tailer handing the batches read by this one to another processor, see
+    /// `crate::runtime::fan_out_batches`
+    pub fn with_processor(&self, processor: Arc<dyn TransactionProcessor>) -> Self {
+        Self {
+            processor,
+            ..self.clone()
+        }
+    }
+
+    /// Only hands whole blocks to the processor, see `BlockAlignedFetcher`
+    pub fn with_block_aligned_batches(mut self, max_batch_size: usize) -> Self {
+        self.transaction_fetcher = Arc::new(Mutex::new(BlockAlignedFetcher::new(
+            self.transaction_fetcher,
+            max_batch_size,
+        )));
+        self
+    }
+
     pub fn run_migrations(&self) {
         let _ = &self
             .connection_pool
@@ -80,9 +136,9 @@ impl Tailer {
             .fetch_ledger_info()
             .chain_id as i64;
 
+        verify_chain_id(maybe_existing_chain_id, new_chain_id)?;
         match maybe_existing_chain_id {
             Some(chain_id) => {
-                ensure!(chain_id == new_chain_id, "Wrong chain detected! Trying to index chain {} now but existing data is for chain {}", new_chain_id, chain_id);
                 info!(
                     processor_name = self.processor.name(),
                     chain_id = chain_id,
@@ -110,11 +166,9 @@ impl Tailer {
     }
 
     pub async fn set_fetcher_version(&self, version: u64) {
-        self.transaction_fetcher
-            .lock()
-            .await
-            .set_version(version)
-            .await;
+        let mut fetcher = self.transaction_fetcher.lock().await;
+        fetcher.set_version(version).await;
+        self.gap_detector.lock().unwrap().set_next_version(version);
         info!(version = version, "Will start fetching from version");
     }
 
@@ -124,46 +178,175 @@ impl Tailer {
         u64,
         Option<Result<ProcessingResult, TransactionProcessingError>>,
     ) {
-        let transactions = self
-            .transaction_fetcher
-            .lock()
-            .await
-            .fetch_next_batch()
-            .await;
-
+        let transactions = match self.fetch_next_batch().await {
+            Ok(transactions) => transactions,
+            Err(err) => return (0, Some(Err(err))),
+        };
         let num_txns = transactions.len() as u64;
-        // When the batch is empty b/c we're caught up
+        // When the batch is empty b/c we're caught up, or past the ending version
         if num_txns == 0 {
             return (0, None);
         }
-        let start_version = transactions.first().unwrap().version();
-        let end_version = transactions.last().unwrap().version();
+        (num_txns, Some(self.process_batch(transactions).await))
+    }
+
+    /// Fetches the next batch, cut right after the ending version. Empty when caught up, or past
+    /// the ending version.
+    pub async fn fetch_next_batch(&self) -> Result<Vec<Transaction>, TransactionProcessingError> {
+        let mut transactions = self.fetch_contiguous_batch().await?;
+        if let Some(ending_version) = self.ending_version {
+            transactions.retain(|txn| txn.version().unwrap() <= ending_version);
+        }
+        Ok(transactions)
+    }
+
+    /// Hands a batch fetched by `fetch_next_batch` to the processor, without the versions in the
+    /// skip list
+    pub async fn process_batch(
+        &self,
+        transactions: Vec<Transaction>,
+    ) -> Result<ProcessingResult, TransactionProcessingError> {
+        let num_txns = transactions.len() as u64;
+        let start_version = transactions.first().unwrap().version().unwrap();
+        let end_version = transactions.last().unwrap().version().unwrap();
 
         debug!(
+            processor_name = self.processor.name(),
             num_txns = num_txns,
             start_version = start_version,
             end_version = end_version,
             "Starting processing of transaction batch"
         );
 
+        let (skipped, transactions): (Vec<Transaction>, Vec<Transaction>) = transactions
+            .into_iter()
+            .partition(|txn| self.skip_list.contains(txn.version().unwrap()));
+        let skipped_versions: Vec<u64> = skipped.iter().map(|txn| txn.version().unwrap()).collect();
+
         let batch_start = chrono::Utc::now().naive_utc();
 
-        let results = self
-            .processor
-            .process_transactions_with_status(transactions)
-            .await;
+        let results = if transactions.is_empty() {
+            Ok(ProcessingResult::new(
+                self.processor.name(),
+                start_version,
+                end_version,
+            ))
+        } else {
+            self.processor
+                .process_transactions_with_status(transactions)
+                .await
+                // Skipped versions are accounted for, so the whole batch counts as processed
+                .map(|res| ProcessingResult {
+                    start_version,
+                    end_version,
+                    ..res
+                })
+        };
+
+        // This must happen after processing, which marks the whole range it processed as done
+        if results.is_ok() && !skipped_versions.is_empty() {
+            self.record_skipped_versions(&skipped_versions);
+        }
 
         let batch_millis = (chrono::Utc::now().naive_utc() - batch_start).num_milliseconds();
 
         info!(
+            processor_name = self.processor.name(),
             num_txns = num_txns,
+            num_skipped = skipped_versions.len(),
             time_millis = batch_millis,
             start_version = start_version,
             end_version = end_version,
             "Finished processing of transaction batch"
         );
 
-        (num_txns, Some(results))
+        results
+    }
+
+    /// Fetches the next batch, without the versions that were already fetched. Fails if versions
+    /// are missing before the batch, unless they can be fetched again, or if the versions of the
+    /// batch don't follow each other.
+    async fn fetch_contiguous_batch(&self) -> Result<Vec<Transaction>, TransactionProcessingError> {
+        let name = self.processor.name();
+        let mut fetcher = self.transaction_fetcher.lock().await;
+        let mut recoveries = 0;
+        loop {
+            let transactions = fetcher.fetch_next_batch().await;
+            let num_txns = transactions.len();
+            let (start_version, end_version) = match (transactions.first(), transactions.last()) {
+                (Some(first), Some(last)) => (first.version().unwrap(), last.version().unwrap()),
+                _ => return Ok(transactions),
+            };
+            let checked = self.gap_detector.lock().unwrap().check(transactions);
+            let err = match checked {
+                Ok(transactions) => {
+                    if transactions.len() < num_txns {
+                        warn!(
+                            processor_name = name,
+                            start_version = start_version,
+                            end_version = end_version,
+                            num_duplicates = num_txns - transactions.len(),
+                            "Dropped the versions of the batch that were already fetched"
+                        );
+                    }
+                    return Ok(transactions);
+                },
+                Err(err) => err,
+            };
+            if let BatchError::Gap { start, end } = err {
+                PROCESSOR_VERSION_GAPS.with_label_values(&[name]).inc();
+                error!(
+                    processor_name = name,
+                    missing_start_version = start,
+                    missing_end_version = end,
+                    batch_start_version = start_version,
+                    "Versions are missing before the batch"
+                );
+                if self.allow_gap_recovery && recoveries < MAX_GAP_RECOVERIES {
+                    recoveries += 1;
+                    warn!(
+                        processor_name = name,
+                        version = start,
+                        attempt = recoveries,
+                        "Fetching the missing versions again"
+                    );
+                    fetcher.restart(start).await;
+                    continue;
+                }
+            }
+            return Err(TransactionProcessingError::InvalidBatchError((
+                anyhow!("{}", err),
+                start_version,
+                end_version,
+                name,
+            )));
+        }
+    }
+
+    /// Records versions skipped by the operator in `processor_statuses`, which the gap detection
+    /// treats as accounted for
+    fn record_skipped_versions(&self, versions: &[u64]) {
+        let name = self.processor.name();
+        warn!(
+            processor_name = name,
+            versions = format!("{:?}", versions),
+            "Skipped versions because of the operator skip list"
+        );
+        PROCESSOR_OPERATOR_SKIPS
+            .with_label_values(&[name])
+            .inc_by(versions.len() as u64);
+        let psms: Vec<ProcessorStatusModel> = versions
+            .iter()
+            .map(|version| {
+                ProcessorStatusModel::new(
+                    name,
+                    *version as i64,
+                    false,
+                    Some(OPERATOR_SKIP_REASON.to_string()),
+                )
+            })
+            .collect();
+        self.processor.apply_processor_status(&psms);
     }
 
     /// Store last processed version from database. We can assume that all previously processed
@@ -174,6 +357,7 @@ impl Tailer {
         let status = ProcessorStatusV2 {
             processor: processor_name.to_owned(),
             last_success_version: version as i64,
+            last_error: None,
         };
         execute_with_better_error(
             &mut conn,
@@ -185,12 +369,124 @@ impl Tailer {
                     processor_status::last_success_version
                         .eq(excluded(processor_status::last_success_version)),
                     processor_status::last_updated.eq(excluded(processor_status::last_updated)),
+                    processor_status::last_error.eq(excluded(processor_status::last_error)),
                 )),
             Some(" WHERE processor_status.last_success_version <= EXCLUDED.last_success_version "),
         )?;
         Ok(())
     }
 
+    /// Sets the last processed version, even if it goes backwards, e.g. when the processor is
+    /// forced to start over from an earlier version. -1 if no version was processed.
+    pub fn reset_last_processed_version(
+        &self,
+        processor_name: &str,
+        last_success_version: i64,
+    ) -> Result<()> {
+        let mut conn = self.connection_pool.get()?;
+
+        let status = ProcessorStatusV2 {
+            processor: processor_name.to_owned(),
+            last_success_version,
+            last_error: None,
+        };
+        execute_with_better_error(
+            &mut conn,
+            diesel::insert_into(processor_status::table)
+                .values(&status)
+                .on_conflict(processor_status::processor)
+                .do_update()
+                .set((
+                    processor_status::last_success_version
+                        .eq(excluded(processor_status::last_success_version)),
+                    processor_status::last_updated.eq(excluded(processor_status::last_updated)),
+                    processor_status::last_error.eq(excluded(processor_status::last_error)),
+                )),
+            None,
+        )?;
+        Ok(())
+    }
+
+    /// Records the error the processor stopped on, the last processed version is kept
+    pub fn record_processing_error(&self, processor_name: &str, error: &str) -> Result<()> {
+        let mut conn = self.connection_pool.get()?;
+        execute_with_better_error(
+            &mut conn,
+            diesel::update(
+                processor_status::table.filter(processor_status::processor.eq(processor_name)),
+            )
+            .set((
+                processor_status::last_error.eq(error),
+                processor_status::last_updated.eq(chrono::Utc::now().naive_utc()),
+            )),
+            None,
+        )?;
+        Ok(())
+    }
+
+    /// Last version processed successfully, every version before it was processed as well
+    pub fn get_last_processed_version(&self, processor_name: &String) -> Result<Option<u64>> {
+        let mut conn = self.connection_pool.get()?;
+        Ok(
+            ProcessorStatusV2Query::get_by_processor(processor_name, &mut conn)?
+                .and_then(|status| u64::try_from(status.last_success_version).ok()),
+        )
+    }
+
+    /// How many versions the processor is behind the latest version of the node, for health
+    /// checks and metrics
+    pub async fn get_indexing_lag(&self, processor_name: &String) -> Result<u64> {
+        let ledger_version = self
+            .transaction_fetcher
+            .lock()
+            .await
+            .fetch_ledger_info()
+            .ledger_version
+            .0;
+        Ok(match self.get_last_processed_version(processor_name)? {
+            Some(version) => ledger_version.saturating_sub(version),
+            None => ledger_version + 1,
+        })
+    }
+
+    /// Makes sure that no version of the range was processed successfully yet, which would be
+    /// overwritten by processing the range again
+    pub fn ensure_unprocessed(
+        &self,
+        processor_name: &str,
+        start_version: u64,
+        end_version: u64,
+    ) -> Result<()> {
+        let mut conn = self.connection_pool.get()?;
+        #[derive(Debug, QueryableByName)]
+        struct Processed {
+            #[diesel(sql_type = Nullable<BigInt>)]
+            first: Option<i64>,
+            #[diesel(sql_type = Nullable<BigInt>)]
+            last: Option<i64>,
+        }
+        let processed: Processed = sql_query(
+            "SELECT MIN(version) AS first, MAX(version) AS last FROM processor_statuses \
+            WHERE name = $1 AND success = TRUE AND version BETWEEN $2 AND $3",
+        )
+        .bind::<Text, _>(processor_name)
+        .bind::<BigInt, _>(start_version as i64)
+        .bind::<BigInt, _>(end_version as i64)
+        .get_result(&mut conn)?;
+        if let (Some(first), Some(last)) = (processed.first, processed.last) {
+            bail!(
+                "Versions {} to {} of the range [{}, {}] were already processed by {}, \
+                set overwrite_processed_versions to process them again",
+                first,
+                last,
+                start_version,
+                end_version,
+                processor_name
+            );
+        }
+        Ok(())
+    }
+
     /// Get last version processed successfully from databse
     pub fn get_start_version(&self, processor_name: &String) -> Result<Option<i64>> {
         let mut conn = self.connection_pool.get()?;
@@ -202,7 +498,7 @@ impl Tailer {
     }
 
     /// Get starting version from database. Starting version is defined as the first version that's either
-    /// not successful or missing from the DB.
+    /// not successful or missing from the DB. Versions skipped by the operator count as successful.
     pub fn get_start_version_long(
         &self,
         processor_name: &String,
@@ -225,7 +521,7 @@ impl Tailer {
                 processor_statuses
             WHERE
                 name = $1
-                AND success = TRUE
+                AND (success = TRUE OR details = 'operator_skip')
         ),
         boundaries AS
         (
@@ -236,7 +532,7 @@ impl Tailer {
                 processor_statuses, raw_boundaries
             WHERE
                 name = $1
-                AND success = true
+                AND (success = TRUE OR details = 'operator_skip')
                 and version >= GREATEST(MAX_V - $2, 0)
         ),
         gap AS
@@ -255,7 +551,7 @@ impl Tailer {
                         boundaries
                     WHERE
                         name = $1
-                        AND success = TRUE
+                        AND (success = TRUE OR details = 'operator_skip')
                         AND version >= GREATEST(MAX_V - $2, 0)
                 ) a
             WHERE
@@ -289,6 +585,29 @@ impl Tailer {
     }
 }
 
+/// Names of the migrations that haven't been applied to the database yet
+pub fn pending_migrations(conn: &mut PgPoolConnection) -> Result<Vec<String>> {
+    Ok(conn
+        .pending_migrations(MIGRATIONS)
+        .map_err(|e| anyhow!("Failed to list pending migrations: {}", e))?
+        .iter()
+        .map(|migration| migration.name().to_string())
+        .collect())
+}
+
+/// Makes sure that the chain we're about to index is the one the existing data is from, if any
+pub fn verify_chain_id(existing_chain_id: Option<i64>, new_chain_id: i64) -> Result<()> {
+    if let Some(chain_id) = existing_chain_id {
+        ensure!(
+            chain_id == new_chain_id,
+            "Wrong chain detected! Trying to index chain {} now but existing data is for chain {}",
+            new_chain_id,
+            chain_id
+        );
+    }
+    Ok(())
+}
+
 pub async fn await_tasks<T: Debug>(tasks: Vec<JoinHandle<T>>) -> Vec<T> {
     let mut results = vec![];
     for task in tasks {
@@ -304,28 +623,41 @@ pub async fn await_tasks<T: Debug>(tasks: Vec<JoinHandle<T>>) -> Vec<T> {
 }
 
 #[cfg(test)]
-mod test {
+pub(crate) mod test {
     use super::*;
     use crate::{
         database::{new_db_pool, PgPoolConnection},
         models::transactions::TransactionQuery,
-        processors::default_processor::DefaultTransactionProcessor,
+        processors::{
+            coin_processor::CoinTransactionProcessor,
+            default_processor::DefaultTransactionProcessor,
+        },
+        schema::coin_balances,
     };
     use aptos_api_test_context::new_test_context;
     use aptos_api_types::{LedgerInfo as APILedgerInfo, Transaction, U64};
-    use diesel::RunQueryDsl;
+    use aptos_indexer_test_fixtures::{
+        load_json, load_transaction, BLOCK_METADATA_TRANSACTION, GENESIS_TRANSACTION,
+        STATE_CHECKPOINT_TRANSACTION, USER_TRANSACTION,
+    };
+    use bigdecimal::BigDecimal;
+    use diesel::{QueryDsl, RunQueryDsl};
     use serde_json::json;
+    use std::collections::VecDeque;
 
-    struct FakeFetcher {
+    /// Hands out its batches in order, then empty batches as if caught up
+    pub(crate) struct FakeFetcher {
         version: u64,
         chain_id: u8,
+        pub batches: VecDeque<Vec<Transaction>>,
     }
 
     impl FakeFetcher {
-        fn new(_starting_version: Option<u64>) -> Self {
+        pub fn new(_starting_version: Option<u64>) -> Self {
             Self {
                 version: 0,
                 chain_id: 0,
+                batches: VecDeque::new(),
             }
         }
     }
@@ -333,7 +665,7 @@ mod test {
     #[async_trait::async_trait]
     impl TransactionFetcherTrait for FakeFetcher {
         async fn fetch_next_batch(&mut self) -> Vec<Transaction> {
-            unimplemented!();
+            self.batches.pop_front().unwrap_or_default()
         }
 
         fn fetch_ledger_info(&mut self) -> APILedgerInfo {
@@ -375,20 +707,26 @@ mod test {
             .expect("must set 'INDEXER_DATABASE_URL' to run tests!");
         let conn_pool = new_db_pool(database_url.as_str())?;
         wipe_database(&mut conn_pool.get()?);
+        let tailer = new_tailer(conn_pool.clone())?;
 
+        Ok((conn_pool, tailer))
+    }
+
+    /// A tailer with a fresh processor over the database as it is, e.g. to restart the indexer
+    pub fn new_tailer(conn_pool: PgDbPool) -> Result<Tailer> {
         let test_context = new_test_context("doesnt_matter".to_string(), true);
         let context: Arc<ApiContext> = Arc::new(test_context.context);
         let pg_transaction_processor = DefaultTransactionProcessor::new(conn_pool.clone());
         let mut tailer = Tailer::new(
             context,
-            conn_pool.clone(),
+            conn_pool,
             Arc::new(pg_transaction_processor),
             TransactionFetcherOptions::default(),
         )?;
         tailer.transaction_fetcher = Arc::new(Mutex::new(FakeFetcher::new(None)));
         tailer.run_migrations();
 
-        Ok((conn_pool, tailer))
+        Ok(tailer)
     }
 
     #[tokio::test(flavor = "multi_thread")]
@@ -398,207 +736,7 @@ mod test {
         }
         let (conn_pool, tailer) = setup_indexer().unwrap();
         // An abridged genesis transaction
-        let genesis_txn: Transaction = serde_json::from_value(json!(
-            {
-               "type":"genesis_transaction",
-               "version":"0",
-               "hash":"0xa4d0d270d71cf031476dd2674d1e4a247489dfc3521c871ee37f42bd71a0a234",
-               "state_change_hash":"0x27b382a98a32256a9e6403ca1f6e26998273d77afa9e8666e7ee13679af40a7a",
-               "event_root_hash":"0xcbdbb1b830d1016d45a828bb3171ea81826e8315f14140acfbd7886f49fbcb40",
-               "gas_used":"0",
-               "block_height":"0",
-               "epoch":"0",
-               "success":true,
-               "vm_status":"Executed successfully",
-               "accumulator_root_hash":"0x6a527d06063dfd42c6b3a862574d5f3ec1660afb8058135edda5072712bfdb51",
-               "changes":[
-                  {
-                     "type":"write_resource",
-                     "address":"0x1",
-                     "state_key_hash":"3502b05382fba777545b45a0a9d40e86cdde7c3afbde19c748ce8b5f142c2b46",
-                     "data":{
-                        "type":"0x1::account::Account",
-                        "data":{
-                           "authentication_key":"0x1e4dcad3d5d94307f30d51ff66d2ce784e0c2822d3138766907179bcb61f9edc",
-                           "sequence_number":"0"
-                        }
-                     }
-                  },
-                  {
-                     "type":"write_module",
-                     "address":"0x1",
-                     "state_key_hash":"e428253ccf0b18f3d8300c6a0d29de93abcdc526e88728abeb85d57aec558935",
-                     "data":{
-                        "bytecode":"0xa11ceb0b050000000a01000a020a04030e2305310e073f940108d3012006f3012c0a9f02050ca402370ddb020200000001000200030004000008000005000100000602000004080000000409000000030a030000020b030400010c05050000010202060c0201060c0105010307436861696e4964064572726f7273065369676e65720f53797374656d4164647265737365730954696d657374616d70036765740a696e697469616c697a65026964106173736572745f6f7065726174696e670e6173736572745f67656e65736973146173736572745f636f72655f7265736f757263650a616464726573735f6f6611616c72656164795f7075626c69736865640000000000000000000000000000000000000000000000000000000000000001030800000000000000000520000000000000000000000000000000000000000000000000000000000a550c18000201070200010001000006110207012b001000140201010000001211030a0011040a001105290020030d0b000107001106270b000b0112002d0002000000",
-                        "abi":{
-                           "address":"0x1",
-                           "name":"ChainId",
-                           "friends":[
-
-                           ],
-                           "exposed_functions":[
-                              {
-                                 "name":"get",
-                                 "visibility":"public",
-                                 "generic_type_params":[
-
-                                 ],
-                                 "params":[
-
-                                 ],
-                                 "return":[
-                                    "u8"
-                                 ]
-                              },
-                              {
-                                 "name":"initialize",
-                                 "visibility":"public",
-                                 "generic_type_params":[
-
-                                 ],
-                                 "params":[
-                                    "&signer",
-                                    "u8"
-                                 ],
-                                 "return":[
-
-                                 ]
-                              }
-                           ],
-                           "structs":[
-                              {
-                                 "name":"ChainId",
-                                 "is_native":false,
-                                 "abilities":[
-                                    "key"
-                                 ],
-                                 "generic_type_params":[
-
-                                 ],
-                                 "fields":[
-                                    {
-                                       "name":"id",
-                                       "type":"u8"
-                                    }
-                                 ]
-                              }
-                           ]
-                        }
-                     }
-                  }
-               ],
-               "payload":{
-                  "type":"write_set_payload",
-                  "write_set":{
-                     "type":"direct_write_set",
-                     "changes":[
-                        {
-                           "type":"write_resource",
-                           "address":"0x1",
-                           "state_key_hash":"3502b05382fba777545b45a0a9d40e86cdde7c3afbde19c748ce8b5f142c2b46",
-                           "data":{
-                              "type":"0x1::account::Account",
-                              "data":{
-                                 "authentication_key":"0x1e4dcad3d5d94307f30d51ff66d2ce784e0c2822d3138766907179bcb61f9edc",
-                                 "sequence_number":"0"
-                              }
-                           }
-                        },
-                        {
-                           "type":"write_module",
-                           "address":"0x1",
-                           "state_key_hash":"e428253ccf0b18f3d8300c6a0d29de93abcdc526e88728abeb85d57aec558935",
-                           "data":{
-                              "bytecode":"0xa11ceb0b050000000a01000a020a04030e2305310e073f940108d3012006f3012c0a9f02050ca402370ddb020200000001000200030004000008000005000100000602000004080000000409000000030a030000020b030400010c05050000010202060c0201060c0105010307436861696e4964064572726f7273065369676e65720f53797374656d4164647265737365730954696d657374616d70036765740a696e697469616c697a65026964106173736572745f6f7065726174696e670e6173736572745f67656e65736973146173736572745f636f72655f7265736f757263650a616464726573735f6f6611616c72656164795f7075626c69736865640000000000000000000000000000000000000000000000000000000000000001030800000000000000000520000000000000000000000000000000000000000000000000000000000a550c18000201070200010001000006110207012b001000140201010000001211030a0011040a001105290020030d0b000107001106270b000b0112002d0002000000",
-                              "abi":{
-                                 "address":"0x1",
-                                 "name":"ChainId",
-                                 "friends":[
-
-                                 ],
-                                 "exposed_functions":[
-                                    {
-                                       "name":"get",
-                                       "visibility":"public",
-                                       "generic_type_params":[
-
-                                       ],
-                                       "params":[
-
-                                       ],
-                                       "return":[
-                                          "u8"
-                                       ]
-                                    },
-                                    {
-                                       "name":"initialize",
-                                       "visibility":"public",
-                                       "generic_type_params":[
-
-                                       ],
-                                       "params":[
-                                          "&signer",
-                                          "u8"
-                                       ],
-                                       "return":[
-
-                                       ]
-                                    }
-                                 ],
-                                 "structs":[
-                                    {
-                                       "name":"ChainId",
-                                       "is_native":false,
-                                       "abilities":[
-                                          "key"
-                                       ],
-                                       "generic_type_params":[
-
-                                       ],
-                                       "fields":[
-                                          {
-                                             "name":"id",
-                                             "type":"u8"
-                                          }
-                                       ]
-                                    }
-                                 ]
-                              }
-                           }
-                        }
-                     ],
-                     "events":[
-                        {
-                          "key":"0x0400000000000000000000000000000000000000000000000000000000000000000000000a550c18",
-                           "guid":{
-                              "account_address":"0xa550c18",
-                              "creation_number":"4",
-                           },
-                           "sequence_number":"0",
-                           "type":"0x1::reconfiguration::NewEpochEvent",
-                           "data":{
-                              "epoch":"1"
-                           }
-                        }
-                     ]
-                  }
-               },
-               "events":[
-                  {
-                    "key":"0x0400000000000000000000000000000000000000000000000000000000000000000000000a550c18",
-                     "guid":{
-                        "account_address":"0xa550c18",
-                        "creation_number":"4",
-                    },
-                     "sequence_number":"0",
-                     "type":"0x1::reconfiguration::NewEpochEvent",
-                     "data":{
-                        "epoch":"1"
-                     }
-                  }
-               ]
-            }
-        )).unwrap();
+        let genesis_txn = load_transaction(GENESIS_TRANSACTION);
 
         tailer
             .processor
@@ -607,86 +745,7 @@ mod test {
             .unwrap();
 
         // A block_metadata_transaction
-        let mut block_metadata_transaction: Transaction = serde_json::from_value(json!(
-            {
-              "type": "block_metadata_transaction",
-              "version": "69158",
-              "block_height": "100",
-              "hash": "0x2b7c58ed8524d228f9d0543a82e2793d04e8871df322f976b0e7bb8c5ced4ff5",
-              "state_change_hash": "0x3ead9eb40582fbc7df5e02f72280931dc3e6f1aae45dc832966b4cd972dac4b8",
-              "event_root_hash": "0x2e481956dea9c59b6fc9f823fe5f4c45efce173e42c551c1fe073b5d76a65504",
-              "gas_used": "0",
-              "success": true,
-              "vm_status": "Executed successfully",
-              "accumulator_root_hash": "0xb0ad602f805eb20c398f0f29a3504a9ef38bcc52c9c451deb9ec4a2d18807b49",
-              "id": "0xeef99391a3fc681f16963a6c03415bc0b1b12b56c00429308fa8bf46ac9eddf0",
-              "round": "57600",
-              "failed_proposer_indices": [],
-              "epoch": "1",
-              "previous_block_votes_bitvec": [],
-              "proposer": "0x68f04222bd9f8846cda028ea5ba3846a806b04a47e1f1a4f0939f350d713b2eb",
-              "timestamp": "1649395495746947",
-              "events": [
-                 {
-                    "key":"0x0600000000000000000000000000000000000000000000000000000000000000000000000a550c18",
-                    "guid":{
-                        "account_address":"0xa550c18",
-                        "creation_number":"6",
-                    },
-                    "sequence_number": "0",
-                    "type": "0x1::block::NewBlockEvent",
-                    "data": {
-                      "epoch": "1",
-                      "failed_proposer_indices": [],
-                      "previous_block_votes_bitvec": [],
-                      "proposer": "0xf7c109be515785bba951fc8c51063515d474f78cad150457d6ebd08c4faf2f3b",
-                      "round": "1",
-                      "time_microseconds": "1656565270489235"
-                    }
-                }
-              ],
-              "changes": [
-                {
-                  "type": "write_resource",
-                  "address": "0xa550c18",
-                  "state_key_hash": "0x220a03e13099533097731c551fe037bbf404dcf765fe4df8743022a298650e6e",
-                  "data": {
-                    "type": "0x1::block::BlockResource",
-                    "data": {
-                      "height": "1",
-                      "new_block_events": {
-                        "counter": "1",
-                        "guid": {
-                          "guid": {
-                            "id": {
-                              "addr": "0xa550c18",
-                              "creation_num": "5"
-                            }
-                          },
-                          "len_bytes": 40
-                        }
-                      }
-                    }
-                  }
-                },
-                {
-                  "type": "write_resource",
-                  "address": "0xa550c18",
-                  "state_key_hash": "0xf113db06626eb7724773e4e9dacecc8a6cb3a710b8b70365768168b24fe06ce3",
-                  "data": {
-                    "type": "0x1::Timestamp::CurrentTimeMicroseconds",
-                    "data": {
-                      "microseconds": "1650419261396337"
-                    }
-                  }
-                }
-              ]
-            }
-        )).unwrap();
-        // This is needed because deserializer only parses epoch once so info.epoch is always None
-        if let Transaction::BlockMetadataTransaction(ref mut bmt) = block_metadata_transaction {
-            bmt.info.epoch = Some(aptos_api_types::U64::from(1));
-        }
+        let block_metadata_transaction = load_transaction(BLOCK_METADATA_TRANSACTION);
 
         tailer
             .processor
@@ -713,103 +772,7 @@ mod test {
         assert_eq!(wsc0.len(), 2);
 
         // A user transaction, with fake events
-        let user_txn: Transaction = serde_json::from_value(json!(
-            {
-              "type": "user_transaction",
-              "version": "691595",
-              "block_height": "100",
-              "epoch":"1",
-              "hash": "0xefd4c865e00c240da0c426a37ceeda10d9b030d0e8a4fb4fb7ff452ad63401fb",
-              "state_change_hash": "0xebfe1eb7aa5321e7a7d741d927487163c34c821eaab60646ae0efd02b286c97c",
-              "event_root_hash": "0x414343554d554c41544f525f504c414345484f4c4445525f4841534800000000",
-              "gas_used": "43",
-              "success": true,
-              "vm_status": "Executed successfully",
-              "accumulator_root_hash": "0x97bfd5949d32f6c9a9efad93411924bfda658a8829de384d531ee73c2f740971",
-              "sender": "0xdfd557c68c6c12b8c65908b3d3c7b95d34bb12ae6eae5a43ee30aa67a4c12494",
-              "sequence_number": "21386",
-              "max_gas_amount": "1000",
-              "gas_unit_price": "1",
-              "expiration_timestamp_secs": "1649713172",
-              "payload": {
-                "type": "entry_function_payload",
-                "function": "0x1::aptos_coin::mint",
-                "type_arguments": [],
-                "arguments": [
-                  "0x45b44793724a5ecc6ad85fa60949d0824cfc7f61d6bd74490b13598379313142",
-                  "20000"
-                ]
-              },
-              "signature": {
-                "type": "ed25519_signature",
-                "public_key": "0x14ff6646855dad4a2dab30db773cdd4b22d6f9e6813f3e50142adf4f3efcf9f8",
-                "signature": "0x70781112e78cc8b54b86805c016cef2478bccdef21b721542af0323276ab906c989172adffed5bf2f475f2ec3a5b284a0ac46a6aef0d79f0dbb6b85bfca0080a"
-              },
-              "events": [
-                {
-                  "key": "0x040000000000000000000000000000000000000000000000000000000000000000000000fefefefe",
-                  "guid":{
-                      "account_address":"0xfefefefe",
-                      "creation_number":"4",
-                  },
-                  "sequence_number": "0",
-                  "type": "0x1::Whatever::FakeEvent1",
-                  "data": {
-                    "amazing": "1"
-                  }
-                },
-                {
-                  "key": "0x040000000000000000000000000000000000000000000000000000000000000000000000fefefefe",
-                  "guid":{
-                      "account_address":"0xfefefefe",
-                      "creation_number":"4",
-                  },
-                  "sequence_number": "1",
-                  "type": "0x1::Whatever::FakeEvent2",
-                  "data": {
-                    "amazing": "2"
-                  }
-                }
-              ],
-              "timestamp": "1649713141723410",
-              "changes": [
-                {
-                  "type": "write_resource",
-                  "address": "0xa550c18",
-                  "state_key_hash": "0x220a03e13099533097731c551fe037bbf404dcf765fe4df8743022a298650e6e",
-                  "data": {
-                    "type": "0x1::block::BlockResource",
-                    "data": {
-                      "height": "1",
-                      "new_block_events": {
-                        "counter": "1",
-                        "guid": {
-                          "guid": {
-                            "id": {
-                              "addr": "0xa550c18",
-                              "creation_num": "5"
-                            }
-                          },
-                          "len_bytes": 40
-                        }
-                      }
-                    }
-                  }
-                },
-                {
-                  "type": "write_resource",
-                  "address": "0xa550c18",
-                  "state_key_hash": "0xf113db06626eb7724773e4e9dacecc8a6cb3a710b8b70365768168b24fe06ce3",
-                  "data": {
-                    "type": "0x1::Timestamp::CurrentTimeMicroseconds",
-                    "data": {
-                      "microseconds": "1650419261396337"
-                    }
-                  }
-                }
-              ]
-            }
-        )).unwrap();
+        let user_txn = load_transaction(USER_TRANSACTION);
 
         // We run it twice to ensure we don't explode. Idempotency!
         tailer
@@ -918,4 +881,342 @@ mod test {
         tailer.set_fetcher_version(4).await;
         assert!(tailer.check_or_update_chain_id().await.is_ok());
     }
+
+    pub fn state_checkpoint_txn(version: u64) -> Transaction {
+        let mut txn: Transaction = serde_json::from_value(json!(
+            {
+              "type": "state_checkpoint_transaction",
+              "version": version.to_string(),
+              "block_height": "100",
+              "epoch": "1",
+              "hash": "0x2b7c58ed8524d228f9d0543a82e2793d04e8871df322f976b0e7bb8c5ced4ff5",
+              "state_change_hash": "0x3ead9eb40582fbc7df5e02f72280931dc3e6f1aae45dc832966b4cd972dac4b8",
+              "event_root_hash": "0x2e481956dea9c59b6fc9f823fe5f4c45efce173e42c551c1fe073b5d76a65504",
+              "state_checkpoint_hash": null,
+              "gas_used": "0",
+              "success": true,
+              "vm_status": "Executed successfully",
+              "accumulator_root_hash": "0xb0ad602f805eb20c398f0f29a3504a9ef38bcc52c9c451deb9ec4a2d18807b49",
+              "changes": [],
+              "timestamp": "1649395495746947"
+            }
+        ))
+        .unwrap();
+        // Same as above, the deserializer doesn't always pick up the epoch
+        if let Transaction::StateCheckpointTransaction(ref mut sct) = txn {
+            sct.info.block_height = Some(U64::from(100));
+            sct.info.epoch = Some(U64::from(1));
+        }
+        txn
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_overwrite() {
+        if crate::should_skip_pg_tests() {
+            return;
+        }
+        let (conn_pool, tailer) = setup_indexer().unwrap();
+        tailer
+            .processor
+            .process_transactions_with_status(vec![state_checkpoint_txn(10)])
+            .await
+            .unwrap();
+
+        // The same version parsed differently, e.g. after a parsing fix
+        let mut reparsed = state_checkpoint_txn(10);
+        if let Transaction::StateCheckpointTransaction(ref mut sct) = reparsed {
+            sct.info.vm_status = "Reparsed".to_string();
+        }
+        let vm_status = |conn_pool: &PgDbPool| {
+            TransactionQuery::get_by_version(10, &mut conn_pool.get().unwrap())
+                .unwrap()
+                .0
+                .vm_status
+        };
+
+        // Rows already written take what the version is parsed into now
+        tailer
+            .processor
+            .process_transactions_with_status(vec![reparsed])
+            .await
+            .unwrap();
+        assert_eq!(vm_status(&conn_pool), "Reparsed");
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_overwrite_coin_balances() {
+        if crate::should_skip_pg_tests() {
+            return;
+        }
+        let (conn_pool, _tailer) = setup_indexer().unwrap();
+        let processor = CoinTransactionProcessor::new(conn_pool.clone());
+        let with_balance = |value: &str| {
+            let mut txn = load_json(USER_TRANSACTION);
+            txn["changes"].as_array_mut().unwrap().push(json!({
+                "type": "write_resource",
+                "address": "0xa550c18",
+                "state_key_hash": "0x01",
+                "data": {
+                    "type": "0x1::coin::CoinStore<0x1::aptos_coin::AptosCoin>",
+                    "data": {
+                        "coin": { "value": value },
+                        "deposit_events": {
+                            "counter": "0",
+                            "guid": { "id": { "addr": "0xa550c18", "creation_num": "2" } }
+                        },
+                        "frozen": false,
+                        "withdraw_events": {
+                            "counter": "0",
+                            "guid": { "id": { "addr": "0xa550c18", "creation_num": "3" } }
+                        }
+                    }
+                }
+            }));
+            serde_json::from_value::<Transaction>(txn).unwrap()
+        };
+        let amounts = |conn_pool: &PgDbPool| -> Vec<BigDecimal> {
+            coin_balances::table
+                .select(coin_balances::amount)
+                .load(&mut conn_pool.get().unwrap())
+                .unwrap()
+        };
+
+        processor
+            .process_transactions_with_status(vec![with_balance("100")])
+            .await
+            .unwrap();
+        assert_eq!(amounts(&conn_pool), vec![BigDecimal::from(100)]);
+
+        // The same version parsed differently replaces the row, instead of being dropped
+        processor
+            .process_transactions_with_status(vec![with_balance("150")])
+            .await
+            .unwrap();
+        assert_eq!(amounts(&conn_pool), vec![BigDecimal::from(150)]);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_reprocessing_is_idempotent() {
+        if crate::should_skip_pg_tests() {
+            return;
+        }
+        let (conn_pool, tailer) = setup_indexer().unwrap();
+
+        /// Every row of the table but the time it was written, in a stable order
+        #[derive(Debug, QueryableByName)]
+        struct Rows {
+            #[diesel(sql_type = diesel::sql_types::Jsonb)]
+            rows: serde_json::Value,
+        }
+        let snapshot = || {
+            let mut conn = conn_pool.get().unwrap();
+            crate::processors::default_processor::TABLE_DEPENDENCIES
+                .write_order()
+                .unwrap()
+                .into_iter()
+                .map(|table| {
+                    let rows: Rows = sql_query(format!(
+                        "SELECT COALESCE(jsonb_agg(to_jsonb(t) - 'inserted_at' \
+                        ORDER BY to_jsonb(t)::text), '[]'::jsonb) AS rows FROM {} t",
+                        table
+                    ))
+                    .get_result(&mut conn)
+                    .unwrap();
+                    (table, rows.rows)
+                })
+                .collect::<Vec<_>>()
+        };
+
+        let batches = [
+            GENESIS_TRANSACTION,
+            BLOCK_METADATA_TRANSACTION,
+            USER_TRANSACTION,
+        ];
+        for name in batches {
+            tailer
+                .processor
+                .process_transactions_with_status(vec![load_transaction(name)])
+                .await
+                .unwrap();
+        }
+        let single_pass = snapshot();
+        assert!(single_pass
+            .iter()
+            .any(|(table, rows)| *table == "block_metadata_transactions"
+                && rows.as_array().unwrap().len() == 1));
+
+        // Going over the same range again writes the exact same rows
+        for name in batches {
+            tailer
+                .processor
+                .process_transactions_with_status(vec![load_transaction(name)])
+                .await
+                .unwrap();
+        }
+        assert_eq!(snapshot(), single_pass);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_transaction_type_tables() {
+        if crate::should_skip_pg_tests() {
+            return;
+        }
+        let (conn_pool, tailer) = setup_indexer().unwrap();
+        // Two blocks of a block metadata, a user transaction and a state checkpoint
+        let batch: Vec<Transaction> = [
+            BLOCK_METADATA_TRANSACTION,
+            USER_TRANSACTION,
+            STATE_CHECKPOINT_TRANSACTION,
+        ]
+        .iter()
+        .cycle()
+        .take(6)
+        .zip(10..)
+        .map(|(name, version)| {
+            let mut txn = load_transaction(name);
+            // Events are keyed by event handle, so the second block can't repeat them
+            let info = match &mut txn {
+                Transaction::BlockMetadataTransaction(txn) => {
+                    txn.events.retain(|_| version < 13);
+                    &mut txn.info
+                },
+                Transaction::UserTransaction(txn) => {
+                    txn.events.retain(|_| version < 13);
+                    &mut txn.info
+                },
+                Transaction::StateCheckpointTransaction(txn) => &mut txn.info,
+                _ => unreachable!(),
+            };
+            info.version = U64::from(version);
+            info.block_height = Some(U64::from(100 + (version - 10) / 3));
+            txn
+        })
+        .collect();
+        tailer
+            .processor
+            .process_transactions_with_status(batch)
+            .await
+            .unwrap();
+
+        #[derive(Debug, QueryableByName)]
+        struct Row {
+            #[diesel(sql_type = BigInt)]
+            version: i64,
+            #[diesel(sql_type = Text)]
+            type_: String,
+            #[diesel(sql_type = BigInt)]
+            details: i64,
+        }
+        let rows: Vec<Row> = sql_query(
+            "SELECT t.version, t.type AS type_, \
+            (SELECT COUNT(*) FROM user_transactions d WHERE d.version = t.version) \
+            + (SELECT COUNT(*) FROM block_metadata_transactions d WHERE d.version = t.version) \
+            + (SELECT COUNT(*) FROM state_checkpoint_transactions d WHERE d.version = t.version) \
+            AS details FROM transactions t ORDER BY t.version",
+        )
+        .get_results(&mut conn_pool.get().unwrap())
+        .unwrap();
+        assert_eq!(
+            rows.iter().map(|row| row.version).collect::<Vec<_>>(),
+            (10..16).collect::<Vec<_>>()
+        );
+        // Every version has its details in exactly one table
+        assert!(rows.iter().all(|row| row.details == 1));
+        assert_eq!(rows[2].type_, "state_checkpoint_transaction");
+        assert_eq!(rows[5].type_, "state_checkpoint_transaction");
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_version_gaps() {
+        if crate::should_skip_pg_tests() {
+            return;
+        }
+        let batches = || {
+            let mut fetcher = FakeFetcher::new(None);
+            // The upstream skips versions 15 to 19, then comes back to them when asked to
+            for versions in [10..15, 20..25, 15..25] {
+                fetcher
+                    .batches
+                    .push_back(versions.map(state_checkpoint_txn).collect());
+            }
+            Arc::new(Mutex::new(fetcher))
+        };
+
+        // The processor stops on the gap by default
+        let (_conn_pool, mut tailer) = setup_indexer().unwrap();
+        tailer.transaction_fetcher = batches();
+        tailer.set_fetcher_version(10).await;
+        assert!(tailer.process_next_batch().await.1.unwrap().is_ok());
+        let err = tailer.process_next_batch().await.1.unwrap().unwrap_err();
+        assert!(matches!(
+            err,
+            TransactionProcessingError::InvalidBatchError(_)
+        ));
+        assert_eq!(err.inner().0.to_string(), "Versions 15 to 19 are missing");
+
+        // Or fetches the missing versions again
+        let (_conn_pool, tailer) = setup_indexer().unwrap();
+        let mut tailer = tailer.with_gap_recovery();
+        tailer.transaction_fetcher = batches();
+        tailer.set_fetcher_version(10).await;
+        assert!(tailer.process_next_batch().await.1.unwrap().is_ok());
+        let (num_txns, result) = tailer.process_next_batch().await;
+        assert_eq!(num_txns, 10);
+        let result = result.unwrap().unwrap();
+        assert_eq!((result.start_version, result.end_version), (15, 24));
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_operator_skip() {
+        if crate::should_skip_pg_tests() {
+            return;
+        }
+        let (conn_pool, tailer) = setup_indexer().unwrap();
+        let mut tailer = tailer.with_skip_list(VersionSkipList::new(&[12], &[]));
+        let mut fetcher = FakeFetcher::new(None);
+        fetcher
+            .batches
+            .push_back((10..15).map(state_checkpoint_txn).collect());
+        tailer.transaction_fetcher = Arc::new(Mutex::new(fetcher));
+
+        let processor_name = tailer.processor.name().to_string();
+        let (num_txns, result) = tailer.process_next_batch().await;
+        assert_eq!(num_txns, 5);
+        let result = result.unwrap().unwrap();
+        assert_eq!((result.start_version, result.end_version), (10, 14));
+        tailer
+            .update_last_processed_version(&processor_name, result.end_version)
+            .unwrap();
+
+        // The watermark moves past the skipped version
+        assert_eq!(tailer.get_start_version(&processor_name).unwrap(), Some(15));
+        assert_eq!(
+            tailer.get_start_version_long(&processor_name, 100),
+            Some(15)
+        );
+
+        // The skipped version is recorded but was never processed
+        #[derive(Debug, QueryableByName)]
+        struct Status {
+            #[diesel(sql_type = BigInt)]
+            version: i64,
+            #[diesel(sql_type = diesel::sql_types::Bool)]
+            success: bool,
+            #[diesel(sql_type = diesel::sql_types::Nullable<Text>)]
+            details: Option<String>,
+        }
+        let skipped: Vec<Status> = sql_query(
+            "SELECT version, success, details FROM processor_statuses WHERE name = $1 AND details = $2",
+        )
+        .bind::<Text, _>(&processor_name)
+        .bind::<Text, _>(OPERATOR_SKIP_REASON)
+        .get_results(&mut conn_pool.get().unwrap())
+        .unwrap();
+        assert_eq!(skipped.len(), 1);
+        assert_eq!(skipped[0].version, 12);
+        assert!(!skipped[0].success);
+        assert_eq!(skipped[0].details.as_deref(), Some(OPERATOR_SKIP_REASON));
+        assert!(TransactionQuery::get_by_version(12, &mut conn_pool.get().unwrap()).is_err());
+        assert!(TransactionQuery::get_by_version(13, &mut conn_pool.get().unwrap()).is_ok());
+    }
 }
@@ -11,11 +11,15 @@ extern crate diesel_migrations;
 #[macro_use]
 extern crate diesel;
 
+pub mod check;
 pub mod counters;
 pub mod database;
+pub mod db_writer;
 pub mod indexer;
 pub mod models;
+pub mod parquet_writer;
 pub mod processors;
+pub mod query_stats;
 pub mod runtime;
 pub mod schema;
 mod util;
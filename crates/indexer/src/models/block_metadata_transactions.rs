@@ -8,7 +8,7 @@
 use super::transactions::{Transaction, TransactionQuery};
 use crate::{
     schema::block_metadata_transactions,
-    util::{parse_timestamp, standardize_address},
+    util::{parse_timestamp, standardize_address, standardize_hash},
 };
 use aptos_api_types::BlockMetadataTransaction as APIBlockMetadataTransaction;
 use field_count::FieldCount;
@@ -52,24 +52,99 @@ pub struct BlockMetadataTransactionQuery {
     pub inserted_at: chrono::NaiveDateTime,
 }
 
+/// Stands in for a block id that isn't a well-formed hash
+pub const INVALID_BLOCK_ID: &str =
+    "0x0000000000000000000000000000000000000000000000000000000000000000";
+
 impl BlockMetadataTransaction {
     pub fn from_transaction(txn: &APIBlockMetadataTransaction, block_height: i64) -> Self {
         let txn_version = txn.info.version.0 as i64;
         Self {
             version: txn_version,
             block_height,
-            id: txn.id.to_string(),
+            id: convert_block_id(&txn.id.to_string(), txn_version),
             epoch: txn.epoch.0 as i64,
             round: txn.round.0 as i64,
             proposer: standardize_address(&txn.proposer.inner().to_hex_literal()),
-            failed_proposer_indices: serde_json::to_value(&txn.failed_proposer_indices).unwrap(),
-            previous_block_votes_bitvec: serde_json::to_value(&txn.previous_block_votes_bitvec)
-                .unwrap(),
+            failed_proposer_indices: convert_failed_proposer_indices(&txn.failed_proposer_indices),
+            previous_block_votes_bitvec: convert_bitvec(&txn.previous_block_votes_bitvec),
             // time is in microseconds
             timestamp: parse_timestamp(txn.timestamp.0, txn_version),
         }
     }
 }
 
+/// The block id as a standardized hash, or `INVALID_BLOCK_ID` if it isn't one
+pub fn convert_block_id(id: &str, version: i64) -> String {
+    standardize_hash(id).unwrap_or_else(|| {
+        aptos_logger::warn!(
+            version = version,
+            id = id,
+            "Block id is not a well-formed hash, storing a sentinel instead"
+        );
+        INVALID_BLOCK_ID.to_string()
+    })
+}
+
+/// The bytes of the bitvec as a JSON array of numbers, e.g. `[255, 1]`
+pub fn convert_bitvec(bitvec: &[u8]) -> serde_json::Value {
+    serde_json::Value::Array(bitvec.iter().map(|byte| (*byte).into()).collect())
+}
+
+/// The indices as a JSON array of numbers
+pub fn convert_failed_proposer_indices(indices: &[u32]) -> serde_json::Value {
+    serde_json::Value::Array(indices.iter().map(|index| (*index as i64).into()).collect())
+}
+
 // Prevent conflicts with other things named `Transaction`
 pub type BlockMetadataTransactionModel = BlockMetadataTransaction;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aptos_api_types::Transaction as APITransaction;
+    use aptos_indexer_test_fixtures::{load_transaction, BLOCK_METADATA_TRANSACTION};
+    use serde_json::json;
+
+    fn block_metadata_transaction() -> APIBlockMetadataTransaction {
+        match load_transaction(BLOCK_METADATA_TRANSACTION) {
+            APITransaction::BlockMetadataTransaction(txn) => txn,
+            _ => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn test_canonical_row() {
+        let mut txn = block_metadata_transaction();
+        txn.proposer = "0x1".parse().unwrap();
+        txn.previous_block_votes_bitvec = vec![];
+        txn.failed_proposer_indices = vec![3, 7];
+        let row = BlockMetadataTransaction::from_transaction(&txn, 5);
+        assert_eq!(
+            row.proposer,
+            "0x0000000000000000000000000000000000000000000000000000000000000001"
+        );
+        assert_eq!(row.previous_block_votes_bitvec, json!([]));
+        assert_eq!(row.failed_proposer_indices, json!([3, 7]));
+        assert_eq!(row.id, txn.id.to_string());
+        assert_eq!(row.id.len(), 66);
+
+        txn.previous_block_votes_bitvec = vec![255, 0, 1];
+        txn.failed_proposer_indices = vec![];
+        let row = BlockMetadataTransaction::from_transaction(&txn, 5);
+        assert_eq!(row.previous_block_votes_bitvec, json!([255, 0, 1]));
+        assert_eq!(row.failed_proposer_indices, json!([]));
+    }
+
+    #[test]
+    fn test_malformed_block_id() {
+        let id = block_metadata_transaction().id.to_string();
+        assert_eq!(
+            convert_block_id(&id.to_uppercase().replace("0X", ""), 1),
+            id
+        );
+        assert_eq!(convert_block_id("0x1", 1), INVALID_BLOCK_ID);
+        assert_eq!(convert_block_id("not a hash", 1), INVALID_BLOCK_ID);
+        assert_eq!(convert_block_id("", 1), INVALID_BLOCK_ID);
+    }
+}
@@ -0,0 +1,347 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+// This is required because a diesel macro makes clippy sad
+#![allow(clippy::extra_unused_lifetimes)]
+#![allow(clippy::unused_unit)]
+
+use super::{
+    block_metadata_transactions::BlockMetadataTransaction, events::EventModel,
+    move_resources::MoveResource, transactions::Transaction,
+};
+use crate::{schema::block_validator_participation, util::standardize_address};
+use field_count::FieldCount;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+
+#[derive(
+    Clone, Debug, Deserialize, Eq, FieldCount, Identifiable, Insertable, PartialEq, Serialize,
+)]
+#[diesel(primary_key(block_height, validator_index))]
+#[diesel(table_name = block_validator_participation)]
+pub struct BlockValidatorParticipation {
+    pub block_height: i64,
+    pub validator_index: i64,
+    pub version: i64,
+    pub epoch: i64,
+    pub validator_address: Option<String>,
+    /// Whether the validator voted for the previous block, as recorded by this block
+    pub voted: bool,
+    pub failed_to_propose: bool,
+}
+
+/// Need a separate struct for queryable because we don't want to define the inserted_at column (letting DB fill)
+#[derive(Clone, Debug, Deserialize, FieldCount, Identifiable, Queryable, Serialize)]
+#[diesel(primary_key(block_height, validator_index))]
+#[diesel(table_name = block_validator_participation)]
+pub struct BlockValidatorParticipationQuery {
+    pub block_height: i64,
+    pub validator_index: i64,
+    pub version: i64,
+    pub epoch: i64,
+    pub validator_address: Option<String>,
+    pub voted: bool,
+    pub failed_to_propose: bool,
+    pub inserted_at: chrono::NaiveDateTime,
+}
+
+impl BlockValidatorParticipation {
+    /// One row per validator of the epoch, given the addresses of the validators of the epoch by
+    /// index. Without them, the size of the validator set is unknown, so there are only rows up
+    /// to the last validator that voted or failed to propose, the bits padding the last byte of
+    /// the bitvec aren't validators.
+    pub fn from_block_metadata_transaction(
+        txn: &BlockMetadataTransaction,
+        validators: Option<&[String]>,
+    ) -> Vec<Self> {
+        let votes = bitvec_bytes(&txn.previous_block_votes_bitvec);
+        let failed_proposer_indices: Vec<u64> = txn
+            .failed_proposer_indices
+            .as_array()
+            .map(|indices| indices.iter().filter_map(|index| index.as_u64()).collect())
+            .unwrap_or_default();
+        let num_validators = match validators {
+            Some(validators) => validators.len() as u64,
+            None => (0..votes.len() as u64 * 8)
+                .rev()
+                .find(|index| is_set(&votes, *index))
+                .map_or(0, |index| index + 1),
+        };
+        // The indices refer to the validator set of the epoch, but don't trust them blindly
+        let num_validators = failed_proposer_indices
+            .iter()
+            .map(|index| index + 1)
+            .fold(num_validators, std::cmp::max);
+        (0..num_validators)
+            .map(|index| Self {
+                block_height: txn.block_height,
+                validator_index: index as i64,
+                version: txn.version,
+                epoch: txn.epoch,
+                validator_address: validators
+                    .and_then(|validators| validators.get(index as usize).cloned()),
+                voted: is_set(&votes, index),
+                failed_to_propose: failed_proposer_indices.contains(&index),
+            })
+            .collect()
+    }
+}
+
+/// The bytes of a bitvec stored as a JSON array of numbers
+fn bitvec_bytes(bitvec: &serde_json::Value) -> Vec<u8> {
+    bitvec
+        .as_array()
+        .map(|bytes| {
+            bytes
+                .iter()
+                .map(|byte| byte.as_u64().unwrap_or_default() as u8)
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Bits are stored from the most significant bit of each byte on, like `aptos_bitvec::BitVec`
+fn is_set(bytes: &[u8], index: u64) -> bool {
+    bytes
+        .get((index / 8) as usize)
+        .map_or(false, |byte| byte & (0b1000_0000 >> (index % 8)) != 0)
+}
+
+/// Addresses of the active validators by validator index, for the epochs whose validator set was
+/// written by the batches processed so far. The active validators only change when a new epoch
+/// starts, so the validator set written along with a new epoch event is the one of the new epoch,
+/// starting with the one written by genesis.
+#[derive(Debug, Default)]
+pub struct ValidatorSetTracker {
+    validators: HashMap<i64, Vec<String>>,
+}
+
+impl ValidatorSetTracker {
+    /// Batches may be processed out of order, so the sets of a couple of epochs are kept around
+    const EPOCHS_KEPT: i64 = 2;
+
+    pub fn add_batch(
+        &mut self,
+        txns: &[Transaction],
+        events: &[EventModel],
+        move_resources: &[MoveResource],
+    ) {
+        let epochs: HashMap<i64, i64> = txns.iter().map(|txn| (txn.version, txn.epoch)).collect();
+        let reconfigurations: HashSet<i64> = events
+            .iter()
+            .filter(|event| event.type_ == "0x1::reconfiguration::NewEpochEvent")
+            .map(|event| event.transaction_version)
+            .collect();
+        let framework_address = standardize_address("0x1");
+        for resource in move_resources {
+            if resource.address != framework_address
+                || resource.module != "stake"
+                || resource.name != "ValidatorSet"
+            {
+                continue;
+            }
+            let (epoch, validators) = match (
+                epochs.get(&resource.transaction_version),
+                resource.data.as_ref().and_then(active_validators),
+            ) {
+                (Some(epoch), Some(validators))
+                    if reconfigurations.contains(&resource.transaction_version) =>
+                {
+                    (epoch + 1, validators)
+                },
+                (Some(epoch), Some(validators)) => (*epoch, validators),
+                _ => continue,
+            };
+            self.validators.insert(epoch, validators);
+        }
+        if let Some(latest) = self.validators.keys().max().copied() {
+            self.validators
+                .retain(|epoch, _| *epoch > latest - Self::EPOCHS_KEPT);
+        }
+    }
+
+    pub fn get(&self, epoch: i64) -> Option<&[String]> {
+        self.validators
+            .get(&epoch)
+            .map(|validators| validators.as_slice())
+    }
+}
+
+/// The addresses of the active validators of a `0x1::stake::ValidatorSet`, in validator index
+/// order
+fn active_validators(data: &serde_json::Value) -> Option<Vec<String>> {
+    data.get("active_validators")?
+        .as_array()?
+        .iter()
+        .map(|validator| Some(standardize_address(validator.get("addr")?.as_str()?)))
+        .collect()
+}
+
+// Prevent conflicts with other things named `BlockValidatorParticipation`
+pub type BlockValidatorParticipationModel = BlockValidatorParticipation;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aptos_indexer_test_fixtures::{load_transaction, STATE_CHECKPOINT_TRANSACTION};
+    use serde_json::json;
+
+    fn block_metadata_transaction(
+        epoch: i64,
+        votes: serde_json::Value,
+        failed_proposer_indices: serde_json::Value,
+    ) -> BlockMetadataTransaction {
+        BlockMetadataTransaction {
+            version: 10,
+            block_height: 5,
+            id: "0x1".to_string(),
+            round: 7,
+            epoch,
+            previous_block_votes_bitvec: votes,
+            proposer: standardize_address("0x1"),
+            failed_proposer_indices,
+            timestamp: chrono::NaiveDateTime::from_timestamp_opt(0, 0).unwrap(),
+        }
+    }
+
+    fn validators(count: u64) -> Vec<String> {
+        (0..count)
+            .map(|index| standardize_address(&format!("0x{:x}", index + 100)))
+            .collect()
+    }
+
+    fn validator_set_resource(version: i64, validators: &[String]) -> MoveResource {
+        MoveResource {
+            transaction_version: version,
+            write_set_change_index: 0,
+            transaction_block_height: 0,
+            name: "ValidatorSet".to_string(),
+            type_: "0x1::stake::ValidatorSet".to_string(),
+            address: standardize_address("0x1"),
+            module: "stake".to_string(),
+            generic_type_params: None,
+            data: Some(json!({
+                "consensus_scheme": 0,
+                "active_validators": validators
+                    .iter()
+                    .map(|addr| json!({"addr": addr, "voting_power": "1"}))
+                    .collect::<Vec<_>>(),
+                "pending_active": [],
+                "pending_inactive": [],
+            })),
+            is_deleted: false,
+        }
+    }
+
+    fn new_epoch_event(version: i64, epoch: i64) -> EventModel {
+        EventModel {
+            sequence_number: epoch,
+            creation_number: 2,
+            account_address: standardize_address("0x1"),
+            transaction_version: version,
+            transaction_block_height: 0,
+            type_: "0x1::reconfiguration::NewEpochEvent".to_string(),
+            data: json!({ "epoch": epoch.to_string() }),
+            event_index: Some(0),
+        }
+    }
+
+    fn votes(rows: &[BlockValidatorParticipation]) -> Vec<bool> {
+        rows.iter().map(|row| row.voted).collect()
+    }
+
+    #[test]
+    fn test_expansion_with_partial_byte() {
+        let validators = validators(9);
+        // The 9th validator is the first bit of the second byte, the rest of it is padding
+        let txn = block_metadata_transaction(1, json!([0b1010_0000, 0b1000_0000]), json!([1]));
+        let rows =
+            BlockValidatorParticipation::from_block_metadata_transaction(&txn, Some(&validators));
+        assert_eq!(
+            votes(&rows),
+            vec![true, false, true, false, false, false, false, false, true]
+        );
+        assert_eq!(
+            rows.iter()
+                .filter(|row| row.failed_to_propose)
+                .map(|row| row.validator_index)
+                .collect::<Vec<_>>(),
+            vec![1]
+        );
+        assert_eq!(rows[8].validator_address.as_ref(), Some(&validators[8]));
+        assert!(rows
+            .iter()
+            .all(|row| row.block_height == 5 && row.version == 10 && row.epoch == 1));
+    }
+
+    #[test]
+    fn test_expansion_without_validator_set() {
+        // Without the validator set, rows stop at the last validator that did anything
+        let txn = block_metadata_transaction(1, json!([0b0100_0000, 0]), json!([3]));
+        let rows = BlockValidatorParticipation::from_block_metadata_transaction(&txn, None);
+        assert_eq!(votes(&rows), vec![false, true, false, false]);
+        assert!(rows[3].failed_to_propose);
+        assert!(rows.iter().all(|row| row.validator_address.is_none()));
+
+        let txn = block_metadata_transaction(1, json!([]), json!([]));
+        assert!(
+            BlockValidatorParticipation::from_block_metadata_transaction(&txn, None).is_empty()
+        );
+    }
+
+    #[test]
+    fn test_validator_set_changes_between_epochs() {
+        let mut tracker = ValidatorSetTracker::default();
+        let txn = |version: i64, epoch: i64| {
+            let (mut txn, _, _, _, _) =
+                Transaction::from_transaction(&load_transaction(STATE_CHECKPOINT_TRANSACTION));
+            txn.version = version;
+            txn.epoch = epoch;
+            txn
+        };
+        // Genesis writes the set of the first epoch, the last block of an epoch the next one
+        tracker.add_batch(
+            &[txn(0, 0)],
+            &[new_epoch_event(0, 1)],
+            &[validator_set_resource(0, &validators(3))],
+        );
+        // Validators joining during the epoch don't change its active validators
+        tracker.add_batch(
+            &[txn(15, 1)],
+            &[],
+            &[validator_set_resource(15, &validators(3))],
+        );
+        assert!(tracker.get(2).is_none());
+        tracker.add_batch(
+            &[txn(20, 1)],
+            &[new_epoch_event(20, 2)],
+            &[validator_set_resource(20, &validators(9))],
+        );
+        assert_eq!(tracker.get(1).unwrap().len(), 3);
+        assert_eq!(tracker.get(2).unwrap().len(), 9);
+        assert!(tracker.get(3).is_none());
+
+        // The bitvec gets longer along with the validator set
+        let first = block_metadata_transaction(1, json!([0b1110_0000]), json!([]));
+        let second = block_metadata_transaction(2, json!([0b1111_1111, 0b1000_0000]), json!([]));
+        let rows = BlockValidatorParticipation::from_block_metadata_transaction(
+            &first,
+            tracker.get(first.epoch),
+        );
+        assert_eq!(votes(&rows), vec![true; 3]);
+        let rows = BlockValidatorParticipation::from_block_metadata_transaction(
+            &second,
+            tracker.get(second.epoch),
+        );
+        assert_eq!(votes(&rows), vec![true; 9]);
+
+        // Only the latest sets are kept
+        tracker.add_batch(
+            &[txn(40, 2)],
+            &[new_epoch_event(40, 3)],
+            &[validator_set_resource(40, &validators(4))],
+        );
+        assert!(tracker.get(1).is_none());
+        assert_eq!(tracker.get(3).unwrap().len(), 4);
+    }
+}
@@ -0,0 +1,267 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+// This is required because a diesel macro makes clippy sad
+#![allow(clippy::extra_unused_lifetimes)]
+#![allow(clippy::unused_unit)]
+
+use super::{block_metadata_transactions::BlockMetadataTransaction, transactions::Transaction};
+use crate::schema::blocks;
+use field_count::FieldCount;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+
+#[derive(
+    Clone, Debug, Deserialize, Eq, FieldCount, Identifiable, Insertable, PartialEq, Serialize,
+)]
+#[diesel(primary_key(block_height))]
+#[diesel(table_name = blocks)]
+pub struct Block {
+    pub block_height: i64,
+    pub block_hash: String,
+    pub first_version: i64,
+    pub last_version: Option<i64>,
+    pub timestamp: chrono::NaiveDateTime,
+    pub epoch: i64,
+    pub proposer: String,
+}
+
+/// Need a separate struct for queryable because we don't want to define the inserted_at column (letting DB fill)
+#[derive(Clone, Debug, Deserialize, FieldCount, Identifiable, Queryable, Serialize)]
+#[diesel(primary_key(block_height))]
+#[diesel(table_name = blocks)]
+pub struct BlockQuery {
+    pub block_height: i64,
+    pub block_hash: String,
+    pub first_version: i64,
+    pub last_version: Option<i64>,
+    pub timestamp: chrono::NaiveDateTime,
+    pub epoch: i64,
+    pub proposer: String,
+    pub inserted_at: chrono::NaiveDateTime,
+}
+
+impl Block {
+    /// The block starting at the block metadata transaction, whose last version is only known
+    /// once the end of the block is seen
+    pub fn from_block_metadata_transaction(txn: &BlockMetadataTransaction) -> Self {
+        Self {
+            block_height: txn.block_height,
+            block_hash: txn.id.clone(),
+            first_version: txn.version,
+            last_version: None,
+            timestamp: txn.timestamp,
+            epoch: txn.epoch,
+            proposer: txn.proposer.clone(),
+        }
+    }
+}
+
+/// End of a block whose start wasn't seen by this run, which is only set on the block if it
+/// doesn't have an end yet
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct BlockEnd {
+    pub block_height: i64,
+    pub last_version: i64,
+}
+
+/// Boundaries of the blocks seen by the batches processed so far, for the blocks spanning
+/// batches. A block ends with its state checkpoint, or right before the next block metadata
+/// transaction. Batches may be processed out of order, so the end of a block may be seen before
+/// its start.
+#[derive(Debug, Default)]
+pub struct BlockTracker {
+    /// Blocks started without an end seen yet, by height
+    open: HashMap<i64, Block>,
+    /// Last versions of the blocks ended without a start seen yet, by height. Only the block the
+    /// run started in never gets its start, so this stays tiny.
+    ends: HashMap<i64, i64>,
+}
+
+impl BlockTracker {
+    /// Returns the blocks started or ended in the batch, sorted by height and with their last
+    /// version if their end was seen so far, along with the ends of the blocks that didn't
+    /// start in any batch seen so far. Those may have been written by a previous run.
+    pub fn add_batch(
+        &mut self,
+        txns: &[Transaction],
+        block_metadata_transactions: &[BlockMetadataTransaction],
+    ) -> (Vec<Block>, Vec<BlockEnd>) {
+        let starts: HashMap<i64, &BlockMetadataTransaction> = block_metadata_transactions
+            .iter()
+            .map(|txn| (txn.version, txn))
+            .collect();
+        let mut blocks = BTreeMap::new();
+        let mut ends = vec![];
+        for txn in txns {
+            match starts.get(&txn.version) {
+                Some(start) => {
+                    // The previous block ends right before, unless its state checkpoint ended it
+                    if let Some(mut previous) = self.open.remove(&(start.block_height - 1)) {
+                        previous.last_version = Some(start.version - 1);
+                        blocks.insert(previous.block_height, previous);
+                    }
+                    let mut block = Block::from_block_metadata_transaction(start);
+                    block.last_version = self.ends.remove(&block.block_height);
+                    if block.last_version.is_none() {
+                        self.open.insert(block.block_height, block.clone());
+                    }
+                    blocks.insert(block.block_height, block);
+                },
+                None if txn.type_ == "state_checkpoint_transaction" => {
+                    match self.open.remove(&txn.block_height) {
+                        Some(mut block) => {
+                            block.last_version = Some(txn.version);
+                            blocks.insert(block.block_height, block);
+                        },
+                        None => {
+                            self.ends.insert(txn.block_height, txn.version);
+                            ends.push(BlockEnd {
+                                block_height: txn.block_height,
+                                last_version: txn.version,
+                            });
+                        },
+                    }
+                },
+                None => {},
+            }
+        }
+        (blocks.into_values().collect(), ends)
+    }
+}
+
+// Prevent conflicts with other things named `Block`
+pub type BlockModel = Block;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::transactions::{TransactionDetail, TransactionModel};
+    use aptos_api_types::{Transaction as APITransaction, U64};
+    use aptos_indexer_test_fixtures::{
+        load_transaction, BLOCK_METADATA_TRANSACTION, STATE_CHECKPOINT_TRANSACTION,
+        USER_TRANSACTION,
+    };
+
+    /// A block of a block metadata, a user transaction and a state checkpoint
+    fn block(block_height: u64, start_version: u64) -> Vec<APITransaction> {
+        [
+            BLOCK_METADATA_TRANSACTION,
+            USER_TRANSACTION,
+            STATE_CHECKPOINT_TRANSACTION,
+        ]
+        .iter()
+        .zip(start_version..)
+        .map(|(name, version)| {
+            let mut txn = load_transaction(name);
+            let info = match &mut txn {
+                APITransaction::BlockMetadataTransaction(txn) => &mut txn.info,
+                APITransaction::UserTransaction(txn) => &mut txn.info,
+                APITransaction::StateCheckpointTransaction(txn) => &mut txn.info,
+                _ => unreachable!(),
+            };
+            info.version = U64::from(version);
+            info.block_height = Some(U64::from(block_height));
+            txn
+        })
+        .collect()
+    }
+
+    fn add_batch(
+        tracker: &mut BlockTracker,
+        transactions: &[APITransaction],
+    ) -> (Vec<Block>, Vec<BlockEnd>) {
+        let (txns, txn_details, _, _, _) = TransactionModel::from_transactions(transactions);
+        let block_metadata_transactions: Vec<_> = txn_details
+            .into_iter()
+            .filter_map(|detail| match detail {
+                TransactionDetail::BlockMetadata(block_metadata) => Some(block_metadata),
+                TransactionDetail::User(_, _) | TransactionDetail::StateCheckpoint(_) => None,
+            })
+            .collect();
+        tracker.add_batch(&txns, &block_metadata_transactions)
+    }
+
+    /// Heights and version ranges of the blocks
+    fn ranges(blocks: &[Block]) -> Vec<(i64, i64, Option<i64>)> {
+        blocks
+            .iter()
+            .map(|block| (block.block_height, block.first_version, block.last_version))
+            .collect()
+    }
+
+    #[test]
+    fn test_blocks_of_a_batch() {
+        let mut tracker = BlockTracker::default();
+        let transactions = [block(5, 10), block(6, 13)].concat();
+        let (blocks, ends) = add_batch(&mut tracker, &transactions);
+        assert_eq!(ranges(&blocks), vec![(5, 10, Some(12)), (6, 13, Some(15))]);
+        assert!(ends.is_empty());
+
+        // The rest of the row comes from the block metadata transaction.
+        let block_metadata = match &transactions[0] {
+            APITransaction::BlockMetadataTransaction(block_metadata) => block_metadata,
+            _ => unreachable!(),
+        };
+        assert_eq!(blocks[0].block_hash, block_metadata.id.to_string());
+        assert_eq!(blocks[0].epoch, block_metadata.epoch.0 as i64);
+        assert!(blocks[0].proposer.starts_with("0x"));
+        assert!(tracker.open.is_empty() && tracker.ends.is_empty());
+    }
+
+    #[test]
+    fn test_block_spanning_batches() {
+        let mut tracker = BlockTracker::default();
+        let transactions = [block(5, 10), block(6, 13)].concat();
+
+        // The second block is still open at the end of the first batch.
+        let (blocks, ends) = add_batch(&mut tracker, &transactions[..4]);
+        assert_eq!(ranges(&blocks), vec![(5, 10, Some(12)), (6, 13, None)]);
+        assert!(ends.is_empty());
+
+        // Its state checkpoint completes the whole row in the next batch.
+        let (blocks, ends) = add_batch(&mut tracker, &transactions[4..]);
+        assert_eq!(ranges(&blocks), vec![(6, 13, Some(15))]);
+        assert!(ends.is_empty());
+        assert!(tracker.open.is_empty() && tracker.ends.is_empty());
+    }
+
+    #[test]
+    fn test_block_ended_by_the_next_block() {
+        let mut tracker = BlockTracker::default();
+        // Blocks without a state checkpoint end right before the next one.
+        let mut transactions = block(5, 10);
+        transactions.pop();
+        transactions.extend(block(6, 12));
+        let (first, second) = transactions.split_at(2);
+
+        let (blocks, _) = add_batch(&mut tracker, first);
+        assert_eq!(ranges(&blocks), vec![(5, 10, None)]);
+        let (blocks, ends) = add_batch(&mut tracker, second);
+        assert_eq!(ranges(&blocks), vec![(5, 10, Some(11)), (6, 12, Some(14))]);
+        assert!(ends.is_empty());
+    }
+
+    #[test]
+    fn test_batches_out_of_order() {
+        let mut tracker = BlockTracker::default();
+        let transactions = [block(5, 10), block(6, 13)].concat();
+
+        // The end of the second block comes first, it can only be set on an existing row.
+        let (blocks, ends) = add_batch(&mut tracker, &transactions[4..]);
+        assert!(blocks.is_empty());
+        assert_eq!(
+            ends,
+            vec![BlockEnd {
+                block_height: 6,
+                last_version: 15,
+            }]
+        );
+
+        // The start of the block picks up its end.
+        let (blocks, ends) = add_batch(&mut tracker, &transactions[..4]);
+        assert_eq!(ranges(&blocks), vec![(5, 10, Some(12)), (6, 13, Some(15))]);
+        assert!(ends.is_empty());
+        assert!(tracker.open.is_empty() && tracker.ends.is_empty());
+    }
+}
@@ -93,7 +93,7 @@ impl CoinActivity {
                 &inner.info.changes,
                 &inner.events,
                 None,
-                chrono::NaiveDateTime::from_timestamp_opt(0, 0).unwrap(),
+                parse_timestamp(0, inner.info.version.0 as i64),
             ),
             APITransaction::UserTransaction(inner) => (
                 &inner.info,
@@ -10,6 +10,7 @@ use super::{
     coin_utils::{CoinInfoType, CoinResource},
 };
 use crate::{
+    indexer::latest_value_aggregator::CurrentStateModel,
     schema::{coin_balances, current_coin_balances},
     util::standardize_address,
 };
@@ -43,6 +44,12 @@ pub struct CurrentCoinBalance {
     pub last_transaction_timestamp: chrono::NaiveDateTime,
 }
 
+impl CurrentStateModel for CurrentCoinBalance {
+    fn last_transaction_version(&self) -> i64 {
+        self.last_transaction_version
+    }
+}
+
 impl CoinBalance {
     /// We can find coin info from resources. If the coin info appears multiple times we will only keep the first transaction because it can't be modified.
     pub fn from_write_resource(
@@ -6,7 +6,7 @@
 #![allow(clippy::unused_unit)]
 
 use super::coin_utils::{CoinInfoType, CoinResource};
-use crate::{database::PgPoolConnection, schema::coin_infos};
+use crate::{database::PgPoolConnection, query_stats::record_query, schema::coin_infos};
 use aptos_api_types::WriteResource as APIWriteResource;
 use diesel::{ExpressionMethods, OptionalExtension, QueryDsl, RunQueryDsl};
 use field_count::FieldCount;
@@ -86,9 +86,11 @@ impl CoinInfoQuery {
         coin_type: String,
         conn: &mut PgPoolConnection,
     ) -> diesel::QueryResult<Option<Self>> {
-        coin_infos::table
+        let coin_info = coin_infos::table
             .filter(coin_infos::coin_type.eq(coin_type))
             .first::<Self>(conn)
-            .optional()
+            .optional()?;
+        record_query("coin_infos", &["coin_type"], &[], coin_info.iter().len());
+        Ok(coin_info)
     }
 }
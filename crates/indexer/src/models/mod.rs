@@ -2,6 +2,8 @@
 // SPDX-License-Identifier: Apache-2.0
 
 pub mod block_metadata_transactions;
+pub mod block_validator_participation;
+pub mod blocks;
 pub mod coin_models;
 pub mod events;
 pub mod ledger_info;
@@ -13,7 +15,9 @@ pub mod processor_statuses;
 pub mod property_map;
 pub mod signatures;
 pub mod stake_models;
+pub mod state_checkpoint_transactions;
 pub mod token_models;
 pub mod transactions;
 pub mod user_transactions;
+pub mod vm_status;
 pub mod write_set_changes;
@@ -2,6 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 #![allow(clippy::extra_unused_lifetimes)]
 use crate::{
+    indexer::latest_value_aggregator::CurrentStateModel,
     models::transactions::Transaction,
     schema::{current_table_items, table_items, table_metadatas},
     util::{hash_str, standardize_address},
@@ -23,6 +24,12 @@ pub struct CurrentTableItem {
     pub is_deleted: bool,
 }
 
+impl CurrentStateModel for CurrentTableItem {
+    fn last_transaction_version(&self) -> i64 {
+        self.last_transaction_version
+    }
+}
+
 #[derive(
     Associations, Clone, Debug, Deserialize, FieldCount, Identifiable, Insertable, Serialize,
 )]
@@ -1,8 +1,12 @@
 // Copyright (c) Aptos
 // SPDX-License-Identifier: Apache-2.0
 #![allow(clippy::extra_unused_lifetimes)]
-use crate::{database::PgPoolConnection, schema::processor_status};
-use diesel::{ExpressionMethods, OptionalExtension, QueryDsl, RunQueryDsl};
+use crate::{
+    database::{execute_with_better_error, PgPoolConnection},
+    query_stats::record_query,
+    schema::processor_status,
+};
+use diesel::{ExpressionMethods, OptionalExtension, PgConnection, QueryDsl, RunQueryDsl};
 
 #[derive(AsChangeset, Debug, Insertable)]
 #[diesel(table_name = processor_status)]
@@ -10,6 +14,7 @@ use diesel::{ExpressionMethods, OptionalExtension, QueryDsl, RunQueryDsl};
 pub struct ProcessorStatusV2 {
     pub processor: String,
     pub last_success_version: i64,
+    pub last_error: Option<String>,
 }
 
 #[derive(AsChangeset, Debug, Queryable)]
@@ -19,6 +24,35 @@ pub struct ProcessorStatusV2Query {
     pub processor: String,
     pub last_success_version: i64,
     pub last_updated: chrono::NaiveDateTime,
+    pub last_error: Option<String>,
+}
+
+impl ProcessorStatusV2 {
+    /// Moves the last version processed to the end of the batch if the batch starts right after
+    /// it. This runs in the transaction writing the batch, so the version never gets ahead of the
+    /// data written. Batches committed out of order are caught up with after every round.
+    pub fn advance(
+        conn: &mut PgConnection,
+        processor_name: &str,
+        start_version: u64,
+        end_version: u64,
+    ) -> Result<(), diesel::result::Error> {
+        execute_with_better_error(
+            conn,
+            diesel::update(
+                processor_status::table
+                    .filter(processor_status::processor.eq(processor_name))
+                    .filter(processor_status::last_success_version.eq(start_version as i64 - 1)),
+            )
+            .set((
+                processor_status::last_success_version.eq(end_version as i64),
+                processor_status::last_updated.eq(chrono::Utc::now().naive_utc()),
+                processor_status::last_error.eq(None::<String>),
+            )),
+            None,
+        )?;
+        Ok(())
+    }
 }
 
 impl ProcessorStatusV2Query {
@@ -26,9 +60,11 @@ impl ProcessorStatusV2Query {
         processor_name: &String,
         conn: &mut PgPoolConnection,
     ) -> diesel::QueryResult<Option<Self>> {
-        processor_status::table
+        let status = processor_status::table
             .filter(processor_status::processor.eq(processor_name))
             .first::<Self>(conn)
-            .optional()
+            .optional()?;
+        record_query("processor_status", &["processor"], &[], status.iter().len());
+        Ok(status)
     }
 }
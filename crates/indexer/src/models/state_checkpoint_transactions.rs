@@ -0,0 +1,92 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+// This is required because a diesel macro makes clippy sad
+#![allow(clippy::extra_unused_lifetimes)]
+#![allow(clippy::unused_unit)]
+
+use super::transactions::{Transaction, TransactionQuery};
+use crate::{schema::state_checkpoint_transactions, util::parse_timestamp};
+use aptos_api_types::TransactionInfo;
+use field_count::FieldCount;
+use serde::{Deserialize, Serialize};
+
+#[derive(
+    Associations, Clone, Debug, Deserialize, FieldCount, Identifiable, Insertable, Serialize,
+)]
+#[diesel(belongs_to(Transaction, foreign_key = version))]
+#[diesel(primary_key(version))]
+#[diesel(table_name = state_checkpoint_transactions)]
+pub struct StateCheckpointTransaction {
+    pub version: i64,
+    pub block_height: i64,
+    pub state_checkpoint_hash: Option<String>,
+    pub epoch: i64,
+    pub timestamp: chrono::NaiveDateTime,
+}
+
+/// Need a separate struct for queryable because we don't want to define the inserted_at column (letting DB fill)
+#[derive(
+    Associations, Clone, Debug, Deserialize, FieldCount, Identifiable, Queryable, Serialize,
+)]
+#[diesel(belongs_to(TransactionQuery, foreign_key = version))]
+#[diesel(primary_key(version))]
+#[diesel(table_name = state_checkpoint_transactions)]
+pub struct StateCheckpointTransactionQuery {
+    pub version: i64,
+    pub block_height: i64,
+    pub state_checkpoint_hash: Option<String>,
+    pub epoch: i64,
+    pub timestamp: chrono::NaiveDateTime,
+    pub inserted_at: chrono::NaiveDateTime,
+}
+
+impl StateCheckpointTransaction {
+    /// The timestamp is in microseconds, like the one of the transaction
+    pub fn from_transaction(
+        txn_info: &TransactionInfo,
+        version: i64,
+        block_height: i64,
+        epoch: i64,
+        timestamp: u64,
+    ) -> Self {
+        Self {
+            version,
+            block_height,
+            state_checkpoint_hash: txn_info.state_checkpoint_hash.map(|h| h.to_string()),
+            epoch,
+            timestamp: parse_timestamp(timestamp, version),
+        }
+    }
+}
+
+// Prevent conflicts with other things named `StateCheckpointTransaction`
+pub type StateCheckpointTransactionModel = StateCheckpointTransaction;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aptos_api_types::Transaction as APITransaction;
+    use aptos_indexer_test_fixtures::{load_transaction, STATE_CHECKPOINT_TRANSACTION};
+
+    #[test]
+    fn test_from_transaction() {
+        let txn = match load_transaction(STATE_CHECKPOINT_TRANSACTION) {
+            APITransaction::StateCheckpointTransaction(txn) => txn,
+            _ => unreachable!(),
+        };
+        let row = StateCheckpointTransaction::from_transaction(
+            &txn.info,
+            txn.info.version.0 as i64,
+            100,
+            1,
+            txn.timestamp.0,
+        );
+        assert_eq!(row.version, 691596);
+        assert_eq!(
+            row.state_checkpoint_hash.as_deref(),
+            Some("0x39f1f6a0f0dc8b3d16e5d8f2c2c2d77b3f3b8d1cf7d3a62c7b5a8d1e0e4b6a11")
+        );
+        assert_eq!(row.timestamp.timestamp(), 1649713141);
+    }
+}
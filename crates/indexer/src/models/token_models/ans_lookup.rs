@@ -6,6 +6,7 @@
 #![allow(clippy::unused_unit)]
 
 use crate::{
+    indexer::latest_value_aggregator::CurrentStateModel,
     schema::current_ans_lookup,
     util::{bigdecimal_to_u64, parse_timestamp_secs, standardize_address},
 };
@@ -33,6 +34,12 @@ pub struct CurrentAnsLookup {
     pub token_name: String,
 }
 
+impl CurrentStateModel for CurrentAnsLookup {
+    fn last_transaction_version(&self) -> i64 {
+        self.last_transaction_version
+    }
+}
+
 pub enum ANSEvent {
     SetNameAddressEventV1(SetNameAddressEventV1),
     RegisterNameEventV1(RegisterNameEventV1),
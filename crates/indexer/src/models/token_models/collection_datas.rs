@@ -11,6 +11,8 @@ use super::{
 };
 use crate::{
     database::PgPoolConnection,
+    indexer::latest_value_aggregator::CurrentStateModel,
+    query_stats::record_query,
     schema::{collection_datas, current_collection_datas},
     util::standardize_address,
 };
@@ -61,6 +63,12 @@ pub struct CurrentCollectionData {
     pub last_transaction_timestamp: chrono::NaiveDateTime,
 }
 
+impl CurrentStateModel for CurrentCollectionData {
+    fn last_transaction_version(&self) -> i64 {
+        self.last_transaction_version
+    }
+}
+
 /// Need a separate struct for queryable because we don't want to define the inserted_at column (letting DB fill)
 #[derive(Debug, Identifiable, Queryable)]
 #[diesel(primary_key(collection_data_id_hash))]
@@ -182,8 +190,10 @@ impl CurrentCollectionDataQuery {
         conn: &mut PgPoolConnection,
         table_handle: &str,
     ) -> diesel::QueryResult<Self> {
-        current_collection_datas::table
+        let collection_data = current_collection_datas::table
             .filter(current_collection_datas::table_handle.eq(table_handle))
-            .first::<Self>(conn)
+            .first::<Self>(conn)?;
+        record_query("current_collection_datas", &["table_handle"], &[], 1);
+        Ok(collection_data)
     }
 }
@@ -187,3 +187,154 @@ impl TokenActivity {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::token_data_id_hash;
+    use std::collections::HashSet;
+
+    const SENDER: &str = "0x00000000000000000000000000000000000000000000000000000000000000a1";
+    const RECEIVER: &str = "0x00000000000000000000000000000000000000000000000000000000000000b2";
+
+    /// An offer/claim pair between two accounts followed by the receiver burning the token.
+    /// The creator is deliberately not zero padded.
+    const FIXTURE_EVENTS: &str = r#"[
+        {
+            "version": 100,
+            "event": {
+                "guid": {"creation_number": "5", "account_address": "0xa1"},
+                "sequence_number": "0",
+                "type": "0x3::token_transfers::TokenOfferEvent",
+                "data": {
+                    "to_address": "0xb2",
+                    "token_id": {
+                        "token_data_id": {"creator": "0xc3", "collection": "Fixtures", "name": "Token #1"},
+                        "property_version": "0"
+                    },
+                    "amount": "1"
+                }
+            }
+        },
+        {
+            "version": 101,
+            "event": {
+                "guid": {"creation_number": "7", "account_address": "0xa1"},
+                "sequence_number": "0",
+                "type": "0x3::token_transfers::TokenClaimEvent",
+                "data": {
+                    "to_address": "0xb2",
+                    "token_id": {
+                        "token_data_id": {"creator": "0xc3", "collection": "Fixtures", "name": "Token #1"},
+                        "property_version": "0"
+                    },
+                    "amount": "1"
+                }
+            }
+        },
+        {
+            "version": 102,
+            "event": {
+                "guid": {"creation_number": "4", "account_address": "0xb2"},
+                "sequence_number": "0",
+                "type": "0x3::token::BurnTokenEvent",
+                "data": {
+                    "id": {
+                        "token_data_id": {"creator": "0xc3", "collection": "Fixtures", "name": "Token #1"},
+                        "property_version": "0"
+                    },
+                    "amount": "1"
+                }
+            }
+        }
+    ]"#;
+
+    fn parse_fixtures() -> Vec<TokenActivity> {
+        let fixtures: Vec<serde_json::Value> = serde_json::from_str(FIXTURE_EVENTS).unwrap();
+        fixtures
+            .into_iter()
+            .enumerate()
+            .map(|(index, fixture)| {
+                let txn_version = fixture["version"].as_i64().unwrap();
+                let event: APIEvent = serde_json::from_value(fixture["event"].clone()).unwrap();
+                let event_type = event.typ.to_string();
+                let token_event = TokenEvent::from_event(&event_type, &event.data, txn_version)
+                    .unwrap()
+                    .unwrap();
+                TokenActivity::from_parsed_event(
+                    &event_type,
+                    &event,
+                    &token_event,
+                    txn_version,
+                    parse_timestamp(1_660_000_000_000_000, txn_version),
+                    index as i64,
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_offer_claim_burn_activities() {
+        let activities = parse_fixtures();
+        let expected_hash = token_data_id_hash("0xc3", "Fixtures", "Token #1");
+
+        for activity in &activities {
+            assert_eq!(activity.token_data_id_hash, expected_hash);
+            assert_eq!(
+                activity.creator_address,
+                "0x00000000000000000000000000000000000000000000000000000000000000c3"
+            );
+            assert_eq!(activity.collection_name, "Fixtures");
+            assert_eq!(activity.name, "Token #1");
+            assert_eq!(activity.property_version, BigDecimal::zero());
+            assert_eq!(activity.token_amount, BigDecimal::from(1));
+        }
+
+        let transfers: Vec<_> = activities
+            .iter()
+            .map(|activity| {
+                (
+                    activity.transfer_type.as_str(),
+                    activity.from_address.as_deref(),
+                    activity.to_address.as_deref(),
+                )
+            })
+            .collect();
+        assert_eq!(transfers, vec![
+            (
+                "0x3::token_transfers::TokenOfferEvent",
+                Some(SENDER),
+                Some(RECEIVER)
+            ),
+            (
+                "0x3::token_transfers::TokenClaimEvent",
+                Some(SENDER),
+                Some(RECEIVER)
+            ),
+            ("0x3::token::BurnTokenEvent", Some(RECEIVER), None),
+        ]);
+    }
+
+    #[test]
+    fn test_replay_is_idempotent() {
+        // Rows are upserted on their primary key, so replaying the same events must produce the
+        // exact same keys and no two events in a batch may collide.
+        let primary_keys = |activities: Vec<TokenActivity>| {
+            activities
+                .into_iter()
+                .map(|activity| {
+                    (
+                        activity.transaction_version,
+                        activity.event_account_address,
+                        activity.event_creation_number,
+                        activity.event_sequence_number,
+                    )
+                })
+                .collect::<Vec<_>>()
+        };
+        let first = primary_keys(parse_fixtures());
+        let replayed = primary_keys(parse_fixtures());
+        assert_eq!(first, replayed);
+        assert_eq!(first.iter().collect::<HashSet<_>>().len(), first.len());
+    }
+}
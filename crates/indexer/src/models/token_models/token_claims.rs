@@ -6,7 +6,10 @@
 #![allow(clippy::unused_unit)]
 
 use super::{token_utils::TokenWriteSet, tokens::TableHandleToOwner};
-use crate::{schema::current_token_pending_claims, util::standardize_address};
+use crate::{
+    indexer::latest_value_aggregator::CurrentStateModel, schema::current_token_pending_claims,
+    util::standardize_address,
+};
 use aptos_api_types::{DeleteTableItem as APIDeleteTableItem, WriteTableItem as APIWriteTableItem};
 use bigdecimal::{BigDecimal, Zero};
 use field_count::FieldCount;
@@ -30,6 +33,12 @@ pub struct CurrentTokenPendingClaim {
     pub last_transaction_timestamp: chrono::NaiveDateTime,
 }
 
+impl CurrentStateModel for CurrentTokenPendingClaim {
+    fn last_transaction_version(&self) -> i64 {
+        self.last_transaction_version
+    }
+}
+
 impl CurrentTokenPendingClaim {
     /// Token claim is stored in a table in the offerer's account. The key is token_offer_id (token_id + to address)
     /// and value is token (token_id + amount)
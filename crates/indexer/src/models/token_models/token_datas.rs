@@ -7,6 +7,7 @@
 
 use super::token_utils::TokenWriteSet;
 use crate::{
+    indexer::latest_value_aggregator::CurrentStateModel,
     schema::{current_token_datas, token_datas},
     util::standardize_address,
 };
@@ -69,6 +70,12 @@ pub struct CurrentTokenData {
     pub description: String,
 }
 
+impl CurrentStateModel for CurrentTokenData {
+    fn last_transaction_version(&self) -> i64 {
+        self.last_transaction_version
+    }
+}
+
 impl TokenData {
     pub fn from_write_table_item(
         table_item: &APIWriteTableItem,
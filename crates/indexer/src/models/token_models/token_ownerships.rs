@@ -10,6 +10,7 @@ use super::{
     tokens::{TableHandleToOwner, Token},
 };
 use crate::{
+    indexer::latest_value_aggregator::CurrentStateModel,
     schema::{current_token_ownerships, token_ownerships},
     util::standardize_address,
 };
@@ -59,6 +60,12 @@ pub struct CurrentTokenOwnership {
     pub last_transaction_timestamp: chrono::NaiveDateTime,
 }
 
+impl CurrentStateModel for CurrentTokenOwnership {
+    fn last_transaction_version(&self) -> i64 {
+        self.last_transaction_version
+    }
+}
+
 impl TokenOwnership {
     /// We only want to track tokens in 0x1::token::TokenStore for now. This is because the table
     /// schema doesn't have table type (i.e. token container) as primary key. TokenStore has token_id
@@ -5,8 +5,8 @@
 #![allow(clippy::extra_unused_lifetimes)]
 
 use crate::util::{
-    deserialize_property_map_from_bcs_hexstring, deserialize_string_from_hexstring, hash_str,
-    standardize_address, truncate_str,
+    collection_data_id_hash, collection_data_id_key, deserialize_property_map_from_bcs_hexstring,
+    deserialize_string_from_hexstring, token_data_id_hash, token_data_id_key, truncate_str,
 };
 use anyhow::{Context, Result};
 use aptos_api_types::deserialize_from_string;
@@ -34,7 +34,7 @@ pub struct TokenDataIdType {
 
 impl TokenDataIdType {
     pub fn to_hash(&self) -> String {
-        hash_str(&self.to_string())
+        token_data_id_hash(&self.creator, &self.collection, &self.name)
     }
 
     pub fn get_collection_trunc(&self) -> String {
@@ -46,7 +46,7 @@ impl TokenDataIdType {
     }
 
     pub fn get_collection_data_id_hash(&self) -> String {
-        CollectionDataIdType::new(self.creator.clone(), self.collection.clone()).to_hash()
+        collection_data_id_hash(&self.creator, &self.collection)
     }
 }
 
@@ -54,10 +54,8 @@ impl fmt::Display for TokenDataIdType {
     fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
         write!(
             f,
-            "{}::{}::{}",
-            standardize_address(self.creator.as_str()),
-            self.collection,
-            self.name
+            "{}",
+            token_data_id_key(&self.creator, &self.collection, &self.name)
         )
     }
 }
@@ -74,7 +72,7 @@ impl CollectionDataIdType {
     }
 
     pub fn to_hash(&self) -> String {
-        hash_str(&self.to_string())
+        collection_data_id_hash(&self.creator, &self.name)
     }
 
     pub fn get_name_trunc(&self) -> String {
@@ -84,12 +82,7 @@ impl CollectionDataIdType {
 
 impl fmt::Display for CollectionDataIdType {
     fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
-        write!(
-            f,
-            "{}::{}",
-            standardize_address(self.creator.as_str()),
-            self.name
-        )
+        write!(f, "{}", collection_data_id_key(&self.creator, &self.name))
     }
 }
 
@@ -9,11 +9,14 @@ use super::{
     block_metadata_transactions::{BlockMetadataTransaction, BlockMetadataTransactionQuery},
     events::{EventModel, EventQuery},
     signatures::Signature,
+    state_checkpoint_transactions::StateCheckpointTransaction,
     user_transactions::{UserTransaction, UserTransactionQuery},
+    vm_status::FailureReason,
     write_set_changes::{WriteSetChangeDetail, WriteSetChangeModel, WriteSetChangeQuery},
 };
 use crate::{
     database::PgPoolConnection,
+    query_stats::record_query,
     schema::{block_metadata_transactions, transactions, user_transactions},
     util::u64_to_bigdecimal,
 };
@@ -44,6 +47,10 @@ pub struct Transaction {
     pub num_events: i64,
     pub num_write_set_changes: i64,
     pub epoch: i64,
+    pub failure_category: Option<String>,
+    pub abort_module_address: Option<String>,
+    pub abort_module_name: Option<String>,
+    pub abort_code: Option<BigDecimal>,
 }
 
 /// Need a separate struct for queryable because we don't want to define the inserted_at column (letting DB fill)
@@ -67,6 +74,10 @@ pub struct TransactionQuery {
     pub num_write_set_changes: i64,
     pub inserted_at: chrono::NaiveDateTime,
     pub epoch: i64,
+    pub failure_category: Option<String>,
+    pub abort_module_address: Option<String>,
+    pub abort_module_name: Option<String>,
+    pub abort_code: Option<BigDecimal>,
 }
 
 impl Transaction {
@@ -78,6 +89,9 @@ impl Transaction {
         block_height: i64,
         epoch: i64,
     ) -> Self {
+        // Only failed transactions get a failure reason, the columns stay null otherwise
+        let failure_reason =
+            (!info.success).then(|| FailureReason::from_vm_status(&info.vm_status));
         Self {
             type_,
             payload,
@@ -94,6 +108,18 @@ impl Transaction {
             num_events,
             num_write_set_changes: info.changes.len() as i64,
             epoch,
+            failure_category: failure_reason
+                .as_ref()
+                .map(|reason| reason.category.as_str().to_string()),
+            abort_module_address: failure_reason
+                .as_ref()
+                .and_then(|reason| reason.module_address.clone()),
+            abort_module_name: failure_reason
+                .as_ref()
+                .and_then(|reason| reason.module_name.clone()),
+            abort_code: failure_reason
+                .and_then(|reason| reason.abort_code)
+                .map(u64_to_bigdecimal),
         }
     }
 
@@ -211,7 +237,15 @@ impl Transaction {
                     block_height,
                     epoch,
                 ),
-                None,
+                Some(TransactionDetail::StateCheckpoint(
+                    StateCheckpointTransaction::from_transaction(
+                        &state_checkpoint_txn.info,
+                        state_checkpoint_txn.info.version.0 as i64,
+                        block_height,
+                        epoch,
+                        state_checkpoint_txn.timestamp.0,
+                    ),
+                )),
                 vec![],
                 vec![],
                 vec![],
@@ -271,6 +305,7 @@ impl TransactionQuery {
             .order(transactions::version.asc())
             .limit(number_to_get)
             .load::<Self>(conn)?;
+        record_query("transactions", &["version"], &["version"], txs.len());
 
         let mut user_transactions: Vec<Vec<UserTransactionQuery>> =
             UserTransactionQuery::belonging_to(&txs)
@@ -319,6 +354,7 @@ impl TransactionQuery {
         let transaction = transactions::table
             .filter(transactions::version.eq(version as i64))
             .first::<Self>(conn)?;
+        record_query("transactions", &["version"], &[], 1);
 
         let (user_transaction, block_metadata_transaction, events, write_set_changes) =
             transaction.get_details_for_transaction(conn)?;
@@ -345,6 +381,7 @@ impl TransactionQuery {
         let transaction = transactions::table
             .filter(transactions::hash.eq(&transaction_hash))
             .first::<Self>(conn)?;
+        record_query("transactions", &["hash"], &[], 1);
 
         let (user_transaction, block_metadata_transaction, events, write_set_changes) =
             transaction.get_details_for_transaction(conn)?;
@@ -358,6 +395,19 @@ impl TransactionQuery {
         ))
     }
 
+    /// The transactions of the block, without their details
+    pub fn get_by_block_height(
+        block_height: u64,
+        conn: &mut PgPoolConnection,
+    ) -> diesel::QueryResult<Vec<Self>> {
+        let txs = transactions::table
+            .filter(transactions::block_height.eq(block_height as i64))
+            .order(transactions::version.asc())
+            .load::<Self>(conn)?;
+        record_query("transactions", &["block_height"], &["version"], txs.len());
+        Ok(txs)
+    }
+
     fn get_details_for_transaction(
         &self,
         conn: &mut PgPoolConnection,
@@ -373,10 +423,17 @@ impl TransactionQuery {
         let events = crate::schema::events::table
             .filter(crate::schema::events::transaction_version.eq(&self.version))
             .load::<EventQuery>(conn)?;
+        record_query("events", &["transaction_version"], &[], events.len());
 
         let write_set_changes = crate::schema::write_set_changes::table
             .filter(crate::schema::write_set_changes::transaction_version.eq(&self.version))
             .load::<WriteSetChangeQuery>(conn)?;
+        record_query(
+            "write_set_changes",
+            &["transaction_version"],
+            &[],
+            write_set_changes.len(),
+        );
 
         match self.type_.as_str() {
             "user_transaction" => {
@@ -384,12 +441,14 @@ impl TransactionQuery {
                     .filter(user_transactions::version.eq(&self.version))
                     .first::<UserTransactionQuery>(conn)
                     .optional()?;
+                record_query("user_transactions", &["version"], &[], 1);
             },
             "block_metadata_transaction" => {
                 block_metadata_transaction = block_metadata_transactions::table
                     .filter(block_metadata_transactions::version.eq(&self.version))
                     .first::<BlockMetadataTransactionQuery>(conn)
                     .optional()?;
+                record_query("block_metadata_transactions", &["version"], &[], 1);
             },
             "genesis_transaction" => {},
             "state_checkpoint_transaction" => {},
@@ -408,6 +467,7 @@ impl TransactionQuery {
 pub enum TransactionDetail {
     User(UserTransaction, Vec<Signature>),
     BlockMetadata(BlockMetadataTransaction),
+    StateCheckpoint(StateCheckpointTransaction),
 }
 
 // Prevent conflicts with other things named `Transaction`
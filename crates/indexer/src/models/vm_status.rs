@@ -0,0 +1,207 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::util::standardize_address;
+
+/**
+ * Parses the human readable `vm_status` produced by the API (see `explain_vm_status`) back into
+ * its parts so that failure reasons can be aggregated. The raw string is always kept on the
+ * transaction, so anything we can't recognize is simply categorized as unknown.
+ */
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FailureCategory {
+    MoveAbort,
+    ExecutionFailure,
+    OutOfGas,
+    Miscellaneous,
+    Unknown,
+}
+
+impl FailureCategory {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::MoveAbort => "move_abort",
+            Self::ExecutionFailure => "execution_failure",
+            Self::OutOfGas => "out_of_gas",
+            Self::Miscellaneous => "miscellaneous",
+            Self::Unknown => "unknown",
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FailureReason {
+    pub category: FailureCategory,
+    /// Standardized address of the module the failure happened in, None for scripts.
+    pub module_address: Option<String>,
+    pub module_name: Option<String>,
+    /// Only set for move aborts.
+    pub abort_code: Option<u64>,
+}
+
+impl FailureReason {
+    fn new(category: FailureCategory) -> Self {
+        Self {
+            category,
+            module_address: None,
+            module_name: None,
+            abort_code: None,
+        }
+    }
+
+    pub fn from_vm_status(vm_status: &str) -> Self {
+        Self::parse(vm_status).unwrap_or_else(|| Self::new(FailureCategory::Unknown))
+    }
+
+    fn parse(vm_status: &str) -> Option<Self> {
+        if vm_status == "Out of gas" {
+            return Some(Self::new(FailureCategory::OutOfGas));
+        }
+        // e.g. "Move abort in 0x1::coin: EINSUFFICIENT_BALANCE(0x10006): Not enough coins"
+        // or "Move abort in 0x1::coin: 0x10006" if the module has no error map
+        if let Some(rest) = vm_status.strip_prefix("Move abort in ") {
+            let (location, detail) = rest.split_once(": ")?;
+            let detail = detail.split(": ").next()?;
+            let code = match detail.split_once('(') {
+                Some((_reason_name, code)) => code.strip_suffix(')')?,
+                None => detail,
+            };
+            let mut reason = Self::from_module_location(FailureCategory::MoveAbort, location)?;
+            reason.abort_code = Some(parse_hex_u64(code)?);
+            return Some(reason);
+        }
+        // e.g. "Move abort: code 0x10006"
+        if let Some(code) = vm_status.strip_prefix("Move abort: code ") {
+            let mut reason = Self::new(FailureCategory::MoveAbort);
+            reason.abort_code = Some(parse_hex_u64(code)?);
+            return Some(reason);
+        }
+        // e.g. "Execution failed in 0x1::coin::transfer at code offset 12"
+        // or "Execution failed in script at code offset 3"
+        if let Some(rest) = vm_status.strip_prefix("Execution failed in ") {
+            let (function, _offset) = rest.rsplit_once(" at code offset ")?;
+            if function == "script" {
+                return Some(Self::new(FailureCategory::ExecutionFailure));
+            }
+            let (location, _function_name) = function.rsplit_once("::")?;
+            return Self::from_module_location(FailureCategory::ExecutionFailure, location);
+        }
+        if vm_status.starts_with("Move bytecode deserialization / verification failed")
+            || vm_status.starts_with("Transaction Executed and Committed with Error")
+        {
+            return Some(Self::new(FailureCategory::Miscellaneous));
+        }
+        None
+    }
+
+    /// Parses an `address::module` location.
+    fn from_module_location(category: FailureCategory, location: &str) -> Option<Self> {
+        let (address, module_name) = location.split_once("::")?;
+        if !address.starts_with("0x") || module_name.is_empty() || module_name.contains(':') {
+            return None;
+        }
+        Some(Self {
+            category,
+            module_address: Some(standardize_address(address)),
+            module_name: Some(module_name.to_string()),
+            abort_code: None,
+        })
+    }
+}
+
+fn parse_hex_u64(val: &str) -> Option<u64> {
+    u64::from_str_radix(val.strip_prefix("0x")?, 16).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FRAMEWORK_ADDRESS: &str = "0x0000000000000000000000000000000000000000000000000000000000000001";
+
+    #[test]
+    fn test_move_abort_in_module() {
+        let reason = FailureReason::from_vm_status(
+            "Move abort in 0x1::coin: EINSUFFICIENT_BALANCE(0x10006): Not enough coins to complete transaction",
+        );
+        assert_eq!(reason, FailureReason {
+            category: FailureCategory::MoveAbort,
+            module_address: Some(FRAMEWORK_ADDRESS.to_string()),
+            module_name: Some("coin".to_string()),
+            abort_code: Some(0x10006),
+        });
+
+        // Modules without an error map only report the code.
+        let reason = FailureReason::from_vm_status("Move abort in 0x1::coin: 0x10006");
+        assert_eq!(reason.category, FailureCategory::MoveAbort);
+        assert_eq!(reason.module_name.as_deref(), Some("coin"));
+        assert_eq!(reason.abort_code, Some(0x10006));
+    }
+
+    #[test]
+    fn test_move_abort_in_script() {
+        let reason = FailureReason::from_vm_status("Move abort: code 0x2a");
+        assert_eq!(reason.category, FailureCategory::MoveAbort);
+        assert_eq!(reason.module_address, None);
+        assert_eq!(reason.abort_code, Some(42));
+    }
+
+    #[test]
+    fn test_out_of_gas() {
+        let reason = FailureReason::from_vm_status("Out of gas");
+        assert_eq!(reason, FailureReason::new(FailureCategory::OutOfGas));
+    }
+
+    #[test]
+    fn test_execution_failure() {
+        let reason = FailureReason::from_vm_status(
+            "Execution failed in 0x1::aptos_account::transfer at code offset 12",
+        );
+        assert_eq!(reason, FailureReason {
+            category: FailureCategory::ExecutionFailure,
+            module_address: Some(FRAMEWORK_ADDRESS.to_string()),
+            module_name: Some("aptos_account".to_string()),
+            abort_code: None,
+        });
+
+        let reason = FailureReason::from_vm_status("Execution failed in script at code offset 3");
+        assert_eq!(
+            reason,
+            FailureReason::new(FailureCategory::ExecutionFailure)
+        );
+    }
+
+    #[test]
+    fn test_miscellaneous() {
+        for vm_status in [
+            "Move bytecode deserialization / verification failed, including entry function not found or invalid arguments",
+            "Transaction Executed and Committed with Error LINKER_ERROR",
+        ] {
+            assert_eq!(
+                FailureReason::from_vm_status(vm_status).category,
+                FailureCategory::Miscellaneous
+            );
+        }
+    }
+
+    #[test]
+    fn test_unknown_format() {
+        for vm_status in [
+            "",
+            "Something new went wrong",
+            "Move abort in 0x1::coin",
+            "Move abort in coin: 0x10006",
+            "Move abort in 0x1::coin: EINSUFFICIENT_BALANCE(10006",
+            "Move abort: code not_a_number",
+            "Execution failed in 0x1 at code offset 3",
+        ] {
+            assert_eq!(
+                FailureReason::from_vm_status(vm_status),
+                FailureReason::new(FailureCategory::Unknown),
+                "{}",
+                vm_status
+            );
+        }
+    }
+}
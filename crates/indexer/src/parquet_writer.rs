@@ -0,0 +1,315 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+//! Parquet files of the parsed models, for data-lake consumers
+use crate::models::block_metadata_transactions::BlockMetadataTransactionModel;
+use anyhow::{bail, Context, Result};
+use arrow::{
+    array::{ArrayRef, Int64Array, StringArray, TimestampMicrosecondArray},
+    datatypes::{DataType, Field, Schema, SchemaRef, TimeUnit},
+    record_batch::RecordBatch,
+};
+use parquet::{arrow::ArrowWriter, file::properties::WriterProperties};
+use std::{
+    fmt::Debug,
+    fs::{self, File},
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+/// Where the Parquet files are uploaded to once written
+pub trait ObjectStore: Debug + Send + Sync {
+    /// Uploads the local file under the name, replacing any object with the same name
+    fn put(&self, name: &str, file: &Path) -> Result<()>;
+}
+
+/// Copies the files to a local directory, e.g. a mounted bucket
+#[derive(Debug)]
+pub struct LocalDirObjectStore {
+    dir: PathBuf,
+}
+
+impl LocalDirObjectStore {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+}
+
+impl ObjectStore for LocalDirObjectStore {
+    fn put(&self, name: &str, file: &Path) -> Result<()> {
+        let target = self.dir.join(name);
+        if let Some(parent) = target.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::copy(file, &target).with_context(|| {
+            format!(
+                "Failed to upload {} to {}",
+                file.display(),
+                target.display()
+            )
+        })?;
+        Ok(())
+    }
+}
+
+/// A model written to Parquet files of its own
+pub trait ParquetModel: Sized {
+    /// Name of the table, also the directory of its files
+    const TABLE: &'static str;
+
+    fn schema() -> SchemaRef;
+
+    fn to_record_batch(rows: &[Self]) -> Result<RecordBatch>;
+}
+
+impl ParquetModel for BlockMetadataTransactionModel {
+    const TABLE: &'static str = "block_metadata_transactions";
+
+    fn schema() -> SchemaRef {
+        Arc::new(Schema::new(vec![
+            Field::new("version", DataType::Int64, false),
+            Field::new("block_height", DataType::Int64, false),
+            Field::new("id", DataType::Utf8, false),
+            Field::new("round", DataType::Int64, false),
+            Field::new("epoch", DataType::Int64, false),
+            // Json columns are written as their text
+            Field::new("previous_block_votes_bitvec", DataType::Utf8, false),
+            Field::new("proposer", DataType::Utf8, false),
+            Field::new("failed_proposer_indices", DataType::Utf8, false),
+            Field::new(
+                "timestamp",
+                DataType::Timestamp(TimeUnit::Microsecond, None),
+                false,
+            ),
+        ]))
+    }
+
+    fn to_record_batch(rows: &[Self]) -> Result<RecordBatch> {
+        let int64 = |f: fn(&Self) -> i64| -> ArrayRef {
+            Arc::new(Int64Array::from(rows.iter().map(f).collect::<Vec<_>>()))
+        };
+        let string = |f: fn(&Self) -> String| -> ArrayRef {
+            Arc::new(StringArray::from(rows.iter().map(f).collect::<Vec<_>>()))
+        };
+        Ok(RecordBatch::try_new(
+            Self::schema(),
+            vec![
+                int64(|row| row.version),
+                int64(|row| row.block_height),
+                string(|row| row.id.clone()),
+                int64(|row| row.round),
+                int64(|row| row.epoch),
+                string(|row| row.previous_block_votes_bitvec.to_string()),
+                string(|row| row.proposer.clone()),
+                string(|row| row.failed_proposer_indices.to_string()),
+                Arc::new(TimestampMicrosecondArray::from(
+                    rows.iter()
+                        .map(|row| row.timestamp.timestamp_micros())
+                        .collect::<Vec<_>>(),
+                )),
+            ],
+        )?)
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct ParquetWriterConfig {
+    /// Local directory the files are written to, in a directory per table
+    pub dir: PathBuf,
+    /// Rows buffered before they are flushed, also the size of the row groups
+    pub max_rows: usize,
+    /// How long rows are buffered at most before they are flushed. Checked as batches are added.
+    pub max_buffer_age: Duration,
+}
+
+/// A file written by a flush, along with the versions it covers
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FlushedFile {
+    pub path: PathBuf,
+    pub start_version: u64,
+    pub end_version: u64,
+}
+
+/// Buffers the rows of a table across batches, and writes them to a Parquet file per flush named
+/// by the range of versions it covers, e.g. `block_metadata_transactions/00000000000000000000_00000000000000000999.parquet`.
+/// The watermark only advances once the file is written and uploaded, so that whatever isn't
+/// flushed gets processed again after a restart.
+#[derive(Debug)]
+pub struct ParquetTableWriter<M> {
+    config: ParquetWriterConfig,
+    object_store: Option<Arc<dyn ObjectStore>>,
+    rows: Vec<M>,
+    /// Versions of the batches buffered so far
+    buffered_versions: Option<(u64, u64)>,
+    buffered_since: Option<Instant>,
+    watermark: Option<u64>,
+}
+
+impl<M: ParquetModel> ParquetTableWriter<M> {
+    pub fn new(config: ParquetWriterConfig, object_store: Option<Arc<dyn ObjectStore>>) -> Self {
+        Self {
+            config,
+            object_store,
+            rows: vec![],
+            buffered_versions: None,
+            buffered_since: None,
+            watermark: None,
+        }
+    }
+
+    /// Last version flushed
+    pub fn watermark(&self) -> Option<u64> {
+        self.watermark
+    }
+
+    /// Adds the rows of the batch of versions `start_version` to `end_version`, flushing them if
+    /// a threshold is reached. The batches have to be added in order.
+    pub fn add(
+        &mut self,
+        rows: Vec<M>,
+        start_version: u64,
+        end_version: u64,
+    ) -> Result<Option<FlushedFile>> {
+        let last_version = self
+            .buffered_versions
+            .map(|(_, end)| end)
+            .or(self.watermark);
+        if let Some(last_version) = last_version {
+            if start_version != last_version + 1 {
+                bail!(
+                    "Batch {} to {} of {} does not follow version {}, batches have to be processed in order",
+                    start_version,
+                    end_version,
+                    M::TABLE,
+                    last_version
+                );
+            }
+        }
+        let start = self
+            .buffered_versions
+            .map_or(start_version, |(start, _)| start);
+        self.buffered_versions = Some((start, end_version));
+        self.buffered_since.get_or_insert_with(Instant::now);
+        self.rows.extend(rows);
+
+        let expired = self
+            .buffered_since
+            .map_or(false, |since| since.elapsed() >= self.config.max_buffer_age);
+        if self.rows.len() >= self.config.max_rows || expired {
+            return self.flush();
+        }
+        Ok(None)
+    }
+
+    /// Writes the buffered rows to a file and uploads it. Nothing is cleared on failure, so that
+    /// the flush can be retried.
+    pub fn flush(&mut self) -> Result<Option<FlushedFile>> {
+        let (start_version, end_version) = match self.buffered_versions {
+            Some(versions) => versions,
+            None => return Ok(None),
+        };
+        let name = format!(
+            "{}/{:020}_{:020}.parquet",
+            M::TABLE,
+            start_version,
+            end_version
+        );
+        let path = self.config.dir.join(&name);
+        self.write(&path)?;
+        if let Some(object_store) = &self.object_store {
+            object_store.put(&name, &path)?;
+        }
+
+        self.rows.clear();
+        self.buffered_versions = None;
+        self.buffered_since = None;
+        self.watermark = Some(end_version);
+        Ok(Some(FlushedFile {
+            path,
+            start_version,
+            end_version,
+        }))
+    }
+
+    /// Written next to the file first, so that a failed write never leaves a partial file behind
+    fn write(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let tmp_path = path.with_extension("parquet.tmp");
+        let properties = WriterProperties::builder()
+            .set_max_row_group_size(self.config.max_rows.max(1))
+            .build();
+        let mut writer =
+            ArrowWriter::try_new(File::create(&tmp_path)?, M::schema(), Some(properties))?;
+        writer.write(&M::to_record_batch(&self.rows)?)?;
+        writer.close()?;
+        fs::rename(&tmp_path, path)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aptos_temppath::TempPath;
+    use std::sync::atomic::{AtomicBool, Ordering};
+
+    /// Fails uploads until told otherwise
+    #[derive(Debug, Default)]
+    struct FlakyStore {
+        available: AtomicBool,
+    }
+
+    impl ObjectStore for FlakyStore {
+        fn put(&self, name: &str, _file: &Path) -> Result<()> {
+            if !self.available.load(Ordering::SeqCst) {
+                bail!("Failed to upload {}", name);
+            }
+            Ok(())
+        }
+    }
+
+    fn row(version: i64) -> BlockMetadataTransactionModel {
+        BlockMetadataTransactionModel {
+            version,
+            block_height: version,
+            id: format!("0x{}", version),
+            round: version,
+            epoch: 1,
+            previous_block_votes_bitvec: serde_json::json!([]),
+            proposer: "0x1".to_string(),
+            failed_proposer_indices: serde_json::json!([]),
+            timestamp: chrono::NaiveDateTime::from_timestamp(version, 0),
+        }
+    }
+
+    #[test]
+    fn test_watermark_after_successful_flush() {
+        let dir = TempPath::new();
+        dir.create_as_dir().unwrap();
+        let store = Arc::new(FlakyStore::default());
+        let mut writer = ParquetTableWriter::new(
+            ParquetWriterConfig {
+                dir: dir.path().to_path_buf(),
+                max_rows: 1,
+                max_buffer_age: Duration::from_secs(3600),
+            },
+            Some(store.clone() as Arc<dyn ObjectStore>),
+        );
+
+        // The upload fails, the rows stay buffered and the watermark doesn't move.
+        assert!(writer.add(vec![row(5)], 5, 9).is_err());
+        assert_eq!(writer.watermark(), None);
+
+        store.available.store(true, Ordering::SeqCst);
+        let flushed = writer.add(vec![row(10)], 10, 19).unwrap().unwrap();
+        assert_eq!((flushed.start_version, flushed.end_version), (5, 19));
+        assert_eq!(writer.watermark(), Some(19));
+        assert!(flushed.path.ends_with(
+            "block_metadata_transactions/00000000000000000005_00000000000000000019.parquet"
+        ));
+        assert_eq!(writer.flush().unwrap(), None);
+    }
+}
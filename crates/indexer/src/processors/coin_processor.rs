@@ -6,14 +6,17 @@ use crate::{
         clean_data_for_db, execute_with_better_error, get_chunks, PgDbPool, PgPoolConnection,
     },
     indexer::{
-        errors::TransactionProcessingError, processing_result::ProcessingResult,
-        transaction_processor::TransactionProcessor,
+        errors::TransactionProcessingError, latest_value_aggregator::LatestValueAggregator,
+        processing_result::ProcessingResult, transaction_processor::TransactionProcessor,
     },
-    models::coin_models::{
-        coin_activities::{CoinActivity, CurrentCoinBalancePK},
-        coin_balances::{CoinBalance, CurrentCoinBalance},
-        coin_infos::{CoinInfo, CoinInfoQuery},
-        coin_supply::CoinSupply,
+    models::{
+        coin_models::{
+            coin_activities::{CoinActivity, CurrentCoinBalancePK},
+            coin_balances::{CoinBalance, CurrentCoinBalance},
+            coin_infos::{CoinInfo, CoinInfoQuery},
+            coin_supply::CoinSupply,
+        },
+        processor_status::ProcessorStatusV2,
     },
     schema,
 };
@@ -90,7 +93,8 @@ fn insert_to_db(
                 &coin_balances,
                 &current_coin_balances,
                 &coin_supply,
-            )
+            )?;
+            ProcessorStatusV2::advance(pg_conn, name, start_version, end_version)
         }) {
         Ok(_) => Ok(()),
         Err(_) => conn
@@ -109,7 +113,8 @@ fn insert_to_db(
                     &coin_balances,
                     &current_coin_balances,
                     &coin_supply,
-                )
+                )?;
+                ProcessorStatusV2::advance(pg_conn, name, start_version, end_version)
             }),
     }
 }
@@ -134,6 +139,15 @@ fn insert_coin_activities(
                 ))
                 .do_update()
                 .set((
+                    owner_address.eq(excluded(owner_address)),
+                    coin_type.eq(excluded(coin_type)),
+                    amount.eq(excluded(amount)),
+                    activity_type.eq(excluded(activity_type)),
+                    is_gas_fee.eq(excluded(is_gas_fee)),
+                    is_transaction_success.eq(excluded(is_transaction_success)),
+                    entry_function_id_str.eq(excluded(entry_function_id_str)),
+                    block_height.eq(excluded(block_height)),
+                    transaction_timestamp.eq(excluded(transaction_timestamp)),
                     inserted_at.eq(excluded(inserted_at)),
                     event_index.eq(excluded(event_index)),
                 )),
@@ -158,6 +172,7 @@ fn insert_coin_infos(
                 .on_conflict(coin_type_hash)
                 .do_update()
                 .set((
+                    coin_type.eq(excluded(coin_type)),
                     transaction_version_created.eq(excluded(transaction_version_created)),
                     creator_address.eq(excluded(creator_address)),
                     name.eq(excluded(name)),
@@ -187,7 +202,13 @@ fn insert_coin_balances(
             diesel::insert_into(schema::coin_balances::table)
                 .values(&item_to_insert[start_ind..end_ind])
                 .on_conflict((transaction_version, owner_address, coin_type_hash))
-                .do_nothing(),
+                .do_update()
+                .set((
+                    coin_type.eq(excluded(coin_type)),
+                    amount.eq(excluded(amount)),
+                    transaction_timestamp.eq(excluded(transaction_timestamp)),
+                    inserted_at.eq(excluded(inserted_at)),
+                )),
             None,
         )?;
     }
@@ -209,6 +230,7 @@ fn insert_current_coin_balances(
                 .on_conflict((owner_address, coin_type_hash))
                 .do_update()
                 .set((
+                    coin_type.eq(excluded(coin_type)),
                     amount.eq(excluded(amount)),
                     last_transaction_version.eq(excluded(last_transaction_version)),
                     last_transaction_timestamp.eq(excluded(last_transaction_timestamp)),
@@ -233,7 +255,14 @@ fn insert_coin_supply(
             diesel::insert_into(schema::coin_supply::table)
                 .values(&item_to_insert[start_ind..end_ind])
                 .on_conflict((transaction_version, coin_type_hash))
-                .do_nothing(),
+                .do_update()
+                .set((
+                    coin_type.eq(excluded(coin_type)),
+                    supply.eq(excluded(supply)),
+                    transaction_timestamp.eq(excluded(transaction_timestamp)),
+                    transaction_epoch.eq(excluded(transaction_epoch)),
+                    inserted_at.eq(excluded(inserted_at)),
+                )),
             None,
         )?;
     }
@@ -261,8 +290,10 @@ impl TransactionProcessor for CoinTransactionProcessor {
         let mut all_coin_activities = vec![];
         let mut all_coin_balances = vec![];
         let mut all_coin_infos: HashMap<String, CoinInfo> = HashMap::new();
-        let mut all_current_coin_balances: HashMap<CurrentCoinBalancePK, CurrentCoinBalance> =
-            HashMap::new();
+        let mut all_current_coin_balances: LatestValueAggregator<
+            CurrentCoinBalancePK,
+            CurrentCoinBalance,
+        > = LatestValueAggregator::new();
         let mut all_coin_supply = vec![];
 
         for txn in &transactions {
@@ -283,15 +314,10 @@ impl TransactionProcessor for CoinTransactionProcessor {
             all_current_coin_balances.extend(current_coin_balances);
         }
         let mut all_coin_infos = all_coin_infos.into_values().collect::<Vec<CoinInfo>>();
-        let mut all_current_coin_balances = all_current_coin_balances
-            .into_values()
-            .collect::<Vec<CurrentCoinBalance>>();
+        let all_current_coin_balances = all_current_coin_balances.into_sorted_values();
 
         // Sort by PK
         all_coin_infos.sort_by(|a, b| a.coin_type.cmp(&b.coin_type));
-        all_current_coin_balances.sort_by(|a, b| {
-            (&a.owner_address, &a.coin_type).cmp(&(&b.owner_address, &b.coin_type))
-        });
 
         let tx_result = insert_to_db(
             &mut conn,
@@ -5,17 +5,22 @@ use crate::{
     database::{
         clean_data_for_db, execute_with_better_error, get_chunks, PgDbPool, PgPoolConnection,
     },
+    db_writer::{BatchWriter, TableDependencies},
     indexer::{
-        errors::TransactionProcessingError, processing_result::ProcessingResult,
-        transaction_processor::TransactionProcessor,
+        errors::TransactionProcessingError, latest_value_aggregator::LatestValueAggregator,
+        processing_result::ProcessingResult, transaction_processor::TransactionProcessor,
     },
     models::{
         block_metadata_transactions::BlockMetadataTransactionModel,
+        block_validator_participation::{BlockValidatorParticipationModel, ValidatorSetTracker},
+        blocks::{BlockEnd, BlockModel, BlockTracker},
         events::EventModel,
         move_modules::MoveModule,
         move_resources::MoveResource,
         move_tables::{CurrentTableItem, TableItem, TableMetadata},
+        processor_status::ProcessorStatusV2,
         signatures::Signature,
+        state_checkpoint_transactions::StateCheckpointTransactionModel,
         transactions::{TransactionDetail, TransactionModel},
         user_transactions::UserTransactionModel,
         write_set_changes::{WriteSetChangeDetail, WriteSetChangeModel},
@@ -24,18 +29,53 @@ use crate::{
 };
 use aptos_api_types::Transaction;
 use async_trait::async_trait;
-use diesel::{pg::upsert::excluded, result::Error, ExpressionMethods, PgConnection};
+use diesel::{
+    dsl::sql,
+    pg::upsert::excluded,
+    result::Error,
+    sql_types::{BigInt, Nullable},
+    ExpressionMethods, PgConnection, QueryDsl,
+};
 use field_count::FieldCount;
-use std::{collections::HashMap, fmt::Debug};
+use once_cell::sync::Lazy;
+use std::{collections::HashMap, fmt::Debug, sync::Mutex};
 
 pub const NAME: &str = "default_processor";
+
+/// Everything is keyed by transaction version, and the details of write set changes by write
+/// set change as well. The current table items and table metadata only reflect the latest state.
+pub static TABLE_DEPENDENCIES: Lazy<TableDependencies> = Lazy::new(|| {
+    TableDependencies::new()
+        .register("transactions", &[])
+        .register("user_transactions", &["transactions"])
+        .register("signatures", &["user_transactions"])
+        .register("block_metadata_transactions", &["transactions"])
+        .register("state_checkpoint_transactions", &["transactions"])
+        .register("blocks", &[])
+        .register("block_validator_participation", &[])
+        .register("events", &["transactions"])
+        .register("write_set_changes", &["transactions"])
+        .register("move_modules", &["write_set_changes"])
+        .register("move_resources", &["write_set_changes"])
+        .register("table_items", &["write_set_changes"])
+        .register("current_table_items", &[])
+        .register("table_metadatas", &[])
+});
 pub struct DefaultTransactionProcessor {
     connection_pool: PgDbPool,
+    // Blocks spanning the batches processed so far
+    block_tracker: Mutex<BlockTracker>,
+    // Validator sets of the latest epochs seen so far
+    validator_sets: Mutex<ValidatorSetTracker>,
 }
 
 impl DefaultTransactionProcessor {
     pub fn new(connection_pool: PgDbPool) -> Self {
-        Self { connection_pool }
+        Self {
+            connection_pool,
+            block_tracker: Mutex::new(BlockTracker::default()),
+            validator_sets: Mutex::new(ValidatorSetTracker::default()),
+        }
     }
 }
 
@@ -57,7 +97,10 @@ fn insert_to_db_impl(
         &[UserTransactionModel],
         &[Signature],
         &[BlockMetadataTransactionModel],
+        &[StateCheckpointTransactionModel],
     ),
+    blocks: (&[BlockModel], &[BlockEnd]),
+    validator_participation: &[BlockValidatorParticipationModel],
     events: &[EventModel],
     wscs: &[WriteSetChangeModel],
     wsc_details: (
@@ -68,21 +111,49 @@ fn insert_to_db_impl(
         &[TableMetadata],
     ),
 ) -> Result<(), diesel::result::Error> {
-    let (user_transactions, signatures, block_metadata_transactions) = txn_details;
+    let (user_transactions, signatures, block_metadata_transactions, state_checkpoint_transactions) =
+        txn_details;
+    let (blocks, block_ends) = blocks;
     let (move_modules, move_resources, table_items, current_table_items, table_metadata) =
         wsc_details;
-    insert_transactions(conn, txns)?;
-    insert_user_transactions(conn, user_transactions)?;
-    insert_signatures(conn, signatures)?;
-    insert_block_metadata_transactions(conn, block_metadata_transactions)?;
-    insert_events(conn, events)?;
-    insert_write_set_changes(conn, wscs)?;
-    insert_move_modules(conn, move_modules)?;
-    insert_move_resources(conn, move_resources)?;
-    insert_table_items(conn, table_items)?;
-    insert_current_table_items(conn, current_table_items)?;
-    insert_table_metadata(conn, table_metadata)?;
-    Ok(())
+    let mut writer = BatchWriter::new(&TABLE_DEPENDENCIES);
+    writer
+        .add("transactions", |conn| insert_transactions(conn, txns))
+        .add("user_transactions", |conn| {
+            insert_user_transactions(conn, user_transactions)
+        })
+        .add("signatures", |conn| insert_signatures(conn, signatures))
+        .add("block_metadata_transactions", |conn| {
+            insert_block_metadata_transactions(conn, block_metadata_transactions)
+        })
+        .add("state_checkpoint_transactions", |conn| {
+            insert_state_checkpoint_transactions(conn, state_checkpoint_transactions)
+        })
+        .add("blocks", |conn| {
+            insert_blocks(conn, blocks)?;
+            update_block_ends(conn, block_ends)
+        })
+        .add("block_validator_participation", |conn| {
+            insert_block_validator_participation(conn, validator_participation)
+        })
+        .add("events", |conn| insert_events(conn, events))
+        .add("write_set_changes", |conn| {
+            insert_write_set_changes(conn, wscs)
+        })
+        .add("move_modules", |conn| {
+            insert_move_modules(conn, move_modules)
+        })
+        .add("move_resources", |conn| {
+            insert_move_resources(conn, move_resources)
+        })
+        .add("table_items", |conn| insert_table_items(conn, table_items))
+        .add("current_table_items", |conn| {
+            insert_current_table_items(conn, current_table_items)
+        })
+        .add("table_metadatas", |conn| {
+            insert_table_metadata(conn, table_metadata)
+        });
+    writer.execute(conn)
 }
 
 fn insert_to_db(
@@ -95,7 +166,10 @@ fn insert_to_db(
         Vec<UserTransactionModel>,
         Vec<Signature>,
         Vec<BlockMetadataTransactionModel>,
+        Vec<StateCheckpointTransactionModel>,
     ),
+    blocks: (Vec<BlockModel>, Vec<BlockEnd>),
+    validator_participation: Vec<BlockValidatorParticipationModel>,
     events: Vec<EventModel>,
     wscs: Vec<WriteSetChangeModel>,
     wsc_details: (
@@ -112,7 +186,9 @@ fn insert_to_db(
         end_version = end_version,
         "Inserting to db",
     );
-    let (user_transactions, signatures, block_metadata_transactions) = txn_details;
+    let (user_transactions, signatures, block_metadata_transactions, state_checkpoint_transactions) =
+        txn_details;
+    let (blocks, block_ends) = blocks;
     let (move_modules, move_resources, table_items, current_table_items, table_metadata) =
         wsc_details;
     match conn
@@ -126,7 +202,10 @@ fn insert_to_db(
                     &user_transactions,
                     &signatures,
                     &block_metadata_transactions,
+                    &state_checkpoint_transactions,
                 ),
+                (&blocks, &block_ends),
+                &validator_participation,
                 &events,
                 &wscs,
                 (
@@ -136,7 +215,8 @@ fn insert_to_db(
                     &current_table_items,
                     &table_metadata,
                 ),
-            )
+            )?;
+            ProcessorStatusV2::advance(pg_conn, name, start_version, end_version)
         }) {
         Ok(_) => Ok(()),
         Err(_) => {
@@ -144,6 +224,10 @@ fn insert_to_db(
             let user_transactions = clean_data_for_db(user_transactions, true);
             let signatures = clean_data_for_db(signatures, true);
             let block_metadata_transactions = clean_data_for_db(block_metadata_transactions, true);
+            let state_checkpoint_transactions =
+                clean_data_for_db(state_checkpoint_transactions, true);
+            let blocks = clean_data_for_db(blocks, true);
+            let validator_participation = clean_data_for_db(validator_participation, true);
             let events = clean_data_for_db(events, true);
             let wscs = clean_data_for_db(wscs, true);
             let move_modules = clean_data_for_db(move_modules, true);
@@ -162,7 +246,10 @@ fn insert_to_db(
                             &user_transactions,
                             &signatures,
                             &block_metadata_transactions,
+                            &state_checkpoint_transactions,
                         ),
+                        (&blocks, &block_ends),
+                        &validator_participation,
                         &events,
                         &wscs,
                         (
@@ -172,7 +259,8 @@ fn insert_to_db(
                             &current_table_items,
                             &table_metadata,
                         ),
-                    )
+                    )?;
+                    ProcessorStatusV2::advance(pg_conn, name, start_version, end_version)
                 })
         },
     }
@@ -190,7 +278,28 @@ fn insert_transactions(
             diesel::insert_into(schema::transactions::table)
                 .values(&items_to_insert[start_ind..end_ind])
                 .on_conflict(version)
-                .do_nothing(),
+                .do_update()
+                .set((
+                    block_height.eq(excluded(block_height)),
+                    hash.eq(excluded(hash)),
+                    type_.eq(excluded(type_)),
+                    payload.eq(excluded(payload)),
+                    state_change_hash.eq(excluded(state_change_hash)),
+                    event_root_hash.eq(excluded(event_root_hash)),
+                    state_checkpoint_hash.eq(excluded(state_checkpoint_hash)),
+                    gas_used.eq(excluded(gas_used)),
+                    success.eq(excluded(success)),
+                    vm_status.eq(excluded(vm_status)),
+                    accumulator_root_hash.eq(excluded(accumulator_root_hash)),
+                    num_events.eq(excluded(num_events)),
+                    num_write_set_changes.eq(excluded(num_write_set_changes)),
+                    inserted_at.eq(excluded(inserted_at)),
+                    epoch.eq(excluded(epoch)),
+                    failure_category.eq(excluded(failure_category)),
+                    abort_module_address.eq(excluded(abort_module_address)),
+                    abort_module_name.eq(excluded(abort_module_name)),
+                    abort_code.eq(excluded(abort_code)),
+                )),
             None,
         )?;
     }
@@ -209,7 +318,20 @@ fn insert_user_transactions(
             diesel::insert_into(schema::user_transactions::table)
                 .values(&items_to_insert[start_ind..end_ind])
                 .on_conflict(version)
-                .do_nothing(),
+                .do_update()
+                .set((
+                    block_height.eq(excluded(block_height)),
+                    parent_signature_type.eq(excluded(parent_signature_type)),
+                    sender.eq(excluded(sender)),
+                    sequence_number.eq(excluded(sequence_number)),
+                    max_gas_amount.eq(excluded(max_gas_amount)),
+                    expiration_timestamp_secs.eq(excluded(expiration_timestamp_secs)),
+                    gas_unit_price.eq(excluded(gas_unit_price)),
+                    timestamp.eq(excluded(timestamp)),
+                    entry_function_id_str.eq(excluded(entry_function_id_str)),
+                    inserted_at.eq(excluded(inserted_at)),
+                    epoch.eq(excluded(epoch)),
+                )),
             None,
         )?;
     }
@@ -233,7 +355,17 @@ fn insert_signatures(
                     multi_sig_index,
                     is_sender_primary,
                 ))
-                .do_nothing(),
+                .do_update()
+                .set((
+                    transaction_block_height.eq(excluded(transaction_block_height)),
+                    signer.eq(excluded(signer)),
+                    type_.eq(excluded(type_)),
+                    public_key.eq(excluded(public_key)),
+                    signature.eq(excluded(signature)),
+                    threshold.eq(excluded(threshold)),
+                    public_key_indices.eq(excluded(public_key_indices)),
+                    inserted_at.eq(excluded(inserted_at)),
+                )),
             None,
         )?;
     }
@@ -255,7 +387,130 @@ fn insert_block_metadata_transactions(
             diesel::insert_into(schema::block_metadata_transactions::table)
                 .values(&items_to_insert[start_ind..end_ind])
                 .on_conflict(version)
-                .do_nothing(),
+                .do_update()
+                .set((
+                    block_height.eq(excluded(block_height)),
+                    id.eq(excluded(id)),
+                    round.eq(excluded(round)),
+                    epoch.eq(excluded(epoch)),
+                    previous_block_votes_bitvec.eq(excluded(previous_block_votes_bitvec)),
+                    proposer.eq(excluded(proposer)),
+                    failed_proposer_indices.eq(excluded(failed_proposer_indices)),
+                    timestamp.eq(excluded(timestamp)),
+                    inserted_at.eq(excluded(inserted_at)),
+                )),
+            None,
+        )?;
+    }
+    Ok(())
+}
+
+fn insert_state_checkpoint_transactions(
+    conn: &mut PgConnection,
+    items_to_insert: &[StateCheckpointTransactionModel],
+) -> Result<(), diesel::result::Error> {
+    use schema::state_checkpoint_transactions::dsl::*;
+    let chunks = get_chunks(
+        items_to_insert.len(),
+        StateCheckpointTransactionModel::field_count(),
+    );
+    for (start_ind, end_ind) in chunks {
+        execute_with_better_error(
+            conn,
+            diesel::insert_into(schema::state_checkpoint_transactions::table)
+                .values(&items_to_insert[start_ind..end_ind])
+                .on_conflict(version)
+                .do_update()
+                .set((
+                    block_height.eq(excluded(block_height)),
+                    state_checkpoint_hash.eq(excluded(state_checkpoint_hash)),
+                    epoch.eq(excluded(epoch)),
+                    timestamp.eq(excluded(timestamp)),
+                    inserted_at.eq(excluded(inserted_at)),
+                )),
+            None,
+        )?;
+    }
+    Ok(())
+}
+
+/// Blocks already written keep their last version if it isn't known yet, the rows written before
+/// their end was seen get it
+fn insert_blocks(
+    conn: &mut PgConnection,
+    items_to_insert: &[BlockModel],
+) -> Result<(), diesel::result::Error> {
+    use schema::blocks::dsl::*;
+    let chunks = get_chunks(items_to_insert.len(), BlockModel::field_count());
+    for (start_ind, end_ind) in chunks {
+        execute_with_better_error(
+            conn,
+            diesel::insert_into(schema::blocks::table)
+                .values(&items_to_insert[start_ind..end_ind])
+                .on_conflict(block_height)
+                .do_update()
+                .set((
+                    block_hash.eq(excluded(block_hash)),
+                    first_version.eq(excluded(first_version)),
+                    last_version.eq(sql::<Nullable<BigInt>>(
+                        "COALESCE(EXCLUDED.last_version, blocks.last_version)",
+                    )),
+                    timestamp.eq(excluded(timestamp)),
+                    epoch.eq(excluded(epoch)),
+                    proposer.eq(excluded(proposer)),
+                    inserted_at.eq(excluded(inserted_at)),
+                )),
+            None,
+        )?;
+    }
+    Ok(())
+}
+
+/// Sets the last version of the blocks written by a previous run before their end was seen
+fn update_block_ends(
+    conn: &mut PgConnection,
+    block_ends: &[BlockEnd],
+) -> Result<(), diesel::result::Error> {
+    use schema::blocks::dsl::*;
+    for end in block_ends {
+        execute_with_better_error(
+            conn,
+            diesel::update(
+                blocks
+                    .filter(block_height.eq(end.block_height))
+                    .filter(last_version.is_null()),
+            )
+            .set(last_version.eq(end.last_version)),
+            None,
+        )?;
+    }
+    Ok(())
+}
+
+fn insert_block_validator_participation(
+    conn: &mut PgConnection,
+    items_to_insert: &[BlockValidatorParticipationModel],
+) -> Result<(), diesel::result::Error> {
+    use schema::block_validator_participation::dsl::*;
+    let chunks = get_chunks(
+        items_to_insert.len(),
+        BlockValidatorParticipationModel::field_count(),
+    );
+    for (start_ind, end_ind) in chunks {
+        execute_with_better_error(
+            conn,
+            diesel::insert_into(schema::block_validator_participation::table)
+                .values(&items_to_insert[start_ind..end_ind])
+                .on_conflict((block_height, validator_index))
+                .do_update()
+                .set((
+                    version.eq(excluded(version)),
+                    epoch.eq(excluded(epoch)),
+                    validator_address.eq(excluded(validator_address)),
+                    voted.eq(excluded(voted)),
+                    failed_to_propose.eq(excluded(failed_to_propose)),
+                    inserted_at.eq(excluded(inserted_at)),
+                )),
             None,
         )?;
     }
@@ -276,6 +531,10 @@ fn insert_events(
                 .on_conflict((account_address, creation_number, sequence_number))
                 .do_update()
                 .set((
+                    transaction_version.eq(excluded(transaction_version)),
+                    transaction_block_height.eq(excluded(transaction_block_height)),
+                    type_.eq(excluded(type_)),
+                    data.eq(excluded(data)),
                     inserted_at.eq(excluded(inserted_at)),
                     event_index.eq(excluded(event_index)),
                 )),
@@ -297,7 +556,14 @@ fn insert_write_set_changes(
             diesel::insert_into(schema::write_set_changes::table)
                 .values(&items_to_insert[start_ind..end_ind])
                 .on_conflict((transaction_version, index))
-                .do_nothing(),
+                .do_update()
+                .set((
+                    hash.eq(excluded(hash)),
+                    transaction_block_height.eq(excluded(transaction_block_height)),
+                    type_.eq(excluded(type_)),
+                    address.eq(excluded(address)),
+                    inserted_at.eq(excluded(inserted_at)),
+                )),
             None,
         )?;
     }
@@ -316,7 +582,18 @@ fn insert_move_modules(
             diesel::insert_into(schema::move_modules::table)
                 .values(&items_to_insert[start_ind..end_ind])
                 .on_conflict((transaction_version, write_set_change_index))
-                .do_nothing(),
+                .do_update()
+                .set((
+                    transaction_block_height.eq(excluded(transaction_block_height)),
+                    name.eq(excluded(name)),
+                    address.eq(excluded(address)),
+                    bytecode.eq(excluded(bytecode)),
+                    friends.eq(excluded(friends)),
+                    exposed_functions.eq(excluded(exposed_functions)),
+                    structs.eq(excluded(structs)),
+                    is_deleted.eq(excluded(is_deleted)),
+                    inserted_at.eq(excluded(inserted_at)),
+                )),
             None,
         )?;
     }
@@ -335,7 +612,18 @@ fn insert_move_resources(
             diesel::insert_into(schema::move_resources::table)
                 .values(&items_to_insert[start_ind..end_ind])
                 .on_conflict((transaction_version, write_set_change_index))
-                .do_nothing(),
+                .do_update()
+                .set((
+                    transaction_block_height.eq(excluded(transaction_block_height)),
+                    name.eq(excluded(name)),
+                    address.eq(excluded(address)),
+                    type_.eq(excluded(type_)),
+                    module.eq(excluded(module)),
+                    generic_type_params.eq(excluded(generic_type_params)),
+                    data.eq(excluded(data)),
+                    is_deleted.eq(excluded(is_deleted)),
+                    inserted_at.eq(excluded(inserted_at)),
+                )),
             None,
         )?;
     }
@@ -354,7 +642,16 @@ fn insert_table_items(
             diesel::insert_into(schema::table_items::table)
                 .values(&items_to_insert[start_ind..end_ind])
                 .on_conflict((transaction_version, write_set_change_index))
-                .do_nothing(),
+                .do_update()
+                .set((
+                    key.eq(excluded(key)),
+                    transaction_block_height.eq(excluded(transaction_block_height)),
+                    table_handle.eq(excluded(table_handle)),
+                    decoded_key.eq(excluded(decoded_key)),
+                    decoded_value.eq(excluded(decoded_value)),
+                    is_deleted.eq(excluded(is_deleted)),
+                    inserted_at.eq(excluded(inserted_at)),
+                )),
             None,
         )?;
     }
@@ -400,7 +697,12 @@ fn insert_table_metadata(
             diesel::insert_into(schema::table_metadatas::table)
                 .values(&items_to_insert[start_ind..end_ind])
                 .on_conflict(handle)
-                .do_nothing(),
+                .do_update()
+                .set((
+                    key_type.eq(excluded(key_type)),
+                    value_type.eq(excluded(value_type)),
+                    inserted_at.eq(excluded(inserted_at)),
+                )),
             None,
         )?;
     }
@@ -425,6 +727,7 @@ impl TransactionProcessor for DefaultTransactionProcessor {
         let mut signatures = vec![];
         let mut user_transactions = vec![];
         let mut block_metadata_transactions = vec![];
+        let mut state_checkpoint_transactions = vec![];
         for detail in txn_details {
             match detail {
                 TransactionDetail::User(user_txn, sigs) => {
@@ -434,12 +737,20 @@ impl TransactionProcessor for DefaultTransactionProcessor {
                 TransactionDetail::BlockMetadata(bmt) => {
                     block_metadata_transactions.push(bmt.clone())
                 },
+                TransactionDetail::StateCheckpoint(sct) => {
+                    state_checkpoint_transactions.push(sct.clone())
+                },
             }
         }
+        let blocks = self
+            .block_tracker
+            .lock()
+            .unwrap()
+            .add_batch(&txns, &block_metadata_transactions);
         let mut move_modules = vec![];
         let mut move_resources = vec![];
         let mut table_items = vec![];
-        let mut current_table_items = HashMap::new();
+        let mut current_table_items = LatestValueAggregator::new();
         let mut table_metadata = HashMap::new();
         for detail in wsc_details {
             match detail {
@@ -460,16 +771,50 @@ impl TransactionProcessor for DefaultTransactionProcessor {
                 },
             }
         }
+        let validator_participation: Vec<BlockValidatorParticipationModel> = {
+            let mut validator_sets = self.validator_sets.lock().unwrap();
+            validator_sets.add_batch(&txns, &events, &move_resources);
+            block_metadata_transactions
+                .iter()
+                .flat_map(|txn| {
+                    BlockValidatorParticipationModel::from_block_metadata_transaction(
+                        txn,
+                        validator_sets.get(txn.epoch),
+                    )
+                })
+                .collect()
+        };
         // Getting list of values and sorting by pk in order to avoid postgres deadlock since we're doing multi threaded db writes
-        let mut current_table_items = current_table_items
-            .into_values()
-            .collect::<Vec<CurrentTableItem>>();
+        let current_table_items = current_table_items.into_sorted_values();
         let mut table_metadata = table_metadata.into_values().collect::<Vec<TableMetadata>>();
         // Sort by PK
-        current_table_items
-            .sort_by(|a, b| (&a.table_handle, &a.key_hash).cmp(&(&b.table_handle, &b.key_hash)));
         table_metadata.sort_by(|a, b| a.handle.cmp(&b.handle));
 
+        let result = ProcessingResult::new(self.name(), start_version, end_version)
+            .with_rows_written("transactions", txns.len())
+            .with_rows_written("user_transactions", user_transactions.len())
+            .with_rows_written("signatures", signatures.len())
+            .with_rows_written(
+                "block_metadata_transactions",
+                block_metadata_transactions.len(),
+            )
+            .with_rows_written(
+                "state_checkpoint_transactions",
+                state_checkpoint_transactions.len(),
+            )
+            .with_rows_written("blocks", blocks.0.len())
+            .with_rows_written(
+                "block_validator_participation",
+                validator_participation.len(),
+            )
+            .with_rows_written("events", events.len())
+            .with_rows_written("write_set_changes", write_set_changes.len())
+            .with_rows_written("move_modules", move_modules.len())
+            .with_rows_written("move_resources", move_resources.len())
+            .with_rows_written("table_items", table_items.len())
+            .with_rows_written("current_table_items", current_table_items.len())
+            .with_rows_written("table_metadatas", table_metadata.len());
+
         let mut conn = self.get_conn();
         let tx_result = insert_to_db(
             &mut conn,
@@ -477,7 +822,14 @@ impl TransactionProcessor for DefaultTransactionProcessor {
             start_version,
             end_version,
             txns,
-            (user_transactions, signatures, block_metadata_transactions),
+            (
+                user_transactions,
+                signatures,
+                block_metadata_transactions,
+                state_checkpoint_transactions,
+            ),
+            blocks,
+            validator_participation,
             events,
             write_set_changes,
             (
@@ -489,11 +841,7 @@ impl TransactionProcessor for DefaultTransactionProcessor {
             ),
         );
         match tx_result {
-            Ok(_) => Ok(ProcessingResult::new(
-                self.name(),
-                start_version,
-                end_version,
-            )),
+            Ok(_) => Ok(result),
             Err(err) => Err(TransactionProcessingError::TransactionCommitError((
                 anyhow::Error::from(err),
                 start_version,
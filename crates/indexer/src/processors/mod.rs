@@ -3,11 +3,13 @@
 
 pub mod coin_processor;
 pub mod default_processor;
+pub mod parquet_block_metadata_processor;
 pub mod stake_processor;
 pub mod token_processor;
 
 use self::{
     coin_processor::NAME as COIN_PROCESSOR_NAME, default_processor::NAME as DEFAULT_PROCESSOR_NAME,
+    parquet_block_metadata_processor::NAME as PARQUET_BLOCK_METADATA_PROCESSOR_NAME,
     stake_processor::NAME as STAKE_PROCESSOR_NAME, token_processor::NAME as TOKEN_PROCESSOR_NAME,
 };
 
@@ -16,16 +18,24 @@ pub enum Processor {
     DefaultProcessor,
     TokenProcessor,
     StakeProcessor,
+    ParquetBlockMetadataProcessor,
 }
 
 impl Processor {
     pub fn from_string(input_str: &String) -> Self {
-        match input_str.as_str() {
-            DEFAULT_PROCESSOR_NAME => Self::DefaultProcessor,
-            TOKEN_PROCESSOR_NAME => Self::TokenProcessor,
-            COIN_PROCESSOR_NAME => Self::CoinProcessor,
-            STAKE_PROCESSOR_NAME => Self::StakeProcessor,
-            _ => panic!("Processor unsupported {}", input_str),
+        Self::try_from_string(input_str)
+            .unwrap_or_else(|| panic!("Processor unsupported {}", input_str))
+    }
+
+    /// Returns None if there is no processor with the given name
+    pub fn try_from_string(input_str: &str) -> Option<Self> {
+        match input_str {
+            DEFAULT_PROCESSOR_NAME => Some(Self::DefaultProcessor),
+            TOKEN_PROCESSOR_NAME => Some(Self::TokenProcessor),
+            COIN_PROCESSOR_NAME => Some(Self::CoinProcessor),
+            STAKE_PROCESSOR_NAME => Some(Self::StakeProcessor),
+            PARQUET_BLOCK_METADATA_PROCESSOR_NAME => Some(Self::ParquetBlockMetadataProcessor),
+            _ => None,
         }
     }
 }
@@ -0,0 +1,227 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    database::PgDbPool,
+    indexer::{
+        errors::TransactionProcessingError, processing_result::ProcessingResult,
+        transaction_processor::TransactionProcessor,
+    },
+    models::{
+        block_metadata_transactions::BlockMetadataTransactionModel,
+        transactions::{TransactionDetail, TransactionModel},
+    },
+    parquet_writer::{ObjectStore, ParquetTableWriter, ParquetWriterConfig},
+};
+use aptos_api_types::Transaction as APITransaction;
+use async_trait::async_trait;
+use std::{fmt::Debug, sync::Arc};
+use tokio::sync::Mutex;
+
+pub const NAME: &str = "parquet_block_metadata_processor";
+
+/// Exports the block metadata transactions as Parquet files rather than to Postgres, which only
+/// keeps the processor statuses. Versions are only reported as processed once they are flushed,
+/// so whatever is buffered on shutdown is processed again. The batches have to be processed in
+/// order, so this processor always runs a single processor task.
+pub struct ParquetBlockMetadataProcessor {
+    connection_pool: PgDbPool,
+    writer: Mutex<ParquetTableWriter<BlockMetadataTransactionModel>>,
+}
+
+impl ParquetBlockMetadataProcessor {
+    pub fn new(
+        connection_pool: PgDbPool,
+        config: ParquetWriterConfig,
+        object_store: Option<Arc<dyn ObjectStore>>,
+    ) -> Self {
+        Self {
+            connection_pool,
+            writer: Mutex::new(ParquetTableWriter::new(config, object_store)),
+        }
+    }
+}
+
+impl Debug for ParquetBlockMetadataProcessor {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let state = &self.connection_pool.state();
+        write!(
+            f,
+            "ParquetBlockMetadataProcessor {{ connections: {:?}  idle_connections: {:?} }}",
+            state.connections, state.idle_connections
+        )
+    }
+}
+
+#[async_trait]
+impl TransactionProcessor for ParquetBlockMetadataProcessor {
+    fn name(&self) -> &'static str {
+        NAME
+    }
+
+    async fn process_transactions(
+        &self,
+        transactions: Vec<APITransaction>,
+        start_version: u64,
+        end_version: u64,
+    ) -> Result<ProcessingResult, TransactionProcessingError> {
+        let (_, txn_details, _, _, _) = TransactionModel::from_transactions(&transactions);
+        let block_metadata_transactions = txn_details
+            .into_iter()
+            .filter_map(|detail| match detail {
+                TransactionDetail::BlockMetadata(block_metadata) => Some(block_metadata),
+                TransactionDetail::User(_, _) | TransactionDetail::StateCheckpoint(_) => None,
+            })
+            .collect();
+
+        let mut writer = self.writer.lock().await;
+        match writer.add(block_metadata_transactions, start_version, end_version) {
+            Ok(Some(flushed)) => Ok(ProcessingResult::new(
+                self.name(),
+                flushed.start_version,
+                flushed.end_version,
+            )),
+            // Nothing was flushed, the result covers no version.
+            Ok(None) => Ok(ProcessingResult::new(
+                self.name(),
+                start_version,
+                start_version.saturating_sub(1),
+            )),
+            Err(err) => Err(TransactionProcessingError::TransactionCommitError((
+                err,
+                start_version,
+                end_version,
+                self.name(),
+            ))),
+        }
+    }
+
+    fn connection_pool(&self) -> &PgDbPool {
+        &self.connection_pool
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{database::PgPool, parquet_writer::LocalDirObjectStore};
+    use aptos_api_types::U64;
+    use aptos_indexer_test_fixtures::{
+        load_transaction, BLOCK_METADATA_TRANSACTION, STATE_CHECKPOINT_TRANSACTION,
+        USER_TRANSACTION,
+    };
+    use aptos_temppath::TempPath;
+    use arrow::array::{Int64Array, StringArray};
+    use diesel::{r2d2::ConnectionManager, PgConnection};
+    use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+    use std::{fs::File, path::Path, time::Duration};
+
+    /// The pool is never connected to, `process_transactions` doesn't touch Postgres
+    fn processor(dir: &Path, upload_dir: &Path, max_rows: usize) -> ParquetBlockMetadataProcessor {
+        let pool = PgPool::builder()
+            .build_unchecked(ConnectionManager::<PgConnection>::new("postgres://unused"));
+        ParquetBlockMetadataProcessor::new(
+            Arc::new(pool),
+            ParquetWriterConfig {
+                dir: dir.to_path_buf(),
+                max_rows,
+                max_buffer_age: Duration::from_secs(3600),
+            },
+            Some(Arc::new(LocalDirObjectStore::new(upload_dir))),
+        )
+    }
+
+    /// A block of a block metadata, a user transaction and a state checkpoint
+    fn block(start_version: u64) -> Vec<APITransaction> {
+        [
+            BLOCK_METADATA_TRANSACTION,
+            USER_TRANSACTION,
+            STATE_CHECKPOINT_TRANSACTION,
+        ]
+        .iter()
+        .zip(start_version..)
+        .map(|(name, version)| {
+            let mut txn = load_transaction(name);
+            match &mut txn {
+                APITransaction::BlockMetadataTransaction(txn) => {
+                    txn.info.version = U64::from(version)
+                },
+                APITransaction::UserTransaction(txn) => txn.info.version = U64::from(version),
+                APITransaction::StateCheckpointTransaction(txn) => {
+                    txn.info.version = U64::from(version)
+                },
+                _ => unreachable!(),
+            }
+            txn
+        })
+        .collect()
+    }
+
+    /// Versions and proposers of the rows of the file
+    fn read_back(path: &Path) -> (Vec<i64>, Vec<String>) {
+        let reader = ParquetRecordBatchReaderBuilder::try_new(File::open(path).unwrap())
+            .unwrap()
+            .build()
+            .unwrap();
+        let mut versions = vec![];
+        let mut proposers = vec![];
+        for batch in reader {
+            let batch = batch.unwrap();
+            let column = |name: &str| batch.column(batch.schema().index_of(name).unwrap()).clone();
+            let version = column("version");
+            let version = version.as_any().downcast_ref::<Int64Array>().unwrap();
+            versions.extend(version.values().iter().copied());
+            let proposer = column("proposer");
+            let proposer = proposer.as_any().downcast_ref::<StringArray>().unwrap();
+            proposers.extend(proposer.iter().map(|p| p.unwrap().to_string()));
+        }
+        (versions, proposers)
+    }
+
+    #[tokio::test]
+    async fn test_parquet_export() {
+        let dir = TempPath::new();
+        dir.create_as_dir().unwrap();
+        let upload_dir = TempPath::new();
+        upload_dir.create_as_dir().unwrap();
+        let processor = processor(dir.path(), upload_dir.path(), 2);
+
+        // A single block metadata row is buffered, nothing is reported as processed.
+        let result = processor
+            .process_transactions(block(10), 10, 12)
+            .await
+            .unwrap();
+        assert!(result.end_version < result.start_version);
+        assert!(processor.writer.lock().await.watermark().is_none());
+
+        // The second row reaches the threshold, both batches are flushed to a single file.
+        let result = processor
+            .process_transactions(block(13), 13, 15)
+            .await
+            .unwrap();
+        assert_eq!((result.start_version, result.end_version), (10, 15));
+        assert_eq!(processor.writer.lock().await.watermark(), Some(15));
+
+        let name = "block_metadata_transactions/00000000000000000010_00000000000000000015.parquet";
+        let (versions, proposers) = read_back(&dir.path().join(name));
+        assert_eq!(versions, vec![10, 13]);
+        let expected_proposer = match &block(10)[0] {
+            APITransaction::BlockMetadataTransaction(txn) => {
+                BlockMetadataTransactionModel::from_transaction(txn, 0).proposer
+            },
+            _ => unreachable!(),
+        };
+        assert_eq!(
+            proposers,
+            vec![expected_proposer.clone(), expected_proposer]
+        );
+        // The uploaded copy has the same contents
+        assert_eq!(read_back(&upload_dir.path().join(name)).0, vec![10, 13]);
+
+        // Batches out of order are refused
+        assert!(processor
+            .process_transactions(block(20), 20, 22)
+            .await
+            .is_err());
+    }
+}
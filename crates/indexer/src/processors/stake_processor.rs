@@ -6,12 +6,12 @@ use crate::{
         clean_data_for_db, execute_with_better_error, get_chunks, PgDbPool, PgPoolConnection,
     },
     indexer::{
-        errors::TransactionProcessingError, processing_result::ProcessingResult,
-        transaction_processor::TransactionProcessor,
+        errors::TransactionProcessingError, latest_value_aggregator::LatestValueAggregator,
+        processing_result::ProcessingResult, transaction_processor::TransactionProcessor,
     },
-    models::stake_models::{
-        proposal_votes::ProposalVote,
-        staking_pool_voter::{CurrentStakingPoolVoter, StakingPoolVoterMap},
+    models::{
+        processor_status::ProcessorStatusV2,
+        stake_models::{proposal_votes::ProposalVote, staking_pool_voter::CurrentStakingPoolVoter},
     },
     schema,
 };
@@ -19,7 +19,7 @@ use aptos_api_types::Transaction as APITransaction;
 use async_trait::async_trait;
 use diesel::{pg::upsert::excluded, result::Error, ExpressionMethods, PgConnection};
 use field_count::FieldCount;
-use std::{collections::HashMap, fmt::Debug};
+use std::fmt::Debug;
 
 pub const NAME: &str = "stake_processor";
 pub struct StakeTransactionProcessor {
@@ -71,7 +71,8 @@ fn insert_to_db(
         .build_transaction()
         .read_write()
         .run::<_, Error, _>(|pg_conn| {
-            insert_to_db_impl(pg_conn, &current_stake_pool_voters, &proposal_votes)
+            insert_to_db_impl(pg_conn, &current_stake_pool_voters, &proposal_votes)?;
+            ProcessorStatusV2::advance(pg_conn, name, start_version, end_version)
         }) {
         Ok(_) => Ok(()),
         Err(_) => conn
@@ -81,7 +82,8 @@ fn insert_to_db(
                 let current_stake_pool_voters = clean_data_for_db(current_stake_pool_voters, true);
                 let proposal_votes = clean_data_for_db(proposal_votes, true);
 
-                insert_to_db_impl(pg_conn, &current_stake_pool_voters, &proposal_votes)
+                insert_to_db_impl(pg_conn, &current_stake_pool_voters, &proposal_votes)?;
+                ProcessorStatusV2::advance(pg_conn, name, start_version, end_version)
             }),
     }
 }
@@ -127,7 +129,14 @@ fn insert_proposal_votes(
             diesel::insert_into(schema::proposal_votes::table)
                 .values(&item_to_insert[start_ind..end_ind])
                 .on_conflict((transaction_version, proposal_id, voter_address))
-                .do_nothing(),
+                .do_update()
+                .set((
+                    staking_pool_address.eq(excluded(staking_pool_address)),
+                    num_votes.eq(excluded(num_votes)),
+                    should_pass.eq(excluded(should_pass)),
+                    transaction_timestamp.eq(excluded(transaction_timestamp)),
+                    inserted_at.eq(excluded(inserted_at)),
+                )),
             None,
         )?;
     }
@@ -146,7 +155,7 @@ impl TransactionProcessor for StakeTransactionProcessor {
         start_version: u64,
         end_version: u64,
     ) -> Result<ProcessingResult, TransactionProcessingError> {
-        let mut all_current_stake_pool_voters: StakingPoolVoterMap = HashMap::new();
+        let mut all_current_stake_pool_voters = LatestValueAggregator::new();
         let mut all_proposal_votes = vec![];
 
         for txn in &transactions {
@@ -155,13 +164,7 @@ impl TransactionProcessor for StakeTransactionProcessor {
             let mut proposal_votes = ProposalVote::from_transaction(txn).unwrap();
             all_proposal_votes.append(&mut proposal_votes);
         }
-        let mut all_current_stake_pool_voters = all_current_stake_pool_voters
-            .into_values()
-            .collect::<Vec<CurrentStakingPoolVoter>>();
-
-        // Sort by PK
-        all_current_stake_pool_voters
-            .sort_by(|a, b| a.staking_pool_address.cmp(&b.staking_pool_address));
+        let all_current_stake_pool_voters = all_current_stake_pool_voters.into_sorted_values();
 
         let mut conn = self.get_conn();
         let tx_result = insert_to_db(
@@ -6,19 +6,22 @@ use crate::{
         clean_data_for_db, execute_with_better_error, get_chunks, PgDbPool, PgPoolConnection,
     },
     indexer::{
-        errors::TransactionProcessingError, processing_result::ProcessingResult,
-        transaction_processor::TransactionProcessor,
+        errors::TransactionProcessingError, latest_value_aggregator::LatestValueAggregator,
+        processing_result::ProcessingResult, transaction_processor::TransactionProcessor,
     },
-    models::token_models::{
-        ans_lookup::{CurrentAnsLookup, CurrentAnsLookupPK},
-        collection_datas::{CollectionData, CurrentCollectionData},
-        token_activities::TokenActivity,
-        token_claims::CurrentTokenPendingClaim,
-        token_datas::{CurrentTokenData, TokenData},
-        token_ownerships::{CurrentTokenOwnership, TokenOwnership},
-        tokens::{
-            CurrentTokenOwnershipPK, CurrentTokenPendingClaimPK, TableMetadataForToken, Token,
-            TokenDataIdHash,
+    models::{
+        processor_status::ProcessorStatusV2,
+        token_models::{
+            ans_lookup::{CurrentAnsLookup, CurrentAnsLookupPK},
+            collection_datas::{CollectionData, CurrentCollectionData},
+            token_activities::TokenActivity,
+            token_claims::CurrentTokenPendingClaim,
+            token_datas::{CurrentTokenData, TokenData},
+            token_ownerships::{CurrentTokenOwnership, TokenOwnership},
+            tokens::{
+                CurrentTokenOwnershipPK, CurrentTokenPendingClaimPK, TableMetadataForToken, Token,
+                TokenDataIdHash,
+            },
         },
     },
     schema,
@@ -27,7 +30,7 @@ use aptos_api_types::Transaction;
 use async_trait::async_trait;
 use diesel::{pg::upsert::excluded, result::Error, ExpressionMethods, PgConnection};
 use field_count::FieldCount;
-use std::{collections::HashMap, fmt::Debug};
+use std::fmt::Debug;
 
 pub const NAME: &str = "token_processor";
 pub struct TokenTransactionProcessor {
@@ -131,7 +134,8 @@ fn insert_to_db(
                 &token_activities,
                 &current_token_claims,
                 &current_ans_lookups,
-            )
+            )?;
+            ProcessorStatusV2::advance(pg_conn, name, start_version, end_version)
         }) {
         Ok(_) => Ok(()),
         Err(_) => conn
@@ -160,7 +164,8 @@ fn insert_to_db(
                     &token_activities,
                     &current_token_claims,
                     &current_ans_lookups,
-                )
+                )?;
+                ProcessorStatusV2::advance(pg_conn, name, start_version, end_version)
             }),
     }
 }
@@ -180,8 +185,13 @@ fn insert_tokens(
                 .on_conflict((token_data_id_hash, property_version, transaction_version))
                 .do_update()
                 .set((
+                    creator_address.eq(excluded(creator_address)),
+                    collection_name.eq(excluded(collection_name)),
+                    name.eq(excluded(name)),
                     token_properties.eq(excluded(token_properties)),
                     inserted_at.eq(excluded(inserted_at)),
+                    collection_data_id_hash.eq(excluded(collection_data_id_hash)),
+                    transaction_timestamp.eq(excluded(transaction_timestamp)),
                 )),
             None,
         )?;
@@ -210,7 +220,18 @@ fn insert_token_ownerships(
                     transaction_version,
                     table_handle,
                 ))
-                .do_nothing(),
+                .do_update()
+                .set((
+                    creator_address.eq(excluded(creator_address)),
+                    collection_name.eq(excluded(collection_name)),
+                    name.eq(excluded(name)),
+                    owner_address.eq(excluded(owner_address)),
+                    amount.eq(excluded(amount)),
+                    table_type.eq(excluded(table_type)),
+                    inserted_at.eq(excluded(inserted_at)),
+                    collection_data_id_hash.eq(excluded(collection_data_id_hash)),
+                    transaction_timestamp.eq(excluded(transaction_timestamp)),
+                )),
             None,
         )?;
     }
@@ -232,8 +253,26 @@ fn insert_token_datas(
                 .on_conflict((token_data_id_hash, transaction_version))
                 .do_update()
                 .set((
+                    creator_address.eq(excluded(creator_address)),
+                    collection_name.eq(excluded(collection_name)),
+                    name.eq(excluded(name)),
+                    maximum.eq(excluded(maximum)),
+                    supply.eq(excluded(supply)),
+                    largest_property_version.eq(excluded(largest_property_version)),
+                    metadata_uri.eq(excluded(metadata_uri)),
+                    payee_address.eq(excluded(payee_address)),
+                    royalty_points_numerator.eq(excluded(royalty_points_numerator)),
+                    royalty_points_denominator.eq(excluded(royalty_points_denominator)),
+                    maximum_mutable.eq(excluded(maximum_mutable)),
+                    uri_mutable.eq(excluded(uri_mutable)),
+                    description_mutable.eq(excluded(description_mutable)),
+                    properties_mutable.eq(excluded(properties_mutable)),
+                    royalty_mutable.eq(excluded(royalty_mutable)),
                     default_properties.eq(excluded(default_properties)),
                     inserted_at.eq(excluded(inserted_at)),
+                    collection_data_id_hash.eq(excluded(collection_data_id_hash)),
+                    transaction_timestamp.eq(excluded(transaction_timestamp)),
+                    description.eq(excluded(description)),
                 )),
             None,
         )?;
@@ -257,7 +296,21 @@ fn insert_collection_datas(
             diesel::insert_into(schema::collection_datas::table)
                 .values(&collection_datas_to_insert[start_ind..end_ind])
                 .on_conflict((collection_data_id_hash, transaction_version))
-                .do_nothing(),
+                .do_update()
+                .set((
+                    creator_address.eq(excluded(creator_address)),
+                    collection_name.eq(excluded(collection_name)),
+                    description.eq(excluded(description)),
+                    metadata_uri.eq(excluded(metadata_uri)),
+                    supply.eq(excluded(supply)),
+                    maximum.eq(excluded(maximum)),
+                    maximum_mutable.eq(excluded(maximum_mutable)),
+                    uri_mutable.eq(excluded(uri_mutable)),
+                    description_mutable.eq(excluded(description_mutable)),
+                    inserted_at.eq(excluded(inserted_at)),
+                    table_handle.eq(excluded(table_handle)),
+                    transaction_timestamp.eq(excluded(transaction_timestamp)),
+                )),
             None,
         )?;
     }
@@ -289,6 +342,7 @@ fn insert_current_token_ownerships(
                     collection_data_id_hash.eq(excluded(collection_data_id_hash)),
                     table_type.eq(excluded(table_type)),
                     inserted_at.eq(excluded(inserted_at)),
+                    last_transaction_timestamp.eq(excluded(last_transaction_timestamp)),
                 )),
             Some(" WHERE current_token_ownerships.last_transaction_version <= excluded.last_transaction_version "),
         )?;
@@ -332,6 +386,7 @@ fn insert_current_token_datas(
                     collection_data_id_hash.eq(excluded(collection_data_id_hash)),
                     description.eq(excluded(description)),
                     inserted_at.eq(excluded(inserted_at)),
+                    last_transaction_timestamp.eq(excluded(last_transaction_timestamp)),
                 )),
             Some(" WHERE current_token_datas.last_transaction_version <= excluded.last_transaction_version "),
         )?;
@@ -367,6 +422,7 @@ fn insert_current_collection_datas(
                     last_transaction_version.eq(excluded(last_transaction_version)),
                     table_handle.eq(excluded(table_handle)),
                     inserted_at.eq(excluded(inserted_at)),
+                    last_transaction_timestamp.eq(excluded(last_transaction_timestamp)),
                 )),
             Some(" WHERE current_collection_datas.last_transaction_version <= excluded.last_transaction_version "),
         )?;
@@ -395,7 +451,20 @@ fn insert_token_activities(
                 ))
                 .do_update()
                 .set((
+                    collection_data_id_hash.eq(excluded(collection_data_id_hash)),
+                    token_data_id_hash.eq(excluded(token_data_id_hash)),
+                    property_version.eq(excluded(property_version)),
+                    creator_address.eq(excluded(creator_address)),
+                    collection_name.eq(excluded(collection_name)),
+                    name.eq(excluded(name)),
+                    transfer_type.eq(excluded(transfer_type)),
+                    from_address.eq(excluded(from_address)),
+                    to_address.eq(excluded(to_address)),
+                    token_amount.eq(excluded(token_amount)),
+                    coin_type.eq(excluded(coin_type)),
+                    coin_amount.eq(excluded(coin_amount)),
                     inserted_at.eq(excluded(inserted_at)),
+                    transaction_timestamp.eq(excluded(transaction_timestamp)),
                     event_index.eq(excluded(event_index)),
                 )),
             None,
@@ -432,6 +501,7 @@ fn insert_current_token_claims(
                     table_handle.eq(excluded(table_handle)),
                     last_transaction_version.eq(excluded(last_transaction_version)),
                     inserted_at.eq(excluded(inserted_at)),
+                    last_transaction_timestamp.eq(excluded(last_transaction_timestamp)),
                 )),
             Some(" WHERE current_token_pending_claims.last_transaction_version <= excluded.last_transaction_version "),
         )?;
@@ -492,21 +562,25 @@ impl TransactionProcessor for TokenTransactionProcessor {
         let mut all_collection_datas = vec![];
         let mut all_token_activities = vec![];
 
-        // Hashmap key will be the PK of the table, we do not want to send duplicates writes to the db within a batch
-        let mut all_current_token_ownerships: HashMap<
+        // Aggregator key will be the PK of the table, we do not want to send duplicates writes to the db within a batch
+        let mut all_current_token_ownerships: LatestValueAggregator<
             CurrentTokenOwnershipPK,
             CurrentTokenOwnership,
-        > = HashMap::new();
-        let mut all_current_token_datas: HashMap<TokenDataIdHash, CurrentTokenData> =
-            HashMap::new();
-        let mut all_current_collection_datas: HashMap<TokenDataIdHash, CurrentCollectionData> =
-            HashMap::new();
-        let mut all_current_token_claims: HashMap<
+        > = LatestValueAggregator::new();
+        let mut all_current_token_datas: LatestValueAggregator<TokenDataIdHash, CurrentTokenData> =
+            LatestValueAggregator::new();
+        let mut all_current_collection_datas: LatestValueAggregator<
+            TokenDataIdHash,
+            CurrentCollectionData,
+        > = LatestValueAggregator::new();
+        let mut all_current_token_claims: LatestValueAggregator<
             CurrentTokenPendingClaimPK,
             CurrentTokenPendingClaim,
-        > = HashMap::new();
-        let mut all_current_ans_lookups: HashMap<CurrentAnsLookupPK, CurrentAnsLookup> =
-            HashMap::new();
+        > = LatestValueAggregator::new();
+        let mut all_current_ans_lookups: LatestValueAggregator<
+            CurrentAnsLookupPK,
+            CurrentAnsLookup,
+        > = LatestValueAggregator::new();
 
         for txn in transactions {
             let (
@@ -523,7 +597,7 @@ impl TransactionProcessor for TokenTransactionProcessor {
             all_token_ownerships.append(&mut token_ownerships);
             all_token_datas.append(&mut token_datas);
             all_collection_datas.append(&mut collection_datas);
-            // Given versions will always be increasing here (within a single batch), we can just override current values
+            // Only the highest version for each PK is kept
             all_current_token_ownerships.extend(current_token_ownerships);
             all_current_token_datas.extend(current_token_datas);
             all_current_collection_datas.extend(current_collection_datas);
@@ -541,51 +615,12 @@ impl TransactionProcessor for TokenTransactionProcessor {
             all_current_ans_lookups.extend(current_ans_lookups);
         }
 
-        // Getting list of values and sorting by pk in order to avoid postgres deadlock since we're doing multi threaded db writes
-        let mut all_current_token_ownerships = all_current_token_ownerships
-            .into_values()
-            .collect::<Vec<CurrentTokenOwnership>>();
-        let mut all_current_token_datas = all_current_token_datas
-            .into_values()
-            .collect::<Vec<CurrentTokenData>>();
-        let mut all_current_collection_datas = all_current_collection_datas
-            .into_values()
-            .collect::<Vec<CurrentCollectionData>>();
-        let mut all_current_token_claims = all_current_token_claims
-            .into_values()
-            .collect::<Vec<CurrentTokenPendingClaim>>();
-
-        // Sort by PK
-        all_current_token_ownerships.sort_by(|a, b| {
-            (&a.token_data_id_hash, &a.property_version, &a.owner_address).cmp(&(
-                &b.token_data_id_hash,
-                &b.property_version,
-                &b.owner_address,
-            ))
-        });
-        all_current_token_datas.sort_by(|a, b| a.token_data_id_hash.cmp(&b.token_data_id_hash));
-        all_current_collection_datas
-            .sort_by(|a, b| a.collection_data_id_hash.cmp(&b.collection_data_id_hash));
-        all_current_token_claims.sort_by(|a, b| {
-            (
-                &a.token_data_id_hash,
-                &a.property_version,
-                &a.from_address,
-                &a.to_address,
-            )
-                .cmp(&(
-                    &b.token_data_id_hash,
-                    &b.property_version,
-                    &b.from_address,
-                    &a.to_address,
-                ))
-        });
-        // Sort ans lookup values for postgres insert
-        let mut all_current_ans_lookups = all_current_ans_lookups
-            .into_values()
-            .collect::<Vec<CurrentAnsLookup>>();
-        all_current_ans_lookups
-            .sort_by(|a, b| a.domain.cmp(&b.domain).then(a.subdomain.cmp(&b.subdomain)));
+        // Getting list of values sorted by pk in order to avoid postgres deadlock since we're doing multi threaded db writes
+        let all_current_token_ownerships = all_current_token_ownerships.into_sorted_values();
+        let all_current_token_datas = all_current_token_datas.into_sorted_values();
+        let all_current_collection_datas = all_current_collection_datas.into_sorted_values();
+        let all_current_token_claims = all_current_token_claims.into_sorted_values();
+        let all_current_ans_lookups = all_current_ans_lookups.into_sorted_values();
 
         let tx_result = insert_to_db(
             &mut conn,
@@ -0,0 +1,342 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+//! Opt-in statistics on how the query helpers of the models access the tables, used to tell
+//! which indexes are missing for the queries that are actually run. Collection is disabled by
+//! default, in which case recording is a single atomic load.
+//! Exposed through `aptos-node --config <path> index-advisor`.
+
+use crate::database::PgPoolConnection;
+use anyhow::Result;
+use diesel::{sql_query, sql_types::Text, QueryResult, RunQueryDsl};
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{BTreeMap, HashMap},
+    fmt,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Mutex,
+    },
+};
+
+static QUERY_STATS: Lazy<QueryStats> = Lazy::new(QueryStats::default);
+
+/// How a query accesses a table
+#[derive(Clone, Debug, Deserialize, Eq, Hash, Ord, PartialEq, PartialOrd, Serialize)]
+pub struct AccessPattern {
+    pub table: String,
+    pub filter_columns: Vec<String>,
+    pub sort_columns: Vec<String>,
+}
+
+impl AccessPattern {
+    /// The columns an index serving the pattern would have, filters first
+    pub fn index_columns(&self) -> Vec<&str> {
+        let mut columns: Vec<&str> = vec![];
+        for column in self.filter_columns.iter().chain(&self.sort_columns) {
+            if !columns.contains(&column.as_str()) {
+                columns.push(column);
+            }
+        }
+        columns
+    }
+}
+
+impl fmt::Display for AccessPattern {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} filtered by ({})",
+            self.table,
+            self.filter_columns.join(", ")
+        )?;
+        if !self.sort_columns.is_empty() {
+            write!(f, " sorted by ({})", self.sort_columns.join(", "))?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+pub struct PatternStats {
+    /// Number of queries with the pattern
+    pub count: u64,
+    /// Number of rows these queries returned
+    pub rows: u64,
+}
+
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct PatternSnapshot {
+    pub pattern: AccessPattern,
+    pub stats: PatternStats,
+}
+
+#[derive(Default)]
+struct QueryStats {
+    enabled: AtomicBool,
+    patterns: Mutex<HashMap<AccessPattern, PatternStats>>,
+}
+
+/// Starts collecting the access patterns of the query helpers.
+pub fn enable_query_stats() {
+    QUERY_STATS.enabled.store(true, Ordering::Relaxed);
+}
+
+pub fn query_stats_enabled() -> bool {
+    QUERY_STATS.enabled.load(Ordering::Relaxed)
+}
+
+/// Records a query of the table, filtered and sorted by the given columns, that returned `rows`
+/// rows. Does nothing unless the collection is enabled.
+pub fn record_query(table: &str, filter_columns: &[&str], sort_columns: &[&str], rows: usize) {
+    if !query_stats_enabled() {
+        return;
+    }
+    let pattern = AccessPattern {
+        table: table.to_string(),
+        filter_columns: filter_columns.iter().map(|c| c.to_string()).collect(),
+        sort_columns: sort_columns.iter().map(|c| c.to_string()).collect(),
+    };
+    let mut patterns = QUERY_STATS.patterns.lock().unwrap();
+    let stats = patterns.entry(pattern).or_default();
+    stats.count += 1;
+    stats.rows += rows as u64;
+}
+
+/// The access patterns recorded so far, most frequent first
+pub fn query_stats_snapshot() -> Vec<PatternSnapshot> {
+    let mut snapshot: Vec<_> = QUERY_STATS
+        .patterns
+        .lock()
+        .unwrap()
+        .iter()
+        .map(|(pattern, stats)| PatternSnapshot {
+            pattern: pattern.clone(),
+            stats: *stats,
+        })
+        .collect();
+    snapshot.sort_by(|a, b| {
+        b.stats
+            .count
+            .cmp(&a.stats.count)
+            .then_with(|| a.pattern.cmp(&b.pattern))
+    });
+    snapshot
+}
+
+/// Writes the recorded access patterns to the file as json, for `index-advisor` to pick up.
+pub fn write_query_stats(path: &str) -> Result<()> {
+    std::fs::write(path, serde_json::to_vec_pretty(&query_stats_snapshot())?)?;
+    Ok(())
+}
+
+pub fn read_query_stats(path: &str) -> Result<Vec<PatternSnapshot>> {
+    Ok(serde_json::from_slice(&std::fs::read(path)?)?)
+}
+
+/// An access pattern that no index of the table serves
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+pub struct IndexSuggestion {
+    pub pattern: AccessPattern,
+    pub stats: PatternStats,
+}
+
+impl fmt::Display for IndexSuggestion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}: {} queries, {} rows on average. Consider CREATE INDEX ON {} ({})",
+            self.pattern,
+            self.stats.count,
+            self.stats.rows / self.stats.count.max(1),
+            self.pattern.table,
+            self.pattern.index_columns().join(", ")
+        )
+    }
+}
+
+#[derive(Debug, QueryableByName)]
+struct PgIndex {
+    #[diesel(sql_type = Text)]
+    tablename: String,
+    #[diesel(sql_type = Text)]
+    indexdef: String,
+}
+
+/// Columns of an index from its definition, e.g.
+/// `CREATE UNIQUE INDEX transactions_pkey ON public.transactions USING btree (version)`.
+/// Expressions are kept as they are, so they never match a column.
+fn index_columns(indexdef: &str) -> Vec<String> {
+    let columns = match indexdef.split_once(" USING ") {
+        Some((_, rest)) => rest,
+        None => return vec![],
+    };
+    let columns = match (columns.find('('), columns.rfind(')')) {
+        (Some(start), Some(end)) if start < end => &columns[start + 1..end],
+        _ => return vec![],
+    };
+    columns
+        .split(',')
+        .map(|column| {
+            let column = column.trim();
+            let column = column
+                .strip_suffix(" DESC")
+                .or_else(|| column.strip_suffix(" ASC"))
+                .unwrap_or(column);
+            column.trim_matches('"').to_string()
+        })
+        .collect()
+}
+
+/// Whether one of the indexes can be used for the pattern, that is if it leads with one of the
+/// filter columns, or with the first sort column if there's no filter.
+fn is_indexed(pattern: &AccessPattern, indexes: &[Vec<String>]) -> bool {
+    let leading = |column: &String| indexes.iter().any(|index| index.first() == Some(column));
+    match pattern.filter_columns.as_slice() {
+        [] => pattern.sort_columns.first().map_or(true, leading),
+        filters => filters.iter().any(leading),
+    }
+}
+
+/// The patterns that none of the indexes of the database serve, most frequent first.
+pub fn suggest_indexes(
+    conn: &mut PgPoolConnection,
+    patterns: &[PatternSnapshot],
+) -> QueryResult<Vec<IndexSuggestion>> {
+    let mut indexes: BTreeMap<String, Vec<Vec<String>>> = BTreeMap::new();
+    for index in sql_query(
+        "SELECT tablename::TEXT, indexdef::TEXT FROM pg_indexes WHERE schemaname = current_schema()",
+    )
+    .load::<PgIndex>(conn)?
+    {
+        indexes
+            .entry(index.tablename)
+            .or_default()
+            .push(index_columns(&index.indexdef));
+    }
+
+    let mut suggestions: Vec<_> = patterns
+        .iter()
+        .filter(|snapshot| {
+            let table_indexes = indexes
+                .get(&snapshot.pattern.table)
+                .map_or(&[][..], |i| i.as_slice());
+            !is_indexed(&snapshot.pattern, table_indexes)
+        })
+        .map(|snapshot| IndexSuggestion {
+            pattern: snapshot.pattern.clone(),
+            stats: snapshot.stats,
+        })
+        .collect();
+    suggestions.sort_by(|a, b| b.stats.count.cmp(&a.stats.count));
+    Ok(suggestions)
+}
+
+/// The access patterns recorded by this process that none of the indexes serve, most frequent
+/// first.
+pub fn report_index_suggestions(conn: &mut PgPoolConnection) -> QueryResult<Vec<IndexSuggestion>> {
+    suggest_indexes(conn, &query_stats_snapshot())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{indexer::tailer::test::setup_indexer, models::transactions::TransactionQuery};
+    use aptos_indexer_test_fixtures::{
+        load_transaction, BLOCK_METADATA_TRANSACTION, GENESIS_TRANSACTION, USER_TRANSACTION,
+    };
+
+    fn pattern(table: &str, filters: &[&str], sorts: &[&str]) -> AccessPattern {
+        AccessPattern {
+            table: table.to_string(),
+            filter_columns: filters.iter().map(|c| c.to_string()).collect(),
+            sort_columns: sorts.iter().map(|c| c.to_string()).collect(),
+        }
+    }
+
+    #[test]
+    fn test_index_columns() {
+        assert_eq!(
+            index_columns("CREATE UNIQUE INDEX transactions_pkey ON public.transactions USING btree (version)"),
+            vec!["version"]
+        );
+        assert_eq!(
+            index_columns("CREATE INDEX ut_sender_seq_index ON public.user_transactions USING btree (sender, sequence_number DESC)"),
+            vec!["sender", "sequence_number"]
+        );
+        assert!(index_columns("not an index").is_empty());
+    }
+
+    #[test]
+    fn test_is_indexed() {
+        let indexes = vec![
+            vec!["version".to_string()],
+            vec!["sender".to_string(), "sequence_number".to_string()],
+        ];
+        assert!(is_indexed(&pattern("t", &["version"], &[]), &indexes));
+        assert!(is_indexed(
+            &pattern("t", &["sender"], &["sequence_number"]),
+            &indexes
+        ));
+        // Only the leading column of an index helps
+        assert!(!is_indexed(
+            &pattern("t", &["sequence_number"], &[]),
+            &indexes
+        ));
+        assert!(is_indexed(&pattern("t", &[], &["version"]), &indexes));
+        assert!(!is_indexed(
+            &pattern("t", &["block_height"], &["version"]),
+            &indexes
+        ));
+        assert!(!is_indexed(&pattern("t", &["version"], &[]), &[]));
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_block_height_index_suggestion() {
+        if crate::should_skip_pg_tests() {
+            return;
+        }
+        let (conn_pool, tailer) = setup_indexer().unwrap();
+        tailer
+            .processor
+            .process_transactions_with_status(vec![
+                load_transaction(GENESIS_TRANSACTION),
+                load_transaction(BLOCK_METADATA_TRANSACTION),
+                load_transaction(USER_TRANSACTION),
+            ])
+            .await
+            .unwrap();
+
+        enable_query_stats();
+        let mut conn = conn_pool.get().unwrap();
+        TransactionQuery::get_by_version(0, &mut conn).unwrap();
+        for _ in 0..3 {
+            TransactionQuery::get_by_block_height(0, &mut conn).unwrap();
+        }
+
+        // Other tests may record queries too, only the block height queries are looked at.
+        let block_height = pattern("transactions", &["block_height"], &["version"]);
+        let flagged = |conn: &mut PgPoolConnection| {
+            report_index_suggestions(conn)
+                .unwrap()
+                .into_iter()
+                .find(|suggestion| suggestion.pattern == block_height)
+        };
+        let suggestion = flagged(&mut conn).expect("block height queries should be flagged");
+        assert!(suggestion.stats.count >= 3);
+        assert!(suggestion
+            .to_string()
+            .contains("CREATE INDEX ON transactions (block_height, version)"));
+        // The primary key serves the version lookups
+        assert!(!report_index_suggestions(&mut conn)
+            .unwrap()
+            .iter()
+            .any(|suggestion| suggestion.pattern == pattern("transactions", &["version"], &[])));
+
+        sql_query("CREATE INDEX txn_block_height_index ON transactions (block_height)")
+            .execute(&mut conn)
+            .unwrap();
+        assert_eq!(flagged(&mut conn), None);
+    }
+}
@@ -2,24 +2,38 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
-    database::new_db_pool,
+    check::{run_checks, CheckReport},
+    database::{new_db_pool, PgDbPool},
     indexer::{
-        fetcher::TransactionFetcherOptions, processing_result::ProcessingResult, tailer::Tailer,
+        errors::TransactionProcessingError,
+        fan_out::fan_out,
+        fetcher::{TransactionFetcher, TransactionFetcherOptions},
+        processing_result::ProcessingResult,
+        skip_list::VersionSkipList,
+        tailer::{await_tasks, Tailer},
         transaction_processor::TransactionProcessor,
     },
+    parquet_writer::{LocalDirObjectStore, ObjectStore, ParquetWriterConfig},
     processors::{
         coin_processor::CoinTransactionProcessor, default_processor::DefaultTransactionProcessor,
+        parquet_block_metadata_processor::ParquetBlockMetadataProcessor,
         stake_processor::StakeTransactionProcessor, token_processor::TokenTransactionProcessor,
         Processor,
     },
+    query_stats::{enable_query_stats, read_query_stats, suggest_indexes, write_query_stats},
 };
 use aptos_api::context::Context;
+use aptos_api_types::Transaction;
 use aptos_config::config::{IndexerConfig, NodeConfig};
-use aptos_logger::{error, info};
+use aptos_logger::{error, info, warn};
 use aptos_mempool::MempoolClientSender;
 use aptos_storage_interface::DbReader;
 use aptos_types::chain_id::ChainId;
-use std::{collections::VecDeque, sync::Arc};
+use std::{
+    collections::{BTreeMap, VecDeque},
+    sync::Arc,
+    time::{Duration, Instant},
+};
 use tokio::runtime::Runtime;
 
 pub struct MovingAverage {
@@ -91,22 +105,73 @@ pub fn bootstrap(
 
     runtime.spawn(async move {
         let context = Arc::new(Context::new(chain_id, db, mp_sender, node_config));
+        // Only returns once every version up to the ending version is processed, which leaves
+        // the node running
         run_forever(indexer_config, context).await;
     });
 
     Some(Ok(runtime))
 }
 
+/// Runs the checks of `crate::check` against the same database and node storage the indexer
+/// would use, without indexing anything
+pub fn check(
+    config: &NodeConfig,
+    chain_id: ChainId,
+    db: Arc<dyn DbReader>,
+    mp_sender: MempoolClientSender,
+) -> anyhow::Result<CheckReport> {
+    let context = Arc::new(Context::new(chain_id, db, mp_sender, config.clone()));
+    let resolver = Arc::new(context.move_resolver()?);
+    let mut fetcher =
+        TransactionFetcher::new(context, resolver, 0, TransactionFetcherOptions::default());
+    Ok(run_checks(&config.indexer, &mut fetcher))
+}
+
+/// Reports the access patterns recorded in `query_stats_path` that no index of the database
+/// serves, most frequent first
+pub fn index_advisor(config: &IndexerConfig) -> anyhow::Result<Vec<String>> {
+    let path = config
+        .query_stats_path
+        .as_ref()
+        .ok_or_else(|| anyhow::anyhow!("query_stats_path must be set to advise on indexes"))?;
+    let db_uri = config
+        .postgres_uri
+        .as_ref()
+        .ok_or_else(|| anyhow::anyhow!("postgres_uri must be set to advise on indexes"))?;
+    let patterns = read_query_stats(path)?;
+    let mut conn = new_db_pool(db_uri)?.get()?;
+    Ok(suggest_indexes(&mut conn, &patterns)?
+        .iter()
+        .map(|suggestion| suggestion.to_string())
+        .collect())
+}
+
+/// What the indexing loop processed, reported once it reached the ending version
+#[derive(Debug, Default)]
+pub struct IndexingSummary {
+    pub versions_processed: u64,
+    pub last_processed_version: Option<u64>,
+    pub rows_written: BTreeMap<&'static str, u64>,
+}
+
+/// Indexes until every version up to `config.ending_version` is processed, or forever if it isn't
+/// set
 pub async fn run_forever(config: IndexerConfig, context: Arc<Context>) {
     // All of these options should be filled already with defaults
     let processor_name = config.processor.clone().unwrap();
     let check_chain_id = config.check_chain_id.unwrap();
+    let align_batches_to_blocks = config.align_batches_to_blocks.unwrap();
+    let allow_gap_recovery = config.allow_gap_recovery.unwrap();
     let skip_migrations = config.skip_migrations.unwrap();
     let fetch_tasks = config.fetch_tasks.unwrap();
     let processor_tasks = config.processor_tasks.unwrap();
     let emit_every = config.emit_every.unwrap();
     let batch_size = config.batch_size.unwrap();
     let lookback_versions = config.gap_lookback_versions.unwrap() as i64;
+    let overwrite = config.overwrite_processed_versions.unwrap();
+    let force_starting_version = config.force_starting_version.unwrap();
+    let fan_out_queue_size = config.fan_out_queue_size.unwrap();
 
     info!(processor_name = processor_name, "Starting indexer...");
 
@@ -121,26 +186,76 @@ pub async fn run_forever(config: IndexerConfig, context: Arc<Context>) {
         "Created the connection pool... "
     );
 
+    if let Some(path) = config.query_stats_path.clone() {
+        info!(
+            processor_name = processor_name,
+            query_stats_path = path,
+            "Collecting query statistics..."
+        );
+        enable_query_stats();
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(Duration::from_secs(60));
+            loop {
+                interval.tick().await;
+                if let Err(err) = write_query_stats(&path) {
+                    warn!(
+                        query_stats_path = path,
+                        error = ?err,
+                        "Failed to write query statistics"
+                    );
+                }
+            }
+        });
+    }
+
     info!(processor_name = processor_name, "Instantiating tailer... ");
 
     let processor_enum = Processor::from_string(&processor_name);
-    let processor: Arc<dyn TransactionProcessor> = match processor_enum {
-        Processor::DefaultProcessor => {
-            Arc::new(DefaultTransactionProcessor::new(conn_pool.clone()))
-        },
-        Processor::TokenProcessor => Arc::new(TokenTransactionProcessor::new(
-            conn_pool.clone(),
-            config.ans_contract_address,
-        )),
-        Processor::CoinProcessor => Arc::new(CoinTransactionProcessor::new(conn_pool.clone())),
-        Processor::StakeProcessor => Arc::new(StakeTransactionProcessor::new(conn_pool.clone())),
+    let processor = new_processor(&processor_enum, &config, conn_pool.clone());
+    // The other processors are fed the batches read for `processor`, each through its own queue
+    let fan_out_processors: Vec<Arc<dyn TransactionProcessor>> = config
+        .fan_out_processors
+        .iter()
+        .map(|name| new_processor(&Processor::from_string(name), &config, conn_pool.clone()))
+        .collect();
+    let processor_names: Vec<String> = std::iter::once(processor_name.clone())
+        .chain(config.fan_out_processors.iter().cloned())
+        .collect();
+    // The Parquet files are written in order of version, a batch at a time
+    let processor_tasks = if matches!(processor_enum, Processor::ParquetBlockMetadataProcessor) {
+        1
+    } else {
+        processor_tasks
     };
 
     let options =
         TransactionFetcherOptions::new(None, None, Some(batch_size), None, fetch_tasks as usize);
 
-    let tailer = Tailer::new(context, conn_pool.clone(), processor, options)
-        .expect("Failed to instantiate tailer");
+    let skip_list = VersionSkipList::new(&config.skip_versions, &config.skip_ranges);
+    if !skip_list.is_empty() {
+        warn!(
+            processor_name = processor_name,
+            skip_list = skip_list.to_string(),
+            "Operator skip list is active, these versions will NOT be processed"
+        );
+    }
+
+    let mut tailer = Tailer::new(context, conn_pool.clone(), processor.clone(), options)
+        .expect("Failed to instantiate tailer")
+        .with_skip_list(skip_list);
+    if align_batches_to_blocks {
+        info!(
+            processor_name = processor_name,
+            "Batches will be aligned to blocks"
+        );
+        tailer = tailer.with_block_aligned_batches(batch_size as usize);
+    }
+    if allow_gap_recovery {
+        tailer = tailer.with_gap_recovery();
+    }
+    if let Some(ending_version) = config.ending_version {
+        tailer = tailer.with_ending_version(ending_version);
+    }
 
     if !skip_migrations {
         info!(processor_name = processor_name, "Running migrations...");
@@ -152,29 +267,35 @@ pub async fn run_forever(config: IndexerConfig, context: Arc<Context>) {
         lookback_versions = lookback_versions,
         "Fetching starting version from db..."
     );
-    // For now this is not being used but we'd want to track it anyway
-    let starting_version_from_db_short = tailer
-        .get_start_version(&processor_name)
-        .unwrap_or_else(|e| panic!("Failed to get starting version: {:?}", e))
-        .unwrap_or_else(|| {
-            info!(
-                processor_name = processor_name,
-                "No starting version from db so starting from version 0"
-            );
-            0
-        }) as u64;
-    let start_version = match config.starting_version {
-        None => starting_version_from_db_short,
-        Some(version) => version,
-    };
-
-    info!(
-        processor_name = processor_name,
-        final_start_version = start_version,
-        start_version_from_config = config.starting_version,
-        starting_version_from_db = starting_version_from_db_short,
-        "Setting starting version..."
+    let start_version = resume_version(
+        &tailer,
+        &processor_names,
+        config.starting_version,
+        force_starting_version,
+        config.ending_version,
     );
+    if let Some(ending_version) = config.ending_version {
+        assert!(
+            start_version <= ending_version,
+            "Starting version {} is after the ending version {}",
+            start_version,
+            ending_version
+        );
+        for name in &processor_names {
+            if overwrite {
+                warn!(
+                    processor_name = name,
+                    start_version = start_version,
+                    ending_version = ending_version,
+                    "Versions already processed in the range will be overwritten"
+                );
+            } else {
+                tailer
+                    .ensure_unprocessed(name, start_version, ending_version)
+                    .unwrap_or_else(|e| panic!("Refusing to process the range: {:?}", e));
+            }
+        }
+    }
     tailer.set_fetcher_version(start_version).await;
 
     info!(processor_name = processor_name, "Starting fetcher...");
@@ -186,9 +307,6 @@ pub async fn run_forever(config: IndexerConfig, context: Arc<Context>) {
         "Indexing loop started!"
     );
 
-    let mut versions_processed: u64 = 0;
-    let mut base: u64 = 0;
-
     // Check once here to avoid a boolean check every iteration
     if check_chain_id {
         tailer
@@ -197,6 +315,163 @@ pub async fn run_forever(config: IndexerConfig, context: Arc<Context>) {
             .expect("Failed to get chain ID");
     }
 
+    let started = Instant::now();
+    let summaries = if fan_out_processors.is_empty() {
+        let summary = process_batches(
+            &tailer,
+            &processor_name,
+            processor_tasks,
+            emit_every,
+            config.ending_version,
+        )
+        .await;
+        vec![(processor_name, summary)]
+    } else {
+        info!(
+            processor_names = format!("{:?}", processor_names),
+            queue_size = fan_out_queue_size,
+            "Fanning the batches out to the processors"
+        );
+        let processors = std::iter::once(processor)
+            .chain(fan_out_processors)
+            .collect();
+        fan_out_batches(
+            &tailer,
+            processors,
+            fan_out_queue_size,
+            config.ending_version,
+        )
+        .await
+    };
+    for (processor_name, summary) in summaries {
+        info!(
+            processor_name = processor_name,
+            start_version = start_version,
+            last_processed_version = summary.last_processed_version,
+            versions_processed = summary.versions_processed,
+            rows_written = format!("{:?}", summary.rows_written),
+            elapsed_secs = started.elapsed().as_secs_f64(),
+            "Processed every version up to the ending version!"
+        );
+    }
+}
+
+/// Creates the processor to run, `config` being filled with defaults already
+fn new_processor(
+    processor: &Processor,
+    config: &IndexerConfig,
+    conn_pool: PgDbPool,
+) -> Arc<dyn TransactionProcessor> {
+    match processor {
+        Processor::DefaultProcessor => Arc::new(DefaultTransactionProcessor::new(conn_pool)),
+        Processor::TokenProcessor => Arc::new(TokenTransactionProcessor::new(
+            conn_pool,
+            config.ans_contract_address.clone(),
+        )),
+        Processor::CoinProcessor => Arc::new(CoinTransactionProcessor::new(conn_pool)),
+        Processor::StakeProcessor => Arc::new(StakeTransactionProcessor::new(conn_pool)),
+        Processor::ParquetBlockMetadataProcessor => {
+            let parquet_config = ParquetWriterConfig {
+                dir: config
+                    .parquet_dir
+                    .clone()
+                    .expect("parquet_dir must be set for the parquet processors")
+                    .into(),
+                max_rows: config.parquet_max_rows.unwrap(),
+                max_buffer_age: Duration::from_secs(config.parquet_max_buffer_secs.unwrap()),
+            };
+            let object_store = config
+                .parquet_upload_dir
+                .clone()
+                .map(|dir| Arc::new(LocalDirObjectStore::new(dir)) as Arc<dyn ObjectStore>);
+            Arc::new(ParquetBlockMetadataProcessor::new(
+                conn_pool,
+                parquet_config,
+                object_store,
+            ))
+        },
+    }
+}
+
+/// Resumes right after the last version processed, falling back to `starting_version` if no
+/// version was processed yet, or 0. A forced `starting_version` is always used, and becomes the
+/// version to resume from. A range with an `ending_version` starts at `starting_version` and
+/// doesn't move the version to resume from backwards.
+///
+/// The processors share the batches read, so they all resume from the earliest of their versions,
+/// and the ones further along process the versions in between again.
+pub fn resume_version(
+    tailer: &Tailer,
+    processor_names: &[String],
+    starting_version: Option<u64>,
+    force_starting_version: bool,
+    ending_version: Option<u64>,
+) -> u64 {
+    let start_versions: Vec<(Option<u64>, u64)> = processor_names
+        .iter()
+        .map(|processor_name| {
+            let starting_version_from_db = tailer
+                .get_start_version(processor_name)
+                .unwrap_or_else(|e| panic!("Failed to get starting version: {:?}", e))
+                .map(|version| version as u64);
+            let start_version = match (starting_version_from_db, starting_version) {
+                (_, Some(version)) if force_starting_version || ending_version.is_some() => version,
+                (Some(version), _) => version,
+                (None, version) => version.unwrap_or(0),
+            };
+            info!(
+                processor_name = processor_name,
+                final_start_version = start_version,
+                start_version_from_config = starting_version,
+                starting_version_from_db = starting_version_from_db,
+                "Setting starting version..."
+            );
+            (starting_version_from_db, start_version)
+        })
+        .collect();
+    let start_version = start_versions
+        .iter()
+        .map(|(_, start_version)| *start_version)
+        .min()
+        .expect("At least one processor to resume");
+
+    // Batches only move the last version processed forward when they follow it
+    if ending_version.is_none() {
+        for (processor_name, (starting_version_from_db, own_start_version)) in
+            processor_names.iter().zip(start_versions)
+        {
+            if starting_version_from_db == Some(start_version) {
+                continue;
+            }
+            if own_start_version > start_version {
+                warn!(
+                    processor_name = processor_name,
+                    own_start_version = own_start_version,
+                    start_version = start_version,
+                    "Moving the version to resume from back to the earliest of the fanned out \
+                     processors, the versions in between will be processed again"
+                );
+            }
+            tailer
+                .reset_last_processed_version(processor_name, start_version as i64 - 1)
+                .unwrap_or_else(|e| panic!("Failed to reset the last processed version: {:?}", e));
+        }
+    }
+    start_version
+}
+
+/// Processes rounds of `processor_tasks` batches, recording the last version processed after
+/// each round. Returns once every version up to `ending_version` is processed, or never if it
+/// isn't set.
+pub async fn process_batches(
+    tailer: &Tailer,
+    processor_name: &str,
+    processor_tasks: u8,
+    emit_every: u64,
+    ending_version: Option<u64>,
+) -> IndexingSummary {
+    let mut summary = IndexingSummary::default();
+    let mut base: u64 = 0;
     let mut ma = MovingAverage::new(10_000);
 
     loop {
@@ -220,55 +495,396 @@ pub async fn run_forever(config: IndexerConfig, context: Arc<Context>) {
                 // When the batch is empty b/c we're caught up, continue to next batch
                 None => continue,
                 Some(Ok(res)) => res,
-                Some(Err(tpe)) => {
-                    let (err, start_version, end_version, _) = tpe.inner();
-                    error!(
-                        processor_name = processor_name,
-                        start_version = start_version,
-                        end_version = end_version,
-                        error =? err,
-                        "Error processing batch!"
-                    );
-                    panic!(
-                        "Error in '{}' while processing batch: {:?}",
-                        processor_name, err
-                    );
-                },
+                Some(Err(tpe)) => fail_batch(tailer, processor_name, tpe),
             };
             batch_start_version =
                 std::cmp::min(batch_start_version, processed_result.start_version);
             batch_end_version = std::cmp::max(batch_end_version, processed_result.end_version);
             num_res += num_txn;
+            for (table, rows) in processed_result.rows_written {
+                *summary.rows_written.entry(table).or_default() += rows;
+            }
         }
 
-        tailer
-            .update_last_processed_version(&processor_name, batch_end_version)
-            .unwrap_or_else(|e| {
-                error!(
-                    processor_name = processor_name,
-                    end_version = batch_end_version,
-                    error = format!("{:?}", e),
-                    "Failed to update last processed version!"
-                );
-                panic!("Failed to update last processed version: {:?}", e);
-            });
+        // The versions before a range to backfill may not be processed yet, the batches only move
+        // the last version processed along when they follow it
+        if ending_version.is_none() {
+            update_last_processed_version(tailer, processor_name, batch_end_version);
+        }
 
         ma.tick_now(num_res);
 
-        versions_processed += num_res;
+        summary.versions_processed += num_res;
         if emit_every != 0 {
-            let new_base: u64 = versions_processed / emit_every;
+            let new_base: u64 = summary.versions_processed / emit_every;
             if base != new_base {
                 base = new_base;
                 info!(
                     processor_name = processor_name,
                     batch_start_version = batch_start_version,
                     batch_end_version = batch_end_version,
-                    versions_processed = versions_processed,
+                    versions_processed = summary.versions_processed,
                     tps = (ma.avg() * 1000.0) as u64,
                     "Processed batch version"
                 );
             }
         }
+
+        // Batches are handed out in order, so every version up to the end of the round is done
+        if batch_start_version != u64::MAX {
+            summary.last_processed_version = Some(batch_end_version);
+        }
+        if let (Some(ending_version), Some(last_processed_version)) =
+            (ending_version, summary.last_processed_version)
+        {
+            if last_processed_version >= ending_version {
+                return summary;
+            }
+        }
+    }
+}
+
+/// Reads every batch once and hands it to each of the processors through a queue of
+/// `queue_size` batches, see `crate::indexer::fan_out`. Every processor processes its batches in
+/// order and records its last processed version after each. Returns once every processor
+/// processed every version up to `ending_version`, or never if it isn't set.
+pub async fn fan_out_batches(
+    tailer: &Tailer,
+    processors: Vec<Arc<dyn TransactionProcessor>>,
+    queue_size: usize,
+    ending_version: Option<u64>,
+) -> Vec<(String, IndexingSummary)> {
+    let processor_names: Vec<String> = processors
+        .iter()
+        .map(|processor| processor.name().to_string())
+        .collect();
+    let (fan_out, queues) = fan_out::<Vec<Transaction>>(&processor_names, queue_size);
+    let tasks: Vec<_> = queues
+        .into_iter()
+        .zip(processors)
+        .map(|(mut queue, processor)| {
+            let tailer = tailer.with_processor(processor);
+            tokio::spawn(async move {
+                let processor_name = queue.processor_name().to_string();
+                let mut summary = IndexingSummary::default();
+                while let Some(transactions) = queue.recv().await {
+                    let num_txns = transactions.len() as u64;
+                    let processed_result = match tailer.process_batch(transactions).await {
+                        Ok(res) => res,
+                        Err(tpe) => fail_batch(&tailer, &processor_name, tpe),
+                    };
+                    // Same as for `process_batches`
+                    if ending_version.is_none() {
+                        update_last_processed_version(
+                            &tailer,
+                            &processor_name,
+                            processed_result.end_version,
+                        );
+                    }
+                    summary.versions_processed += num_txns;
+                    summary.last_processed_version = Some(processed_result.end_version);
+                    for (table, rows) in processed_result.rows_written {
+                        *summary.rows_written.entry(table).or_default() += rows;
+                    }
+                }
+                (processor_name, summary)
+            })
+        })
+        .collect();
+
+    let mut last_fetched_version = None;
+    loop {
+        if let (Some(ending_version), Some(last_fetched_version)) =
+            (ending_version, last_fetched_version)
+        {
+            if last_fetched_version >= ending_version {
+                break;
+            }
+        }
+        let transactions = match tailer.fetch_next_batch().await {
+            Ok(transactions) => transactions,
+            Err(tpe) => fail_batch(tailer, tpe.inner().3, tpe),
+        };
+        // When the batch is empty b/c we're caught up, continue to next batch
+        let end_version = match transactions.last() {
+            Some(txn) => txn.version().unwrap(),
+            None => continue,
+        };
+        // Only fails if a processor stopped, which its task reports below
+        if let Err(err) = fan_out.send(transactions).await {
+            error!(error = format!("{:?}", err), "Stopped fanning out batches");
+            break;
+        }
+        last_fetched_version = Some(end_version);
+    }
+    // Lets the processors drain their queues and stop
+    drop(fan_out);
+    await_tasks(tasks).await
+}
+
+/// Records the error the batch failed with, and stops the indexer
+fn fail_batch(tailer: &Tailer, processor_name: &str, tpe: TransactionProcessingError) -> ! {
+    let (err, start_version, end_version, _) = tpe.inner();
+    error!(
+        processor_name = processor_name,
+        start_version = start_version,
+        end_version = end_version,
+        error =? err,
+        "Error processing batch!"
+    );
+    if let Err(e) = tailer.record_processing_error(processor_name, &format!("{:?}", err)) {
+        error!(
+            processor_name = processor_name,
+            error = format!("{:?}", e),
+            "Failed to record the processing error!"
+        );
+    }
+    panic!(
+        "Error in '{}' while processing batch: {:?}",
+        processor_name, err
+    );
+}
+
+fn update_last_processed_version(tailer: &Tailer, processor_name: &str, end_version: u64) {
+    tailer
+        .update_last_processed_version(processor_name, end_version)
+        .unwrap_or_else(|e| {
+            error!(
+                processor_name = processor_name,
+                end_version = end_version,
+                error = format!("{:?}", e),
+                "Failed to update last processed version!"
+            );
+            panic!("Failed to update last processed version: {:?}", e);
+        });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        indexer::{
+            tailer::test::{new_tailer, setup_indexer, state_checkpoint_txn, FakeFetcher},
+            transaction_processor::TransactionProcessor,
+        },
+        processors::{
+            coin_processor::{self, CoinTransactionProcessor},
+            default_processor::{DefaultTransactionProcessor, NAME},
+        },
+        schema::transactions,
+    };
+    use diesel::{QueryDsl, RunQueryDsl};
+    use tokio::sync::Mutex;
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_stops_at_the_ending_version() {
+        if crate::should_skip_pg_tests() {
+            return;
+        }
+        let (conn_pool, tailer) = setup_indexer().unwrap();
+        let mut tailer = tailer.with_ending_version(17);
+        let mut fetcher = FakeFetcher::new(None);
+        for start_version in [10, 15, 20] {
+            fetcher.batches.push_back(
+                (start_version..start_version + 5)
+                    .map(state_checkpoint_txn)
+                    .collect(),
+            );
+        }
+        let fetcher = Arc::new(Mutex::new(fetcher));
+        tailer.transaction_fetcher = fetcher.clone();
+
+        let summary = process_batches(&tailer, NAME, 1, 0, Some(17)).await;
+        assert_eq!(summary.last_processed_version, Some(17));
+        assert_eq!(summary.versions_processed, 8);
+        assert_eq!(summary.rows_written.get("transactions"), Some(&8));
+        assert_eq!(summary.rows_written.get("user_transactions"), Some(&0));
+
+        // The batch reaching the ending version is cut right after it, and the next one is never
+        // fetched
+        let versions: Vec<i64> = transactions::table
+            .select(transactions::version)
+            .order(transactions::version)
+            .load(&mut conn_pool.get().unwrap())
+            .unwrap();
+        assert_eq!(versions, (10..=17).collect::<Vec<_>>());
+        assert_eq!(fetcher.lock().await.batches.len(), 1);
+        // Nothing was processed before the range, so there is no version to resume from
+        assert_eq!(tailer.get_start_version(&NAME.to_string()).unwrap(), None);
+
+        // Processing the range again would overwrite it
+        assert!(tailer.ensure_unprocessed(NAME, 15, 30).is_err());
+        assert!(tailer.ensure_unprocessed(NAME, 18, 30).is_ok());
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_fans_batches_out_to_every_processor() {
+        if crate::should_skip_pg_tests() {
+            return;
+        }
+        let (conn_pool, mut tailer) = setup_indexer().unwrap();
+        let fetcher = batches(&[10, 15, 20, 25]);
+        tailer.transaction_fetcher = fetcher.clone();
+        let tailer = tailer.with_ending_version(27);
+        tailer.set_fetcher_version(10).await;
+        let processors: Vec<Arc<dyn TransactionProcessor>> = vec![
+            Arc::new(DefaultTransactionProcessor::new(conn_pool.clone())),
+            Arc::new(CoinTransactionProcessor::new(conn_pool.clone())),
+        ];
+
+        // Queues of a single batch, so reading pauses whenever a processor is a batch behind
+        let summaries = fan_out_batches(&tailer, processors, 1, Some(27)).await;
+        assert_eq!(summaries.len(), 2);
+        for (processor_name, summary) in &summaries {
+            assert_eq!(summary.versions_processed, 18);
+            assert_eq!(summary.last_processed_version, Some(27));
+            assert_eq!(
+                tailer.get_last_processed_version(processor_name).unwrap(),
+                Some(27)
+            );
+        }
+        assert_eq!(summaries[0].0, NAME);
+        assert_eq!(summaries[0].1.rows_written.get("transactions"), Some(&18));
+
+        // Every batch was read once, for both processors
+        assert!(fetcher.lock().await.batches.is_empty());
+        let versions: Vec<i64> = transactions::table
+            .select(transactions::version)
+            .order(transactions::version)
+            .load(&mut conn_pool.get().unwrap())
+            .unwrap();
+        assert_eq!(versions, (10..=27).collect::<Vec<_>>());
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_backfill_ahead_of_the_last_processed_version() {
+        if crate::should_skip_pg_tests() {
+            return;
+        }
+        let (_conn_pool, mut tailer) = setup_indexer().unwrap();
+        let name = NAME.to_string();
+        tailer.transaction_fetcher = batches(&[10]);
+        let start_version = resume_version(&tailer, &[name.clone()], Some(10), false, None);
+        tailer.set_fetcher_version(start_version).await;
+        process_batches(&tailer, NAME, 1, 0, Some(14)).await;
+        assert_eq!(tailer.get_last_processed_version(&name).unwrap(), Some(14));
+
+        // Backfilling 50 to 59 leaves 15 to 49 to process after a restart
+        let backfill = |start_versions: &[u64], ending_version| {
+            let mut tailer = tailer.clone().with_ending_version(ending_version);
+            tailer.transaction_fetcher = batches(start_versions);
+            tailer
+        };
+        let tailer_50 = backfill(&[50, 55], 59);
+        let start_version = resume_version(&tailer_50, &[name.clone()], Some(50), false, Some(59));
+        assert_eq!(start_version, 50);
+        tailer_50.set_fetcher_version(start_version).await;
+        let summary = process_batches(&tailer_50, NAME, 2, 0, Some(59)).await;
+        assert_eq!(summary.last_processed_version, Some(59));
+        assert_eq!(tailer.get_last_processed_version(&name).unwrap(), Some(14));
+        assert_eq!(
+            resume_version(&tailer, &[name.clone()], Some(10), false, None),
+            15
+        );
+
+        // While a range right after the last version processed moves it along
+        let tailer_15 = backfill(&[15], 19);
+        tailer_15.set_fetcher_version(15).await;
+        process_batches(&tailer_15, NAME, 1, 0, Some(19)).await;
+        assert_eq!(tailer.get_last_processed_version(&name).unwrap(), Some(19));
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_fanned_out_processors_resume_from_the_earliest_version() {
+        if crate::should_skip_pg_tests() {
+            return;
+        }
+        let (_conn_pool, mut tailer) = setup_indexer().unwrap();
+        let name = NAME.to_string();
+        tailer.transaction_fetcher = batches(&[10]);
+        let start_version = resume_version(&tailer, &[name.clone()], Some(10), false, None);
+        tailer.set_fetcher_version(start_version).await;
+        process_batches(&tailer, NAME, 1, 0, Some(14)).await;
+        assert_eq!(tailer.get_start_version(&name).unwrap(), Some(15));
+
+        // A processor added to the fan out starts at 10, which moves the first one back to it
+        let coin_name = coin_processor::NAME.to_string();
+        let names = [name.clone(), coin_name.clone()];
+        assert_eq!(resume_version(&tailer, &names, Some(10), false, None), 10);
+        assert_eq!(tailer.get_start_version(&name).unwrap(), Some(10));
+        assert_eq!(tailer.get_start_version(&coin_name).unwrap(), Some(10));
+    }
+
+    fn batches(start_versions: &[u64]) -> Arc<Mutex<FakeFetcher>> {
+        let mut fetcher = FakeFetcher::new(None);
+        for start_version in start_versions {
+            fetcher.batches.push_back(
+                (*start_version..start_version + 5)
+                    .map(state_checkpoint_txn)
+                    .collect(),
+            );
+        }
+        Arc::new(Mutex::new(fetcher))
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_resumes_after_a_crash() {
+        if crate::should_skip_pg_tests() {
+            return;
+        }
+        let (conn_pool, mut tailer) = setup_indexer().unwrap();
+        let name = NAME.to_string();
+
+        // Nothing was processed yet, so the configured version is used
+        tailer.transaction_fetcher = batches(&[10, 15]);
+        let start_version = resume_version(&tailer, &[name.clone()], Some(10), false, None);
+        assert_eq!(start_version, 10);
+        assert_eq!(tailer.get_start_version(&name).unwrap(), Some(10));
+        tailer.set_fetcher_version(start_version).await;
+
+        // The batch moves the last processed version along with its rows
+        assert!(tailer.process_next_batch().await.1.unwrap().is_ok());
+        assert_eq!(tailer.get_last_processed_version(&name).unwrap(), Some(14));
+
+        // A batch of the same round committed first doesn't, as 15 to 19 aren't written yet
+        DefaultTransactionProcessor::new(conn_pool.clone())
+            .process_transactions_with_status((20..25).map(state_checkpoint_txn).collect())
+            .await
+            .unwrap();
+        assert_eq!(tailer.get_last_processed_version(&name).unwrap(), Some(14));
+
+        // The indexer crashes before the end of the round and restarts with the same config
+        drop(tailer);
+        let mut tailer = new_tailer(conn_pool.clone()).unwrap();
+        tailer.transaction_fetcher = batches(&[15, 20]);
+        let start_version = resume_version(&tailer, &[name.clone()], Some(10), false, None);
+        assert_eq!(start_version, 15);
+        tailer.set_fetcher_version(start_version).await;
+        let summary = process_batches(&tailer, NAME, 1, 0, Some(24)).await;
+        assert_eq!(summary.versions_processed, 10);
+        assert_eq!(summary.last_processed_version, Some(24));
+        assert_eq!(tailer.get_last_processed_version(&name).unwrap(), Some(24));
+
+        // Every version was written, and only the ones after the crash were processed again
+        let versions: Vec<i64> = transactions::table
+            .select(transactions::version)
+            .order(transactions::version)
+            .load(&mut conn_pool.get().unwrap())
+            .unwrap();
+        assert_eq!(versions, (10..25).collect::<Vec<_>>());
+
+        // A range to backfill leaves the version to resume from alone
+        assert_eq!(
+            resume_version(&tailer, &[name.clone()], Some(0), false, Some(5)),
+            0
+        );
+        assert_eq!(tailer.get_start_version(&name).unwrap(), Some(25));
+
+        // Unlike forcing the starting version
+        assert_eq!(
+            resume_version(&tailer, &[name.clone()], Some(12), true, None),
+            12
+        );
+        assert_eq!(tailer.get_start_version(&name).unwrap(), Some(12));
     }
 }
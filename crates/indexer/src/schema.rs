@@ -18,6 +18,32 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    block_validator_participation (block_height, validator_index) {
+        block_height -> Int8,
+        validator_index -> Int8,
+        version -> Int8,
+        epoch -> Int8,
+        validator_address -> Nullable<Varchar>,
+        voted -> Bool,
+        failed_to_propose -> Bool,
+        inserted_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    blocks (block_height) {
+        block_height -> Int8,
+        block_hash -> Varchar,
+        first_version -> Int8,
+        last_version -> Nullable<Int8>,
+        timestamp -> Timestamp,
+        epoch -> Int8,
+        proposer -> Varchar,
+        inserted_at -> Timestamp,
+    }
+}
+
 diesel::table! {
     coin_activities (transaction_version, event_account_address, event_creation_number, event_sequence_number) {
         transaction_version -> Int8,
@@ -292,6 +318,7 @@ diesel::table! {
         processor -> Varchar,
         last_success_version -> Int8,
         last_updated -> Timestamp,
+        last_error -> Nullable<Text>,
     }
 }
 
@@ -336,6 +363,17 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    state_checkpoint_transactions (version) {
+        version -> Int8,
+        block_height -> Int8,
+        state_checkpoint_hash -> Nullable<Varchar>,
+        epoch -> Int8,
+        timestamp -> Timestamp,
+        inserted_at -> Timestamp,
+    }
+}
+
 diesel::table! {
     table_items (transaction_version, write_set_change_index) {
         key -> Text,
@@ -462,6 +500,10 @@ diesel::table! {
         num_write_set_changes -> Int8,
         inserted_at -> Timestamp,
         epoch -> Int8,
+        failure_category -> Nullable<Varchar>,
+        abort_module_address -> Nullable<Varchar>,
+        abort_module_name -> Nullable<Varchar>,
+        abort_code -> Nullable<Numeric>,
     }
 }
 
@@ -497,6 +539,8 @@ diesel::table! {
 
 diesel::allow_tables_to_appear_in_same_query!(
     block_metadata_transactions,
+    block_validator_participation,
+    blocks,
     coin_activities,
     coin_balances,
     coin_infos,
@@ -519,6 +563,7 @@ diesel::allow_tables_to_appear_in_same_query!(
     processor_statuses,
     proposal_votes,
     signatures,
+    state_checkpoint_transactions,
     table_items,
     table_metadatas,
     token_activities,
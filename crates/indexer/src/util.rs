@@ -17,10 +17,39 @@ pub fn standardize_address(handle: &str) -> String {
     format!("0x{:0>64}", &handle[2..])
 }
 
+/// Standardizes a 32 byte hash to be 0x-prefixed lowercase hex, if it is one
+pub fn standardize_hash(hash: &str) -> Option<String> {
+    let hex = hash.strip_prefix("0x").unwrap_or(hash);
+    if hex.len() == 64 && hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        Some(format!("0x{}", hex.to_ascii_lowercase()))
+    } else {
+        None
+    }
+}
+
 pub fn hash_str(val: &str) -> String {
     hex::encode(sha2::Sha256::digest(val.as_bytes()))
 }
 
+/// Normalized form of a 0x3 token data id, i.e. `creator::collection::name` with the creator
+/// address standardized so that `0x1` and `0x0..01` refer to the same token
+pub fn token_data_id_key(creator: &str, collection: &str, name: &str) -> String {
+    format!("{}::{}", collection_data_id_key(creator, collection), name)
+}
+
+/// Normalized form of a 0x3 collection data id, i.e. `creator::collection`
+pub fn collection_data_id_key(creator: &str, collection: &str) -> String {
+    format!("{}::{}", standardize_address(creator), collection)
+}
+
+pub fn token_data_id_hash(creator: &str, collection: &str, name: &str) -> String {
+    hash_str(&token_data_id_key(creator, collection, name))
+}
+
+pub fn collection_data_id_hash(creator: &str, collection: &str) -> String {
+    hash_str(&collection_data_id_key(creator, collection))
+}
+
 pub fn truncate_str(val: &str, max_chars: usize) -> String {
     let mut trunc = val.to_string();
     trunc.truncate(max_chars);
@@ -43,17 +72,32 @@ pub fn ensure_not_negative(val: BigDecimal) -> BigDecimal {
     val
 }
 
+/// Parses a timestamp in microseconds, the unit of the transaction and block timestamps
 pub fn parse_timestamp(ts: u64, version: i64) -> chrono::NaiveDateTime {
-    chrono::NaiveDateTime::from_timestamp_opt((ts / 1000000) as i64, 0)
-        .unwrap_or_else(|| panic!("Could not parse timestamp {:?} for version {}", ts, version))
+    to_clamped_datetime(ts / 1_000_000, (ts % 1_000_000) as u32 * 1_000, ts, version)
 }
 
+/// Parses a timestamp in seconds, the unit of e.g. expiration timestamps set on chain
 pub fn parse_timestamp_secs(ts: u64, version: i64) -> chrono::NaiveDateTime {
-    chrono::NaiveDateTime::from_timestamp_opt(
-        std::cmp::min(ts, MAX_TIMESTAMP_SECS as u64) as i64,
-        0,
-    )
-    .unwrap_or_else(|| panic!("Could not parse timestamp {:?} for version {}", ts, version))
+    to_clamped_datetime(ts, 0, ts, version)
+}
+
+/// Timestamps past `MAX_TIMESTAMP_SECS` can't be stored everywhere, or even represented, so
+/// they are clamped to it rather than failing the whole batch
+fn to_clamped_datetime(secs: u64, nanos: u32, ts: u64, version: i64) -> chrono::NaiveDateTime {
+    let (secs, nanos) = if secs > MAX_TIMESTAMP_SECS as u64 {
+        aptos_logger::warn!(
+            version = version,
+            timestamp = ts,
+            "Timestamp is out of range, clamping it to {}",
+            MAX_TIMESTAMP_SECS
+        );
+        (MAX_TIMESTAMP_SECS, 0)
+    } else {
+        (secs as i64, nanos)
+    };
+    chrono::NaiveDateTime::from_timestamp_opt(secs, nanos)
+        .unwrap_or_else(|| panic!("Could not parse timestamp {:?} for version {}", ts, version))
 }
 
 pub fn remove_null_bytes<T: serde::Serialize + for<'de> serde::Deserialize<'de>>(input: &T) -> T {
@@ -169,6 +213,7 @@ mod tests {
     fn test_parse_timestamp() {
         let ts = parse_timestamp(1649560602763949, 1);
         assert_eq!(ts.timestamp(), 1649560602);
+        assert_eq!(ts.timestamp_subsec_micros(), 763949);
         assert_eq!(ts.year(), 2022);
 
         let ts2 = parse_timestamp_secs(600000000000000, 2);
@@ -178,6 +223,76 @@ mod tests {
         assert_eq!(ts3.timestamp(), 1659386386);
     }
 
+    #[test]
+    fn test_parse_genesis_timestamp() {
+        assert_eq!(parse_timestamp(0, 0).timestamp(), 0);
+        assert_eq!(parse_timestamp_secs(0, 0).timestamp(), 0);
+    }
+
+    #[test]
+    fn test_parse_timestamp_units() {
+        // Microseconds read as seconds are far in the future, and clamped
+        let ts = parse_timestamp_secs(1649560602763949, 1);
+        assert_eq!(ts.timestamp(), MAX_TIMESTAMP_SECS);
+        // Seconds read as microseconds are in 1970
+        let ts = parse_timestamp(1649560602, 1);
+        assert_eq!(ts.year(), 1970);
+    }
+
+    #[test]
+    fn test_parse_out_of_range_timestamp() {
+        let ts = parse_timestamp(u64::MAX, 1);
+        assert_eq!(ts.timestamp(), MAX_TIMESTAMP_SECS);
+        assert_eq!(ts.timestamp_subsec_nanos(), 0);
+        assert_eq!(
+            parse_timestamp_secs(u64::MAX, 1).timestamp(),
+            MAX_TIMESTAMP_SECS
+        );
+        // The bound itself is kept as is
+        let ts = parse_timestamp((MAX_TIMESTAMP_SECS as u64) * 1_000_000 + 999_999, 1);
+        assert_eq!(ts.timestamp(), MAX_TIMESTAMP_SECS);
+        assert_eq!(ts.timestamp_subsec_micros(), 999_999);
+    }
+
+    #[test]
+    fn test_standardize_hash() {
+        let hash = "0x2b7c58ed8524d228f9d0543a82e2793d04e8871df322f976b0e7bb8c5ced4ff5";
+        assert_eq!(standardize_hash(hash).unwrap(), hash);
+        assert_eq!(standardize_hash(&hash[2..]).unwrap(), hash);
+        assert_eq!(standardize_hash(&hash.to_uppercase()[2..]).unwrap(), hash);
+        // Unlike addresses, hashes are never shortened
+        assert!(standardize_hash("0x1").is_none());
+        assert!(standardize_hash(&format!("{}00", hash)).is_none());
+        assert!(standardize_hash(&hash.replace('b', "g")).is_none());
+        assert!(standardize_hash("").is_none());
+    }
+
+    #[test]
+    fn test_token_data_id_hash() {
+        let creator = "0x0000000000000000000000000000000000000000000000000000000000000abc";
+        assert_eq!(
+            token_data_id_key("0xabc", "Aptos Monkeys", "Monkey #1"),
+            format!("{}::Aptos Monkeys::Monkey #1", creator)
+        );
+        assert_eq!(
+            token_data_id_hash("0xabc", "Aptos Monkeys", "Monkey #1"),
+            "fbc74eec90185ea014412a6a3aaffbd78debb66417472bf8722c6d5993fb089a"
+        );
+        assert_eq!(
+            token_data_id_hash("0xabc", "Aptos Monkeys", "Monkey #1"),
+            token_data_id_hash(creator, "Aptos Monkeys", "Monkey #1")
+        );
+        assert_eq!(
+            collection_data_id_hash("0xabc", "Aptos Monkeys"),
+            "42dd6a7c23a49972ac18cc685291c5eb868c80427b02da217791ac4e50528ec6"
+        );
+        // Collection and token names are case sensitive.
+        assert_ne!(
+            token_data_id_hash("0xabc", "Aptos Monkeys", "Monkey #1"),
+            token_data_id_hash("0xabc", "aptos monkeys", "Monkey #1")
+        );
+    }
+
     #[test]
     fn test_deserialize_string_from_bcs() {
         let test_struct = TypeInfoMock {
@@ -0,0 +1,14 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use aptos_metrics_core::{register_int_counter, IntCounter};
+use once_cell::sync::Lazy;
+
+/// Number of times the stream from the fullnode stopped sending messages and was re-established.
+pub static STREAM_STALLED_COUNT: Lazy<IntCounter> = Lazy::new(|| {
+    register_int_counter!(
+        "indexer_grpc_cache_worker_stream_stalled_count",
+        "Number of times the stream from the fullnode stopped sending messages and was re-established"
+    )
+    .unwrap()
+});
@@ -5,12 +5,13 @@ use aptos_protos::datastream::v1::{self as datastream};
 use serde::{Deserialize, Serialize};
 use std::{fs::File, io::Read, path::PathBuf};
 
+pub mod counters;
 pub mod worker;
 
 pub type GrpcClientType =
     datastream::indexer_stream_client::IndexerStreamClient<tonic::transport::Channel>;
 
-#[derive(Clone, Debug, Deserialize, Serialize, Default)]
+#[derive(Clone, Debug, Deserialize, Serialize)]
 #[serde(deny_unknown_fields)]
 pub struct IndexerGrpcCacheWorkerConfig {
     /// Indexer GRPC address, i.e., `127.0.0.1:50051`.
@@ -25,9 +26,41 @@ pub struct IndexerGrpcCacheWorkerConfig {
     /// Starting version; if not provided, will start from the latest version in the cache.
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub starting_version: Option<u64>,
+
+    /// Maximum time to wait for the next message (data or status) before the stream is
+    /// considered stuck and re-established. The fullnode doesn't send heartbeats, so an idle
+    /// chain can't be told apart from a stuck stream: this has to be well above the longest
+    /// expected gap between two blocks, and a quiet chain gets its stream re-established.
+    #[serde(default = "IndexerGrpcCacheWorkerConfig::default_stream_idle_timeout_secs")]
+    pub stream_idle_timeout_secs: u64,
+
+    /// Number of consecutive stream failures after which the worker reports as not ready.
+    #[serde(default = "IndexerGrpcCacheWorkerConfig::default_max_consecutive_stream_failures")]
+    pub max_consecutive_stream_failures: u64,
+}
+
+impl Default for IndexerGrpcCacheWorkerConfig {
+    fn default() -> Self {
+        Self {
+            indexer_address: String::default(),
+            redis_address: String::default(),
+            chain_id: 0,
+            starting_version: None,
+            stream_idle_timeout_secs: Self::default_stream_idle_timeout_secs(),
+            max_consecutive_stream_failures: Self::default_max_consecutive_stream_failures(),
+        }
+    }
 }
 
 impl IndexerGrpcCacheWorkerConfig {
+    pub const fn default_stream_idle_timeout_secs() -> u64 {
+        300
+    }
+
+    pub const fn default_max_consecutive_stream_failures() -> u64 {
+        5
+    }
+
     pub fn load(path: PathBuf) -> Result<Self, anyhow::Error> {
         let mut file = File::open(&path).map_err(|e| {
             anyhow::anyhow!(
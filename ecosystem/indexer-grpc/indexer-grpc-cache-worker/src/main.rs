@@ -31,16 +31,23 @@ fn main() {
 
     let runtime = aptos_runtimes::spawn_named_runtime("indexercache".to_string(), None);
 
+    let mut worker = runtime.block_on(Worker::new(config));
+    let worker_ready = worker.readiness();
+
     // Start processing.
     runtime.spawn(async move {
-        let mut worker = Worker::new(config).await;
         worker.run().await;
     });
 
     // Start liveness/readiness probe.
     runtime.spawn(async move {
-        let readiness = warp::path("readiness")
-            .map(move || warp::reply::with_status("ready", warp::http::StatusCode::OK));
+        let readiness = warp::path("readiness").map(move || {
+            if worker_ready.load(Ordering::SeqCst) {
+                warp::reply::with_status("ready", warp::http::StatusCode::OK)
+            } else {
+                warp::reply::with_status("not ready", warp::http::StatusCode::SERVICE_UNAVAILABLE)
+            }
+        });
         warp::serve(readiness).run(([0, 0, 0, 0], 8080)).await;
     });
     let term = Arc::new(AtomicBool::new(false));
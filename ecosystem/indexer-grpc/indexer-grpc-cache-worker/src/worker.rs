@@ -1,24 +1,46 @@
 // Copyright (c) Aptos
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::{create_grpc_client, get_ttl_in_seconds, IndexerGrpcCacheWorkerConfig};
-use aptos_logger::{error, info};
+use crate::{
+    counters::STREAM_STALLED_COUNT, create_grpc_client, get_ttl_in_seconds,
+    IndexerGrpcCacheWorkerConfig,
+};
+use aptos_logger::{error, info, warn};
 use aptos_moving_average::MovingAverage;
-use aptos_protos::datastream::v1::{
-    self as datastream, RawDatastreamRequest, RawDatastreamResponse,
+use aptos_protos::{
+    datastream::v1::{self as datastream, RawDatastreamRequest, RawDatastreamResponse},
+    TRANSACTION_SCHEMA_VERSION,
 };
 use futures::{self, StreamExt};
 use redis::{Commands, ConnectionLike};
+use std::{
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+
+const RECONNECT_BACKOFF_BASE_MILLIS: u64 = 500;
+const RECONNECT_BACKOFF_MAX_MILLIS: u64 = 30_000;
+
 pub struct Worker {
     redis_client: redis::Client,
     chain_id: u32,
     current_version: u64,
     grpc_address: String,
+    stream_idle_timeout: Duration,
+    max_consecutive_stream_failures: u64,
+    consecutive_stream_failures: u64,
+    ready: Arc<AtomicBool>,
 }
 
+#[derive(Debug)]
 pub(crate) enum WorkerError {
     // Transient error; worker should retry.
     GrpcError(String),
+    // No message received within the idle timeout; worker should reconnect.
+    StreamStalled(Duration),
     // Fatal error; worker should be restarted.
     FatalError(String),
 }
@@ -32,9 +54,18 @@ impl Worker {
             chain_id: config.chain_id,
             current_version: 0,
             grpc_address: format!("http://{}", config.indexer_address),
+            stream_idle_timeout: Duration::from_secs(config.stream_idle_timeout_secs),
+            max_consecutive_stream_failures: config.max_consecutive_stream_failures,
+            consecutive_stream_failures: 0,
+            ready: Arc::new(AtomicBool::new(true)),
         }
     }
 
+    /// Readiness of the worker, cleared after too many consecutive stream failures.
+    pub fn readiness(&self) -> Arc<AtomicBool> {
+        self.ready.clone()
+    }
+
     pub async fn run(&mut self) {
         // Re-connect if lost.
         // TODO: Add a restart from file store.
@@ -43,29 +74,55 @@ impl Worker {
             let mut rpc_client = create_grpc_client(self.grpc_address.clone()).await;
             let request = tonic::Request::new(RawDatastreamRequest {
                 starting_version: self.current_version,
+                max_supported_schema_version: Some(TRANSACTION_SCHEMA_VERSION),
+                resume_token: None,
+                strict_resume: false,
+                ending_version: None,
+                transaction_types: vec![],
+                entry_function_address_prefixes: vec![],
             });
             let response = rpc_client.raw_datastream(request).await.unwrap();
 
-            match self
-                .process_streaming_response(self.current_version, response.into_inner(), conn)
-                .await
-            {
-                Err(WorkerError::FatalError(e)) => {
-                    panic!("[Indexer Cache] Fatal Error: {}", e);
-                },
-                _ => {
-                    // If streaming ends, try to reconnect.
-                    error!("[Indexer Cache] Indexer grpc connection lost. Reconnecting...");
-                },
+            let result = self
+                .process_streaming_response(response.into_inner(), conn)
+                .await;
+            if let Err(WorkerError::FatalError(e)) = result {
+                panic!("[Indexer Cache] Fatal Error: {}", e);
             }
+            // If streaming ends, try to reconnect from the last processed version.
+            error!(
+                current_version = self.current_version,
+                error = ?result.err(),
+                "[Indexer Cache] Indexer grpc connection lost. Reconnecting..."
+            );
+            tokio::time::sleep(self.record_stream_failure()).await;
+        }
+    }
+
+    /// Bumps the consecutive failure count, flipping readiness once it reaches the configured
+    /// maximum, and returns how long to wait before reconnecting.
+    fn record_stream_failure(&mut self) -> Duration {
+        self.consecutive_stream_failures += 1;
+        if self.consecutive_stream_failures >= self.max_consecutive_stream_failures {
+            self.ready.store(false, Ordering::SeqCst);
         }
+        let exponent = std::cmp::min(self.consecutive_stream_failures - 1, 16) as u32;
+        Duration::from_millis(std::cmp::min(
+            RECONNECT_BACKOFF_BASE_MILLIS * 2u64.pow(exponent),
+            RECONNECT_BACKOFF_MAX_MILLIS,
+        ))
+    }
+
+    fn record_stream_progress(&mut self) {
+        self.consecutive_stream_failures = 0;
+        self.ready.store(true, Ordering::SeqCst);
     }
 
-    /// Function to process streaming response from datastream.
-    /// It accepts a starting version, a response stream, and a function to process each transaction.
+    /// Function to process streaming response from datastream, starting at the current version.
+    /// The current version is advanced at the end of every batch so that a reconnection resumes
+    /// right after the last fully processed batch.
     pub(crate) async fn process_streaming_response(
-        &self,
-        starting_version: u64,
+        &mut self,
         mut resp_stream: impl futures_core::Stream<Item = Result<RawDatastreamResponse, tonic::Status>>
             + std::marker::Unpin,
         mut conn: impl ConnectionLike,
@@ -73,9 +130,24 @@ impl Worker {
         let mut ma = MovingAverage::new(10_000);
         let mut init_signal_received = false;
         let mut transaction_count = 0;
-        let mut current_version = starting_version;
+        let mut current_version = self.current_version;
 
-        while let Some(received) = resp_stream.next().await {
+        loop {
+            // A half-open connection never yields another message, so bound the wait.
+            let received =
+                match tokio::time::timeout(self.stream_idle_timeout, resp_stream.next()).await {
+                    Ok(Some(received)) => received,
+                    Ok(None) => break,
+                    Err(_) => {
+                        STREAM_STALLED_COUNT.inc();
+                        warn!(
+                            current_version = self.current_version,
+                            idle_timeout_secs = self.stream_idle_timeout.as_secs(),
+                            "[Indexer Cache] No message received from the stream, reconnecting."
+                        );
+                        return Err(WorkerError::StreamStalled(self.stream_idle_timeout));
+                    },
+                };
             let received = match received {
                 Ok(r) => r,
                 Err(e) => {
@@ -114,6 +186,25 @@ impl Worker {
                             );
                             current_version = status.end_version.expect("End version exists.") + 1;
                             transaction_count = 0;
+                            self.current_version = current_version;
+                            self.record_stream_progress();
+                        },
+                        3 => {
+                            // Dropped by the server for not reading fast enough, resume from
+                            // the last batch end.
+                            return Err(WorkerError::GrpcError(
+                                "[Indexer Cache] Disconnected as a slow consumer. Reconnecting..."
+                                    .to_string(),
+                            ));
+                        },
+                        4 => {
+                            // The server couldn't serve the next batch, resume from the last
+                            // batch end, where the server left off.
+                            return Err(WorkerError::GrpcError(format!(
+                                "[Indexer Cache] Stream failed at version {}: {}. Reconnecting...",
+                                status.start_version,
+                                status.error_message.unwrap_or_default()
+                            )));
                         },
                         _ => {
                             // There might be protobuf inconsistency between server and client.
@@ -158,3 +249,162 @@ impl Worker {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use redis::{RedisResult, Value};
+
+    /// Accepts every command, we only care about the stream handling here.
+    struct AcceptAllConnection;
+
+    impl ConnectionLike for AcceptAllConnection {
+        fn req_packed_command(&mut self, _cmd: &[u8]) -> RedisResult<Value> {
+            Ok(Value::Okay)
+        }
+
+        fn req_packed_commands(
+            &mut self,
+            _cmd: &[u8],
+            _offset: usize,
+            count: usize,
+        ) -> RedisResult<Vec<Value>> {
+            Ok(vec![Value::Okay; count])
+        }
+
+        fn get_db(&self) -> i64 {
+            0
+        }
+
+        fn check_connection(&mut self) -> bool {
+            true
+        }
+
+        fn is_open(&self) -> bool {
+            true
+        }
+    }
+
+    async fn test_worker() -> Worker {
+        Worker::new(IndexerGrpcCacheWorkerConfig {
+            indexer_address: "127.0.0.1:50051".to_string(),
+            redis_address: "127.0.0.1:6379".to_string(),
+            chain_id: 1,
+            stream_idle_timeout_secs: 1,
+            max_consecutive_stream_failures: 2,
+            ..Default::default()
+        })
+        .await
+    }
+
+    fn status(
+        status_type: datastream::stream_status::StatusType,
+        start_version: u64,
+        end_version: Option<u64>,
+    ) -> Result<RawDatastreamResponse, tonic::Status> {
+        Ok(RawDatastreamResponse {
+            chain_id: 1,
+            response: Some(datastream::raw_datastream_response::Response::Status(
+                datastream::StreamStatus {
+                    r#type: status_type as i32,
+                    start_version,
+                    end_version,
+                    schema_version: None,
+                    resume_token: None,
+                    redactions: vec![],
+                    error_message: None,
+                },
+            )),
+        })
+    }
+
+    fn data(versions: std::ops::Range<u64>) -> Result<RawDatastreamResponse, tonic::Status> {
+        Ok(RawDatastreamResponse {
+            chain_id: 1,
+            response: Some(datastream::raw_datastream_response::Response::Data(
+                datastream::TransactionsOutput {
+                    transactions: versions
+                        .map(|version| datastream::TransactionOutput {
+                            encoded_proto_data: "data".to_string(),
+                            version,
+                            timestamp: None,
+                        })
+                        .collect(),
+                },
+            )),
+        })
+    }
+
+    /// A stream that yields the given messages and then goes silent without ever closing.
+    fn stalling_stream(
+        messages: Vec<Result<RawDatastreamResponse, tonic::Status>>,
+    ) -> impl futures_core::Stream<Item = Result<RawDatastreamResponse, tonic::Status>> + Unpin
+    {
+        futures::stream::iter(messages).chain(futures::stream::pending())
+    }
+
+    #[tokio::test]
+    async fn test_stalled_stream_reconnects_without_skipping_versions() {
+        use datastream::stream_status::StatusType;
+        let mut worker = test_worker().await;
+
+        // The stall happens in the middle of the second batch.
+        let first_stream = stalling_stream(vec![
+            status(StatusType::Init, 0, None),
+            data(0..10),
+            status(StatusType::BatchEnd, 0, Some(9)),
+            data(10..15),
+        ]);
+        let result = worker
+            .process_streaming_response(first_stream, AcceptAllConnection)
+            .await;
+        assert!(matches!(result, Err(WorkerError::StreamStalled(_))));
+        // Resumes from the first version of the unfinished batch.
+        assert_eq!(worker.current_version, 10);
+
+        let second_stream = stalling_stream(vec![
+            status(StatusType::Init, 10, None),
+            data(10..20),
+            status(StatusType::BatchEnd, 10, Some(19)),
+        ]);
+        let result = worker
+            .process_streaming_response(second_stream, AcceptAllConnection)
+            .await;
+        assert!(matches!(result, Err(WorkerError::StreamStalled(_))));
+        assert_eq!(worker.current_version, 20);
+    }
+
+    #[tokio::test]
+    async fn test_readiness_after_consecutive_failures() {
+        let mut worker = test_worker().await;
+        let ready = worker.readiness();
+
+        let first_backoff = worker.record_stream_failure();
+        assert!(ready.load(Ordering::SeqCst));
+        let second_backoff = worker.record_stream_failure();
+        assert!(!ready.load(Ordering::SeqCst));
+        assert!(second_backoff > first_backoff);
+
+        worker.record_stream_progress();
+        assert!(ready.load(Ordering::SeqCst));
+    }
+
+    #[test]
+    fn test_default_config_matches_serde_defaults() {
+        let config: IndexerGrpcCacheWorkerConfig = serde_yaml::from_str(
+            "indexer_address: 127.0.0.1:50051\nredis_address: 127.0.0.1:6379\nchain_id: 1\n",
+        )
+        .unwrap();
+        let default = IndexerGrpcCacheWorkerConfig::default();
+        assert_eq!(
+            config.stream_idle_timeout_secs,
+            default.stream_idle_timeout_secs
+        );
+        assert_eq!(
+            config.max_consecutive_stream_failures,
+            default.max_consecutive_stream_failures
+        );
+        assert_eq!(default.stream_idle_timeout_secs, 300);
+        assert_eq!(default.max_consecutive_stream_failures, 5);
+    }
+}
@@ -0,0 +1,93 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+// Run this bencher via `cargo bench -p aptos-indexer-grpc-fullnode --bench stream_benches`.
+use aptos_indexer_grpc_fullnode::stream_coordinator::IndexerStreamCoordinator;
+use aptos_protos::transaction::v1::{
+    transaction::TxnData, transaction_payload::Payload, ModuleBundlePayload, MoveModuleBytecode,
+    Transaction as TransactionPB, TransactionPayload, UserTransaction, UserTransactionRequest,
+};
+use bytes::Bytes;
+use criterion::{criterion_group, criterion_main, BatchSize, BenchmarkId, Criterion, Throughput};
+use prost::Message;
+
+const BYTECODE_SIZES: [usize; 2] = [1 << 20, 4 << 20];
+const NUM_TRANSACTIONS: u64 = 10;
+
+/// A user transaction publishing a module of the given size
+fn module_transaction(version: u64, bytecode_size: usize) -> TransactionPB {
+    let payload = TransactionPayload {
+        payload: Some(Payload::ModuleBundlePayload(ModuleBundlePayload {
+            modules: vec![MoveModuleBytecode {
+                bytecode: Bytes::from(vec![0xAB; bytecode_size]),
+                abi: None,
+            }],
+        })),
+        ..Default::default()
+    };
+    TransactionPB {
+        version,
+        txn_data: Some(TxnData::User(UserTransaction {
+            request: Some(UserTransactionRequest {
+                payload: Some(payload),
+                ..Default::default()
+            }),
+            events: vec![],
+        })),
+        ..Default::default()
+    }
+}
+
+fn transactions(bytecode_size: usize) -> Vec<TransactionPB> {
+    (0..NUM_TRANSACTIONS)
+        .map(|version| module_transaction(version, bytecode_size))
+        .collect()
+}
+
+// Encoding a batch of transactions with multi-MB payloads into stream responses, and decoding
+// them on the client side.
+fn large_transaction_benches(c: &mut Criterion) {
+    let mut group = c.benchmark_group("large_transactions");
+    for bytecode_size in BYTECODE_SIZES {
+        group.throughput(Throughput::Bytes(bytecode_size as u64 * NUM_TRANSACTIONS));
+        group.bench_with_input(
+            BenchmarkId::new("encode_responses", bytecode_size),
+            &bytecode_size,
+            |b, bytecode_size| {
+                b.iter_batched(
+                    || transactions(*bytecode_size),
+                    |txns| {
+                        IndexerStreamCoordinator::into_data_responses(
+                            IndexerStreamCoordinator::encode_pb_txns(txns),
+                            100,
+                            4,
+                        )
+                    },
+                    BatchSize::LargeInput,
+                )
+            },
+        );
+
+        let encoded: Vec<Bytes> = transactions(bytecode_size)
+            .iter()
+            .map(|txn| Bytes::from(txn.encode_to_vec()))
+            .collect();
+        group.bench_with_input(
+            BenchmarkId::new("decode", bytecode_size),
+            &encoded,
+            |b, encoded| {
+                b.iter(|| {
+                    encoded
+                        .iter()
+                        .map(|buf| TransactionPB::decode(buf.clone()).unwrap())
+                        .collect::<Vec<_>>()
+                })
+            },
+        );
+    }
+    group.finish();
+}
+
+criterion_group!(benches, large_transaction_benches);
+
+criterion_main!(benches);
@@ -0,0 +1,190 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    convert::convert_timestamp_usecs, counters::CHAIN_STATUS_WATCHERS_DROPPED,
+    failover::ReaderFailover,
+};
+use aptos_api::context::Context;
+use aptos_api_types::LedgerInfo;
+use aptos_logger::warn;
+use aptos_protos::datastream::v1::{WatchChainStatusRequest, WatchChainStatusResponse};
+use futures::{Stream, StreamExt};
+use std::{pin::Pin, sync::Arc, time::Duration};
+use tokio::{
+    sync::{
+        mpsc::{self, error::TrySendError},
+        oneshot, watch, Notify,
+    },
+    time::MissedTickBehavior,
+};
+use tokio_stream::wrappers::ReceiverStream;
+use tonic::Status;
+
+pub const DEFAULT_SAMPLE_INTERVAL_MILLIS: u64 = 100;
+pub const DEFAULT_WATCH_EVERY_VERSIONS: u64 = 1;
+pub const DEFAULT_WATCH_EVERY_MILLIS: u64 = 1000;
+/// Statuses buffered for a watcher, which is dropped once they're all unread
+pub const WATCHER_BUFFER_SIZE: usize = 16;
+
+pub type ChainStatusStream =
+    Pin<Box<dyn Stream<Item = Result<WatchChainStatusResponse, Status>> + Send>>;
+
+/// Where the ledger sampler reads the latest ledger info from
+pub trait LedgerSource: Send + Sync + 'static {
+    fn latest_ledger_info(&self) -> anyhow::Result<LedgerInfo>;
+}
+
+impl LedgerSource for ReaderFailover<Arc<Context>> {
+    fn latest_ledger_info(&self) -> anyhow::Result<LedgerInfo> {
+        let (_, context) = self.active();
+        context.get_latest_ledger_info_wrapped()
+    }
+}
+
+/// Reads the latest ledger info in the background, and publishes it whenever the version moved,
+/// so that the watchers don't each hit the storage. Stops when shut down or dropped, which ends
+/// the streams of the watchers.
+pub struct LedgerSampler {
+    receiver: watch::Receiver<Option<LedgerInfo>>,
+    shutdown: Arc<Notify>,
+}
+
+impl LedgerSampler {
+    /// Has to be called within a tokio runtime
+    pub fn spawn(source: Arc<dyn LedgerSource>, interval: Duration) -> Self {
+        let (sender, receiver) = watch::channel(None);
+        let shutdown = Arc::new(Notify::new());
+        let notified = shutdown.clone();
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+            loop {
+                tokio::select! {
+                    _ = notified.notified() => break,
+                    _ = ticker.tick() => {},
+                }
+                match source.latest_ledger_info() {
+                    Ok(info) => {
+                        sender.send_if_modified(|latest| {
+                            let moved = latest.as_ref().map_or(true, |latest| {
+                                latest.ledger_version != info.ledger_version
+                            });
+                            if moved {
+                                *latest = Some(info);
+                            }
+                            moved
+                        });
+                    },
+                    Err(e) => warn!(
+                        error = ?e,
+                        "[indexer-grpc] Unable to sample the latest ledger info"
+                    ),
+                }
+            }
+        });
+        Self { receiver, shutdown }
+    }
+
+    pub fn subscribe(&self) -> watch::Receiver<Option<LedgerInfo>> {
+        self.receiver.clone()
+    }
+
+    pub fn shutdown(&self) {
+        self.shutdown.notify_one();
+    }
+}
+
+impl Drop for LedgerSampler {
+    fn drop(&mut self) {
+        self.shutdown();
+    }
+}
+
+/// Streams the status of the chain to a watcher, pushing the latest sample right away, and then
+/// whenever the version advanced by `every_versions` or `every_millis` elapsed since the last push
+/// with the version advanced, whichever comes first. A watcher with `WATCHER_BUFFER_SIZE` statuses
+/// unread is dropped, its stream terminating with `RESOURCE_EXHAUSTED` once the buffered statuses
+/// are read. The stream ends when the sampler stops.
+pub fn watch_chain_status(
+    mut ledger: watch::Receiver<Option<LedgerInfo>>,
+    request: &WatchChainStatusRequest,
+    chain_id: u8,
+) -> ChainStatusStream {
+    let every_versions = request
+        .every_versions
+        .unwrap_or(DEFAULT_WATCH_EVERY_VERSIONS)
+        .max(1);
+    let every = Duration::from_millis(
+        request
+            .every_millis
+            .unwrap_or(DEFAULT_WATCH_EVERY_MILLIS)
+            .max(1),
+    );
+    let (tx, rx) = mpsc::channel(WATCHER_BUFFER_SIZE);
+    let (dropped_tx, dropped_rx) = oneshot::channel();
+
+    tokio::spawn(async move {
+        let mut last_pushed: Option<u64> = None;
+        // The first tick is immediate, which pushes the latest sample if there is one.
+        let mut timer = tokio::time::interval(every);
+        timer.set_missed_tick_behavior(MissedTickBehavior::Delay);
+        loop {
+            let timer_fired = tokio::select! {
+                changed = ledger.changed() => {
+                    if changed.is_err() {
+                        // The sampler stopped
+                        break;
+                    }
+                    false
+                },
+                _ = timer.tick() => true,
+                _ = tx.closed() => break,
+            };
+            let info = match ledger.borrow().clone() {
+                Some(info) => info,
+                None => continue,
+            };
+            let version = info.ledger_version.0;
+            let due = match last_pushed {
+                None => true,
+                Some(last) if version <= last => false,
+                Some(last) => timer_fired || version - last >= every_versions,
+            };
+            if !due {
+                continue;
+            }
+            match tx.try_send(Ok(to_response(&info, chain_id))) {
+                Ok(()) => {
+                    last_pushed = Some(version);
+                    timer.reset();
+                },
+                Err(TrySendError::Full(_)) => {
+                    CHAIN_STATUS_WATCHERS_DROPPED.inc();
+                    let _ = dropped_tx.send(Status::resource_exhausted(format!(
+                        "Watcher fell {} statuses behind the chain",
+                        WATCHER_BUFFER_SIZE
+                    )));
+                    break;
+                },
+                Err(TrySendError::Closed(_)) => break,
+            }
+        }
+    });
+
+    // The termination status of a dropped watcher comes after the statuses buffered for it.
+    let termination = futures::stream::once(dropped_rx)
+        .filter_map(|dropped| futures::future::ready(dropped.ok().map(Err)));
+    Box::pin(ReceiverStream::new(rx).chain(termination))
+}
+
+fn to_response(info: &LedgerInfo, chain_id: u8) -> WatchChainStatusResponse {
+    WatchChainStatusResponse {
+        chain_id: chain_id as u32,
+        latest_version: info.ledger_version.0,
+        epoch: info.epoch.0,
+        block_height: info.block_height.0,
+        timestamp: Some(convert_timestamp_usecs(info.ledger_timestamp.0)),
+        oldest_available_version: info.oldest_ledger_version.0,
+    }
+}
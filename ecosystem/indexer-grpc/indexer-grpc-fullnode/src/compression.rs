@@ -0,0 +1,97 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::counters::COMPRESSED_BYTES_SENT;
+use bytes::Bytes;
+use hyper::body::{HttpBody, SizeHint};
+use std::{
+    pin::Pin,
+    task::{Context, Poll},
+};
+use tonic::{
+    body::BoxBody,
+    codegen::{http, BoxFuture, Service},
+    server::NamedService,
+    Status,
+};
+
+const RAW_DATASTREAM_PATH: &str = "/aptos.datastream.v1.IndexerStream/RawDatastream";
+
+/// Counts the bytes of the raw datastream responses as they go out in `COMPRESSED_BYTES_SENT`,
+/// i.e. after compression for the clients that negotiated it. Compared with the encoded size of
+/// the responses in `UNCOMPRESSED_BYTES_SENT`, this is what compression saves.
+#[derive(Clone, Debug)]
+pub struct WireBytesCounter<S> {
+    inner: S,
+}
+
+impl<S> WireBytesCounter<S> {
+    pub fn new(inner: S) -> Self {
+        Self { inner }
+    }
+}
+
+impl<S, B> Service<http::Request<B>> for WireBytesCounter<S>
+where
+    S: Service<http::Request<B>, Response = http::Response<BoxBody>>,
+    S::Future: Send + 'static,
+{
+    type Error = S::Error;
+    type Future = BoxFuture<Self::Response, Self::Error>;
+    type Response = http::Response<BoxBody>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: http::Request<B>) -> Self::Future {
+        let counted = req.uri().path() == RAW_DATASTREAM_PATH;
+        let response = self.inner.call(req);
+        Box::pin(async move {
+            let response = response.await?;
+            Ok(if counted {
+                response.map(|body| tonic::body::boxed(CountedBody(body)))
+            } else {
+                response
+            })
+        })
+    }
+}
+
+impl<S: NamedService> NamedService for WireBytesCounter<S> {
+    const NAME: &'static str = S::NAME;
+}
+
+/// Response body counting the bytes of its frames
+struct CountedBody(BoxBody);
+
+impl HttpBody for CountedBody {
+    type Data = Bytes;
+    type Error = Status;
+
+    fn poll_data(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<Self::Data, Self::Error>>> {
+        let data = Pin::new(&mut self.0).poll_data(cx);
+        if let Poll::Ready(Some(Ok(bytes))) = &data {
+            COMPRESSED_BYTES_SENT.inc_by(bytes.len() as u64);
+        }
+        data
+    }
+
+    fn poll_trailers(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<Option<http::HeaderMap>, Self::Error>> {
+        Pin::new(&mut self.0).poll_trailers(cx)
+    }
+
+    fn is_end_stream(&self) -> bool {
+        self.0.is_end_stream()
+    }
+
+    fn size_hint(&self) -> SizeHint {
+        self.0.size_hint()
+    }
+}
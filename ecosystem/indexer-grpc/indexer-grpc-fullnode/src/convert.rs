@@ -117,15 +117,21 @@ pub fn convert_move_module(move_module: &MoveModule) -> transaction::MoveModule
 }
 
 pub fn convert_move_module_bytecode(mmb: &MoveModuleBytecode) -> transaction::MoveModuleBytecode {
-    let abi = mmb.clone().try_parse_abi().map_or_else(
-        |e| {
+    // The bytecode is moved out of the parsed copy rather than copied again
+    let (bytecode, abi) = match mmb.clone().try_parse_abi() {
+        Ok(parsed) => (
+            parsed.bytecode.0,
+            parsed
+                .abi
+                .map(|move_module| convert_move_module(&move_module)),
+        ),
+        Err(e) => {
             warn!("[fh-stream] Could not decode MoveModuleBytecode ABI: {}", e);
-            None
+            (mmb.bytecode.0.clone(), None)
         },
-        |mmb| mmb.abi.map(|move_module| convert_move_module(&move_module)),
-    );
+    };
     transaction::MoveModuleBytecode {
-        bytecode: mmb.bytecode.0.clone(),
+        bytecode: bytecode.into(),
         abi,
     }
 }
@@ -415,14 +421,13 @@ pub fn convert_write_set_change(change: &WriteSetChange) -> transaction::WriteSe
 }
 
 pub fn convert_move_script_bytecode(msb: &MoveScriptBytecode) -> transaction::MoveScriptBytecode {
-    let abi = msb
-        .clone()
-        .try_parse_abi()
+    let parsed = msb.clone().try_parse_abi();
+    let abi = parsed
         .abi
         .map(|move_func| convert_move_function(&move_func));
 
     transaction::MoveScriptBytecode {
-        bytecode: msb.bytecode.0.clone(),
+        bytecode: parsed.bytecode.0.into(),
         abi,
     }
 }
@@ -675,3 +680,70 @@ pub fn convert_transaction(
         txn_data: Some(txn_data),
     }
 }
+
+/// Strips the fields added to the transaction protos after `schema_version`, so that clients
+/// generated from that version of the schema get exactly the data they know about. See
+/// `aptos_protos::TRANSACTION_SCHEMA_VERSION` for the history.
+pub fn downgrade_transaction(txn: &mut transaction::Transaction, schema_version: u32) {
+    if schema_version >= 2 {
+        return;
+    }
+    // Version 2 added `type_str` to events and resource changes.
+    if let Some(info) = txn.info.as_mut() {
+        downgrade_write_set_changes_v1(&mut info.changes);
+    }
+    match txn.txn_data.as_mut() {
+        Some(transaction::transaction::TxnData::BlockMetadata(bm)) => {
+            downgrade_events_v1(&mut bm.events)
+        },
+        Some(transaction::transaction::TxnData::Genesis(gt)) => {
+            if let Some(payload) = gt.payload.as_mut() {
+                downgrade_write_set_v1(payload);
+            }
+            downgrade_events_v1(&mut gt.events);
+        },
+        Some(transaction::transaction::TxnData::User(ut)) => {
+            if let Some(transaction::transaction_payload::Payload::WriteSetPayload(wsp)) = ut
+                .request
+                .as_mut()
+                .and_then(|request| request.payload.as_mut())
+                .and_then(|payload| payload.payload.as_mut())
+            {
+                if let Some(write_set) = wsp.write_set.as_mut() {
+                    downgrade_write_set_v1(write_set);
+                }
+            }
+            downgrade_events_v1(&mut ut.events);
+        },
+        Some(transaction::transaction::TxnData::StateCheckpoint(_)) | None => {},
+    }
+}
+
+fn downgrade_write_set_v1(write_set: &mut transaction::WriteSet) {
+    if let Some(transaction::write_set::WriteSet::DirectWriteSet(direct)) =
+        write_set.write_set.as_mut()
+    {
+        downgrade_write_set_changes_v1(&mut direct.write_set_change);
+        downgrade_events_v1(&mut direct.events);
+    }
+}
+
+fn downgrade_write_set_changes_v1(changes: &mut [transaction::WriteSetChange]) {
+    for change in changes {
+        match change.change.as_mut() {
+            Some(transaction::write_set_change::Change::DeleteResource(delete)) => {
+                delete.type_str.clear()
+            },
+            Some(transaction::write_set_change::Change::WriteResource(write)) => {
+                write.type_str.clear()
+            },
+            _ => {},
+        }
+    }
+}
+
+fn downgrade_events_v1(events: &mut [transaction::Event]) {
+    for event in events {
+        event.type_str.clear();
+    }
+}
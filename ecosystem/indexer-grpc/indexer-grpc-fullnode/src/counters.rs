@@ -1,7 +1,10 @@
 // Copyright (c) Aptos
 // SPDX-License-Identifier: Apache-2.0
 
-use aptos_metrics_core::{register_int_counter, IntCounter};
+use aptos_metrics_core::{
+    register_histogram, register_int_counter, register_int_counter_vec, register_int_gauge,
+    register_int_gauge_vec, Histogram, IntCounter, IntCounterVec, IntGauge, IntGaugeVec,
+};
 use once_cell::sync::Lazy;
 
 pub static TRANSACTIONS_SENT: Lazy<IntCounter> = Lazy::new(|| {
@@ -29,3 +32,100 @@ pub static FETCHED_TRANSACTION: Lazy<IntCounter> = Lazy::new(|| {
     )
     .unwrap()
 });
+
+/// Number of times the streams switched storage readers, by whether they failed over from the
+/// primary or reverted to it
+pub static READER_FAILOVERS: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "indexer_grpc_reader_failover_count",
+        "Number of times the streams switched storage readers",
+        &["event"]
+    )
+    .unwrap()
+});
+
+/// Number of chain status watchers dropped for falling too far behind
+pub static CHAIN_STATUS_WATCHERS_DROPPED: Lazy<IntCounter> = Lazy::new(|| {
+    register_int_counter!(
+        "indexer_grpc_chain_status_watchers_dropped_count",
+        "Number of chain status watchers dropped for falling too far behind"
+    )
+    .unwrap()
+});
+
+/// Number of messages of the raw datastreams that broke the protocol, terminating their stream
+pub static STREAM_PROTOCOL_VIOLATIONS: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "indexer_grpc_stream_protocol_violation_count",
+        "Number of messages of the raw datastreams that broke the protocol",
+        &["violation"]
+    )
+    .unwrap()
+});
+
+/// Number of raw datastreams currently open
+pub static ACTIVE_STREAMS: Lazy<IntGauge> = Lazy::new(|| {
+    register_int_gauge!(
+        "indexer_grpc_active_streams",
+        "Number of raw datastreams currently open"
+    )
+    .unwrap()
+});
+
+/// Number of transactions sent by each open raw datastream, by stream id
+pub static STREAM_TRANSACTIONS_SENT: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "indexer_grpc_stream_transactions_sent_count",
+        "Number of transactions sent by each open raw datastream",
+        &["stream_id"]
+    )
+    .unwrap()
+});
+
+/// Number of batches sent by each open raw datastream, by stream id
+pub static STREAM_BATCHES_SENT: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "indexer_grpc_stream_batches_sent_count",
+        "Number of batches sent by each open raw datastream",
+        &["stream_id"]
+    )
+    .unwrap()
+});
+
+/// Number of versions each open raw datastream is behind the latest ledger version, by stream id
+pub static STREAM_VERSION_LAG: Lazy<IntGaugeVec> = Lazy::new(|| {
+    register_int_gauge_vec!(
+        "indexer_grpc_stream_version_lag",
+        "Number of versions each open raw datastream is behind the latest ledger version",
+        &["stream_id"]
+    )
+    .unwrap()
+});
+
+/// Time taken to fetch, convert and send a batch of the raw datastreams
+pub static PROCESS_BATCH_LATENCY: Lazy<Histogram> = Lazy::new(|| {
+    register_histogram!(
+        "indexer_grpc_process_batch_latency_seconds",
+        "Time taken to fetch, convert and send a batch of the raw datastreams"
+    )
+    .unwrap()
+});
+
+/// Encoded size of the raw datastream responses sent, before compression
+pub static UNCOMPRESSED_BYTES_SENT: Lazy<IntCounter> = Lazy::new(|| {
+    register_int_counter!(
+        "indexer_grpc_uncompressed_bytes_sent_count",
+        "Encoded size of the raw datastream responses sent, before compression"
+    )
+    .unwrap()
+});
+
+/// Bytes of the raw datastream responses sent, after compression for the clients that
+/// negotiated it
+pub static COMPRESSED_BYTES_SENT: Lazy<IntCounter> = Lazy::new(|| {
+    register_int_counter!(
+        "indexer_grpc_compressed_bytes_sent_count",
+        "Bytes of the raw datastream responses sent, after compression for the clients that negotiated it"
+    )
+    .unwrap()
+});
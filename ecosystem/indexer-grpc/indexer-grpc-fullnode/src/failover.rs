@@ -0,0 +1,172 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    counters::READER_FAILOVERS,
+    runtime::{DEFAULT_NUM_RETRIES, RETRY_TIME_MILLIS},
+};
+use aptos_infallible::Mutex;
+use aptos_logger::{error, info, warn};
+use std::{
+    collections::VecDeque,
+    time::{Duration, Instant},
+};
+
+pub const DEFAULT_MAX_FAILURES: usize = 3;
+pub const DEFAULT_FAILURE_WINDOW_SECS: u64 = 30;
+pub const DEFAULT_COOL_DOWN_SECS: u64 = 60;
+
+#[derive(Clone, Copy, Debug)]
+pub struct FailoverOptions {
+    /// Failures of the active reader within the window after which it fails over to the next one
+    pub max_failures: usize,
+    pub failure_window: Duration,
+    /// How long to stay on a fallback reader before probing the primary again
+    pub cool_down: Duration,
+    /// Attempts of a fetch on a single reader before giving up
+    pub max_retries: usize,
+    /// Delay before the first retry on a reader, doubling with each retry after it
+    pub retry_delay: Duration,
+}
+
+impl Default for FailoverOptions {
+    fn default() -> Self {
+        Self {
+            max_failures: DEFAULT_MAX_FAILURES,
+            failure_window: Duration::from_secs(DEFAULT_FAILURE_WINDOW_SECS),
+            cool_down: Duration::from_secs(DEFAULT_COOL_DOWN_SECS),
+            max_retries: DEFAULT_NUM_RETRIES,
+            retry_delay: Duration::from_millis(RETRY_TIME_MILLIS),
+        }
+    }
+}
+
+/// Readers of the same ledger in order of preference, e.g. the node's storage followed by
+/// replicas of it. Fetches go to the primary unless it kept failing recently, in which case they
+/// go to the next reader until the primary responds to a probe again. Shared by all the streams
+/// of a node so that they fail over together.
+pub struct ReaderFailover<R> {
+    readers: Vec<R>,
+    options: FailoverOptions,
+    state: Mutex<FailoverState>,
+}
+
+struct FailoverState {
+    active: usize,
+    // Failures of the active reader, oldest first
+    failures: VecDeque<Instant>,
+    // Last time the primary was failed over from or unsuccessfully probed
+    last_failover: Instant,
+}
+
+impl<R: Clone> ReaderFailover<R> {
+    pub fn new(readers: Vec<R>, options: FailoverOptions) -> Self {
+        assert!(!readers.is_empty(), "At least one reader is required");
+        Self {
+            readers,
+            options,
+            state: Mutex::new(FailoverState {
+                active: 0,
+                failures: VecDeque::new(),
+                last_failover: Instant::now(),
+            }),
+        }
+    }
+
+    pub fn primary(&self) -> &R {
+        &self.readers[0]
+    }
+
+    /// Index of the reader fetches currently go to, along with the reader
+    pub fn active(&self) -> (usize, R) {
+        let active = self.state.lock().active;
+        (active, self.readers[active].clone())
+    }
+
+    /// Records a failed fetch from the given reader, failing over to the next one if it is the
+    /// active reader and it failed too often within the window. Returns whether fetches now go to
+    /// another reader than the given one.
+    pub fn record_failure(&self, index: usize) -> bool {
+        let mut state = self.state.lock();
+        if state.active != index {
+            return true;
+        }
+
+        let now = Instant::now();
+        state.failures.push_back(now);
+        while let Some(failed_at) = state.failures.front() {
+            if now.duration_since(*failed_at) > self.options.failure_window {
+                state.failures.pop_front();
+            } else {
+                break;
+            }
+        }
+
+        if state.failures.len() >= self.options.max_failures && index + 1 < self.readers.len() {
+            state.active = index + 1;
+            state.failures.clear();
+            state.last_failover = now;
+            warn!(
+                from_reader = index,
+                to_reader = index + 1,
+                "[indexer-grpc] Failing over to the next storage reader"
+            );
+            READER_FAILOVERS.with_label_values(&["failover"]).inc();
+            return true;
+        }
+        false
+    }
+
+    /// Goes back to the primary if fetches are going to a fallback reader, the cool down since
+    /// the failover is over, and the probe of the primary succeeds. A failed probe starts another
+    /// cool down.
+    pub fn maybe_revert(&self, probe: impl FnOnce(&R) -> bool) {
+        let mut state = self.state.lock();
+        if state.active == 0 || state.last_failover.elapsed() < self.options.cool_down {
+            return;
+        }
+
+        if probe(&self.readers[0]) {
+            info!(
+                from_reader = state.active,
+                "[indexer-grpc] Primary storage reader recovered, reverting to it"
+            );
+            READER_FAILOVERS.with_label_values(&["revert"]).inc();
+            state.active = 0;
+            state.failures.clear();
+        } else {
+            state.last_failover = Instant::now();
+        }
+    }
+
+    /// Fetches from the active reader, retrying on errors. Retries go to the next reader once the
+    /// active one failed over. Returns the reader the result came from, so that everything else
+    /// read for the same batch comes from it too.
+    pub async fn fetch_with_retries<T>(
+        &self,
+        mut fetch: impl FnMut(&R) -> anyhow::Result<T>,
+    ) -> anyhow::Result<(R, T)> {
+        let mut retries = 0;
+        loop {
+            let (index, reader) = self.active();
+            match fetch(&reader) {
+                Ok(result) => return Ok((reader, result)),
+                Err(err) => {
+                    retries += 1;
+                    if self.record_failure(index) {
+                        retries = 0;
+                    } else if retries >= self.options.max_retries {
+                        return Err(err);
+                    }
+                    error!(
+                        reader = index,
+                        error = format!("{:?}", err),
+                        "Could not fetch from storage reader: will retry",
+                    );
+                    let backoff = 1 << retries.saturating_sub(1);
+                    tokio::time::sleep(self.options.retry_delay * backoff).await;
+                },
+            }
+        }
+    }
+}
@@ -0,0 +1,111 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use aptos_protos::{
+    datastream::v1::{
+        raw_datastream_request::TransactionType as RequestedType, RawDatastreamRequest,
+    },
+    transaction::v1::{
+        transaction::{TransactionType, TxnData},
+        transaction_payload::Payload,
+        Transaction,
+    },
+};
+use tonic::Status;
+
+/// Hex digits of an address, once padded with its leading zeros
+const ADDRESS_HEX_DIGITS: usize = 64;
+
+/// The transactions a client asked to be streamed, everything if empty. Filtered out transactions
+/// are dropped before they get encoded, their versions still being covered by the batch ends.
+#[derive(Clone, Debug, Default)]
+pub struct TransactionFilter {
+    types: Vec<TransactionType>,
+    /// Lowercase hex digits, without the `0x`
+    entry_function_address_prefixes: Vec<String>,
+}
+
+impl TransactionFilter {
+    pub fn new(request: &RawDatastreamRequest) -> Result<Self, Status> {
+        let types = request
+            .transaction_types
+            .iter()
+            .map(|value| match RequestedType::from_i32(*value) {
+                Some(RequestedType::Genesis) => Ok(TransactionType::Genesis),
+                Some(RequestedType::BlockMetadata) => Ok(TransactionType::BlockMetadata),
+                Some(RequestedType::StateCheckpoint) => Ok(TransactionType::StateCheckpoint),
+                Some(RequestedType::User) => Ok(TransactionType::User),
+                None => Err(Status::invalid_argument(format!(
+                    "Invalid transaction type {}",
+                    value
+                ))),
+            })
+            .collect::<Result<_, _>>()?;
+        let entry_function_address_prefixes = request
+            .entry_function_address_prefixes
+            .iter()
+            .map(|prefix| parse_address_prefix(prefix))
+            .collect::<Result<_, _>>()?;
+        Ok(Self {
+            types,
+            entry_function_address_prefixes,
+        })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.types.is_empty() && self.entry_function_address_prefixes.is_empty()
+    }
+
+    /// Whether the transaction is sent. Address prefixes only apply to user transactions, which
+    /// have to call an entry function of a matching module.
+    pub fn matches(&self, txn: &Transaction) -> bool {
+        let txn_type = txn.r#type();
+        if !self.types.is_empty() && !self.types.contains(&txn_type) {
+            return false;
+        }
+        if self.entry_function_address_prefixes.is_empty() || txn_type != TransactionType::User {
+            return true;
+        }
+        entry_function_address(txn).map_or(false, |address| {
+            self.entry_function_address_prefixes
+                .iter()
+                .any(|prefix| address.starts_with(prefix.as_str()))
+        })
+    }
+}
+
+fn parse_address_prefix(prefix: &str) -> Result<String, Status> {
+    match prefix.strip_prefix("0x") {
+        Some(digits)
+            if !digits.is_empty()
+                && digits.len() <= ADDRESS_HEX_DIGITS
+                && digits.chars().all(|c| c.is_ascii_hexdigit()) =>
+        {
+            Ok(digits.to_lowercase())
+        },
+        _ => Err(Status::invalid_argument(format!(
+            "Invalid entry function address prefix '{}', expected 0x followed by up to {} hex digits",
+            prefix, ADDRESS_HEX_DIGITS
+        ))),
+    }
+}
+
+/// Address of the module of the entry function called by the transaction, with all of its hex
+/// digits
+fn entry_function_address(txn: &Transaction) -> Option<String> {
+    let payload = match txn.txn_data.as_ref()? {
+        TxnData::User(user) => user.request.as_ref()?.payload.as_ref()?.payload.as_ref()?,
+        _ => return None,
+    };
+    match payload {
+        Payload::EntryFunctionPayload(payload) => {
+            let address = &payload.function.as_ref()?.module.as_ref()?.address;
+            Some(format!(
+                "{:0>width$}",
+                address.trim_start_matches("0x").to_lowercase(),
+                width = ADDRESS_HEX_DIGITS
+            ))
+        },
+        _ => None,
+    }
+}
@@ -0,0 +1,69 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::runtime::IndexerStreamService;
+use aptos_api_types::LedgerInfo;
+use aptos_logger::{info, warn};
+use aptos_protos::datastream::v1::indexer_stream_server::IndexerStreamServer;
+use std::time::{Duration, Instant};
+use tokio::{sync::watch, task::JoinHandle, time::MissedTickBehavior};
+use tonic::server::NamedService;
+use tonic_health::{server::HealthReporter, ServingStatus};
+
+pub const DEFAULT_HEALTH_CHECK_INTERVAL_MILLIS: u64 = 1000;
+
+/// Services reported to the health service: the server as a whole, which is what load balancers
+/// usually probe, and the stream
+const REPORTED_SERVICES: [&str; 2] = [
+    "",
+    <IndexerStreamServer<IndexerStreamService> as NamedService>::NAME,
+];
+
+/// Reports the stream as serving while the ledger sampler sees the ledger advance, refreshing
+/// the status every `interval`. It is not serving until the ledger could be read, whenever no
+/// new version was sampled for `staleness`, and once the sampler stopped, i.e. on shutdown.
+pub fn spawn_health_check(
+    mut ledger: watch::Receiver<Option<LedgerInfo>>,
+    mut reporter: HealthReporter,
+    staleness: Duration,
+    interval: Duration,
+) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        // The sampler only publishes the ledger when its version moved
+        let mut last_moved = ledger.borrow().as_ref().map(|_| Instant::now());
+        let mut reported = None;
+        let mut ticker = tokio::time::interval(interval);
+        ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+        loop {
+            let stopped = tokio::select! {
+                changed = ledger.changed() => {
+                    if changed.is_ok() {
+                        last_moved = Some(Instant::now());
+                    }
+                    changed.is_err()
+                },
+                _ = ticker.tick() => false,
+            };
+            let status = match last_moved {
+                Some(moved) if !stopped && moved.elapsed() < staleness => ServingStatus::Serving,
+                _ => ServingStatus::NotServing,
+            };
+            if reported != Some(status) {
+                match status {
+                    ServingStatus::Serving => info!("[indexer-grpc] Serving the stream"),
+                    _ => warn!(
+                        stopped = stopped,
+                        "[indexer-grpc] Not serving the stream, the ledger isn't advancing"
+                    ),
+                }
+                for service in REPORTED_SERVICES {
+                    reporter.set_service_status(service, status).await;
+                }
+                reported = Some(status);
+            }
+            if stopped {
+                break;
+            }
+        }
+    })
+}
@@ -1,10 +1,20 @@
 // Copyright (c) Aptos
 // SPDX-License-Identifier: Apache-2.0
 
+pub mod chain_status;
+pub mod compression;
 pub mod convert;
 pub mod counters;
+pub mod failover;
+pub mod filter;
+pub mod health;
+pub mod redaction;
+pub mod resume;
 pub mod runtime;
 pub mod stream_coordinator;
+pub mod stream_events;
+pub mod stream_metrics;
+pub mod stream_state_machine;
 
 #[cfg(test)]
 pub(crate) mod tests;
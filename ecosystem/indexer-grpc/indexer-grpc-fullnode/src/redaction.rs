@@ -0,0 +1,172 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use anyhow::{bail, Result};
+use aptos_crypto::HashValue;
+use aptos_protos::transaction::v1::{
+    transaction::TxnData, transaction_payload::Payload, Event, Transaction,
+};
+use std::{fmt, str::FromStr};
+
+/// Prefix of the content that replaces redacted content, followed by the hex encoded sha3-256
+/// hash of the original content, so that it can still be matched against known content.
+pub const REDACTION_MARKER: &str = "redacted:sha3-256:";
+
+pub const SCRIPT_CODE_SELECTOR: &str = "user_transaction.payload.script.code";
+pub const MODULE_BYTECODE_SELECTOR: &str = "user_transaction.payload.module_bundle.modules";
+/// Prefix of the rules redacting the data of events by type, e.g. `event:0x1::coin::*`
+pub const EVENT_TYPE_PREFIX: &str = "event:";
+
+/// Content that must not leave the node, as configured in `redaction_rules`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum RedactionRule {
+    /// The bytecode of scripts
+    ScriptCode,
+    /// The bytecode of published modules
+    ModuleBytecode,
+    /// The data of the events whose type matches the pattern, where `*` matches anything
+    EventType(String),
+}
+
+impl FromStr for RedactionRule {
+    type Err = anyhow::Error;
+
+    fn from_str(rule: &str) -> Result<Self> {
+        match rule {
+            SCRIPT_CODE_SELECTOR => Ok(Self::ScriptCode),
+            MODULE_BYTECODE_SELECTOR => Ok(Self::ModuleBytecode),
+            _ => match rule.strip_prefix(EVENT_TYPE_PREFIX) {
+                Some(pattern) if !pattern.is_empty() => Ok(Self::EventType(pattern.to_string())),
+                _ => bail!(
+                    "Invalid redaction rule '{}', expected '{}', '{}' or '{}<event type pattern>'",
+                    rule,
+                    SCRIPT_CODE_SELECTOR,
+                    MODULE_BYTECODE_SELECTOR,
+                    EVENT_TYPE_PREFIX
+                ),
+            },
+        }
+    }
+}
+
+impl fmt::Display for RedactionRule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::ScriptCode => write!(f, "{}", SCRIPT_CODE_SELECTOR),
+            Self::ModuleBytecode => write!(f, "{}", MODULE_BYTECODE_SELECTOR),
+            Self::EventType(pattern) => write!(f, "{}{}", EVENT_TYPE_PREFIX, pattern),
+        }
+    }
+}
+
+/// The marker replacing the content
+pub fn redaction_marker(content: &[u8]) -> String {
+    format!(
+        "{}{}",
+        REDACTION_MARKER,
+        HashValue::sha3_256_of(content).to_hex()
+    )
+}
+
+/// Whether the value matches the pattern, where `*` matches any (possibly empty) substring
+pub(crate) fn matches_pattern(pattern: &str, value: &str) -> bool {
+    let mut parts = pattern.split('*');
+    // There's always a first part, empty if the pattern starts with a wildcard
+    let first = parts.next().unwrap_or_default();
+    let mut rest = match value.strip_prefix(first) {
+        Some(rest) => rest,
+        None => return false,
+    };
+    let parts: Vec<_> = parts.collect();
+    for (idx, part) in parts.iter().enumerate() {
+        if idx == parts.len() - 1 {
+            return rest.ends_with(part);
+        }
+        match rest.find(part) {
+            Some(position) => rest = &rest[position + part.len()..],
+            None => return false,
+        }
+    }
+    // No wildcard, the value has to match exactly
+    rest.is_empty()
+}
+
+/// Replaces the content matched by the rules with its redaction marker in the transactions,
+/// before they get encoded into the responses. Deterministic, the same content is always
+/// replaced by the same marker.
+#[derive(Clone, Debug, Default)]
+pub struct Redactor {
+    rules: Vec<RedactionRule>,
+}
+
+impl Redactor {
+    pub fn new(rules: &[String]) -> Result<Self> {
+        Ok(Self {
+            rules: rules
+                .iter()
+                .map(|rule| rule.parse())
+                .collect::<Result<_>>()?,
+        })
+    }
+
+    /// The rules, as advertised to clients in the init status
+    pub fn rules(&self) -> Vec<String> {
+        self.rules.iter().map(|rule| rule.to_string()).collect()
+    }
+
+    /// Has to run before the transaction is downgraded, as the events are matched on their
+    /// `type_str`.
+    pub fn redact(&self, txn: &mut Transaction) {
+        if self.rules.is_empty() {
+            return;
+        }
+        match txn.txn_data.as_mut() {
+            Some(TxnData::User(user)) => {
+                if let Some(payload) = user
+                    .request
+                    .as_mut()
+                    .and_then(|request| request.payload.as_mut())
+                    .and_then(|payload| payload.payload.as_mut())
+                {
+                    self.redact_payload(payload);
+                }
+                self.redact_events(&mut user.events);
+            },
+            Some(TxnData::BlockMetadata(block_metadata)) => {
+                self.redact_events(&mut block_metadata.events)
+            },
+            Some(TxnData::Genesis(genesis)) => self.redact_events(&mut genesis.events),
+            Some(TxnData::StateCheckpoint(_)) | None => {},
+        }
+    }
+
+    fn redact_payload(&self, payload: &mut Payload) {
+        match payload {
+            Payload::ScriptPayload(script) if self.rules.contains(&RedactionRule::ScriptCode) => {
+                if let Some(code) = script.code.as_mut() {
+                    code.bytecode = redaction_marker(&code.bytecode).into_bytes().into();
+                }
+            },
+            Payload::ModuleBundlePayload(bundle)
+                if self.rules.contains(&RedactionRule::ModuleBytecode) =>
+            {
+                for module in bundle.modules.iter_mut() {
+                    module.bytecode = redaction_marker(&module.bytecode).into_bytes().into();
+                }
+            },
+            _ => {},
+        }
+    }
+
+    fn redact_events(&self, events: &mut [Event]) {
+        for event in events {
+            let redacted = self.rules.iter().any(|rule| match rule {
+                RedactionRule::EventType(pattern) => matches_pattern(pattern, &event.type_str),
+                _ => false,
+            });
+            if redacted {
+                event.data = redaction_marker(event.data.as_bytes());
+            }
+        }
+    }
+}
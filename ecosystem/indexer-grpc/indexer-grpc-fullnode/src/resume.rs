@@ -0,0 +1,85 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use aptos_protos::datastream::v1::RawDatastreamRequest;
+use tonic::Status;
+
+const RESUME_TOKEN_PREFIX: &str = "v1";
+
+/// Where a stream left off, handed out with every batch end so that clients can resume from
+/// there, possibly on another node. Carries the chain id so that it can't be resumed from on
+/// a node of another chain.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ResumeToken {
+    pub chain_id: u8,
+    /// First version that wasn't streamed yet
+    pub version: u64,
+}
+
+impl ResumeToken {
+    pub fn new(chain_id: u8, version: u64) -> Self {
+        Self { chain_id, version }
+    }
+
+    pub fn encode(&self) -> String {
+        format!("{}:{}:{}", RESUME_TOKEN_PREFIX, self.chain_id, self.version)
+    }
+
+    pub fn decode(token: &str) -> Result<Self, Status> {
+        let invalid = || Status::invalid_argument(format!("Invalid resume token '{}'", token));
+        match token.split(':').collect::<Vec<_>>().as_slice() {
+            [RESUME_TOKEN_PREFIX, chain_id, version] => Ok(Self {
+                chain_id: chain_id.parse().map_err(|_| invalid())?,
+                version: version.parse().map_err(|_| invalid())?,
+            }),
+            _ => Err(invalid()),
+        }
+    }
+}
+
+/// Resolves the version the requested stream starts from. A resume token has to be from the
+/// chain of the node, and agree with the starting version. As the starting version can't be
+/// told apart from an unset one when it is 0, the token wins in that case unless the request
+/// is strict. Requests are also rejected if the node already pruned the start version, given
+/// the oldest version it still has: out of range, or a failed precondition for strict resumes.
+pub fn resolve_starting_version(
+    request: &RawDatastreamRequest,
+    chain_id: u8,
+    oldest_available_version: Option<u64>,
+) -> Result<u64, Status> {
+    let starting_version = match request.resume_token.as_deref() {
+        None => request.starting_version,
+        Some(token) => {
+            let token = ResumeToken::decode(token)?;
+            if token.chain_id != chain_id {
+                return Err(Status::failed_precondition(format!(
+                    "Resume token is from chain {}, but the node is on chain {}",
+                    token.chain_id, chain_id
+                )));
+            }
+            let unset = request.starting_version == 0 && !request.strict_resume;
+            if request.starting_version != token.version && !unset {
+                return Err(Status::invalid_argument(format!(
+                    "Starting version {} disagrees with version {} of the resume token",
+                    request.starting_version, token.version
+                )));
+            }
+            token.version
+        },
+    };
+
+    match oldest_available_version {
+        Some(oldest) if starting_version < oldest => {
+            let message = format!(
+                "Starting version {} was pruned, the oldest available version is {}",
+                starting_version, oldest
+            );
+            if request.strict_resume {
+                Err(Status::failed_precondition(message))
+            } else {
+                Err(Status::out_of_range(message))
+            }
+        },
+        _ => Ok(starting_version),
+    }
+}
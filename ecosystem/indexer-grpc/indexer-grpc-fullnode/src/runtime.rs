@@ -1,48 +1,89 @@
 // Copyright (c) Aptos
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::stream_coordinator::IndexerStreamCoordinator;
+use crate::{
+    chain_status::{
+        watch_chain_status, ChainStatusStream, LedgerSampler, DEFAULT_SAMPLE_INTERVAL_MILLIS,
+    },
+    compression::WireBytesCounter,
+    failover::{FailoverOptions, ReaderFailover},
+    filter::TransactionFilter,
+    health::{spawn_health_check, DEFAULT_HEALTH_CHECK_INTERVAL_MILLIS},
+    redaction::Redactor,
+    resume::{resolve_starting_version, ResumeToken},
+    stream_coordinator::IndexerStreamCoordinator,
+    stream_events::{StreamEvents, TerminationReason},
+    stream_metrics::StreamMetrics,
+};
+use anyhow::{ensure, Context as _};
 use aptos_api::context::Context;
-use aptos_config::config::NodeConfig;
+use aptos_config::config::{
+    IndexerGrpcConfig, IndexerGrpcTlsConfig, NodeConfig, StreamEventsVerbosity,
+};
 use aptos_logger::{error, info};
 use aptos_mempool::MempoolClientSender;
-use aptos_moving_average::MovingAverage;
-use aptos_protos::datastream::v1::{
-    indexer_stream_server::{IndexerStream, IndexerStreamServer},
-    raw_datastream_response,
-    stream_status::StatusType,
-    RawDatastreamRequest, RawDatastreamResponse, StreamStatus,
+use aptos_protos::{
+    datastream::v1::{
+        indexer_stream_server::{IndexerStream, IndexerStreamServer},
+        raw_datastream_response,
+        stream_status::StatusType,
+        RawDatastreamRequest, RawDatastreamResponse, StreamStatus, WatchChainStatusRequest,
+    },
+    MIN_TRANSACTION_SCHEMA_VERSION, TRANSACTION_SCHEMA_VERSION,
 };
 use aptos_storage_interface::DbReader;
 use aptos_types::chain_id::ChainId;
 use futures::Stream;
-use std::{net::ToSocketAddrs, pin::Pin, sync::Arc};
+use std::{
+    net::{SocketAddr, ToSocketAddrs},
+    path::Path,
+    pin::Pin,
+    sync::Arc,
+    time::{Duration, Instant},
+};
 use tokio::{runtime::Runtime, sync::mpsc};
 use tokio_stream::wrappers::ReceiverStream;
-use tonic::{transport::Server, Request, Response, Status};
+use tonic::{
+    codec::CompressionEncoding,
+    transport::{Certificate, Identity, Server, ServerTlsConfig},
+    Code, Request, Response, Status,
+};
 
 // Default Values
 pub const DEFAULT_NUM_RETRIES: usize = 3;
 pub const RETRY_TIME_MILLIS: u64 = 300;
-const TRANSACTION_CHANNEL_SIZE: usize = 35;
-const DEFAULT_EMIT_SIZE: usize = 1000;
+// Request header that clients can set to identify themselves in the stream logs
+const CLIENT_NAME_HEADER: &str = "x-aptos-client-name";
 
 type ResponseStream = Pin<Box<dyn Stream<Item = Result<RawDatastreamResponse, Status>> + Send>>;
 
 // The GRPC server
 pub struct IndexerStreamService {
-    pub context: Arc<Context>,
+    pub readers: Arc<ReaderFailover<Arc<Context>>>,
     pub processor_task_count: u16,
     pub processor_batch_size: u16,
     pub output_batch_size: u16,
+    /// Packs the transactions of a batch into as few responses as possible, for compression
+    pub coalesce_responses: bool,
+    pub stream_events_verbosity: StreamEventsVerbosity,
+    pub stream_events_batch_sample_rate: u64,
+    pub reject_schema_downgrades: bool,
+    pub redactor: Arc<Redactor>,
+    /// Responses buffered for each stream
+    pub transaction_channel_size: usize,
+    /// How long a stream waits on its client to read before terminating it
+    pub slow_consumer_timeout: Duration,
+    /// Shared by the chain status watchers
+    pub ledger_sampler: Arc<LedgerSampler>,
 }
 
 /// Creates a runtime which creates a thread pool which sets up the grpc streaming service
 /// Returns corresponding Tokio runtime
+/// Transactions are read from the first of `dbs` that is healthy, the others being fallbacks
 pub fn bootstrap(
     config: &NodeConfig,
     chain_id: ChainId,
-    db: Arc<dyn DbReader>,
+    dbs: Vec<Arc<dyn DbReader>>,
     mp_sender: MempoolClientSender,
 ) -> Option<Runtime> {
     if !config.indexer_grpc.enabled {
@@ -57,19 +98,86 @@ pub fn bootstrap(
     let processor_task_count = node_config.indexer_grpc.processor_task_count.unwrap();
     let processor_batch_size = node_config.indexer_grpc.processor_batch_size.unwrap();
     let output_batch_size = node_config.indexer_grpc.output_batch_size.unwrap();
+    let stream_events_verbosity = node_config.indexer_grpc.stream_events_verbosity;
+    let stream_events_batch_sample_rate = node_config
+        .indexer_grpc
+        .stream_events_batch_sample_rate
+        .unwrap();
+    let reject_schema_downgrades = node_config.indexer_grpc.reject_schema_downgrades;
+    let compression_enabled = node_config.indexer_grpc.compression_enabled;
+    let transaction_channel_size = node_config.indexer_grpc.transaction_channel_size.unwrap();
+    let slow_consumer_timeout =
+        Duration::from_secs(node_config.indexer_grpc.slow_consumer_timeout_secs.unwrap());
+    let redactor = Arc::new(
+        Redactor::new(&node_config.indexer_grpc.redaction_rules)
+            .expect("Invalid indexer grpc redaction rules"),
+    );
+    let health_check_enabled = node_config.indexer_grpc.health_check_enabled.unwrap();
+    let health_staleness =
+        Duration::from_secs(node_config.indexer_grpc.health_staleness_secs.unwrap());
     let address = node_config.indexer_grpc.address.clone().unwrap();
+    // Everything served on the address goes through the builder, TLS included
+    let mut builder = server_builder(&node_config.indexer_grpc)
+        .unwrap_or_else(|e| panic!("[indexer-grpc] Unable to set up the GRPC server: {:#}", e));
 
     runtime.spawn(async move {
-        let context = Arc::new(Context::new(chain_id, db, mp_sender, node_config));
+        let contexts: Vec<_> = dbs
+            .into_iter()
+            .map(|db| {
+                Arc::new(Context::new(
+                    chain_id,
+                    db,
+                    mp_sender.clone(),
+                    node_config.clone(),
+                ))
+            })
+            .collect();
+        let readers = Arc::new(ReaderFailover::new(contexts, FailoverOptions::default()));
+        let ledger_sampler = Arc::new(LedgerSampler::spawn(
+            readers.clone(),
+            Duration::from_millis(DEFAULT_SAMPLE_INTERVAL_MILLIS),
+        ));
+        // Health checks follow the ledger through the sampler rather than reading the storage
+        let health_service = health_check_enabled.then(|| {
+            let (health_reporter, health_service) = tonic_health::server::health_reporter();
+            spawn_health_check(
+                ledger_sampler.subscribe(),
+                health_reporter,
+                health_staleness,
+                Duration::from_millis(DEFAULT_HEALTH_CHECK_INTERVAL_MILLIS),
+            );
+            health_service
+        });
+        // Lets tools like grpcurl discover the services without the proto files
+        let reflection_service = tonic_reflection::server::Builder::configure()
+            .register_encoded_file_descriptor_set(aptos_protos::datastream::v1::FILE_DESCRIPTOR_SET)
+            .register_encoded_file_descriptor_set(
+                aptos_protos::transaction::v1::FILE_DESCRIPTOR_SET,
+            )
+            .register_encoded_file_descriptor_set(
+                aptos_protos::util::timestamp::FILE_DESCRIPTOR_SET,
+            )
+            .build()
+            .expect("[indexer-grpc] Unable to build the reflection service");
         let server = IndexerStreamService {
-            context,
+            readers,
             processor_task_count,
             processor_batch_size,
             output_batch_size,
+            coalesce_responses: compression_enabled,
+            stream_events_verbosity,
+            stream_events_batch_sample_rate,
+            reject_schema_downgrades,
+            redactor,
+            transaction_channel_size,
+            slow_consumer_timeout,
+            ledger_sampler,
         };
 
-        Server::builder()
-            .add_service(IndexerStreamServer::new(server))
+        builder
+            .add_service(stream_server(server, compression_enabled))
+            .add_service(reflection_service)
+            .add_optional_service(health_service)
             // Make port into a config
             .serve(address.to_socket_addrs().unwrap().next().unwrap())
             .await
@@ -79,9 +187,80 @@ pub fn bootstrap(
     Some(runtime)
 }
 
+/// Wraps the service into the server of the stream, compressing responses with gzip for the
+/// clients that accept it and accepting compressed requests if compression is enabled. The bytes
+/// sent are counted either way.
+pub fn stream_server(
+    service: IndexerStreamService,
+    compression_enabled: bool,
+) -> WireBytesCounter<IndexerStreamServer<IndexerStreamService>> {
+    let mut server = IndexerStreamServer::new(service);
+    if compression_enabled {
+        server = server
+            .send_compressed(CompressionEncoding::Gzip)
+            .accept_compressed(CompressionEncoding::Gzip);
+    }
+    WireBytesCounter::new(server)
+}
+
+/// Creates the server builder with the HTTP/2 settings of the config applied. Long-lived streams
+/// rely on keepalive pings to not be dropped by load balancers while waiting on new versions.
+/// Fails if TLS is configured but its certificates or key can't be loaded, rather than serving
+/// plaintext.
+pub fn server_builder(config: &IndexerGrpcConfig) -> anyhow::Result<Server> {
+    let builder = Server::builder()
+        .http2_keepalive_interval(
+            config
+                .http2_keepalive_interval_secs
+                .map(Duration::from_secs),
+        )
+        .http2_keepalive_timeout(config.http2_keepalive_timeout_secs.map(Duration::from_secs))
+        .initial_connection_window_size(config.initial_connection_window_size)
+        .initial_stream_window_size(config.initial_stream_window_size)
+        .max_frame_size(config.max_frame_size);
+    match &config.tls {
+        Some(tls) => builder
+            .tls_config(server_tls_config(tls)?)
+            .context("Invalid indexer grpc TLS certificate or key"),
+        None => Ok(builder),
+    }
+}
+
+/// Clients have to present a certificate signed by the client CA if there is one
+pub fn server_tls_config(config: &IndexerGrpcTlsConfig) -> anyhow::Result<ServerTlsConfig> {
+    let cert = read_pem(&config.cert_path, "CERTIFICATE")?;
+    let key = read_pem(&config.key_path, "PRIVATE KEY")?;
+    let mut tls_config = ServerTlsConfig::new().identity(Identity::from_pem(cert, key));
+    if let Some(path) = &config.client_ca_cert_path {
+        tls_config =
+            tls_config.client_ca_root(Certificate::from_pem(read_pem(path, "CERTIFICATE")?));
+    }
+    Ok(tls_config)
+}
+
+/// Reads a PEM file, checking that it has the expected block. Tonic ignores anything else in the
+/// file, and would take one without certificates as an empty certificate chain.
+fn read_pem(path: &Path, label: &str) -> anyhow::Result<Vec<u8>> {
+    let pem = std::fs::read(path)
+        .with_context(|| format!("Unable to read the TLS file {}", path.display()))?;
+    let contents = String::from_utf8_lossy(&pem);
+    // Keys can be labelled e.g. "EC PRIVATE KEY" or "RSA PRIVATE KEY"
+    ensure!(
+        contents
+            .lines()
+            .any(|line| line.starts_with("-----BEGIN ")
+                && line.ends_with(&format!("{}-----", label))),
+        "{} has no PEM encoded {}",
+        path.display(),
+        label.to_lowercase()
+    );
+    Ok(pem)
+}
+
 #[tonic::async_trait]
 impl IndexerStream for IndexerStreamService {
     type RawDatastreamStream = ResponseStream;
+    type WatchChainStatusStream = ChainStatusStream;
 
     /// This function is required by the GRPC tonic server. It basically handles the request.
     /// Given we want to persist the stream for better performance, our approach is that when
@@ -94,89 +273,149 @@ impl IndexerStream for IndexerStreamService {
         &self,
         req: Request<RawDatastreamRequest>,
     ) -> Result<Response<Self::RawDatastreamStream>, Status> {
+        let peer_address = req.remote_addr();
+        let client_name = req
+            .metadata()
+            .get(CLIENT_NAME_HEADER)
+            .and_then(|name| name.to_str().ok())
+            .map(|name| name.to_string())
+            .or_else(|| peer_address.map(|addr| addr.to_string()))
+            .unwrap_or_else(|| "unknown".to_string());
+
         // Gets configs for the stream, partly from the request and partly from the node config
         let r = req.into_inner();
-        let starting_version = r.starting_version;
         let processor_task_count = self.processor_task_count;
         let processor_batch_size = self.processor_batch_size;
         let output_batch_size = self.output_batch_size;
+        let coalesce_responses = self.coalesce_responses;
+        let schema_version = Self::negotiate_schema_version(
+            r.max_supported_schema_version,
+            self.reject_schema_downgrades,
+        )?;
 
         // Some node metadata
-        let context = self.context.clone();
-        let ledger_chain_id = context.chain_id().id();
+        let readers = self.readers.clone();
+        let redactor = self.redactor.clone();
+        let ledger_chain_id = readers.primary().chain_id().id();
+        // Goes through the failover like the rest of the stream, the primary may be down
+        let (_, ledger_info) = readers
+            .fetch_with_retries(|context| context.get_latest_ledger_info_wrapped())
+            .await
+            .map_err(|e| Status::unavailable(format!("Unable to get ledger info: {}", e)))?;
+        let oldest_available_version = ledger_info.oldest_ledger_version.0;
+        // The init status echoes the version resolved here, for clients to check it
+        let starting_version =
+            resolve_starting_version(&r, ledger_chain_id, Some(oldest_available_version))?;
+        let ending_version = r.ending_version;
+        if let Some(ending_version) = ending_version.filter(|end| *end < starting_version) {
+            return Err(Status::invalid_argument(format!(
+                "Ending version {} is before the starting version {}",
+                ending_version, starting_version
+            )));
+        }
+        let filter = Arc::new(TransactionFilter::new(&r)?);
 
         // Creates a channel to send the stream to the client
-        let (tx, rx) = mpsc::channel(TRANSACTION_CHANNEL_SIZE);
+        let (tx, rx) = mpsc::channel(self.transaction_channel_size);
+        let slow_consumer_timeout = self.slow_consumer_timeout;
+        let ledger = self.ledger_sampler.subscribe();
 
-        // Creates a moving average to track tps
-        let mut ma = MovingAverage::new(10_000);
+        let mut stream_events = StreamEvents::new(
+            self.stream_events_verbosity,
+            self.stream_events_batch_sample_rate,
+            client_name,
+            starting_version,
+        );
 
         // This is the main thread handling pushing to the stream
         tokio::spawn(async move {
             // Initialize the coordinator that tracks starting version and processes transactions
             let mut coordinator = IndexerStreamCoordinator::new(
-                context,
+                readers,
                 starting_version,
+                ending_version,
                 processor_task_count,
                 processor_batch_size,
                 output_batch_size,
-                tx.clone(),
+                coalesce_responses,
+                schema_version,
+                redactor.clone(),
+                filter,
+                tx,
+                slow_consumer_timeout,
             );
-            // Sends init message (one time per request) to the client in the with chain id and starting version. Basically a handshake
-            let init_status =
-                Self::get_status(StatusType::Init, starting_version, None, ledger_chain_id);
-            match tx.send(Result::<_, Status>::Ok(init_status)).await {
-                Ok(_) => {
-                    // TODO: Add request details later
-                    info!("[indexer-grpc] Init connection");
-                },
-                Err(_) => {
-                    panic!("[indexer-grpc] Unable to initialize stream");
+            // Declared after the coordinator so that it is dropped first, the stream no longer
+            // counting as active by the time its channel closes
+            let stream_metrics = StreamMetrics::new(stream_events.stream_id());
+            // Sends init message (one time per request) to the client in the with chain id, starting version and schema version. Basically a handshake
+            let init_status = Self::get_init_status(
+                starting_version,
+                schema_version,
+                redactor.rules(),
+                ledger_chain_id,
+            );
+            match coordinator.send(Ok(init_status)).await {
+                Ok(_) => stream_events.connect(),
+                Err(e) => {
+                    aptos_logger::warn!("[indexer-grpc] Unable to initialize stream");
+                    Self::disconnect(&stream_events, &e, starting_version, peer_address);
+                    return;
                 },
             }
-            let mut base: u64 = 0;
             loop {
                 // Processes and sends batch of transactions to client
+                let batch_start_time = Instant::now();
                 let results = coordinator.process_next_batch().await;
                 let max_version = match IndexerStreamCoordinator::get_max_batch_version(results) {
                     Ok(max_version) => max_version,
+                    // A failed batch already terminated the stream with an error status
                     Err(e) => {
                         error!("[indexer-grpc] Error sending to stream: {}", e);
+                        Self::disconnect(&stream_events, &e, starting_version, peer_address);
                         break;
                     },
                 };
                 // send end batch message (each batch) upon success of the entire batch
                 // client can use the start and end version to ensure that there are no gaps
                 // end loop if this message fails to send because otherwise the client can't validate
-                let batch_end_status = Self::get_status(
-                    StatusType::BatchEnd,
+                let batch_end_status = Self::get_batch_end_status(
                     coordinator.current_version,
-                    Some(max_version),
+                    max_version,
                     ledger_chain_id,
                 );
-                match tx.send(Result::<_, Status>::Ok(batch_end_status)).await {
+                match coordinator.send(Ok(batch_end_status)).await {
                     Ok(_) => {
-                        // tps logging
-                        let new_base: u64 = ma.sum() / (DEFAULT_EMIT_SIZE as u64);
-                        ma.tick_now(max_version - coordinator.current_version + 1);
-                        if base != new_base {
-                            base = new_base;
-
-                            info!(
-                                batch_start_version = coordinator.current_version,
-                                batch_end_version = max_version,
-                                versions_processed = ma.sum(),
-                                tps = (ma.avg() * 1000.0) as u64,
-                                "[indexer-grpc] Sent batch successfully"
-                            );
-                        }
+                        stream_events.batch_sent(
+                            coordinator.current_version,
+                            max_version,
+                            coordinator.take_bytes_sent(),
+                            batch_start_time.elapsed(),
+                        );
+                        stream_metrics.batch_sent(coordinator.take_transactions_sent());
                     },
-                    Err(_) => {
+                    Err(e) => {
                         aptos_logger::warn!("[indexer-grpc] Unable to send end batch status");
+                        Self::disconnect(&stream_events, &e, starting_version, peer_address);
                         break;
                     },
                 }
                 coordinator.current_version = max_version + 1;
+                if let Some(info) = ledger.borrow().as_ref() {
+                    stream_metrics
+                        .set_version_lag(coordinator.current_version, info.ledger_version.0);
+                }
+                // A bounded stream ends once its last version is sent, closing the channel
+                if coordinator.is_complete() {
+                    let completed_status =
+                        Self::get_completed_status(starting_version, max_version, ledger_chain_id);
+                    match coordinator.send(Ok(completed_status)).await {
+                        Ok(_) => stream_events.disconnect(TerminationReason::Completed),
+                        Err(e) => {
+                            Self::disconnect(&stream_events, &e, starting_version, peer_address)
+                        },
+                    }
+                    break;
+                }
             }
         });
         let output_stream = ReceiverStream::new(rx);
@@ -184,6 +423,20 @@ impl IndexerStream for IndexerStreamService {
             Box::pin(output_stream) as Self::RawDatastreamStream
         ))
     }
+
+    /// Pushes the status of the chain to the watcher as the ledger advances, from the samples of
+    /// the ledger sampler rather than reading the storage for each watcher
+    async fn watch_chain_status(
+        &self,
+        req: Request<WatchChainStatusRequest>,
+    ) -> Result<Response<Self::WatchChainStatusStream>, Status> {
+        let ledger_chain_id = self.readers.primary().chain_id().id();
+        Ok(Response::new(watch_chain_status(
+            self.ledger_sampler.subscribe(),
+            &req.into_inner(),
+            ledger_chain_id,
+        )))
+    }
 }
 
 impl IndexerStreamService {
@@ -198,8 +451,132 @@ impl IndexerStreamService {
                 r#type: status_type as i32,
                 start_version,
                 end_version,
+                schema_version: None,
+                resume_token: None,
+                redactions: vec![],
+                error_message: None,
             })),
             chain_id: ledger_chain_id as u32,
         }
     }
+
+    /// The init status, advertising the schema version and the redactions of the stream
+    pub fn get_init_status(
+        start_version: u64,
+        schema_version: u32,
+        redactions: Vec<String>,
+        ledger_chain_id: u8,
+    ) -> RawDatastreamResponse {
+        let mut response = Self::get_status(StatusType::Init, start_version, None, ledger_chain_id);
+        if let Some(raw_datastream_response::Response::Status(status)) = response.response.as_mut()
+        {
+            status.schema_version = Some(schema_version);
+            status.redactions = redactions;
+        }
+        response
+    }
+
+    /// The batch end status, with a token to resume the stream right after the batch
+    pub fn get_batch_end_status(
+        start_version: u64,
+        end_version: u64,
+        ledger_chain_id: u8,
+    ) -> RawDatastreamResponse {
+        let mut response = Self::get_status(
+            StatusType::BatchEnd,
+            start_version,
+            Some(end_version),
+            ledger_chain_id,
+        );
+        if let Some(raw_datastream_response::Response::Status(status)) = response.response.as_mut()
+        {
+            status.resume_token = Some(ResumeToken::new(ledger_chain_id, end_version + 1).encode());
+        }
+        response
+    }
+
+    /// Logs the termination of the stream on a failed send or batch. Slow consumers are logged
+    /// regardless of the stream events verbosity, as they held on to the stream until timing out.
+    fn disconnect(
+        stream_events: &StreamEvents,
+        status: &Status,
+        starting_version: u64,
+        peer_address: Option<SocketAddr>,
+    ) {
+        stream_events.disconnect(match status.code() {
+            Code::Aborted => TerminationReason::ClientDisconnected,
+            Code::Internal => TerminationReason::ProtocolViolation,
+            Code::ResourceExhausted => {
+                aptos_logger::warn!(
+                    starting_version = starting_version,
+                    peer_address = peer_address,
+                    "[indexer-grpc] Slow consumer, terminating the stream"
+                );
+                TerminationReason::SlowConsumer
+            },
+            _ => TerminationReason::BatchFailed,
+        });
+    }
+
+    /// The completed status, ending a bounded stream at its last version
+    pub fn get_completed_status(
+        start_version: u64,
+        end_version: u64,
+        ledger_chain_id: u8,
+    ) -> RawDatastreamResponse {
+        Self::get_status(
+            StatusType::Completed,
+            start_version,
+            Some(end_version),
+            ledger_chain_id,
+        )
+    }
+
+    /// The error status, terminating the stream after a failed batch. Resumes right after the last
+    /// batch end, if any since the stream started.
+    pub fn get_error_status(
+        start_version: u64,
+        end_version: Option<u64>,
+        error_message: String,
+        ledger_chain_id: u8,
+    ) -> RawDatastreamResponse {
+        let mut response = Self::get_status(
+            StatusType::Error,
+            start_version,
+            end_version,
+            ledger_chain_id,
+        );
+        if let Some(raw_datastream_response::Response::Status(status)) = response.response.as_mut()
+        {
+            status.resume_token = Some(ResumeToken::new(ledger_chain_id, start_version).encode());
+            status.error_message = Some(error_message);
+        }
+        response
+    }
+
+    /// Picks the transaction schema version to stream with, given the highest one the client
+    /// supports. Clients behind the node get their transactions downgraded to their version,
+    /// unless downgrades are rejected.
+    pub fn negotiate_schema_version(
+        max_supported_schema_version: Option<u32>,
+        reject_schema_downgrades: bool,
+    ) -> Result<u32, Status> {
+        match max_supported_schema_version {
+            None => Ok(TRANSACTION_SCHEMA_VERSION),
+            Some(version) if version >= TRANSACTION_SCHEMA_VERSION => {
+                Ok(TRANSACTION_SCHEMA_VERSION)
+            },
+            Some(version) if version < MIN_TRANSACTION_SCHEMA_VERSION => {
+                Err(Status::failed_precondition(format!(
+                    "Transaction schema version {} is not supported, the oldest supported version is {}",
+                    version, MIN_TRANSACTION_SCHEMA_VERSION
+                )))
+            },
+            Some(version) if reject_schema_downgrades => Err(Status::failed_precondition(format!(
+                "Transaction schema version {} is older than the version {} of the node, which doesn't downgrade streams",
+                version, TRANSACTION_SCHEMA_VERSION
+            ))),
+            Some(version) => Ok(version),
+        }
+    }
 }
@@ -2,21 +2,36 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
-    convert::convert_transaction,
-    counters::{FETCHED_TRANSACTION, UNABLE_TO_FETCH_TRANSACTION},
-    runtime::{DEFAULT_NUM_RETRIES, RETRY_TIME_MILLIS},
+    convert::{convert_transaction, downgrade_transaction},
+    counters::{
+        FETCHED_TRANSACTION, PROCESS_BATCH_LATENCY, STREAM_PROTOCOL_VIOLATIONS,
+        UNABLE_TO_FETCH_TRANSACTION, UNCOMPRESSED_BYTES_SENT,
+    },
+    failover::ReaderFailover,
+    filter::TransactionFilter,
+    redaction::Redactor,
+    runtime::{IndexerStreamService, RETRY_TIME_MILLIS},
+    stream_state_machine::StreamStateMachine,
 };
 use aptos_api::context::Context;
 use aptos_api_types::{AsConverter, Transaction as APITransaction, TransactionOnChainData};
 use aptos_logger::{error, info, sample, sample::SampleRate};
 use aptos_protos::{
     datastream::v1::{
-        raw_datastream_response, RawDatastreamResponse, TransactionOutput, TransactionsOutput,
+        raw_datastream_response, stream_status::StatusType, RawDatastreamResponse,
+        TransactionOutput, TransactionsOutput,
     },
     transaction::v1::Transaction as TransactionPB,
 };
+use aptos_types::account_config::NewBlockEvent;
 use prost::Message;
-use std::{sync::Arc, time::Duration};
+use std::{
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
 use tokio::sync::mpsc;
 use tonic::Status;
 
@@ -24,13 +39,36 @@ type EndVersion = u64;
 
 // Basically a handler for a single GRPC stream request
 pub struct IndexerStreamCoordinator {
+    // First version of the stream
+    pub starting_version: u64,
+    // First version not covered by a batch end yet, only advanced once the batch end is sent
     pub current_version: u64,
+    // Last version of the stream if it is bounded, inclusive
+    pub ending_version: Option<u64>,
     pub processor_task_count: u16,
     pub processor_batch_size: u16,
     pub output_batch_size: u16,
+    // Whether the transactions of the tasks of a batch are packed together into responses of
+    // `output_batch_size`, rather than each task sending its own
+    pub coalesce_responses: bool,
+    // Transaction schema version negotiated with the client
+    pub schema_version: u32,
+    // Content that must not leave the node
+    pub redactor: Arc<Redactor>,
+    // Transactions the client asked for, the others are dropped before encoding
+    pub filter: Arc<TransactionFilter>,
     pub highest_known_version: u64,
-    pub context: Arc<Context>,
+    // Storage to fetch from, each batch coming entirely from one of them
+    pub readers: Arc<ReaderFailover<Arc<Context>>>,
     pub transactions_sender: mpsc::Sender<Result<RawDatastreamResponse, tonic::Status>>,
+    // How long a send waits on the client to read, before giving up on the stream
+    pub slow_consumer_timeout: Duration,
+    // Every message sent goes through it, so that protocol bugs never reach the client
+    state_machine: StreamStateMachine,
+    // Encoded size of the responses sent since the last `take_bytes_sent`
+    bytes_sent: Arc<AtomicU64>,
+    // Transactions sent since the last `take_transactions_sent`
+    transactions_sent: u64,
 }
 
 // Single batch of transactions to fetch, convert, and stream
@@ -42,76 +80,221 @@ pub struct TransactionBatchInfo {
 impl IndexerStreamCoordinator {
     /// Coordinates the fetching, processing, and streaming of transactions
     pub fn new(
-        context: Arc<Context>,
+        readers: Arc<ReaderFailover<Arc<Context>>>,
         request_start_version: u64,
+        request_ending_version: Option<u64>,
         processor_task_count: u16,
         processor_batch_size: u16,
         output_batch_size: u16,
+        coalesce_responses: bool,
+        schema_version: u32,
+        redactor: Arc<Redactor>,
+        filter: Arc<TransactionFilter>,
         transactions_sender: mpsc::Sender<Result<RawDatastreamResponse, tonic::Status>>,
+        slow_consumer_timeout: Duration,
     ) -> Self {
         Self {
+            starting_version: request_start_version,
             current_version: request_start_version,
+            ending_version: request_ending_version,
             processor_task_count,
             processor_batch_size,
             output_batch_size,
+            coalesce_responses,
+            schema_version,
+            redactor,
+            filter,
             highest_known_version: 0,
-            context,
+            readers,
             transactions_sender,
+            slow_consumer_timeout,
+            state_machine: StreamStateMachine::new(),
+            bytes_sent: Arc::new(AtomicU64::new(0)),
+            transactions_sent: 0,
         }
     }
 
     /// Fans out a bunch of threads and processes transactions in parallel.
-    /// Pushes results in parallel to the stream, but only return that the batch is
-    /// fully completed if every job in the batch is successful
+    /// Pushes the results to the stream in order as the jobs complete, but only return that the
+    /// batch is fully completed if every job in the batch is successful
     /// Processing transactions in 4 stages:
     /// 1. Fetch transactions from storage
     /// 2. Convert transactions to rust objects (for example stringifying move structs into json)
-    /// 3. Convert into protobuf objects
+    /// 3. Convert into protobuf objects, filtered, redacted and downgraded to the negotiated schema
+    ///    version
     /// 4. Encode protobuf objects (base64)
+    /// Storage reads are retried, a batch still failing after that terminates the stream with an
+    /// error status, `Unavailable` if it couldn't be fetched and `Unknown` otherwise.
+    /// Each job sends its own responses, unless they are coalesced, in which case only the last
+    /// response of the batch may hold fewer than `output_batch_size` transactions.
     pub async fn process_next_batch(&mut self) -> Vec<Result<EndVersion, Status>> {
-        let ledger_chain_id = self.context.chain_id().id();
+        let _timer = PROCESS_BATCH_LATENCY.start_timer();
+        let ledger_chain_id = self.readers.primary().chain_id().id();
+        self.readers
+            .maybe_revert(|context| context.get_latest_ledger_info_wrapped().is_ok());
         let mut tasks = vec![];
         let batches = self.get_batches().await;
         let output_batch_size = self.output_batch_size;
+        let schema_version = self.schema_version;
 
         for batch in batches {
-            let context = self.context.clone();
+            let readers = self.readers.clone();
             let ledger_version = self.highest_known_version;
-            let transaction_sender = self.transactions_sender.clone();
+            let redactor = self.redactor.clone();
+            let filter = self.filter.clone();
 
             let task = tokio::spawn(async move {
-                // Fetch and convert transactions from API
-                let raw_txns =
-                    Self::fetch_raw_txns_with_retries(context.clone(), ledger_version, batch).await;
-                let api_txns = Self::convert_to_api_txns(context, raw_txns).await;
-                let pb_txns = Self::convert_to_pb_txns(api_txns);
-                let encoded = Self::encode_pb_txns(pb_txns);
-                // Wrap in stream response object and send to channel
-                for chunk in encoded.chunks(output_batch_size as usize) {
-                    let item = RawDatastreamResponse {
-                        response: Some(raw_datastream_response::Response::Data(
-                            TransactionsOutput {
-                                transactions: chunk.to_vec(),
-                            },
-                        )),
-                        chain_id: ledger_chain_id as u32,
-                    };
-                    match transaction_sender.send(Result::<_, Status>::Ok(item)).await {
-                        Ok(_) => {},
-                        Err(_) => {
-                            // Client disconnects.
-                            return Err(Status::aborted("Client disconnected"));
-                        },
-                    }
-                }
-                Ok(encoded.last().unwrap().version)
+                // Fetch and convert transactions from API, both from the same reader
+                let (context, (raw_txns, block_event)) =
+                    Self::fetch_raw_txns_with_retries(&readers, ledger_version, batch).await?;
+                // The batch ends where the fetched transactions do, whatever is filtered out
+                let end_version = raw_txns.last().unwrap().version;
+                let api_txns = Self::convert_to_api_txns(context, raw_txns, block_event).await?;
+                let pb_txns =
+                    Self::convert_to_pb_txns(api_txns, schema_version, &redactor, &filter);
+                Ok::<_, Status>((end_version, Self::encode_pb_txns(pb_txns)))
             });
             tasks.push(task);
         }
-        match futures::future::try_join_all(tasks).await {
-            Ok(res) => res,
-            Err(err) => panic!("Error processing transaction batches: {:?}", err),
+
+        // Data of the jobs completing out of order waits for the jobs before them
+        let mut results = vec![];
+        let mut pending = vec![];
+        let mut tasks = tasks.into_iter();
+        while let Some(task) = tasks.next() {
+            let result = task.await.unwrap_or_else(|err| {
+                Err(Status::unknown(format!(
+                    "Error processing transaction batches: {:?}",
+                    err
+                )))
+            });
+            let (end_version, encoded) = match result {
+                Ok(output) => output,
+                Err(status) => {
+                    // Nothing after the failed batch is sent, the client resumes before it
+                    tasks.for_each(|task| task.abort());
+                    results.push(self.send_error(status).await);
+                    return results;
+                },
+            };
+            // When coalescing, a partial response waits on the next task unless this is the last
+            pending.extend(encoded);
+            let ready = if self.coalesce_responses && !tasks.as_slice().is_empty() {
+                pending.len() - pending.len() % (output_batch_size as usize).max(1)
+            } else {
+                pending.len()
+            };
+            let responses = Self::into_data_responses(
+                pending.drain(..ready).collect(),
+                output_batch_size,
+                ledger_chain_id,
+            );
+            for response in responses {
+                let bytes = response.encoded_len() as u64;
+                let transactions = match &response.response {
+                    Some(raw_datastream_response::Response::Data(data)) => data.transactions.len(),
+                    _ => 0,
+                };
+                if let Err(status) = self.send(Ok(response)).await {
+                    results.push(Err(status));
+                    return results;
+                }
+                self.bytes_sent.fetch_add(bytes, Ordering::Relaxed);
+                UNCOMPRESSED_BYTES_SENT.inc_by(bytes);
+                self.transactions_sent += transactions as u64;
+            }
+            results.push(Ok(end_version));
+        }
+        results
+    }
+
+    /// Sends the message to the stream, unless it breaks the protocol of the stream, in which
+    /// case the stream is terminated with an internal error instead. Fails with `Aborted` if the
+    /// client disconnected, with `Internal` on a protocol violation, and with `ResourceExhausted`
+    /// if the client didn't read the stream within the slow consumer timeout, in which case the
+    /// stream is terminated with a slow consumer status.
+    pub async fn send(
+        &mut self,
+        message: Result<RawDatastreamResponse, Status>,
+    ) -> Result<(), Status> {
+        if let Err(violation) = self.state_machine.on_message(&message) {
+            STREAM_PROTOCOL_VIOLATIONS
+                .with_label_values(&[violation.label()])
+                .inc();
+            error!(
+                violation = violation.to_string(),
+                "[indexer-grpc] Stream protocol violation, terminating the stream"
+            );
+            let status = Status::internal(format!("Stream protocol violation: {}", violation));
+            if self.state_machine.terminate() {
+                // The client is gone if this fails, which terminates the stream all the same
+                let _ = self.transactions_sender.send(Err(status.clone())).await;
+            }
+            return Err(status);
         }
+        match tokio::time::timeout(
+            self.slow_consumer_timeout,
+            self.transactions_sender.send(message),
+        )
+        .await
+        {
+            Ok(result) => result.map_err(|_| Status::aborted("Client disconnected")),
+            Err(_) => {
+                let slow_consumer = IndexerStreamService::get_status(
+                    StatusType::SlowConsumer,
+                    self.current_version,
+                    None,
+                    self.readers.primary().chain_id().id(),
+                );
+                if self.state_machine.terminate() {
+                    // Best effort, the client most likely still didn't read anything
+                    let _ = self.transactions_sender.try_send(Ok(slow_consumer));
+                }
+                Err(Status::resource_exhausted(format!(
+                    "Client didn't read the stream for {:?}",
+                    self.slow_consumer_timeout
+                )))
+            },
+        }
+    }
+
+    /// Terminates the stream with an error status after a failed batch, the data of the batches
+    /// before it being left without a batch end. Returns the error of the batch, unless sending
+    /// the error status fails.
+    async fn send_error(&mut self, error: Status) -> Result<EndVersion, Status> {
+        error!(
+            current_version = self.current_version,
+            error = error.to_string(),
+            "[indexer-grpc] Batch failed, terminating the stream"
+        );
+        let last_version = Some(self.current_version)
+            .filter(|version| *version > self.starting_version)
+            .map(|version| version - 1);
+        let error_status = IndexerStreamService::get_error_status(
+            self.current_version,
+            last_version,
+            error.message().to_string(),
+            self.readers.primary().chain_id().id(),
+        );
+        self.send(Ok(error_status)).await?;
+        Err(error)
+    }
+
+    /// Returns the number of bytes sent to the stream since the last call
+    pub fn take_bytes_sent(&self) -> u64 {
+        self.bytes_sent.swap(0, Ordering::Relaxed)
+    }
+
+    /// Returns the number of transactions sent to the stream since the last call
+    pub fn take_transactions_sent(&mut self) -> u64 {
+        std::mem::take(&mut self.transactions_sent)
+    }
+
+    /// Whether the last version of a bounded stream was fetched
+    pub fn is_complete(&self) -> bool {
+        self.ending_version.map_or(false, |ending_version| {
+            self.current_version > ending_version
+        })
     }
 
     /// Gets the last version of the batch if the entire batch is successful, otherwise return error
@@ -144,16 +327,20 @@ impl IndexerStreamCoordinator {
             "Preparing to fetch transactions"
         );
 
+        // Nothing past the end of a bounded stream is fetched
+        let last_version = self
+            .ending_version
+            .map_or(self.highest_known_version, |ending_version| {
+                ending_version.min(self.highest_known_version)
+            });
         let mut starting_version = self.current_version;
         let mut num_fetches = 0;
         let mut batches = vec![];
 
-        while num_fetches < self.processor_task_count
-            && starting_version <= self.highest_known_version
-        {
+        while num_fetches < self.processor_task_count && starting_version <= last_version {
             let num_transactions_to_fetch = std::cmp::min(
                 self.processor_batch_size as u64,
-                self.highest_known_version - starting_version + 1,
+                last_version - starting_version + 1,
             ) as u16;
 
             batches.push(TransactionBatchInfo {
@@ -166,71 +353,65 @@ impl IndexerStreamCoordinator {
         batches
     }
 
+    /// Fetches the transactions of the batch along with the block info of the first one, which
+    /// are retried together on failure
     async fn fetch_raw_txns_with_retries(
-        context: Arc<Context>,
+        readers: &ReaderFailover<Arc<Context>>,
         ledger_version: u64,
         batch: TransactionBatchInfo,
-    ) -> Vec<TransactionOnChainData> {
-        let mut retries = 0;
-        loop {
-            match context.get_transactions(
-                batch.start_version,
-                batch.num_transactions_to_fetch,
-                ledger_version,
-            ) {
-                Ok(raw_txns) => return raw_txns,
-                Err(err) => {
+    ) -> Result<(Arc<Context>, (Vec<TransactionOnChainData>, NewBlockEvent)), Status> {
+        let result = readers
+            .fetch_with_retries(|context| {
+                let fetch = || {
+                    let raw_txns = context.get_transactions(
+                        batch.start_version,
+                        batch.num_transactions_to_fetch,
+                        ledger_version,
+                    )?;
+                    let (_, _, block_event) =
+                        context.db.get_block_info_by_version(batch.start_version)?;
+                    Ok::<_, anyhow::Error>((raw_txns, block_event))
+                };
+                fetch().map_err(|err| {
                     UNABLE_TO_FETCH_TRANSACTION.inc();
-                    retries += 1;
-
-                    if retries >= DEFAULT_NUM_RETRIES {
-                        error!(
-                            starting_version = batch.start_version,
-                            num_transactions = batch.num_transactions_to_fetch,
-                            error = format!("{:?}", err),
-                            "Could not fetch transactions: retries exhausted",
-                        );
-                        panic!(
-                            "Could not fetch {} transactions after {} retries, starting at {}: {:?}",
-                            batch.num_transactions_to_fetch, retries, batch.start_version, err
-                        );
-                    } else {
-                        error!(
-                            starting_version = batch.start_version,
-                            num_transactions = batch.num_transactions_to_fetch,
-                            error = format!("{:?}", err),
-                            "Could not fetch transactions: will retry",
-                        );
-                    }
-                    tokio::time::sleep(Duration::from_millis(300)).await;
-                },
-            }
-        }
+                    err
+                })
+            })
+            .await;
+        result.map_err(|err| {
+            error!(
+                starting_version = batch.start_version,
+                num_transactions = batch.num_transactions_to_fetch,
+                error = format!("{:?}", err),
+                "Could not fetch transactions: retries exhausted",
+            );
+            Status::unavailable(format!(
+                "Could not fetch {} transactions, starting at {}: {}",
+                batch.num_transactions_to_fetch, batch.start_version, err
+            ))
+        })
     }
 
     async fn convert_to_api_txns(
         context: Arc<Context>,
         raw_txns: Vec<TransactionOnChainData>,
-    ) -> Vec<APITransaction> {
+        block_event: NewBlockEvent,
+    ) -> Result<Vec<APITransaction>, Status> {
         if raw_txns.is_empty() {
-            return vec![];
+            return Ok(vec![]);
         }
         let start_millis = chrono::Utc::now().naive_utc();
 
         let first_version = raw_txns.first().map(|txn| txn.version).unwrap();
-        let resolver = context.move_resolver().unwrap();
+        let resolver = context.move_resolver().map_err(|err| {
+            Status::unavailable(format!(
+                "Could not read the state to convert transactions: {}",
+                err
+            ))
+        })?;
         let converter = resolver.as_converter(context.db.clone());
 
         // Enrich data with block metadata
-        let (_, _, block_event) = context
-            .db
-            .get_block_info_by_version(first_version)
-            .unwrap_or_else(|_| {
-                panic!(
-                    "Could not get block_info for start version {}",
-                    first_version,
-                )
-            });
         let mut timestamp = block_event.proposed_time();
         let mut epoch = block_event.epoch();
         let mut epoch_bcs = aptos_api_types::U64::from(epoch);
@@ -289,18 +470,14 @@ impl IndexerStreamCoordinator {
                     );
                     // IN CASE WE NEED TO SKIP BAD TXNS
                     // continue;
-                    panic!(
-                        "Could not convert txn {} from OnChainTransactions: {:?}",
+                    return Err(Status::unknown(format!(
+                        "Could not convert txn {} from OnChainTransactions: {}",
                         txn_version, err
-                    );
+                    )));
                 },
             }
         }
 
-        if transactions.is_empty() {
-            panic!("No transactions!");
-        }
-
         let fetch_millis = (chrono::Utc::now().naive_utc() - start_millis).num_milliseconds();
 
         info!(
@@ -315,31 +492,38 @@ impl IndexerStreamCoordinator {
         );
 
         FETCHED_TRANSACTION.inc();
-        transactions
+        Ok(transactions)
     }
 
-    fn convert_to_pb_txns(api_txns: Vec<APITransaction>) -> Vec<TransactionPB> {
+    fn convert_to_pb_txns(
+        api_txns: Vec<APITransaction>,
+        schema_version: u32,
+        redactor: &Redactor,
+        filter: &TransactionFilter,
+    ) -> Vec<TransactionPB> {
         api_txns
             .iter()
-            .map(|txn| {
+            .filter_map(|txn| {
                 let info = txn.transaction_info().unwrap();
-                convert_transaction(txn, info.block_height.unwrap().0, info.epoch.unwrap().0)
+                let mut pb_txn =
+                    convert_transaction(txn, info.block_height.unwrap().0, info.epoch.unwrap().0);
+                if !filter.matches(&pb_txn) {
+                    return None;
+                }
+                // Events are redacted by type, which older schema versions don't have
+                redactor.redact(&mut pb_txn);
+                downgrade_transaction(&mut pb_txn, schema_version);
+                Some(pb_txn)
             })
             .collect()
     }
 
-    fn encode_pb_txns(pb_txns: Vec<TransactionPB>) -> Vec<TransactionOutput> {
+    pub fn encode_pb_txns(pb_txns: Vec<TransactionPB>) -> Vec<TransactionOutput> {
         pb_txns
             .iter()
             .map(|txn| {
-                let mut buf = vec![];
-                txn.encode(&mut buf).unwrap_or_else(|_| {
-                    panic!(
-                        "Could not convert protobuf transaction to bytes '{:?}'",
-                        txn
-                    )
-                });
-                let encoded_proto_data = base64::encode(buf);
+                // Sized upfront, which matters for transactions of several MB
+                let encoded_proto_data = base64::encode(txn.encode_to_vec());
                 TransactionOutput {
                     encoded_proto_data,
                     version: txn.version,
@@ -349,8 +533,33 @@ impl IndexerStreamCoordinator {
             .collect()
     }
 
+    /// Groups the encoded transactions into responses of `output_batch_size` transactions.
+    /// Takes ownership so that the encoded transactions are moved rather than copied.
+    pub fn into_data_responses(
+        encoded: Vec<TransactionOutput>,
+        output_batch_size: u16,
+        ledger_chain_id: u8,
+    ) -> Vec<RawDatastreamResponse> {
+        let mut encoded = encoded.into_iter().peekable();
+        let mut responses = vec![];
+        while encoded.peek().is_some() {
+            let transactions = encoded
+                .by_ref()
+                .take((output_batch_size as usize).max(1))
+                .collect();
+            responses.push(RawDatastreamResponse {
+                response: Some(raw_datastream_response::Response::Data(
+                    TransactionsOutput { transactions },
+                )),
+                chain_id: ledger_chain_id as u32,
+            });
+        }
+        responses
+    }
+
     pub fn set_highest_known_version(&mut self) -> anyhow::Result<()> {
-        let info = self.context.get_latest_ledger_info_wrapped()?;
+        let (_, context) = self.readers.active();
+        let info = context.get_latest_ledger_info_wrapped()?;
         self.highest_known_version = info.ledger_version.0;
         Ok(())
     }
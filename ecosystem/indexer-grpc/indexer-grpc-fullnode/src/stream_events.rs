@@ -0,0 +1,277 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+//! Structured logs for the lifecycle of the streams served by the indexer grpc runtime, so
+//! that log pipelines can parse connects, batches and disconnects without regexes.
+
+use aptos_config::config::StreamEventsVerbosity;
+use aptos_logger::{info, Schema};
+use serde::Serialize;
+use std::{
+    sync::atomic::{AtomicU64, Ordering},
+    time::{Duration, Instant},
+};
+
+static NEXT_STREAM_ID: AtomicU64 = AtomicU64::new(0);
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StreamEventType {
+    Connect,
+    BatchSent,
+    Disconnect,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TerminationReason {
+    /// The client went away, i.e. sending to the stream failed.
+    ClientDisconnected,
+    /// A batch of transactions could not be fetched or converted.
+    BatchFailed,
+    /// The last version of a bounded stream was sent.
+    Completed,
+    /// A message broke the protocol of the stream, which was terminated with an internal error.
+    ProtocolViolation,
+    /// The client didn't read the stream within the slow consumer timeout.
+    SlowConsumer,
+}
+
+#[derive(Schema)]
+pub struct StreamEventLog<'a> {
+    event_type: StreamEventType,
+    stream_id: u64,
+    client_name: &'a str,
+    start_version: Option<u64>,
+    end_version: Option<u64>,
+    bytes: Option<u64>,
+    duration_ms: Option<u64>,
+    termination_reason: Option<TerminationReason>,
+}
+
+impl<'a> StreamEventLog<'a> {
+    pub fn new(event_type: StreamEventType, stream_id: u64, client_name: &'a str) -> Self {
+        Self {
+            event_type,
+            stream_id,
+            client_name,
+            start_version: None,
+            end_version: None,
+            bytes: None,
+            duration_ms: None,
+            termination_reason: None,
+        }
+    }
+}
+
+/// Emits the lifecycle events of a single stream, according to the configured verbosity.
+pub struct StreamEvents {
+    verbosity: StreamEventsVerbosity,
+    batch_sample_rate: u64,
+    stream_id: u64,
+    client_name: String,
+    starting_version: u64,
+    connected_at: Instant,
+    num_batches: u64,
+    total_bytes: u64,
+    last_version: Option<u64>,
+}
+
+impl StreamEvents {
+    pub fn new(
+        verbosity: StreamEventsVerbosity,
+        batch_sample_rate: u64,
+        client_name: String,
+        starting_version: u64,
+    ) -> Self {
+        Self {
+            verbosity,
+            batch_sample_rate: batch_sample_rate.max(1),
+            stream_id: NEXT_STREAM_ID.fetch_add(1, Ordering::Relaxed),
+            client_name,
+            starting_version,
+            connected_at: Instant::now(),
+            num_batches: 0,
+            total_bytes: 0,
+            last_version: None,
+        }
+    }
+
+    pub fn stream_id(&self) -> u64 {
+        self.stream_id
+    }
+
+    pub fn connect(&self) {
+        if self.verbosity == StreamEventsVerbosity::Disabled {
+            return;
+        }
+        info!(
+            StreamEventLog::new(StreamEventType::Connect, self.stream_id, &self.client_name)
+                .start_version(self.starting_version),
+            "[indexer-grpc] Stream connected"
+        );
+    }
+
+    /// Records a batch sent to the client, only every `batch_sample_rate`-th batch is logged.
+    pub fn batch_sent(
+        &mut self,
+        start_version: u64,
+        end_version: u64,
+        bytes: u64,
+        duration: Duration,
+    ) {
+        self.num_batches += 1;
+        self.total_bytes += bytes;
+        self.last_version = Some(end_version);
+        if self.verbosity != StreamEventsVerbosity::Batches
+            || (self.num_batches - 1) % self.batch_sample_rate != 0
+        {
+            return;
+        }
+        info!(
+            StreamEventLog::new(
+                StreamEventType::BatchSent,
+                self.stream_id,
+                &self.client_name
+            )
+            .start_version(start_version)
+            .end_version(end_version)
+            .bytes(bytes)
+            .duration_ms(duration.as_millis() as u64),
+            "[indexer-grpc] Sent batch successfully"
+        );
+    }
+
+    /// Logs the end of the stream, with the range of versions and bytes sent over its lifetime.
+    pub fn disconnect(&self, reason: TerminationReason) {
+        if self.verbosity == StreamEventsVerbosity::Disabled {
+            return;
+        }
+        let mut log = StreamEventLog::new(
+            StreamEventType::Disconnect,
+            self.stream_id,
+            &self.client_name,
+        )
+        .start_version(self.starting_version)
+        .bytes(self.total_bytes)
+        .duration_ms(self.connected_at.elapsed().as_millis() as u64)
+        .termination_reason(reason);
+        if let Some(last_version) = self.last_version {
+            log = log.end_version(last_version);
+        }
+        info!(log, "[indexer-grpc] Stream disconnected");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aptos_logger::{aptos_logger::AptosData, Writer};
+    use serde_json::{json, Value};
+    use std::sync::{Arc, Mutex};
+
+    #[derive(Default)]
+    struct VecWriter {
+        logs: Arc<Mutex<Vec<String>>>,
+    }
+
+    impl Writer for VecWriter {
+        fn write(&self, log: String) {
+            self.logs.lock().unwrap().push(log)
+        }
+
+        fn write_buferred(&mut self, log: String) {
+            self.write(log);
+        }
+    }
+
+    /// Installs a logger that captures the structured fields of every log line.
+    fn capture_logs() -> Arc<Mutex<Vec<String>>> {
+        let writer = VecWriter::default();
+        let logs = writer.logs.clone();
+        AptosData::builder()
+            .is_async(false)
+            .printer(Box::new(writer))
+            .custom_format(|entry| Ok(serde_json::to_string(&entry.data()).unwrap()))
+            .build();
+        logs
+    }
+
+    fn stream_logs(logs: &Mutex<Vec<String>>, stream_id: u64) -> Vec<Value> {
+        logs.lock()
+            .unwrap()
+            .iter()
+            .map(|log| serde_json::from_str::<Value>(log).unwrap())
+            .filter(|log| log["stream_id"] == json!(stream_id))
+            .collect()
+    }
+
+    #[test]
+    fn test_stream_lifecycle_events() {
+        let captured = capture_logs();
+
+        // A mock stream sending 5 batches of 100 versions, logging every other batch.
+        let mut events = StreamEvents::new(
+            StreamEventsVerbosity::Batches,
+            2,
+            "test-client".to_string(),
+            1000,
+        );
+        events.connect();
+        for batch in 0..5 {
+            let start_version = 1000 + batch * 100;
+            events.batch_sent(
+                start_version,
+                start_version + 99,
+                10,
+                Duration::from_millis(3),
+            );
+        }
+        events.disconnect(TerminationReason::ClientDisconnected);
+
+        let logs = stream_logs(&captured, events.stream_id());
+        let event_types: Vec<_> = logs.iter().map(|log| log["event_type"].clone()).collect();
+        assert_eq!(event_types, vec![
+            json!("connect"),
+            json!("batch_sent"),
+            json!("batch_sent"),
+            json!("batch_sent"),
+            json!("disconnect"),
+        ]);
+
+        assert_eq!(logs[0]["client_name"], json!("test-client"));
+        assert_eq!(logs[0]["start_version"], json!(1000));
+
+        // Batches 0, 2 and 4 are sampled.
+        assert_eq!(logs[2]["start_version"], json!(1200));
+        assert_eq!(logs[2]["end_version"], json!(1299));
+        assert_eq!(logs[2]["bytes"], json!(10));
+        assert_eq!(logs[2]["duration_ms"], json!(3));
+
+        let disconnect = &logs[4];
+        assert_eq!(disconnect["start_version"], json!(1000));
+        assert_eq!(disconnect["end_version"], json!(1499));
+        assert_eq!(disconnect["bytes"], json!(50));
+        assert_eq!(
+            disconnect["termination_reason"],
+            json!("client_disconnected")
+        );
+        assert!(disconnect.get("duration_ms").is_some());
+
+        // Batches are not logged with the default verbosity.
+        let mut events = StreamEvents::new(
+            StreamEventsVerbosity::Lifecycle,
+            1,
+            "test-client".to_string(),
+            0,
+        );
+        events.connect();
+        events.batch_sent(0, 99, 10, Duration::from_millis(3));
+        events.disconnect(TerminationReason::BatchFailed);
+        let event_types: Vec<_> = stream_logs(&captured, events.stream_id())
+            .iter()
+            .map(|log| log["event_type"].clone())
+            .collect();
+        assert_eq!(event_types, vec![json!("connect"), json!("disconnect")]);
+    }
+}
@@ -0,0 +1,51 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::counters::{
+    ACTIVE_STREAMS, STREAM_BATCHES_SENT, STREAM_TRANSACTIONS_SENT, STREAM_VERSION_LAG,
+};
+
+/// Per-stream metrics of a raw datastream, labeled by the stream id of its events. The stream
+/// counts as active until this is dropped, which also removes its series, so that the labels are
+/// bounded by the number of streams open at a time rather than growing with every stream served.
+pub struct StreamMetrics {
+    stream_id: String,
+}
+
+impl StreamMetrics {
+    pub fn new(stream_id: u64) -> Self {
+        ACTIVE_STREAMS.inc();
+        Self {
+            stream_id: stream_id.to_string(),
+        }
+    }
+
+    /// Counts a batch sent to the client, once its batch end is
+    pub fn batch_sent(&self, transactions: u64) {
+        STREAM_BATCHES_SENT
+            .with_label_values(&[&self.stream_id])
+            .inc();
+        STREAM_TRANSACTIONS_SENT
+            .with_label_values(&[&self.stream_id])
+            .inc_by(transactions);
+    }
+
+    /// Records how many versions up to the latest ledger version the stream still has to send
+    pub fn set_version_lag(&self, next_version: u64, latest_version: u64) {
+        let lag = (latest_version + 1).saturating_sub(next_version);
+        STREAM_VERSION_LAG
+            .with_label_values(&[&self.stream_id])
+            .set(lag as i64);
+    }
+}
+
+impl Drop for StreamMetrics {
+    fn drop(&mut self) {
+        ACTIVE_STREAMS.dec();
+        // Series are only created once a batch is sent, a stream may end before that
+        for metric in [&*STREAM_BATCHES_SENT, &*STREAM_TRANSACTIONS_SENT] {
+            let _ = metric.remove_label_values(&[&self.stream_id]);
+        }
+        let _ = STREAM_VERSION_LAG.remove_label_values(&[&self.stream_id]);
+    }
+}
@@ -0,0 +1,244 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use aptos_protos::datastream::v1::{
+    raw_datastream_response::Response as ResponsePB, stream_status::StatusType,
+    RawDatastreamResponse,
+};
+use std::fmt;
+use tonic::Status;
+
+/// A message of the stream breaking the protocol clients rely on
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ProtocolViolation {
+    /// The first message isn't the init status
+    MissingInit,
+    /// An init status after the first message
+    DuplicateInit,
+    /// A batch end that doesn't start right after the previous one, or at the start of the stream
+    NonContiguousBatchEnd { expected_start: u64, start: u64 },
+    /// A batch end without an end version, or ending before it starts
+    InvalidBatchEnd { start: u64, end: Option<u64> },
+    /// Data at or below a version already sent, covered by a batch end or before the start of
+    /// the stream
+    NonIncreasingData { previous: u64, version: u64 },
+    /// Data after the end version of the batch end covering it
+    DataOutsideBatch { end: u64, version: u64 },
+    /// A completed status that doesn't come right after a batch end, or doesn't end where it does
+    InvalidCompleted { end: Option<u64> },
+    /// A response with neither a status nor data
+    EmptyResponse,
+    /// A message after the error status terminating the stream
+    MessageAfterTermination,
+}
+
+impl ProtocolViolation {
+    /// Label of the violation in the counters
+    pub fn label(&self) -> &'static str {
+        match self {
+            ProtocolViolation::MissingInit => "missing_init",
+            ProtocolViolation::DuplicateInit => "duplicate_init",
+            ProtocolViolation::NonContiguousBatchEnd { .. } => "non_contiguous_batch_end",
+            ProtocolViolation::InvalidBatchEnd { .. } => "invalid_batch_end",
+            ProtocolViolation::NonIncreasingData { .. } => "non_increasing_data",
+            ProtocolViolation::DataOutsideBatch { .. } => "data_outside_batch",
+            ProtocolViolation::InvalidCompleted { .. } => "invalid_completed",
+            ProtocolViolation::EmptyResponse => "empty_response",
+            ProtocolViolation::MessageAfterTermination => "message_after_termination",
+        }
+    }
+}
+
+impl fmt::Display for ProtocolViolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ProtocolViolation::MissingInit => write!(f, "the stream doesn't start with init"),
+            ProtocolViolation::DuplicateInit => write!(f, "init sent twice"),
+            ProtocolViolation::NonContiguousBatchEnd {
+                expected_start,
+                start,
+            } => write!(
+                f,
+                "batch end starts at {} instead of {}",
+                start, expected_start
+            ),
+            ProtocolViolation::InvalidBatchEnd { start, end } => {
+                write!(f, "batch end from {} to {:?}", start, end)
+            },
+            ProtocolViolation::NonIncreasingData { previous, version } => write!(
+                f,
+                "transaction {} sent after transaction {}",
+                version, previous
+            ),
+            ProtocolViolation::DataOutsideBatch { end, version } => write!(
+                f,
+                "transaction {} sent before a batch end at {}",
+                version, end
+            ),
+            ProtocolViolation::InvalidCompleted { end } => {
+                write!(f, "stream completed at {:?} outside of a batch end", end)
+            },
+            ProtocolViolation::EmptyResponse => write!(f, "empty response"),
+            ProtocolViolation::MessageAfterTermination => {
+                write!(f, "message after the stream terminated")
+            },
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum State {
+    AwaitingInit,
+    Streaming {
+        /// Start version of the next batch end
+        batch_start: u64,
+        /// Last transaction sent, if any since the stream started
+        last_version: Option<u64>,
+    },
+    Terminated,
+}
+
+/// The protocol of a raw datastream, which every message of the stream goes through before it is
+/// sent: a single init status first, then data with strictly increasing versions, each batch of
+/// it followed by a batch end covering exactly the versions after the previous one, and at most
+/// one terminal message: either a gRPC error, a slow consumer or error status, or a completed status
+/// ending at the last batch end of a bounded stream.
+#[derive(Debug)]
+pub struct StreamStateMachine {
+    state: State,
+}
+
+impl Default for StreamStateMachine {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl StreamStateMachine {
+    pub fn new() -> Self {
+        Self {
+            state: State::AwaitingInit,
+        }
+    }
+
+    /// Checks the message against the protocol, and moves on to the state after it if it
+    /// follows the protocol. Nothing changes on a violation.
+    pub fn on_message(
+        &mut self,
+        message: &Result<RawDatastreamResponse, Status>,
+    ) -> Result<(), ProtocolViolation> {
+        let response = match (self.state, message) {
+            (State::Terminated, _) => return Err(ProtocolViolation::MessageAfterTermination),
+            (_, Err(_)) => {
+                self.state = State::Terminated;
+                return Ok(());
+            },
+            (_, Ok(response)) => response
+                .response
+                .as_ref()
+                .ok_or(ProtocolViolation::EmptyResponse)?,
+        };
+        self.state = match (self.state, response) {
+            (_, ResponsePB::Status(status))
+                if matches!(
+                    status.r#type(),
+                    StatusType::SlowConsumer | StatusType::Error
+                ) =>
+            {
+                State::Terminated
+            },
+            (State::AwaitingInit, ResponsePB::Status(status))
+                if status.r#type() == StatusType::Init =>
+            {
+                State::Streaming {
+                    batch_start: status.start_version,
+                    last_version: None,
+                }
+            },
+            (State::AwaitingInit, _) => return Err(ProtocolViolation::MissingInit),
+            (State::Streaming { .. }, ResponsePB::Status(status))
+                if status.r#type() == StatusType::Init =>
+            {
+                return Err(ProtocolViolation::DuplicateInit)
+            },
+            (
+                State::Streaming {
+                    batch_start,
+                    last_version,
+                },
+                ResponsePB::Status(status),
+            ) if status.r#type() == StatusType::Completed => {
+                // Right after a batch end, the last version sent being its end version
+                let batch_ended =
+                    last_version.is_some() && last_version == batch_start.checked_sub(1);
+                if !batch_ended || status.end_version != last_version {
+                    return Err(ProtocolViolation::InvalidCompleted {
+                        end: status.end_version,
+                    });
+                }
+                State::Terminated
+            },
+            (
+                State::Streaming {
+                    batch_start,
+                    last_version,
+                },
+                ResponsePB::Status(status),
+            ) => {
+                if status.start_version != batch_start {
+                    return Err(ProtocolViolation::NonContiguousBatchEnd {
+                        expected_start: batch_start,
+                        start: status.start_version,
+                    });
+                }
+                let end = match status.end_version {
+                    Some(end) if end >= batch_start => end,
+                    end => {
+                        return Err(ProtocolViolation::InvalidBatchEnd {
+                            start: status.start_version,
+                            end,
+                        })
+                    },
+                };
+                if let Some(version) = last_version.filter(|version| *version > end) {
+                    return Err(ProtocolViolation::DataOutsideBatch { end, version });
+                }
+                State::Streaming {
+                    batch_start: end + 1,
+                    last_version: Some(end),
+                }
+            },
+            (
+                State::Streaming {
+                    batch_start,
+                    mut last_version,
+                },
+                ResponsePB::Data(data),
+            ) => {
+                for txn in &data.transactions {
+                    // Versions before the start of the stream count as sent
+                    let previous = last_version.or_else(|| batch_start.checked_sub(1));
+                    if let Some(previous) = previous.filter(|previous| txn.version <= *previous) {
+                        return Err(ProtocolViolation::NonIncreasingData {
+                            previous,
+                            version: txn.version,
+                        });
+                    }
+                    last_version = Some(txn.version);
+                }
+                State::Streaming {
+                    batch_start,
+                    last_version,
+                }
+            },
+            (State::Terminated, _) => unreachable!("Terminated streams are handled above"),
+        };
+        Ok(())
+    }
+
+    /// Terminates the stream, returning whether it wasn't already, i.e. whether a terminal status
+    /// still has to be sent
+    pub fn terminate(&mut self) -> bool {
+        std::mem::replace(&mut self.state, State::Terminated) != State::Terminated
+    }
+}
@@ -0,0 +1,275 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    chain_status::LedgerSampler,
+    failover::{FailoverOptions, ReaderFailover},
+    redaction::Redactor,
+    runtime::IndexerStreamService,
+    stream_state_machine::StreamStateMachine,
+    tests::{super_new_test_context, TestContext},
+};
+use anyhow::{bail, Result};
+use aptos_api::context::Context;
+use aptos_config::config::StreamEventsVerbosity;
+use aptos_protos::datastream::v1::{
+    indexer_stream_server::IndexerStream, raw_datastream_response::Response as ResponsePB,
+    stream_status::StatusType, RawDatastreamRequest, StreamStatus,
+};
+use aptos_storage_interface::DbReader;
+use aptos_types::{
+    account_config::NewBlockEvent,
+    ledger_info::LedgerInfoWithSignatures,
+    state_store::{
+        state_key::StateKey,
+        state_storage_usage::StateStorageUsage,
+        state_value::StateValue,
+        table::{TableHandle, TableInfo},
+    },
+    transaction::{TransactionOutputListWithProof, Version},
+};
+use futures::StreamExt;
+use std::{
+    sync::{
+        atomic::{AtomicBool, AtomicU64, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+use tonic::Request;
+
+/// Storage failing to fetch transactions from a version on, a given number of times, and to read
+/// the latest ledger info while it's down
+struct FlakyDb {
+    inner: Arc<dyn DbReader>,
+    fail_from: Version,
+    failures_left: AtomicU64,
+    ledger_info_down: AtomicBool,
+}
+
+impl DbReader for FlakyDb {
+    fn get_transaction_outputs(
+        &self,
+        start_version: Version,
+        limit: u64,
+        ledger_version: Version,
+    ) -> Result<TransactionOutputListWithProof> {
+        if start_version >= self.fail_from
+            && self
+                .failures_left
+                .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |left| {
+                    left.checked_sub(1)
+                })
+                .is_ok()
+        {
+            bail!("Storage is unavailable");
+        }
+        self.inner
+            .get_transaction_outputs(start_version, limit, ledger_version)
+    }
+
+    fn get_first_viable_txn_version(&self) -> Result<Version> {
+        self.inner.get_first_viable_txn_version()
+    }
+
+    fn get_latest_ledger_info_option(&self) -> Result<Option<LedgerInfoWithSignatures>> {
+        if self.ledger_info_down.load(Ordering::SeqCst) {
+            bail!("Storage is unavailable");
+        }
+        self.inner.get_latest_ledger_info_option()
+    }
+
+    fn get_next_block_event(&self, version: Version) -> Result<(Version, NewBlockEvent)> {
+        self.inner.get_next_block_event(version)
+    }
+
+    fn get_block_info_by_version(
+        &self,
+        version: Version,
+    ) -> Result<(Version, Version, NewBlockEvent)> {
+        self.inner.get_block_info_by_version(version)
+    }
+
+    fn get_latest_state_checkpoint_version(&self) -> Result<Option<Version>> {
+        self.inner.get_latest_state_checkpoint_version()
+    }
+
+    fn get_state_value_by_version(
+        &self,
+        state_key: &StateKey,
+        version: Version,
+    ) -> Result<Option<StateValue>> {
+        self.inner.get_state_value_by_version(state_key, version)
+    }
+
+    fn get_table_info(&self, handle: TableHandle) -> Result<TableInfo> {
+        self.inner.get_table_info(handle)
+    }
+
+    fn indexer_enabled(&self) -> bool {
+        self.inner.indexer_enabled()
+    }
+
+    fn get_state_storage_usage(&self, version: Option<Version>) -> Result<StateStorageUsage> {
+        self.inner.get_state_storage_usage(version)
+    }
+}
+
+/// A service over a few blocks of storage failing from `fail_from` on, one batch of 3
+/// transactions at a time. The test context has to outlive the service.
+async fn flaky_service(
+    name: &str,
+    fail_from: Version,
+    failures: u64,
+) -> (TestContext, IndexerStreamService, Arc<FlakyDb>) {
+    let (context, flaky_context, db) = flaky_context(name, fail_from, failures).await;
+    let service = service(vec![Arc::new(flaky_context)]);
+    (context, service, db)
+}
+
+async fn flaky_context(
+    name: &str,
+    fail_from: Version,
+    failures: u64,
+) -> (TestContext, Context, Arc<FlakyDb>) {
+    let mut context = super_new_test_context(name.to_string(), false);
+    let mut root_account = context.root_account();
+    for _ in 0..3 {
+        let account = context.gen_account();
+        let txn = context.create_user_account_by(&mut root_account, &account);
+        context.commit_block(&[txn]).await;
+    }
+    let db = Arc::new(FlakyDb {
+        inner: context.context.db.clone(),
+        fail_from,
+        failures_left: AtomicU64::new(failures),
+        ledger_info_down: AtomicBool::new(false),
+    });
+    let mut flaky_context = context.context.clone();
+    flaky_context.db = db.clone();
+    (context, flaky_context, db)
+}
+
+fn service(readers: Vec<Arc<Context>>) -> IndexerStreamService {
+    let readers = Arc::new(ReaderFailover::new(
+        readers,
+        FailoverOptions {
+            retry_delay: Duration::from_millis(1),
+            ..FailoverOptions::default()
+        },
+    ));
+    IndexerStreamService {
+        readers: readers.clone(),
+        processor_task_count: 1,
+        processor_batch_size: 3,
+        output_batch_size: 1,
+        coalesce_responses: false,
+        stream_events_verbosity: StreamEventsVerbosity::Disabled,
+        stream_events_batch_sample_rate: 1,
+        reject_schema_downgrades: false,
+        redactor: Arc::new(Redactor::default()),
+        transaction_channel_size: 35,
+        slow_consumer_timeout: Duration::from_secs(60),
+        ledger_sampler: Arc::new(LedgerSampler::spawn(readers, Duration::from_secs(1))),
+    }
+}
+
+fn request(
+    starting_version: u64,
+    resume_token: Option<String>,
+    ending_version: u64,
+) -> RawDatastreamRequest {
+    RawDatastreamRequest {
+        starting_version,
+        max_supported_schema_version: None,
+        resume_token,
+        strict_resume: false,
+        ending_version: Some(ending_version),
+        transaction_types: vec![],
+        entry_function_address_prefixes: vec![],
+    }
+}
+
+/// Reads the stream until it closes, returning the versions sent and the terminal status
+async fn read_stream(
+    service: &IndexerStreamService,
+    request: RawDatastreamRequest,
+) -> (Vec<u64>, StreamStatus) {
+    let mut stream = service
+        .raw_datastream(Request::new(request))
+        .await
+        .unwrap()
+        .into_inner();
+    let mut state_machine = StreamStateMachine::new();
+    let mut versions = vec![];
+    let mut terminal = None;
+    while let Some(message) = tokio::time::timeout(Duration::from_secs(10), stream.next())
+        .await
+        .expect("Timed out waiting on the stream")
+    {
+        state_machine.on_message(&message).unwrap();
+        match message.unwrap().response.unwrap() {
+            ResponsePB::Data(data) => {
+                versions.extend(data.transactions.iter().map(|txn| txn.version))
+            },
+            ResponsePB::Status(status)
+                if matches!(status.r#type(), StatusType::Completed | StatusType::Error) =>
+            {
+                terminal = Some(status)
+            },
+            ResponsePB::Status(_) => {},
+        }
+    }
+    (
+        versions,
+        terminal.expect("The stream closed without a terminal status"),
+    )
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_transient_failures_retried() {
+    let (_context, service, db) = flaky_service("test_transient_failures_retried", 3, 2).await;
+
+    // The second batch fails twice, which the retries absorb.
+    let (versions, terminal) = read_stream(&service, request(0, None, 7)).await;
+    assert_eq!(versions, (0..=7).collect::<Vec<_>>());
+    assert_eq!(terminal.r#type(), StatusType::Completed);
+    assert_eq!(db.failures_left.load(Ordering::SeqCst), 0);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_permanent_failure_ends_with_error_status() {
+    let (_context, service, db) =
+        flaky_service("test_permanent_failure_ends_with_error_status", 3, u64::MAX).await;
+
+    // The first batch goes through, then the stream ends on the second one.
+    let (versions, terminal) = read_stream(&service, request(0, None, 7)).await;
+    assert_eq!(versions, vec![0, 1, 2]);
+    assert_eq!(terminal.r#type(), StatusType::Error);
+    assert_eq!(terminal.start_version, 3);
+    assert_eq!(terminal.end_version, Some(2));
+    assert!(terminal
+        .error_message
+        .unwrap()
+        .contains("Storage is unavailable"));
+
+    // Once storage recovers, resuming from the error status leaves no gap.
+    db.failures_left.store(0, Ordering::SeqCst);
+    let (versions, terminal) = read_stream(&service, request(0, terminal.resume_token, 7)).await;
+    assert_eq!(versions, (3..=7).collect::<Vec<_>>());
+    assert_eq!(terminal.r#type(), StatusType::Completed);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_starts_streaming_while_the_primary_is_down() {
+    let (context, primary, db) =
+        flaky_context("test_starts_streaming_while_the_primary_is_down", 0, 0).await;
+    db.ledger_info_down.store(true, Ordering::SeqCst);
+    let service = service(vec![Arc::new(primary), Arc::new(context.context.clone())]);
+
+    // The oldest available version is read from the fallback, like the transactions
+    let (versions, terminal) = read_stream(&service, request(0, None, 7)).await;
+    assert_eq!(versions, (0..=7).collect::<Vec<_>>());
+    assert_eq!(terminal.r#type(), StatusType::Completed);
+    assert_eq!(service.readers.active().0, 1);
+}
@@ -0,0 +1,92 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    chain_status::LedgerSampler,
+    failover::{FailoverOptions, ReaderFailover},
+    redaction::Redactor,
+    runtime::IndexerStreamService,
+    stream_state_machine::StreamStateMachine,
+    tests::super_new_test_context,
+};
+use aptos_config::config::StreamEventsVerbosity;
+use aptos_protos::datastream::v1::{
+    indexer_stream_server::IndexerStream, raw_datastream_response::Response as ResponsePB,
+    stream_status::StatusType, RawDatastreamRequest,
+};
+use futures::StreamExt;
+use std::{sync::Arc, time::Duration};
+use tonic::{Code, Request};
+
+fn request(starting_version: u64, ending_version: u64) -> RawDatastreamRequest {
+    RawDatastreamRequest {
+        starting_version,
+        max_supported_schema_version: None,
+        resume_token: None,
+        strict_resume: false,
+        ending_version: Some(ending_version),
+        transaction_types: vec![],
+        entry_function_address_prefixes: vec![],
+    }
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_bounded_stream_completes() {
+    let mut context = super_new_test_context("test_bounded_stream_completes".to_string(), false);
+    let mut root_account = context.root_account();
+    for _ in 0..3 {
+        let account = context.gen_account();
+        let txn = context.create_user_account_by(&mut root_account, &account);
+        context.commit_block(&[txn]).await;
+    }
+    let readers = Arc::new(ReaderFailover::new(
+        vec![Arc::new(context.context.clone())],
+        FailoverOptions::default(),
+    ));
+    // Small batches, so that the ending version falls in the middle of one
+    let service = IndexerStreamService {
+        readers: readers.clone(),
+        processor_task_count: 2,
+        processor_batch_size: 3,
+        output_batch_size: 2,
+        coalesce_responses: false,
+        stream_events_verbosity: StreamEventsVerbosity::Disabled,
+        stream_events_batch_sample_rate: 1,
+        reject_schema_downgrades: false,
+        redactor: Arc::new(Redactor::default()),
+        transaction_channel_size: 35,
+        slow_consumer_timeout: Duration::from_secs(60),
+        ledger_sampler: Arc::new(LedgerSampler::spawn(readers, Duration::from_secs(1))),
+    };
+
+    let mut stream = service
+        .raw_datastream(Request::new(request(0, 7)))
+        .await
+        .unwrap()
+        .into_inner();
+    let mut state_machine = StreamStateMachine::new();
+    let mut versions = vec![];
+    let mut completed = None;
+    while let Some(message) = tokio::time::timeout(Duration::from_secs(10), stream.next())
+        .await
+        .expect("Timed out waiting on the bounded stream")
+    {
+        state_machine.on_message(&message).unwrap();
+        match message.unwrap().response.unwrap() {
+            ResponsePB::Data(data) => {
+                versions.extend(data.transactions.iter().map(|txn| txn.version))
+            },
+            ResponsePB::Status(status) if status.r#type() == StatusType::Completed => {
+                completed = Some((status.start_version, status.end_version))
+            },
+            ResponsePB::Status(_) => {},
+        }
+    }
+    // Exactly the versions up to the ending version, then the stream closes once completed.
+    assert_eq!(versions, (0..=7).collect::<Vec<_>>());
+    assert_eq!(completed, Some((0, Some(7))));
+
+    // An ending version before the starting version is rejected right away.
+    let result = service.raw_datastream(Request::new(request(5, 4))).await;
+    assert_eq!(result.err().unwrap().code(), Code::InvalidArgument);
+}
@@ -0,0 +1,153 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::chain_status::{watch_chain_status, LedgerSampler, LedgerSource, WATCHER_BUFFER_SIZE};
+use aptos_api_types::{LedgerInfo, U64};
+use aptos_protos::datastream::v1::{WatchChainStatusRequest, WatchChainStatusResponse};
+use futures::{Stream, StreamExt};
+use std::{
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+    time::{Duration, Instant},
+};
+use tonic::{Code, Status};
+
+const CHAIN_ID: u8 = 4;
+/// Sampling interval of the tests, waiting a few of them lets the sampler see a version
+const SAMPLE_INTERVAL: Duration = Duration::from_millis(5);
+
+/// A ledger that only moves when told to
+struct MockLedger {
+    version: AtomicU64,
+}
+
+impl MockLedger {
+    fn new(version: u64) -> Arc<Self> {
+        Arc::new(Self {
+            version: AtomicU64::new(version),
+        })
+    }
+
+    /// Advances the ledger a version at a time, giving the sampler time to see each of them
+    async fn advance(&self, versions: u64) {
+        for _ in 0..versions {
+            self.version.fetch_add(1, Ordering::SeqCst);
+            tokio::time::sleep(SAMPLE_INTERVAL * 3).await;
+        }
+    }
+}
+
+impl LedgerSource for MockLedger {
+    fn latest_ledger_info(&self) -> anyhow::Result<LedgerInfo> {
+        let version = self.version.load(Ordering::SeqCst);
+        Ok(LedgerInfo {
+            chain_id: CHAIN_ID,
+            epoch: U64::from(version / 100 + 1),
+            ledger_version: U64::from(version),
+            oldest_ledger_version: U64::from(version / 2),
+            block_height: U64::from(version / 3),
+            oldest_block_height: U64::from(0),
+            ledger_timestamp: U64::from(version * 1_000_000),
+        })
+    }
+}
+
+fn request(every_versions: u64, every_millis: u64) -> WatchChainStatusRequest {
+    WatchChainStatusRequest {
+        every_versions: Some(every_versions),
+        every_millis: Some(every_millis),
+    }
+}
+
+async fn next(
+    stream: &mut (impl Stream<Item = Result<WatchChainStatusResponse, Status>> + Unpin),
+) -> Option<Result<WatchChainStatusResponse, Status>> {
+    tokio::time::timeout(Duration::from_secs(10), stream.next())
+        .await
+        .expect("Timed out waiting on the chain status stream")
+}
+
+#[tokio::test]
+async fn test_push_cadence() {
+    let ledger = MockLedger::new(100);
+    let sampler = LedgerSampler::spawn(ledger.clone(), SAMPLE_INTERVAL);
+    let mut stream = watch_chain_status(sampler.subscribe(), &request(10, 1000), CHAIN_ID);
+
+    // The latest version is pushed right away, with the rest of the status.
+    let status = next(&mut stream).await.unwrap().unwrap();
+    assert_eq!(status.chain_id, CHAIN_ID as u32);
+    assert_eq!(status.latest_version, 100);
+    assert_eq!(status.epoch, 2);
+    assert_eq!(status.block_height, 33);
+    assert_eq!(status.timestamp.unwrap().seconds, 100);
+    assert_eq!(status.oldest_available_version, 50);
+
+    // The versions in between aren't pushed, the ledger has to advance by 10 first.
+    ledger.advance(10).await;
+    let status = next(&mut stream).await.unwrap().unwrap();
+    assert_eq!(status.latest_version, 110);
+    let pushed_at = Instant::now();
+
+    // Short of 10 versions, the ledger is only pushed once the interval elapsed.
+    ledger.advance(3).await;
+    let status = next(&mut stream).await.unwrap().unwrap();
+    assert_eq!(status.latest_version, 113);
+    assert!(pushed_at.elapsed() >= Duration::from_millis(900));
+
+    // Nothing is pushed while the ledger doesn't move.
+    assert!(
+        tokio::time::timeout(Duration::from_millis(1500), stream.next())
+            .await
+            .is_err()
+    );
+}
+
+#[tokio::test]
+async fn test_slow_watcher_dropped() {
+    let ledger = MockLedger::new(0);
+    let sampler = LedgerSampler::spawn(ledger.clone(), SAMPLE_INTERVAL);
+    let mut slow = watch_chain_status(sampler.subscribe(), &request(1, 1000), CHAIN_ID);
+    let mut fast = watch_chain_status(sampler.subscribe(), &request(1, 1000), CHAIN_ID);
+
+    // The slow watcher reads nothing while the ledger moves further than its buffer.
+    let versions = WATCHER_BUFFER_SIZE as u64 + 4;
+    let reader = tokio::spawn(async move {
+        let mut latest = 0;
+        while latest < versions {
+            latest = next(&mut fast).await.unwrap().unwrap().latest_version;
+        }
+        fast
+    });
+    ledger.advance(versions).await;
+    let mut fast = reader.await.unwrap();
+
+    // The buffered statuses are still delivered, then the stream terminates.
+    let mut previous = None;
+    for _ in 0..WATCHER_BUFFER_SIZE {
+        let version = next(&mut slow).await.unwrap().unwrap().latest_version;
+        assert!(previous < Some(version));
+        previous = Some(version);
+    }
+    let termination = next(&mut slow).await.unwrap().unwrap_err();
+    assert_eq!(termination.code(), Code::ResourceExhausted);
+    assert!(next(&mut slow).await.is_none());
+
+    // The watcher keeping up isn't affected.
+    ledger.advance(1).await;
+    let status = next(&mut fast).await.unwrap().unwrap();
+    assert_eq!(status.latest_version, versions + 1);
+}
+
+#[tokio::test]
+async fn test_streams_end_on_shutdown() {
+    let ledger = MockLedger::new(7);
+    let sampler = LedgerSampler::spawn(ledger.clone(), SAMPLE_INTERVAL);
+    let mut stream = watch_chain_status(sampler.subscribe(), &request(1, 1000), CHAIN_ID);
+    assert_eq!(next(&mut stream).await.unwrap().unwrap().latest_version, 7);
+
+    // The stream ends without an error status.
+    sampler.shutdown();
+    assert!(next(&mut stream).await.is_none());
+}
@@ -0,0 +1,165 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    chain_status::LedgerSampler,
+    counters::COMPRESSED_BYTES_SENT,
+    failover::{FailoverOptions, ReaderFailover},
+    redaction::Redactor,
+    runtime::{stream_server, IndexerStreamService},
+    stream_state_machine::StreamStateMachine,
+    tests::super_new_test_context,
+};
+use aptos_config::{config::StreamEventsVerbosity, utils::get_available_port};
+use aptos_protos::datastream::v1::{
+    indexer_stream_client::IndexerStreamClient, indexer_stream_server::IndexerStream,
+    raw_datastream_response::Response as ResponsePB, RawDatastreamRequest, RawDatastreamResponse,
+    TransactionOutput,
+};
+use futures::{Stream, StreamExt};
+use prost::Message;
+use std::{net::SocketAddr, sync::Arc, time::Duration};
+use tonic::{codec::CompressionEncoding, transport::Server, Request, Status};
+
+fn request() -> RawDatastreamRequest {
+    RawDatastreamRequest {
+        starting_version: 0,
+        max_supported_schema_version: None,
+        resume_token: None,
+        strict_resume: false,
+        ending_version: Some(7),
+        transaction_types: vec![],
+        entry_function_address_prefixes: vec![],
+    }
+}
+
+/// Reads the stream until it closes
+async fn read_stream(
+    mut stream: impl Stream<Item = Result<RawDatastreamResponse, Status>> + Unpin,
+) -> Vec<RawDatastreamResponse> {
+    let mut state_machine = StreamStateMachine::new();
+    let mut responses = vec![];
+    while let Some(message) = tokio::time::timeout(Duration::from_secs(10), stream.next())
+        .await
+        .expect("Timed out waiting on the stream")
+    {
+        state_machine.on_message(&message).unwrap();
+        responses.push(message.unwrap());
+    }
+    responses
+}
+
+fn transactions(responses: &[RawDatastreamResponse]) -> Vec<TransactionOutput> {
+    responses
+        .iter()
+        .filter_map(|response| match response.response.as_ref().unwrap() {
+            ResponsePB::Data(data) => Some(data.transactions.clone()),
+            ResponsePB::Status(_) => None,
+        })
+        .flatten()
+        .collect()
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_compressed_stream_round_trip() {
+    let mut context =
+        super_new_test_context("test_compressed_stream_round_trip".to_string(), false);
+    let mut root_account = context.root_account();
+    for _ in 0..3 {
+        let account = context.gen_account();
+        let txn = context.create_user_account_by(&mut root_account, &account);
+        context.commit_block(&[txn]).await;
+    }
+    let readers = Arc::new(ReaderFailover::new(
+        vec![Arc::new(context.context.clone())],
+        FailoverOptions::default(),
+    ));
+    // Each task fetches 3 transactions, sent as responses of 2 then 1 unless coalesced
+    let service = |coalesce_responses| IndexerStreamService {
+        readers: readers.clone(),
+        processor_task_count: 2,
+        processor_batch_size: 3,
+        output_batch_size: 2,
+        coalesce_responses,
+        stream_events_verbosity: StreamEventsVerbosity::Disabled,
+        stream_events_batch_sample_rate: 1,
+        reject_schema_downgrades: false,
+        redactor: Arc::new(Redactor::default()),
+        transaction_channel_size: 35,
+        slow_consumer_timeout: Duration::from_secs(60),
+        ledger_sampler: Arc::new(LedgerSampler::spawn(
+            readers.clone(),
+            Duration::from_secs(1),
+        )),
+    };
+
+    // The same stream, uncompressed and straight from the service.
+    let expected = read_stream(
+        service(false)
+            .raw_datastream(Request::new(request()))
+            .await
+            .unwrap()
+            .into_inner(),
+    )
+    .await;
+
+    let address: SocketAddr = format!("127.0.0.1:{}", get_available_port())
+        .parse()
+        .unwrap();
+    tokio::spawn(
+        Server::builder()
+            .add_service(stream_server(service(true), true))
+            .serve(address),
+    );
+    // Give the server a moment to start listening.
+    tokio::time::sleep(Duration::from_millis(200)).await;
+    let mut client = IndexerStreamClient::connect(format!("http://{}", address))
+        .await
+        .unwrap()
+        .send_compressed(CompressionEncoding::Gzip)
+        .accept_compressed(CompressionEncoding::Gzip);
+    let compressed_bytes = COMPRESSED_BYTES_SENT.get();
+    let response = client.raw_datastream(request()).await.unwrap();
+    assert_eq!(
+        response
+            .metadata()
+            .get("grpc-encoding")
+            .unwrap()
+            .to_str()
+            .unwrap(),
+        "gzip"
+    );
+    let responses = read_stream(response.into_inner()).await;
+
+    // Exactly the same transactions once decompressed.
+    let sent = transactions(&responses);
+    assert_eq!(sent, transactions(&expected));
+    assert_eq!(
+        sent.iter().map(|txn| txn.version).collect::<Vec<_>>(),
+        (0..=7).collect::<Vec<_>>()
+    );
+    // Only the last response before each batch end isn't full.
+    let mut data_sizes = vec![];
+    for response in &responses {
+        match response.response.as_ref().unwrap() {
+            ResponsePB::Data(data) => data_sizes.push(data.transactions.len()),
+            ResponsePB::Status(_) => {
+                if let Some((_, full)) = data_sizes.split_last() {
+                    assert!(full.iter().all(|size| *size == 2), "{:?}", data_sizes);
+                }
+                data_sizes.clear();
+            },
+        }
+    }
+    // Fewer, fuller responses than without coalescing.
+    assert!(responses.len() < expected.len());
+
+    // Less went out than the size of the responses.
+    let uncompressed_bytes: usize = responses
+        .iter()
+        .map(|response| response.encoded_len())
+        .sum();
+    let compressed_bytes = COMPRESSED_BYTES_SENT.get() - compressed_bytes;
+    assert!(compressed_bytes > 0);
+    assert!((compressed_bytes as usize) < uncompressed_bytes);
+}
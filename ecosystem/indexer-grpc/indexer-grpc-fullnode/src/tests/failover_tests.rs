@@ -0,0 +1,182 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::failover::{FailoverOptions, ReaderFailover};
+use anyhow::bail;
+use std::{
+    sync::{
+        atomic::{AtomicBool, AtomicU64, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+
+const BATCH_SIZE: u64 = 5;
+const COOL_DOWN: Duration = Duration::from_millis(200);
+
+/// A replica of a ledger with one transaction per version, which errors while unhealthy.
+struct FakeReader {
+    name: &'static str,
+    healthy: AtomicBool,
+    fetches: AtomicU64,
+}
+
+impl FakeReader {
+    fn new(name: &'static str) -> Arc<Self> {
+        Arc::new(Self {
+            name,
+            healthy: AtomicBool::new(true),
+            fetches: AtomicU64::new(0),
+        })
+    }
+
+    fn get_transactions(&self, start_version: u64) -> anyhow::Result<Vec<(u64, &'static str)>> {
+        self.fetches.fetch_add(1, Ordering::Relaxed);
+        if !self.healthy.load(Ordering::Relaxed) {
+            bail!("{} is unavailable", self.name);
+        }
+        Ok((start_version..start_version + BATCH_SIZE)
+            .map(|version| (version, self.name))
+            .collect())
+    }
+}
+
+fn options() -> FailoverOptions {
+    FailoverOptions {
+        max_failures: 2,
+        failure_window: Duration::from_secs(10),
+        cool_down: COOL_DOWN,
+        max_retries: 3,
+        retry_delay: Duration::from_millis(1),
+    }
+}
+
+/// Streams the given number of batches from `start_version` like the coordinator does,
+/// returning the transactions of each batch.
+async fn stream(
+    readers: &ReaderFailover<Arc<FakeReader>>,
+    start_version: u64,
+    num_batches: u64,
+) -> Vec<Vec<(u64, &'static str)>> {
+    let mut batches = vec![];
+    for i in 0..num_batches {
+        readers.maybe_revert(|reader| reader.healthy.load(Ordering::Relaxed));
+        let (_, batch) = readers
+            .fetch_with_retries(|reader| reader.get_transactions(start_version + i * BATCH_SIZE))
+            .await
+            .unwrap();
+        batches.push(batch);
+    }
+    batches
+}
+
+fn assert_contiguous(batches: &[Vec<(u64, &'static str)>], start_version: u64) {
+    let versions: Vec<_> = batches
+        .iter()
+        .flatten()
+        .map(|(version, _)| *version)
+        .collect();
+    let expected: Vec<_> = (start_version..start_version + versions.len() as u64).collect();
+    assert_eq!(versions, expected);
+}
+
+/// Which reader each batch came from, making sure none of them mixes readers.
+fn batch_sources(batches: &[Vec<(u64, &'static str)>]) -> Vec<&'static str> {
+    batches
+        .iter()
+        .map(|batch| {
+            let source = batch[0].1;
+            assert!(batch.iter().all(|(_, name)| *name == source));
+            source
+        })
+        .collect()
+}
+
+#[tokio::test]
+async fn test_fails_over_to_secondary_mid_stream() {
+    let primary = FakeReader::new("primary");
+    let secondary = FakeReader::new("secondary");
+    let readers = ReaderFailover::new(vec![primary.clone(), secondary.clone()], options());
+
+    let mut batches = stream(&readers, 0, 2).await;
+    primary.healthy.store(false, Ordering::Relaxed);
+    batches.extend(stream(&readers, 2 * BATCH_SIZE, 3).await);
+
+    assert_contiguous(&batches, 0);
+    assert_eq!(batch_sources(&batches), vec![
+        "primary",
+        "primary",
+        "secondary",
+        "secondary",
+        "secondary"
+    ]);
+    // The primary isn't retried once failed over from.
+    assert_eq!(primary.fetches.load(Ordering::Relaxed), 2 + 2);
+    assert_eq!(readers.active().0, 1);
+}
+
+#[tokio::test]
+async fn test_reverts_to_primary_after_cool_down() {
+    let primary = FakeReader::new("primary");
+    let secondary = FakeReader::new("secondary");
+    let readers = ReaderFailover::new(vec![primary.clone(), secondary.clone()], options());
+
+    primary.healthy.store(false, Ordering::Relaxed);
+    let mut batches = stream(&readers, 0, 1).await;
+    primary.healthy.store(true, Ordering::Relaxed);
+    // Still within the cool down.
+    batches.extend(stream(&readers, BATCH_SIZE, 1).await);
+
+    // The first probe fails, which starts another cool down.
+    tokio::time::sleep(COOL_DOWN).await;
+    primary.healthy.store(false, Ordering::Relaxed);
+    batches.extend(stream(&readers, 2 * BATCH_SIZE, 1).await);
+    primary.healthy.store(true, Ordering::Relaxed);
+    batches.extend(stream(&readers, 3 * BATCH_SIZE, 1).await);
+
+    tokio::time::sleep(COOL_DOWN).await;
+    batches.extend(stream(&readers, 4 * BATCH_SIZE, 1).await);
+
+    assert_contiguous(&batches, 0);
+    assert_eq!(batch_sources(&batches), vec![
+        "secondary",
+        "secondary",
+        "secondary",
+        "secondary",
+        "primary"
+    ]);
+    assert_eq!(readers.active().0, 0);
+}
+
+#[tokio::test]
+async fn test_gives_up_without_fallback() {
+    let primary = FakeReader::new("primary");
+    let readers = ReaderFailover::new(vec![primary.clone()], options());
+
+    primary.healthy.store(false, Ordering::Relaxed);
+    let result = readers
+        .fetch_with_retries(|reader| reader.get_transactions(0))
+        .await;
+    assert!(result.is_err());
+    assert_eq!(primary.fetches.load(Ordering::Relaxed), 3);
+}
+
+#[tokio::test]
+async fn test_failures_outside_window_dont_fail_over() {
+    let primary = FakeReader::new("primary");
+    let secondary = FakeReader::new("secondary");
+    let readers = ReaderFailover::new(vec![primary, secondary], FailoverOptions {
+        failure_window: Duration::from_millis(50),
+        ..options()
+    });
+
+    assert!(!readers.record_failure(0));
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    assert!(!readers.record_failure(0));
+    assert_eq!(readers.active().0, 0);
+    assert!(readers.record_failure(0));
+    assert_eq!(readers.active().0, 1);
+    // Failures of readers that were already failed over from are ignored.
+    assert!(readers.record_failure(0));
+    assert_eq!(readers.active().0, 1);
+}
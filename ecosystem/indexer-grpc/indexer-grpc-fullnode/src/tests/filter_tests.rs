@@ -0,0 +1,241 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    chain_status::LedgerSampler,
+    failover::{FailoverOptions, ReaderFailover},
+    filter::TransactionFilter,
+    redaction::Redactor,
+    runtime::IndexerStreamService,
+    stream_state_machine::StreamStateMachine,
+    tests::super_new_test_context,
+};
+use aptos_config::config::StreamEventsVerbosity;
+use aptos_protos::{
+    datastream::v1::{
+        indexer_stream_server::IndexerStream, raw_datastream_request::TransactionType,
+        raw_datastream_response::Response as ResponsePB, stream_status::StatusType,
+        RawDatastreamRequest,
+    },
+    transaction::v1::{
+        transaction::{TransactionType as TransactionTypePB, TxnData},
+        transaction_payload::Payload,
+        EntryFunctionId, EntryFunctionPayload, MoveModuleId, ScriptPayload,
+        Transaction as TransactionPB, TransactionPayload, UserTransaction, UserTransactionRequest,
+    },
+};
+use futures::StreamExt;
+use prost::Message;
+use std::{sync::Arc, time::Duration};
+use tonic::{Code, Request};
+
+fn request(types: &[TransactionType], prefixes: &[&str]) -> RawDatastreamRequest {
+    RawDatastreamRequest {
+        starting_version: 0,
+        max_supported_schema_version: None,
+        resume_token: None,
+        strict_resume: false,
+        ending_version: None,
+        transaction_types: types.iter().map(|txn_type| *txn_type as i32).collect(),
+        entry_function_address_prefixes: prefixes.iter().map(|prefix| prefix.to_string()).collect(),
+    }
+}
+
+fn filter(types: &[TransactionType], prefixes: &[&str]) -> TransactionFilter {
+    TransactionFilter::new(&request(types, prefixes)).unwrap()
+}
+
+fn transaction(txn_type: TransactionTypePB) -> TransactionPB {
+    TransactionPB {
+        r#type: txn_type as i32,
+        ..Default::default()
+    }
+}
+
+fn user_transaction(payload: Payload) -> TransactionPB {
+    TransactionPB {
+        r#type: TransactionTypePB::User as i32,
+        txn_data: Some(TxnData::User(UserTransaction {
+            request: Some(UserTransactionRequest {
+                payload: Some(TransactionPayload {
+                    payload: Some(payload),
+                    ..Default::default()
+                }),
+                ..Default::default()
+            }),
+            events: vec![],
+        })),
+        ..Default::default()
+    }
+}
+
+fn entry_function_transaction(address: &str) -> TransactionPB {
+    user_transaction(Payload::EntryFunctionPayload(EntryFunctionPayload {
+        function: Some(EntryFunctionId {
+            module: Some(MoveModuleId {
+                address: address.to_string(),
+                name: "coin".to_string(),
+            }),
+            name: "transfer".to_string(),
+        }),
+        ..Default::default()
+    }))
+}
+
+#[test]
+fn test_empty_filter_matches_everything() {
+    let filter = filter(&[], &[]);
+    assert!(filter.is_empty());
+    for txn in [
+        transaction(TransactionTypePB::Genesis),
+        transaction(TransactionTypePB::BlockMetadata),
+        transaction(TransactionTypePB::StateCheckpoint),
+        transaction(TransactionTypePB::User),
+        entry_function_transaction("0x1"),
+    ] {
+        assert!(filter.matches(&txn));
+    }
+}
+
+#[test]
+fn test_types_filter() {
+    let filter = filter(
+        &[TransactionType::User, TransactionType::StateCheckpoint],
+        &[],
+    );
+    assert!(!filter.is_empty());
+    assert!(filter.matches(&transaction(TransactionTypePB::User)));
+    assert!(filter.matches(&transaction(TransactionTypePB::StateCheckpoint)));
+    assert!(filter.matches(&entry_function_transaction("0x1")));
+    assert!(!filter.matches(&transaction(TransactionTypePB::Genesis)));
+    assert!(!filter.matches(&transaction(TransactionTypePB::BlockMetadata)));
+}
+
+#[test]
+fn test_entry_function_address_prefixes_filter() {
+    let filter = filter(
+        &[],
+        &[
+            "0xABCD",
+            "0x00000000000000000000000000000000000000000000000000000000000000",
+        ],
+    );
+    // Matched against the address with all of its digits
+    assert!(filter.matches(&entry_function_transaction("0xabcd12")));
+    assert!(filter.matches(&entry_function_transaction("0x1")));
+    assert!(filter.matches(&entry_function_transaction("0xff")));
+    assert!(!filter.matches(&entry_function_transaction(
+        "0x1abcd00000000000000000000000000000000000000000000000000000000000"
+    )));
+    // User transactions have to call an entry function
+    assert!(!filter.matches(&transaction(TransactionTypePB::User)));
+    assert!(!filter.matches(&user_transaction(Payload::ScriptPayload(
+        ScriptPayload::default()
+    ))));
+    // Other transactions aren't concerned
+    assert!(filter.matches(&transaction(TransactionTypePB::BlockMetadata)));
+
+    // Along with types, both have to match
+    let filter = filter(&[TransactionType::User], &["0x0000"]);
+    assert!(filter.matches(&entry_function_transaction("0x1")));
+    assert!(!filter.matches(&entry_function_transaction(
+        "0xabcd000000000000000000000000000000000000000000000000000000000000"
+    )));
+    assert!(!filter.matches(&transaction(TransactionTypePB::BlockMetadata)));
+}
+
+#[test]
+fn test_invalid_filter() {
+    let too_long = format!("0x{}", "0".repeat(65));
+    for prefix in ["abcd", "0x", "0xabcg", too_long.as_str()] {
+        assert_eq!(
+            TransactionFilter::new(&request(&[], &[prefix]))
+                .unwrap_err()
+                .code(),
+            Code::InvalidArgument
+        );
+    }
+    let mut invalid_type = request(&[], &[]);
+    invalid_type.transaction_types = vec![42];
+    assert_eq!(
+        TransactionFilter::new(&invalid_type).unwrap_err().code(),
+        Code::InvalidArgument
+    );
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_stream_of_user_transactions() {
+    let mut context = super_new_test_context("test_stream_of_user_transactions".to_string(), false);
+    let mut root_account = context.root_account();
+    for _ in 0..3 {
+        let account = context.gen_account();
+        let txn = context.create_user_account_by(&mut root_account, &account);
+        context.commit_block(&[txn]).await;
+    }
+    let ledger_version = context
+        .context
+        .get_latest_ledger_info_wrapped()
+        .unwrap()
+        .ledger_version
+        .0;
+    let readers = Arc::new(ReaderFailover::new(
+        vec![Arc::new(context.context.clone())],
+        FailoverOptions::default(),
+    ));
+    // Small batches, so that some of them only have filtered out transactions
+    let service = IndexerStreamService {
+        readers: readers.clone(),
+        processor_task_count: 2,
+        processor_batch_size: 1,
+        output_batch_size: 1,
+        coalesce_responses: false,
+        stream_events_verbosity: StreamEventsVerbosity::Disabled,
+        stream_events_batch_sample_rate: 1,
+        reject_schema_downgrades: false,
+        redactor: Arc::new(Redactor::default()),
+        transaction_channel_size: 35,
+        slow_consumer_timeout: Duration::from_secs(60),
+        ledger_sampler: Arc::new(LedgerSampler::spawn(readers, Duration::from_secs(1))),
+    };
+
+    let mut request = request(&[TransactionType::User], &[]);
+    request.ending_version = Some(ledger_version);
+    let mut stream = service
+        .raw_datastream(Request::new(request))
+        .await
+        .unwrap()
+        .into_inner();
+    let mut state_machine = StreamStateMachine::new();
+    let mut user_transactions = 0;
+    let mut batch_ends = vec![];
+    while let Some(message) = tokio::time::timeout(Duration::from_secs(10), stream.next())
+        .await
+        .expect("Timed out waiting on the filtered stream")
+    {
+        state_machine.on_message(&message).unwrap();
+        match message.unwrap().response.unwrap() {
+            ResponsePB::Data(data) => {
+                for output in data.transactions {
+                    let txn = TransactionPB::decode(
+                        base64::decode(&output.encoded_proto_data)
+                            .unwrap()
+                            .as_slice(),
+                    )
+                    .unwrap();
+                    assert_eq!(txn.r#type(), TransactionTypePB::User);
+                    user_transactions += 1;
+                }
+            },
+            ResponsePB::Status(status) if status.r#type() == StatusType::BatchEnd => {
+                batch_ends.push((status.start_version, status.end_version.unwrap()))
+            },
+            ResponsePB::Status(_) => {},
+        }
+    }
+    assert_eq!(user_transactions, 3);
+    // The batch ends still cover every version, block metadata transactions included, which
+    // the protocol checks along with the stream completing at the ledger version.
+    assert_eq!(batch_ends.first().unwrap().0, 0);
+    assert_eq!(batch_ends.last().unwrap().1, ledger_version);
+    assert!(batch_ends.len() as u64 > user_transactions);
+}
@@ -0,0 +1,114 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::convert::convert_transaction;
+use aptos_api_types::Transaction;
+use aptos_indexer_test_fixtures::{load_corpus, HUGE_WRITE_SET_SIZE, HUGE_WRITE_SET_TRANSACTION};
+use aptos_protos::transaction::v1::{
+    transaction::{TransactionType, TxnData},
+    Transaction as TransactionPB,
+};
+use goldenfile::Mint;
+use prost::Message;
+use std::{io::Write, path::PathBuf};
+
+/// Where the encoded protos of the corpus are checked in, as hex. Regenerate them with
+/// `UPDATE_GOLDENFILES=1 cargo test -p aptos-indexer-grpc-fullnode test_corpus_wire_format`.
+const GOLDEN_DIR_PATH: &str = "src/tests/goldens";
+
+fn convert(txn: &Transaction) -> TransactionPB {
+    let info = txn.transaction_info().unwrap();
+    convert_transaction(txn, info.block_height.unwrap().0, info.epoch.unwrap().0)
+}
+
+fn num_events(txn_data: &TxnData) -> usize {
+    match txn_data {
+        TxnData::User(txn) => txn.events.len(),
+        TxnData::Genesis(txn) => txn.events.len(),
+        TxnData::BlockMetadata(txn) => txn.events.len(),
+        TxnData::StateCheckpoint(_) => 0,
+    }
+}
+
+#[test]
+fn test_corpus_conversion_matches_api_transactions() {
+    for (name, txn) in load_corpus() {
+        let info = txn.transaction_info().unwrap();
+        let converted = convert(&txn);
+
+        assert_eq!(converted.version, txn.version().unwrap(), "{}", name);
+        assert_eq!(
+            converted.block_height,
+            info.block_height.unwrap().0,
+            "{}",
+            name
+        );
+        assert_eq!(converted.epoch, info.epoch.unwrap().0, "{}", name);
+        let expected_type = match &txn {
+            Transaction::UserTransaction(_) => TransactionType::User,
+            Transaction::GenesisTransaction(_) => TransactionType::Genesis,
+            Transaction::BlockMetadataTransaction(_) => TransactionType::BlockMetadata,
+            Transaction::StateCheckpointTransaction(_) => TransactionType::StateCheckpoint,
+            Transaction::PendingTransaction(_) => unreachable!(),
+        };
+        assert_eq!(converted.r#type(), expected_type, "{}", name);
+
+        let converted_info = converted.info.as_ref().unwrap();
+        assert_eq!(converted_info.hash, info.hash.0.to_vec(), "{}", name);
+        assert_eq!(converted_info.success, info.success, "{}", name);
+        assert_eq!(converted_info.vm_status, info.vm_status, "{}", name);
+        assert_eq!(converted_info.changes.len(), info.changes.len(), "{}", name);
+
+        let expected_events = match &txn {
+            Transaction::UserTransaction(txn) => txn.events.len(),
+            Transaction::GenesisTransaction(txn) => txn.events.len(),
+            Transaction::BlockMetadataTransaction(txn) => txn.events.len(),
+            _ => 0,
+        };
+        assert_eq!(
+            num_events(converted.txn_data.as_ref().unwrap()),
+            expected_events,
+            "{}",
+            name
+        );
+    }
+}
+
+#[test]
+fn test_corpus_wire_format_is_stable() {
+    let mut mint = Mint::new(PathBuf::from(env!("CARGO_MANIFEST_DIR")).join(GOLDEN_DIR_PATH));
+    for (name, txn) in load_corpus() {
+        let converted = convert(&txn);
+        let encoded = converted.encode_to_vec();
+
+        // The encoding matches the checked in one, except for the huge write set, which is
+        // derived from the user transaction and too big to check in
+        if name != HUGE_WRITE_SET_TRANSACTION {
+            let mut golden = mint.new_goldenfile(format!("{}.hex", name)).unwrap();
+            for line in hex::encode(&encoded).as_bytes().chunks(64) {
+                golden.write_all(line).unwrap();
+                golden.write_all(b"\n").unwrap();
+            }
+        }
+
+        // Conversion and encoding are deterministic
+        assert_eq!(convert(&txn).encode_to_vec(), encoded, "{}", name);
+
+        // The binary and the json encodings, as consumed downstream, carry the same data
+        let decoded = TransactionPB::decode(encoded.as_slice()).unwrap();
+        assert_eq!(decoded, converted, "{}", name);
+        let json = serde_json::to_string(&decoded).unwrap();
+        let from_json: TransactionPB = serde_json::from_str(&json).unwrap();
+        assert_eq!(from_json.encode_to_vec(), encoded, "{}", name);
+    }
+}
+
+#[test]
+fn test_huge_write_set() {
+    let (_, txn) = load_corpus()
+        .into_iter()
+        .find(|(name, _)| *name == HUGE_WRITE_SET_TRANSACTION)
+        .unwrap();
+    let converted = convert(&txn);
+    assert_eq!(converted.info.unwrap().changes.len(), HUGE_WRITE_SET_SIZE);
+}
@@ -0,0 +1,116 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    chain_status::{LedgerSampler, LedgerSource},
+    health::spawn_health_check,
+};
+use anyhow::bail;
+use aptos_api_types::{LedgerInfo, U64};
+use aptos_config::utils::get_available_port;
+use std::{
+    net::SocketAddr,
+    sync::{
+        atomic::{AtomicBool, AtomicU64, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+use tonic::transport::{Channel, Server};
+use tonic_health::proto::{
+    health_check_response::ServingStatus, health_client::HealthClient, HealthCheckRequest,
+};
+
+const SAMPLE_INTERVAL: Duration = Duration::from_millis(5);
+const CHECK_INTERVAL: Duration = Duration::from_millis(10);
+const STALENESS: Duration = Duration::from_millis(500);
+const STREAM_SERVICE: &str = "aptos.datastream.v1.IndexerStream";
+
+/// A ledger that only moves when told to, and whose storage can be made unreadable
+struct MockLedger {
+    version: AtomicU64,
+    readable: AtomicBool,
+}
+
+impl LedgerSource for MockLedger {
+    fn latest_ledger_info(&self) -> anyhow::Result<LedgerInfo> {
+        if !self.readable.load(Ordering::SeqCst) {
+            bail!("Storage is unreadable");
+        }
+        let version = self.version.load(Ordering::SeqCst);
+        Ok(LedgerInfo {
+            chain_id: 4,
+            epoch: U64::from(1),
+            ledger_version: U64::from(version),
+            oldest_ledger_version: U64::from(0),
+            block_height: U64::from(version / 3),
+            oldest_block_height: U64::from(0),
+            ledger_timestamp: U64::from(version * 1_000_000),
+        })
+    }
+}
+
+async fn health_client() -> (HealthClient<Channel>, tonic_health::server::HealthReporter) {
+    let (reporter, service) = tonic_health::server::health_reporter();
+    let address: SocketAddr = format!("127.0.0.1:{}", get_available_port())
+        .parse()
+        .unwrap();
+    tokio::spawn(Server::builder().add_service(service).serve(address));
+    // Give the server a moment to start listening.
+    tokio::time::sleep(Duration::from_millis(200)).await;
+    let client = HealthClient::connect(format!("http://{}", address))
+        .await
+        .unwrap();
+    (client, reporter)
+}
+
+/// Waits until both the server and the stream are reported with the status
+async fn wait_for(client: &mut HealthClient<Channel>, expected: ServingStatus) {
+    for service in ["", STREAM_SERVICE] {
+        tokio::time::timeout(Duration::from_secs(5), async {
+            loop {
+                let status = client
+                    .check(HealthCheckRequest {
+                        service: service.to_string(),
+                    })
+                    .await
+                    .unwrap()
+                    .into_inner()
+                    .status;
+                if status == expected as i32 {
+                    break;
+                }
+                tokio::time::sleep(CHECK_INTERVAL).await;
+            }
+        })
+        .await
+        .unwrap_or_else(|_| panic!("Timed out waiting on {:?} for '{}'", expected, service));
+    }
+}
+
+#[tokio::test]
+async fn test_health_follows_the_ledger() {
+    let ledger = Arc::new(MockLedger {
+        version: AtomicU64::new(10),
+        readable: AtomicBool::new(false),
+    });
+    let sampler = LedgerSampler::spawn(ledger.clone(), SAMPLE_INTERVAL);
+    let (mut client, reporter) = health_client().await;
+    spawn_health_check(sampler.subscribe(), reporter, STALENESS, CHECK_INTERVAL);
+
+    // Not serving until the ledger can be read.
+    wait_for(&mut client, ServingStatus::NotServing).await;
+    ledger.readable.store(true, Ordering::SeqCst);
+    wait_for(&mut client, ServingStatus::Serving).await;
+
+    // The ledger stops advancing, which goes stale.
+    wait_for(&mut client, ServingStatus::NotServing).await;
+
+    // Serving again once it advances.
+    ledger.version.fetch_add(1, Ordering::SeqCst);
+    wait_for(&mut client, ServingStatus::Serving).await;
+
+    // Not serving anymore once the sampler stops, e.g. on shutdown.
+    sampler.shutdown();
+    wait_for(&mut client, ServingStatus::NotServing).await;
+}
@@ -0,0 +1,172 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::runtime::{server_builder, IndexerStreamService};
+use aptos_config::{config::IndexerGrpcConfig, utils::get_available_port};
+use aptos_protos::datastream::v1::{
+    indexer_stream_client::IndexerStreamClient,
+    indexer_stream_server::{IndexerStream, IndexerStreamServer},
+    raw_datastream_response::Response as ResponsePB,
+    stream_status::StatusType,
+    RawDatastreamRequest, RawDatastreamResponse, WatchChainStatusRequest, WatchChainStatusResponse,
+};
+use futures::Stream;
+use std::{net::SocketAddr, pin::Pin, time::Duration};
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::{TcpListener, TcpStream},
+    sync::mpsc,
+};
+use tokio_stream::wrappers::ReceiverStream;
+use tonic::{Request, Response, Status};
+
+/// Idle timeout of the mock load balancer.
+const LB_IDLE_TIMEOUT: Duration = Duration::from_secs(3);
+/// How long the mock ledger goes without new transactions, longer than the LB idle timeout.
+const LEDGER_PAUSE: Duration = Duration::from_secs(7);
+
+/// Sends the init status right away, then nothing until the ledger resumes after the pause.
+struct PausedLedgerService;
+
+#[tonic::async_trait]
+impl IndexerStream for PausedLedgerService {
+    type RawDatastreamStream =
+        Pin<Box<dyn Stream<Item = Result<RawDatastreamResponse, Status>> + Send>>;
+
+    async fn raw_datastream(
+        &self,
+        req: Request<RawDatastreamRequest>,
+    ) -> Result<Response<Self::RawDatastreamStream>, Status> {
+        let starting_version = req.into_inner().starting_version;
+        let (tx, rx) = mpsc::channel(1);
+        tokio::spawn(async move {
+            let init =
+                IndexerStreamService::get_status(StatusType::Init, starting_version, None, 4);
+            tx.send(Ok(init)).await.unwrap();
+            tokio::time::sleep(LEDGER_PAUSE).await;
+            let batch_end = IndexerStreamService::get_status(
+                StatusType::BatchEnd,
+                starting_version,
+                Some(starting_version),
+                4,
+            );
+            // The client is gone if the connection got dropped.
+            let _ = tx.send(Ok(batch_end)).await;
+        });
+        Ok(Response::new(Box::pin(ReceiverStream::new(rx))))
+    }
+
+    type WatchChainStatusStream =
+        Pin<Box<dyn Stream<Item = Result<WatchChainStatusResponse, Status>> + Send>>;
+
+    async fn watch_chain_status(
+        &self,
+        _req: Request<WatchChainStatusRequest>,
+    ) -> Result<Response<Self::WatchChainStatusStream>, Status> {
+        Err(Status::unimplemented("Not needed by the keepalive tests"))
+    }
+}
+
+/// A TCP proxy that drops connections without traffic in either direction for
+/// `LB_IDLE_TIMEOUT`, like cloud load balancers do.
+async fn start_idle_dropping_proxy(upstream: SocketAddr) -> SocketAddr {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let address = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        loop {
+            let (client, _) = listener.accept().await.unwrap();
+            let server = TcpStream::connect(upstream).await.unwrap();
+            tokio::spawn(proxy_connection(client, server));
+        }
+    });
+    address
+}
+
+async fn proxy_connection(mut client: TcpStream, mut server: TcpStream) {
+    let (mut client_read, mut client_write) = client.split();
+    let (mut server_read, mut server_write) = server.split();
+    let mut client_buf = vec![0u8; 64 * 1024];
+    let mut server_buf = vec![0u8; 64 * 1024];
+    loop {
+        let forwarded = tokio::time::timeout(LB_IDLE_TIMEOUT, async {
+            tokio::select! {
+                read = client_read.read(&mut client_buf) => match read {
+                    Ok(n) if n > 0 => server_write.write_all(&client_buf[..n]).await.is_ok(),
+                    _ => false,
+                },
+                read = server_read.read(&mut server_buf) => match read {
+                    Ok(n) if n > 0 => client_write.write_all(&server_buf[..n]).await.is_ok(),
+                    _ => false,
+                },
+            }
+        })
+        .await;
+        if !matches!(forwarded, Ok(true)) {
+            // Idle for too long or closed, dropping both sides closes the connection.
+            return;
+        }
+    }
+}
+
+/// Returns whether the batch sent after the ledger pause made it through the proxy.
+async fn stream_survives_pause(config: IndexerGrpcConfig) -> bool {
+    let server_address: SocketAddr = format!("127.0.0.1:{}", get_available_port())
+        .parse()
+        .unwrap();
+    tokio::spawn(
+        server_builder(&config)
+            .unwrap()
+            .add_service(IndexerStreamServer::new(PausedLedgerService))
+            .serve(server_address),
+    );
+    // Give the server a moment to start listening.
+    tokio::time::sleep(Duration::from_millis(200)).await;
+    let proxy_address = start_idle_dropping_proxy(server_address).await;
+
+    let mut client = IndexerStreamClient::connect(format!("http://{}", proxy_address))
+        .await
+        .unwrap();
+    let mut stream = client
+        .raw_datastream(RawDatastreamRequest {
+            starting_version: 10,
+            max_supported_schema_version: None,
+            resume_token: None,
+            strict_resume: false,
+            ending_version: None,
+            transaction_types: vec![],
+            entry_function_address_prefixes: vec![],
+        })
+        .await
+        .unwrap()
+        .into_inner();
+
+    let init = stream.message().await.unwrap().unwrap();
+    assert!(matches!(
+        init.response,
+        Some(ResponsePB::Status(status)) if status.r#type() == StatusType::Init
+    ));
+    match stream.message().await {
+        Ok(Some(RawDatastreamResponse {
+            response: Some(ResponsePB::Status(status)),
+            ..
+        })) => status.r#type() == StatusType::BatchEnd,
+        _ => false,
+    }
+}
+
+#[tokio::test]
+async fn test_stream_survives_idle_period_with_keepalive() {
+    let config = IndexerGrpcConfig {
+        http2_keepalive_interval_secs: Some(1),
+        http2_keepalive_timeout_secs: Some(1),
+        ..Default::default()
+    };
+    assert!(stream_survives_pause(config).await);
+
+    // Without keepalive the load balancer drops the idle stream, i.e. the above isn't vacuous.
+    let config = IndexerGrpcConfig {
+        http2_keepalive_interval_secs: None,
+        ..Default::default()
+    };
+    assert!(!stream_survives_pause(config).await);
+}
@@ -0,0 +1,96 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::stream_coordinator::IndexerStreamCoordinator;
+use aptos_protos::{
+    datastream::v1::{raw_datastream_response::Response as ResponsePB, TransactionOutput},
+    transaction::v1::{MoveModuleBytecode, MoveScriptBytecode},
+};
+use bytes::Bytes;
+use prost::Message;
+
+// The bytecode messages as generated before their bytes became `Bytes`.
+
+#[derive(Clone, PartialEq, ::prost::Message)]
+struct MoveModuleBytecodeVec {
+    #[prost(bytes = "vec", tag = "1")]
+    bytecode: Vec<u8>,
+}
+
+#[derive(Clone, PartialEq, ::prost::Message)]
+struct MoveScriptBytecodeVec {
+    #[prost(bytes = "vec", tag = "1")]
+    bytecode: Vec<u8>,
+}
+
+fn large_bytecode() -> Vec<u8> {
+    (0..(3 << 20)).map(|i| (i % 251) as u8).collect()
+}
+
+#[test]
+fn test_bytes_fields_unchanged_on_the_wire() {
+    let bytecode = large_bytecode();
+
+    let module = MoveModuleBytecode {
+        bytecode: Bytes::from(bytecode.clone()),
+        abi: None,
+    };
+    let legacy_module = MoveModuleBytecodeVec {
+        bytecode: bytecode.clone(),
+    };
+    assert_eq!(module.encode_to_vec(), legacy_module.encode_to_vec());
+
+    let script = MoveScriptBytecode {
+        bytecode: Bytes::from(bytecode.clone()),
+        abi: None,
+    };
+    let legacy_script = MoveScriptBytecodeVec { bytecode };
+    assert_eq!(script.encode_to_vec(), legacy_script.encode_to_vec());
+}
+
+#[test]
+fn test_decoding_from_bytes_does_not_copy_bytecode() {
+    let module = MoveModuleBytecode {
+        bytecode: Bytes::from(large_bytecode()),
+        abi: None,
+    };
+    let encoded = Bytes::from(module.encode_to_vec());
+    let decoded = MoveModuleBytecode::decode(encoded.clone()).unwrap();
+    assert_eq!(decoded, module);
+
+    // The decoded bytecode points into the encoded buffer
+    let buffer = encoded.as_ptr_range();
+    let bytecode = decoded.bytecode.as_ptr_range();
+    assert!(buffer.start <= bytecode.start && bytecode.end <= buffer.end);
+}
+
+#[test]
+fn test_data_responses_keep_transactions_in_order() {
+    let encoded: Vec<TransactionOutput> = (0..25)
+        .map(|version| TransactionOutput {
+            encoded_proto_data: version.to_string(),
+            version,
+            timestamp: None,
+        })
+        .collect();
+
+    let responses = IndexerStreamCoordinator::into_data_responses(encoded.clone(), 10, 4);
+    let sizes: Vec<usize> = responses
+        .iter()
+        .map(|response| match response.response.as_ref() {
+            Some(ResponsePB::Data(data)) => data.transactions.len(),
+            _ => panic!("Expected data"),
+        })
+        .collect();
+    assert_eq!(sizes, vec![10, 10, 5]);
+    assert!(responses.iter().all(|response| response.chain_id == 4));
+
+    let transactions: Vec<TransactionOutput> = responses
+        .into_iter()
+        .flat_map(|response| match response.response {
+            Some(ResponsePB::Data(data)) => data.transactions,
+            _ => unreachable!(),
+        })
+        .collect();
+    assert_eq!(transactions, encoded);
+}
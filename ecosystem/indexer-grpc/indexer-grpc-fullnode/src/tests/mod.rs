@@ -1,6 +1,23 @@
 // Copyright (c) Aptos
 // SPDX-License-Identifier: Apache-2.0
 
+mod batch_failure_tests;
+mod bounded_stream_tests;
+mod chain_status_tests;
+mod compression_tests;
+mod failover_tests;
+mod filter_tests;
+mod fixture_conversion_tests;
+mod health_tests;
+mod keepalive_tests;
+mod large_payload_tests;
+mod redaction_tests;
+mod resume_tests;
+mod schema_downgrade_tests;
+mod slow_consumer_tests;
+mod stream_metrics_tests;
+mod stream_state_machine_tests;
+mod tls_tests;
 // mod proto_converter_tests;
 
 pub use aptos_api_test_context::{new_test_context as super_new_test_context, TestContext};
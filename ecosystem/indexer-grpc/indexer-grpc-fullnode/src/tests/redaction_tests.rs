@@ -0,0 +1,197 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    redaction::{
+        matches_pattern, redaction_marker, RedactionRule, Redactor, MODULE_BYTECODE_SELECTOR,
+        SCRIPT_CODE_SELECTOR,
+    },
+    runtime::IndexerStreamService,
+    stream_coordinator::IndexerStreamCoordinator,
+};
+use aptos_crypto::HashValue;
+use aptos_protos::{
+    datastream::v1::raw_datastream_response::Response as ResponsePB,
+    transaction::v1::{
+        transaction::TxnData, transaction_payload::Payload, Event, MoveScriptBytecode,
+        ScriptPayload, Transaction as TransactionPB, TransactionPayload, UserTransaction,
+        UserTransactionRequest,
+    },
+};
+use prost::Message;
+
+const SCRIPT_CODE: &[u8] = &[0xa1, 0x1c, 0xeb, 0x0b, 0x05, 0x00, 0x00, 0x00, 0x06, 0x01];
+
+fn script_transaction() -> TransactionPB {
+    TransactionPB {
+        version: 42,
+        block_height: 7,
+        txn_data: Some(TxnData::User(UserTransaction {
+            request: Some(UserTransactionRequest {
+                sender: "0x1234".to_string(),
+                sequence_number: 3,
+                max_gas_amount: 1000,
+                gas_unit_price: 100,
+                payload: Some(TransactionPayload {
+                    r#type: 1,
+                    payload: Some(Payload::ScriptPayload(ScriptPayload {
+                        code: Some(MoveScriptBytecode {
+                            bytecode: SCRIPT_CODE.to_vec().into(),
+                            abi: None,
+                        }),
+                        type_arguments: vec![],
+                        arguments: vec!["\"0x1\"".to_string()],
+                    })),
+                }),
+                ..Default::default()
+            }),
+            events: vec![Event {
+                sequence_number: 0,
+                type_str: "0x1::coin::DepositEvent".to_string(),
+                data: "{\"amount\":\"100\"}".to_string(),
+                ..Default::default()
+            }],
+        })),
+        ..Default::default()
+    }
+}
+
+fn script_code(txn: &TransactionPB) -> &[u8] {
+    match txn.txn_data.as_ref() {
+        Some(TxnData::User(user)) => match user
+            .request
+            .as_ref()
+            .and_then(|request| request.payload.as_ref())
+            .and_then(|payload| payload.payload.as_ref())
+        {
+            Some(Payload::ScriptPayload(script)) => &script.code.as_ref().unwrap().bytecode,
+            _ => panic!("Expected a script payload"),
+        },
+        _ => panic!("Expected a user transaction"),
+    }
+}
+
+fn set_script_code(txn: &mut TransactionPB, code: &[u8]) {
+    if let Some(TxnData::User(user)) = txn.txn_data.as_mut() {
+        if let Some(Payload::ScriptPayload(script)) = user
+            .request
+            .as_mut()
+            .and_then(|request| request.payload.as_mut())
+            .and_then(|payload| payload.payload.as_mut())
+        {
+            script.code.as_mut().unwrap().bytecode = code.to_vec().into();
+        }
+    }
+}
+
+#[test]
+fn test_rules_round_trip() {
+    for rule in [
+        SCRIPT_CODE_SELECTOR,
+        MODULE_BYTECODE_SELECTOR,
+        "event:0x1::coin::*",
+    ] {
+        assert_eq!(rule.parse::<RedactionRule>().unwrap().to_string(), rule);
+    }
+    for invalid in ["", "event:", "user_transaction.payload"] {
+        assert!(invalid.parse::<RedactionRule>().is_err());
+    }
+    assert!(Redactor::new(&["user_transaction.payload".to_string()]).is_err());
+}
+
+#[test]
+fn test_event_type_patterns() {
+    assert!(matches_pattern("0x1::coin::*", "0x1::coin::DepositEvent"));
+    assert!(matches_pattern(
+        "*::DepositEvent",
+        "0x1::coin::DepositEvent"
+    ));
+    assert!(matches_pattern(
+        "0x1::*::Deposit*",
+        "0x1::coin::DepositEvent"
+    ));
+    assert!(matches_pattern(
+        "0x1::coin::DepositEvent",
+        "0x1::coin::DepositEvent"
+    ));
+    assert!(!matches_pattern(
+        "0x1::coin::Deposit",
+        "0x1::coin::DepositEvent"
+    ));
+    assert!(!matches_pattern(
+        "0x1::coin::*",
+        "0x1::account::CoinRegisterEvent"
+    ));
+}
+
+#[test]
+fn test_script_code_redacted_on_the_wire() {
+    let redactor = Redactor::new(&[SCRIPT_CODE_SELECTOR.to_string()]).unwrap();
+    let original = script_transaction();
+    let mut txn = original.clone();
+    redactor.redact(&mut txn);
+
+    // What clients decode from the stream
+    let encoded = IndexerStreamCoordinator::encode_pb_txns(vec![txn]);
+    let decoded = TransactionPB::decode(
+        base64::decode(&encoded[0].encoded_proto_data)
+            .unwrap()
+            .as_slice(),
+    )
+    .unwrap();
+
+    let expected_marker = format!(
+        "redacted:sha3-256:{}",
+        HashValue::sha3_256_of(SCRIPT_CODE).to_hex()
+    );
+    assert_eq!(script_code(&decoded), expected_marker.as_bytes());
+    assert_eq!(redaction_marker(SCRIPT_CODE), expected_marker);
+
+    // Nothing else changed, events included as there is no rule for them
+    let mut restored = decoded.clone();
+    set_script_code(&mut restored, SCRIPT_CODE);
+    assert_eq!(restored, original);
+
+    // Deterministic
+    let mut again = original;
+    redactor.redact(&mut again);
+    assert_eq!(again, decoded);
+}
+
+#[test]
+fn test_event_data_redacted() {
+    let redactor = Redactor::new(&["event:0x1::coin::*".to_string()]).unwrap();
+    let original = script_transaction();
+    let mut txn = original.clone();
+    redactor.redact(&mut txn);
+
+    match (txn.txn_data.as_ref(), original.txn_data.as_ref()) {
+        (Some(TxnData::User(redacted)), Some(TxnData::User(user))) => {
+            assert_eq!(
+                redacted.events[0].data,
+                redaction_marker(user.events[0].data.as_bytes())
+            );
+            assert_eq!(redacted.events[0].type_str, user.events[0].type_str);
+        },
+        _ => panic!("Expected a user transaction"),
+    }
+    assert_eq!(script_code(&txn), SCRIPT_CODE);
+}
+
+#[test]
+fn test_init_status_advertises_redactions() {
+    let redactor = Redactor::new(&[SCRIPT_CODE_SELECTOR.to_string()]).unwrap();
+    let init = IndexerStreamService::get_init_status(10, 2, redactor.rules(), 4);
+    match init.response {
+        Some(ResponsePB::Status(status)) => {
+            assert_eq!(status.redactions, vec![SCRIPT_CODE_SELECTOR.to_string()])
+        },
+        _ => panic!("Expected a status"),
+    }
+
+    let init = IndexerStreamService::get_init_status(10, 2, Redactor::default().rules(), 4);
+    match init.response {
+        Some(ResponsePB::Status(status)) => assert!(status.redactions.is_empty()),
+        _ => panic!("Expected a status"),
+    }
+}
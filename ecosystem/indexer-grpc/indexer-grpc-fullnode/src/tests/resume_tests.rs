@@ -0,0 +1,185 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    chain_status::LedgerSampler,
+    failover::{FailoverOptions, ReaderFailover},
+    redaction::Redactor,
+    resume::{resolve_starting_version, ResumeToken},
+    runtime::IndexerStreamService,
+    tests::super_new_test_context,
+};
+use aptos_config::config::StreamEventsVerbosity;
+use aptos_protos::datastream::v1::{
+    indexer_stream_server::IndexerStream, raw_datastream_response::Response as ResponsePB,
+    stream_status::StatusType, RawDatastreamRequest,
+};
+use futures::StreamExt;
+use std::{sync::Arc, time::Duration};
+use tonic::{Code, Request};
+
+const CHAIN_ID: u8 = 4;
+
+fn request(
+    starting_version: u64,
+    token: Option<ResumeToken>,
+    strict: bool,
+) -> RawDatastreamRequest {
+    RawDatastreamRequest {
+        starting_version,
+        max_supported_schema_version: None,
+        resume_token: token.map(|token| token.encode()),
+        strict_resume: strict,
+        ending_version: None,
+        transaction_types: vec![],
+        entry_function_address_prefixes: vec![],
+    }
+}
+
+#[test]
+fn test_resume_token_round_trip() {
+    let token = ResumeToken::new(CHAIN_ID, 1234);
+    assert_eq!(ResumeToken::decode(&token.encode()).unwrap(), token);
+
+    for invalid in [
+        "", "1234", "v1:4", "v1:4:x", "v1:400:1", "v2:4:1", "v1:4:1:2",
+    ] {
+        assert_eq!(
+            ResumeToken::decode(invalid).unwrap_err().code(),
+            Code::InvalidArgument
+        );
+    }
+}
+
+#[test]
+fn test_batch_end_resumes_after_batch() {
+    let batch_end = IndexerStreamService::get_batch_end_status(10, 19, CHAIN_ID);
+    let status = match batch_end.response.unwrap() {
+        ResponsePB::Status(status) => status,
+        ResponsePB::Data(_) => unreachable!(),
+    };
+    let token = ResumeToken::decode(&status.resume_token.unwrap()).unwrap();
+    assert_eq!(token, ResumeToken::new(CHAIN_ID, 20));
+}
+
+#[test]
+fn test_token_and_version_agree() {
+    let token = Some(ResumeToken::new(CHAIN_ID, 20));
+    for strict in [false, true] {
+        assert_eq!(
+            resolve_starting_version(&request(20, token, strict), CHAIN_ID, Some(0)).unwrap(),
+            20
+        );
+        // Without a token, the starting version is used as is.
+        assert_eq!(
+            resolve_starting_version(&request(7, None, strict), CHAIN_ID, Some(0)).unwrap(),
+            7
+        );
+    }
+    // An unset starting version is taken from the token, unless strict.
+    assert_eq!(
+        resolve_starting_version(&request(0, token, false), CHAIN_ID, None).unwrap(),
+        20
+    );
+    assert_eq!(
+        resolve_starting_version(&request(0, token, true), CHAIN_ID, Some(0))
+            .unwrap_err()
+            .code(),
+        Code::InvalidArgument
+    );
+}
+
+#[test]
+fn test_token_and_version_disagree() {
+    let token = Some(ResumeToken::new(CHAIN_ID, 20));
+    for (starting_version, strict) in [(19, false), (21, false), (19, true), (21, true)] {
+        let result =
+            resolve_starting_version(&request(starting_version, token, strict), CHAIN_ID, Some(0));
+        assert_eq!(result.unwrap_err().code(), Code::InvalidArgument);
+    }
+}
+
+#[test]
+fn test_cross_chain_token_rejected() {
+    let token = Some(ResumeToken::new(CHAIN_ID + 1, 20));
+    for (starting_version, strict) in [(0, false), (20, false), (20, true)] {
+        let result =
+            resolve_starting_version(&request(starting_version, token, strict), CHAIN_ID, Some(0));
+        assert_eq!(result.unwrap_err().code(), Code::FailedPrecondition);
+    }
+}
+
+#[test]
+fn test_pruned_version_rejected() {
+    let token = Some(ResumeToken::new(CHAIN_ID, 20));
+    assert_eq!(
+        resolve_starting_version(&request(20, token, true), CHAIN_ID, Some(21))
+            .unwrap_err()
+            .code(),
+        Code::FailedPrecondition
+    );
+    assert_eq!(
+        resolve_starting_version(&request(20, token, true), CHAIN_ID, Some(20)).unwrap(),
+        20
+    );
+    // Without a strict resume the version is out of range rather than a broken resume.
+    assert_eq!(
+        resolve_starting_version(&request(20, token, false), CHAIN_ID, Some(21))
+            .unwrap_err()
+            .code(),
+        Code::OutOfRange
+    );
+    assert_eq!(
+        resolve_starting_version(&request(20, None, false), CHAIN_ID, Some(21))
+            .unwrap_err()
+            .code(),
+        Code::OutOfRange
+    );
+}
+
+#[tokio::test]
+async fn test_init_echoes_resolved_version() {
+    let context = super_new_test_context("test_init_echoes_resolved_version".to_string(), false);
+    let chain_id = context.context.chain_id().id();
+    let readers = Arc::new(ReaderFailover::new(
+        vec![Arc::new(context.context.clone())],
+        FailoverOptions::default(),
+    ));
+    let service = IndexerStreamService {
+        readers: readers.clone(),
+        processor_task_count: 1,
+        processor_batch_size: 10,
+        output_batch_size: 10,
+        coalesce_responses: false,
+        stream_events_verbosity: StreamEventsVerbosity::Disabled,
+        stream_events_batch_sample_rate: 1,
+        reject_schema_downgrades: false,
+        redactor: Arc::new(Redactor::default()),
+        transaction_channel_size: 35,
+        slow_consumer_timeout: Duration::from_secs(60),
+        ledger_sampler: Arc::new(LedgerSampler::spawn(readers, Duration::from_secs(1))),
+    };
+
+    // The starting version is left unset, the stream starts from the token.
+    let token = Some(ResumeToken::new(chain_id, 5));
+    let mut stream = service
+        .raw_datastream(Request::new(request(0, token, false)))
+        .await
+        .unwrap()
+        .into_inner();
+    let init = stream.next().await.unwrap().unwrap();
+    assert_eq!(init.chain_id, chain_id as u32);
+    match init.response.unwrap() {
+        ResponsePB::Status(status) => {
+            assert_eq!(status.r#type(), StatusType::Init);
+            assert_eq!(status.start_version, 5);
+        },
+        ResponsePB::Data(_) => panic!("Expected the init status first"),
+    }
+
+    // Strict requests fail right away instead.
+    let result = service
+        .raw_datastream(Request::new(request(0, token, true)))
+        .await;
+    assert_eq!(result.err().unwrap().code(), Code::InvalidArgument);
+}
@@ -0,0 +1,214 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    convert::{convert_transaction, downgrade_transaction},
+    runtime::IndexerStreamService,
+};
+use aptos_indexer_test_fixtures::load_corpus;
+use aptos_protos::{
+    datastream::v1::raw_datastream_response::Response as ResponsePB,
+    transaction::v1::{
+        transaction::TxnData, transaction_payload::Payload, write_set::WriteSet as WriteSetPB,
+        write_set_change::Change, Event, EventKey, MoveStructTag, MoveType,
+        Transaction as TransactionPB, WriteSet, WriteSetChange,
+    },
+    MIN_TRANSACTION_SCHEMA_VERSION, TRANSACTION_SCHEMA_VERSION,
+};
+use prost::Message;
+use tonic::Code;
+
+// The messages that changed since schema version 1, as generated back then.
+
+#[derive(Clone, PartialEq, ::prost::Message)]
+struct EventV1 {
+    #[prost(message, optional, tag = "1")]
+    key: Option<EventKey>,
+    #[prost(uint64, tag = "2")]
+    sequence_number: u64,
+    #[prost(message, optional, tag = "3")]
+    r#type: Option<MoveType>,
+    #[prost(string, tag = "4")]
+    data: String,
+}
+
+#[derive(Clone, PartialEq, ::prost::Message)]
+struct DeleteResourceV1 {
+    #[prost(string, tag = "1")]
+    address: String,
+    #[prost(bytes = "vec", tag = "2")]
+    state_key_hash: Vec<u8>,
+    #[prost(message, optional, tag = "3")]
+    r#type: Option<MoveStructTag>,
+}
+
+#[derive(Clone, PartialEq, ::prost::Message)]
+struct WriteResourceV1 {
+    #[prost(string, tag = "1")]
+    address: String,
+    #[prost(bytes = "vec", tag = "2")]
+    state_key_hash: Vec<u8>,
+    #[prost(message, optional, tag = "3")]
+    r#type: Option<MoveStructTag>,
+    #[prost(string, tag = "5")]
+    data: String,
+}
+
+fn convert(txn: &aptos_api_types::Transaction) -> TransactionPB {
+    let info = txn.transaction_info().unwrap();
+    convert_transaction(txn, info.block_height.unwrap().0, info.epoch.unwrap().0)
+}
+
+fn collect_write_set<'a>(
+    write_set: &'a WriteSet,
+    events: &mut Vec<&'a Event>,
+    changes: &mut Vec<&'a WriteSetChange>,
+) {
+    if let Some(WriteSetPB::DirectWriteSet(direct)) = write_set.write_set.as_ref() {
+        events.extend(&direct.events);
+        changes.extend(&direct.write_set_change);
+    }
+}
+
+/// All the events and write set changes anywhere in the transaction.
+fn collect(txn: &TransactionPB) -> (Vec<&Event>, Vec<&WriteSetChange>) {
+    let mut events = vec![];
+    let mut changes: Vec<_> = txn.info.as_ref().unwrap().changes.iter().collect();
+    match txn.txn_data.as_ref().unwrap() {
+        TxnData::BlockMetadata(bm) => events.extend(&bm.events),
+        TxnData::Genesis(gt) => {
+            collect_write_set(gt.payload.as_ref().unwrap(), &mut events, &mut changes);
+            events.extend(&gt.events);
+        },
+        TxnData::User(ut) => {
+            let payload = ut.request.as_ref().unwrap().payload.as_ref().unwrap();
+            if let Some(Payload::WriteSetPayload(wsp)) = payload.payload.as_ref() {
+                collect_write_set(wsp.write_set.as_ref().unwrap(), &mut events, &mut changes);
+            }
+            events.extend(&ut.events);
+        },
+        TxnData::StateCheckpoint(_) => {},
+    }
+    (events, changes)
+}
+
+/// Decodes with the older message and re-encodes it, which only round trips if the encoding has
+/// no fields the older message doesn't know about.
+fn assert_clean_decode<M: Message + Default>(encoded: Vec<u8>) {
+    let decoded = M::decode(encoded.as_slice()).unwrap();
+    assert_eq!(decoded.encode_to_vec(), encoded);
+}
+
+#[test]
+fn test_downgraded_corpus_decodes_cleanly_with_v1_messages() {
+    let mut num_type_strs = 0;
+    for (name, txn) in load_corpus() {
+        let converted = convert(&txn);
+        let mut downgraded = converted.clone();
+        downgrade_transaction(&mut downgraded, 1);
+
+        let (events, changes) = collect(&converted);
+        num_type_strs += events.iter().filter(|e| !e.type_str.is_empty()).count();
+        num_type_strs += changes
+            .iter()
+            .filter(|change| match change.change.as_ref() {
+                Some(Change::WriteResource(write)) => !write.type_str.is_empty(),
+                Some(Change::DeleteResource(delete)) => !delete.type_str.is_empty(),
+                _ => false,
+            })
+            .count();
+
+        let (events, changes) = collect(&downgraded);
+        for event in events {
+            assert!(event.type_str.is_empty(), "{}", name);
+            assert_clean_decode::<EventV1>(event.encode_to_vec());
+        }
+        for change in changes {
+            match change.change.as_ref() {
+                Some(Change::WriteResource(write)) => {
+                    assert_clean_decode::<WriteResourceV1>(write.encode_to_vec())
+                },
+                Some(Change::DeleteResource(delete)) => {
+                    assert_clean_decode::<DeleteResourceV1>(delete.encode_to_vec())
+                },
+                _ => {},
+            }
+        }
+
+        // Everything else makes it through, and the current client still decodes it.
+        let encoded = downgraded.encode_to_vec();
+        assert_eq!(
+            TransactionPB::decode(encoded.as_slice()).unwrap(),
+            downgraded
+        );
+        assert_eq!(downgraded.version, converted.version, "{}", name);
+        assert_eq!(
+            downgraded.info.as_ref().unwrap().hash,
+            converted.info.as_ref().unwrap().hash
+        );
+    }
+    // Make sure the corpus actually exercises the downgrade.
+    assert!(num_type_strs > 0);
+}
+
+#[test]
+fn test_downgrade_to_current_version_is_noop() {
+    for (name, txn) in load_corpus() {
+        let converted = convert(&txn);
+        let mut downgraded = converted.clone();
+        downgrade_transaction(&mut downgraded, TRANSACTION_SCHEMA_VERSION);
+        assert_eq!(downgraded, converted, "{}", name);
+    }
+}
+
+#[test]
+fn test_negotiate_schema_version() {
+    for reject_schema_downgrades in [false, true] {
+        assert_eq!(
+            IndexerStreamService::negotiate_schema_version(None, reject_schema_downgrades).unwrap(),
+            TRANSACTION_SCHEMA_VERSION
+        );
+        // Newer clients get the version of the node.
+        assert_eq!(
+            IndexerStreamService::negotiate_schema_version(
+                Some(TRANSACTION_SCHEMA_VERSION + 1),
+                reject_schema_downgrades
+            )
+            .unwrap(),
+            TRANSACTION_SCHEMA_VERSION
+        );
+        assert_eq!(
+            IndexerStreamService::negotiate_schema_version(
+                Some(MIN_TRANSACTION_SCHEMA_VERSION - 1),
+                reject_schema_downgrades
+            )
+            .unwrap_err()
+            .code(),
+            Code::FailedPrecondition
+        );
+    }
+
+    let older_version = TRANSACTION_SCHEMA_VERSION - 1;
+    assert_eq!(
+        IndexerStreamService::negotiate_schema_version(Some(older_version), false).unwrap(),
+        older_version
+    );
+    assert_eq!(
+        IndexerStreamService::negotiate_schema_version(Some(older_version), true)
+            .unwrap_err()
+            .code(),
+        Code::FailedPrecondition
+    );
+}
+
+#[test]
+fn test_init_status_has_schema_version() {
+    let init = IndexerStreamService::get_init_status(10, 1, vec![], 4);
+    match init.response {
+        Some(ResponsePB::Status(status)) => {
+            assert_eq!(status.start_version, 10);
+            assert_eq!(status.schema_version, Some(1));
+        },
+        _ => panic!("Expected a status"),
+    }
+}
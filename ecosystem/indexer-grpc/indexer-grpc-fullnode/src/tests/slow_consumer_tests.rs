@@ -0,0 +1,91 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    chain_status::LedgerSampler,
+    failover::{FailoverOptions, ReaderFailover},
+    redaction::Redactor,
+    runtime::IndexerStreamService,
+    tests::super_new_test_context,
+};
+use aptos_config::config::StreamEventsVerbosity;
+use aptos_protos::datastream::v1::{
+    indexer_stream_server::IndexerStream, raw_datastream_response::Response as ResponsePB,
+    stream_status::StatusType, RawDatastreamRequest,
+};
+use futures::StreamExt;
+use std::{
+    sync::Arc,
+    time::{Duration, Instant},
+};
+use tonic::Request;
+
+const SLOW_CONSUMER_TIMEOUT: Duration = Duration::from_secs(1);
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_unread_stream_terminated() {
+    let mut context = super_new_test_context("test_unread_stream_terminated".to_string(), false);
+    let mut root_account = context.root_account();
+    for _ in 0..3 {
+        let account = context.gen_account();
+        let txn = context.create_user_account_by(&mut root_account, &account);
+        context.commit_block(&[txn]).await;
+    }
+    let readers = Arc::new(ReaderFailover::new(
+        vec![Arc::new(context.context.clone())],
+        FailoverOptions::default(),
+    ));
+    // A response per transaction, and a channel with room for the init status only
+    let service = IndexerStreamService {
+        readers: readers.clone(),
+        processor_task_count: 1,
+        processor_batch_size: 10,
+        output_batch_size: 1,
+        coalesce_responses: false,
+        stream_events_verbosity: StreamEventsVerbosity::Disabled,
+        stream_events_batch_sample_rate: 1,
+        reject_schema_downgrades: false,
+        redactor: Arc::new(Redactor::default()),
+        transaction_channel_size: 1,
+        slow_consumer_timeout: SLOW_CONSUMER_TIMEOUT,
+        ledger_sampler: Arc::new(LedgerSampler::spawn(readers, Duration::from_secs(1))),
+    };
+
+    let request = RawDatastreamRequest {
+        starting_version: 0,
+        max_supported_schema_version: None,
+        resume_token: None,
+        strict_resume: false,
+        ending_version: None,
+        transaction_types: vec![],
+        entry_function_address_prefixes: vec![],
+    };
+    let started_at = Instant::now();
+    let mut stream = service
+        .raw_datastream(Request::new(request))
+        .await
+        .unwrap()
+        .into_inner();
+    // The client doesn't read anything while the stream times out on it.
+    tokio::time::sleep(SLOW_CONSUMER_TIMEOUT * 3).await;
+
+    // Only what was buffered before the timeout is left, then the stream ends rather than
+    // waiting on new versions, the coordinator task having dropped the sender.
+    let mut messages = vec![];
+    while let Some(message) = tokio::time::timeout(Duration::from_secs(1), stream.next())
+        .await
+        .expect("The stream of the slow consumer is still open")
+    {
+        messages.push(message.unwrap());
+    }
+    assert!(started_at.elapsed() < SLOW_CONSUMER_TIMEOUT * 5);
+    let types: Vec<_> = messages
+        .into_iter()
+        .map(|message| match message.response.unwrap() {
+            ResponsePB::Status(status) => Some(status.r#type()),
+            ResponsePB::Data(_) => None,
+        })
+        .collect();
+    // The slow consumer status didn't fit in the channel, there's no data past the first response
+    assert_eq!(types, vec![Some(StatusType::Init)]);
+}
@@ -0,0 +1,104 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    chain_status::LedgerSampler,
+    counters::ACTIVE_STREAMS,
+    failover::{FailoverOptions, ReaderFailover},
+    redaction::Redactor,
+    runtime::IndexerStreamService,
+    tests::super_new_test_context,
+};
+use aptos_config::config::StreamEventsVerbosity;
+use aptos_metrics_core::gather;
+use aptos_protos::datastream::v1::{
+    indexer_stream_server::IndexerStream, raw_datastream_response::Response as ResponsePB,
+    stream_status::StatusType, RawDatastreamRequest,
+};
+use futures::StreamExt;
+use std::{sync::Arc, time::Duration};
+use tonic::Request;
+
+const PER_STREAM_METRICS: [&str; 3] = [
+    "indexer_grpc_stream_transactions_sent_count",
+    "indexer_grpc_stream_batches_sent_count",
+    "indexer_grpc_stream_version_lag",
+];
+
+/// Number of series left in the per-stream metrics
+fn per_stream_series() -> usize {
+    gather()
+        .iter()
+        .filter(|family| PER_STREAM_METRICS.contains(&family.get_name()))
+        .map(|family| family.get_metric().len())
+        .sum()
+}
+
+// Each test runs in its own process under nextest, so no other stream counts here.
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_stream_metrics_removed_once_terminated() {
+    let mut context = super_new_test_context(
+        "test_stream_metrics_removed_once_terminated".to_string(),
+        false,
+    );
+    let mut root_account = context.root_account();
+    for _ in 0..3 {
+        let account = context.gen_account();
+        let txn = context.create_user_account_by(&mut root_account, &account);
+        context.commit_block(&[txn]).await;
+    }
+    let readers = Arc::new(ReaderFailover::new(
+        vec![Arc::new(context.context.clone())],
+        FailoverOptions::default(),
+    ));
+    let service = IndexerStreamService {
+        readers: readers.clone(),
+        processor_task_count: 1,
+        processor_batch_size: 3,
+        output_batch_size: 1,
+        coalesce_responses: false,
+        stream_events_verbosity: StreamEventsVerbosity::Disabled,
+        stream_events_batch_sample_rate: 1,
+        reject_schema_downgrades: false,
+        redactor: Arc::new(Redactor::default()),
+        transaction_channel_size: 35,
+        slow_consumer_timeout: Duration::from_secs(60),
+        ledger_sampler: Arc::new(LedgerSampler::spawn(readers, Duration::from_millis(5))),
+    };
+    assert_eq!(ACTIVE_STREAMS.get(), 0);
+
+    let mut stream = service
+        .raw_datastream(Request::new(RawDatastreamRequest {
+            starting_version: 0,
+            max_supported_schema_version: None,
+            resume_token: None,
+            strict_resume: false,
+            ending_version: Some(7),
+            transaction_types: vec![],
+            entry_function_address_prefixes: vec![],
+        }))
+        .await
+        .unwrap()
+        .into_inner();
+    let mut completed = false;
+    while let Some(message) = tokio::time::timeout(Duration::from_secs(10), stream.next())
+        .await
+        .expect("Timed out waiting on the stream")
+    {
+        // The stream is active from the init status on, until it terminates.
+        assert_eq!(ACTIVE_STREAMS.get(), 1);
+        match message.unwrap().response.unwrap() {
+            ResponsePB::Status(status) if status.r#type() == StatusType::Completed => {
+                // The batches were counted before the stream completed.
+                assert!(per_stream_series() >= 2);
+                completed = true;
+            },
+            _ => {},
+        }
+    }
+    assert!(completed);
+
+    // The channel closes after the metrics of the stream are dropped.
+    assert_eq!(ACTIVE_STREAMS.get(), 0);
+    assert_eq!(per_stream_series(), 0);
+}
@@ -0,0 +1,486 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    counters::STREAM_PROTOCOL_VIOLATIONS,
+    failover::{FailoverOptions, ReaderFailover},
+    filter::TransactionFilter,
+    redaction::Redactor,
+    runtime::IndexerStreamService,
+    stream_coordinator::IndexerStreamCoordinator,
+    stream_state_machine::{ProtocolViolation, StreamStateMachine},
+    tests::super_new_test_context,
+};
+use aptos_protos::{
+    datastream::v1::{
+        raw_datastream_response::Response as ResponsePB, stream_status::StatusType,
+        RawDatastreamResponse, TransactionOutput, TransactionsOutput,
+    },
+    TRANSACTION_SCHEMA_VERSION,
+};
+use std::{sync::Arc, time::Duration};
+use tokio::sync::mpsc;
+use tonic::{Code, Status};
+
+const CHAIN_ID: u8 = 4;
+
+type Message = Result<RawDatastreamResponse, Status>;
+
+fn init(start_version: u64) -> Message {
+    Ok(IndexerStreamService::get_init_status(
+        start_version,
+        TRANSACTION_SCHEMA_VERSION,
+        vec![],
+        CHAIN_ID,
+    ))
+}
+
+fn batch_end(start_version: u64, end_version: u64) -> Message {
+    Ok(IndexerStreamService::get_batch_end_status(
+        start_version,
+        end_version,
+        CHAIN_ID,
+    ))
+}
+
+fn completed(start_version: u64, end_version: u64) -> Message {
+    Ok(IndexerStreamService::get_completed_status(
+        start_version,
+        end_version,
+        CHAIN_ID,
+    ))
+}
+
+fn data(versions: &[u64]) -> Message {
+    Ok(RawDatastreamResponse {
+        response: Some(ResponsePB::Data(TransactionsOutput {
+            transactions: versions
+                .iter()
+                .map(|version| TransactionOutput {
+                    version: *version,
+                    ..TransactionOutput::default()
+                })
+                .collect(),
+        })),
+        chain_id: CHAIN_ID as u32,
+    })
+}
+
+/// A state machine that went through the messages, all following the protocol
+fn after(messages: Vec<Message>) -> StreamStateMachine {
+    let mut state_machine = StreamStateMachine::new();
+    for message in messages {
+        state_machine.on_message(&message).unwrap();
+    }
+    state_machine
+}
+
+#[test]
+fn test_valid_stream() {
+    let mut state_machine = after(vec![
+        init(10),
+        data(&[10, 11]),
+        data(&[12]),
+        batch_end(10, 12),
+        data(&[13]),
+        batch_end(13, 13),
+        // Batches don't have to have data
+        batch_end(14, 20),
+        data(&[21, 25]),
+    ]);
+    assert_eq!(
+        state_machine.on_message(&Err(Status::unavailable("Shutting down"))),
+        Ok(())
+    );
+    // The terminal status was sent already
+    assert!(!state_machine.terminate());
+}
+
+#[test]
+fn test_missing_init() {
+    let mut state_machine = StreamStateMachine::new();
+    for message in [data(&[10]), batch_end(10, 10)] {
+        assert_eq!(
+            state_machine.on_message(&message),
+            Err(ProtocolViolation::MissingInit)
+        );
+    }
+    // Violations don't change the state
+    assert_eq!(state_machine.on_message(&init(10)), Ok(()));
+}
+
+#[test]
+fn test_duplicate_init() {
+    let mut state_machine = after(vec![init(10)]);
+    assert_eq!(
+        state_machine.on_message(&init(10)),
+        Err(ProtocolViolation::DuplicateInit)
+    );
+    let mut state_machine = after(vec![init(10), data(&[10]), batch_end(10, 10)]);
+    assert_eq!(
+        state_machine.on_message(&init(11)),
+        Err(ProtocolViolation::DuplicateInit)
+    );
+}
+
+#[test]
+fn test_non_contiguous_batch_end() {
+    let mut state_machine = after(vec![init(10), data(&[10, 11])]);
+    assert_eq!(
+        state_machine.on_message(&batch_end(11, 11)),
+        Err(ProtocolViolation::NonContiguousBatchEnd {
+            expected_start: 10,
+            start: 11
+        })
+    );
+    state_machine.on_message(&batch_end(10, 11)).unwrap();
+    // Overlapping the previous batch, like a batch sent again
+    assert_eq!(
+        state_machine.on_message(&batch_end(10, 11)),
+        Err(ProtocolViolation::NonContiguousBatchEnd {
+            expected_start: 12,
+            start: 10
+        })
+    );
+    // A gap after the previous batch
+    assert_eq!(
+        state_machine.on_message(&batch_end(13, 15)),
+        Err(ProtocolViolation::NonContiguousBatchEnd {
+            expected_start: 12,
+            start: 13
+        })
+    );
+}
+
+#[test]
+fn test_invalid_batch_end() {
+    let mut state_machine = after(vec![init(10)]);
+    let mut without_end = batch_end(10, 10).unwrap();
+    if let Some(ResponsePB::Status(status)) = without_end.response.as_mut() {
+        status.end_version = None;
+    }
+    assert_eq!(
+        state_machine.on_message(&Ok(without_end)),
+        Err(ProtocolViolation::InvalidBatchEnd {
+            start: 10,
+            end: None
+        })
+    );
+    assert_eq!(
+        state_machine.on_message(&batch_end(10, 9)),
+        Err(ProtocolViolation::InvalidBatchEnd {
+            start: 10,
+            end: Some(9)
+        })
+    );
+}
+
+#[test]
+fn test_non_increasing_data() {
+    // Before the start of the stream
+    let mut state_machine = after(vec![init(10)]);
+    assert_eq!(
+        state_machine.on_message(&data(&[9])),
+        Err(ProtocolViolation::NonIncreasingData {
+            previous: 9,
+            version: 9
+        })
+    );
+    // Within a response
+    assert_eq!(
+        state_machine.on_message(&data(&[10, 12, 11])),
+        Err(ProtocolViolation::NonIncreasingData {
+            previous: 12,
+            version: 11
+        })
+    );
+    // Across responses, like the data of parallel fetches interleaving
+    state_machine.on_message(&data(&[10, 12])).unwrap();
+    assert_eq!(
+        state_machine.on_message(&data(&[11])),
+        Err(ProtocolViolation::NonIncreasingData {
+            previous: 12,
+            version: 11
+        })
+    );
+    assert_eq!(
+        state_machine.on_message(&data(&[12])),
+        Err(ProtocolViolation::NonIncreasingData {
+            previous: 12,
+            version: 12
+        })
+    );
+    // Covered by a batch end already
+    state_machine.on_message(&batch_end(10, 14)).unwrap();
+    assert_eq!(
+        state_machine.on_message(&data(&[14])),
+        Err(ProtocolViolation::NonIncreasingData {
+            previous: 14,
+            version: 14
+        })
+    );
+    state_machine.on_message(&data(&[15])).unwrap();
+}
+
+#[test]
+fn test_data_outside_batch() {
+    let mut state_machine = after(vec![init(10), data(&[10, 11, 12])]);
+    assert_eq!(
+        state_machine.on_message(&batch_end(10, 11)),
+        Err(ProtocolViolation::DataOutsideBatch {
+            end: 11,
+            version: 12
+        })
+    );
+    state_machine.on_message(&batch_end(10, 12)).unwrap();
+}
+
+#[test]
+fn test_completed() {
+    let mut state_machine = after(vec![init(10), data(&[10, 11]), batch_end(10, 11)]);
+    assert_eq!(state_machine.on_message(&completed(10, 11)), Ok(()));
+    // Nothing follows the completed status, not even an error status
+    for message in [
+        data(&[12]),
+        batch_end(12, 12),
+        Err(Status::internal("Failed")),
+    ] {
+        assert_eq!(
+            state_machine.on_message(&message),
+            Err(ProtocolViolation::MessageAfterTermination)
+        );
+    }
+    assert!(!state_machine.terminate());
+}
+
+#[test]
+fn test_invalid_completed() {
+    // Before any batch end
+    let mut state_machine = after(vec![init(10)]);
+    assert_eq!(
+        state_machine.on_message(&completed(10, 9)),
+        Err(ProtocolViolation::InvalidCompleted { end: Some(9) })
+    );
+    // With data after the last batch end
+    let mut state_machine = after(vec![init(10), data(&[10]), batch_end(10, 10), data(&[11])]);
+    assert_eq!(
+        state_machine.on_message(&completed(10, 11)),
+        Err(ProtocolViolation::InvalidCompleted { end: Some(11) })
+    );
+    // Not ending where the last batch end does
+    let mut state_machine = after(vec![init(10), data(&[10]), batch_end(10, 12)]);
+    assert_eq!(
+        state_machine.on_message(&completed(10, 13)),
+        Err(ProtocolViolation::InvalidCompleted { end: Some(13) })
+    );
+    state_machine.on_message(&completed(10, 12)).unwrap();
+}
+
+#[test]
+fn test_slow_consumer() {
+    let slow_consumer = Ok(IndexerStreamService::get_status(
+        StatusType::SlowConsumer,
+        11,
+        None,
+        CHAIN_ID,
+    ));
+    // Terminates the stream wherever it is, even in the middle of a batch
+    for mut state_machine in [
+        after(vec![init(10)]),
+        after(vec![init(10), data(&[10])]),
+        after(vec![init(10), data(&[10]), batch_end(10, 10)]),
+    ] {
+        assert_eq!(state_machine.on_message(&slow_consumer), Ok(()));
+        assert_eq!(
+            state_machine.on_message(&data(&[11])),
+            Err(ProtocolViolation::MessageAfterTermination)
+        );
+        assert!(!state_machine.terminate());
+    }
+}
+
+#[test]
+fn test_error_status() {
+    let error = Ok(IndexerStreamService::get_error_status(
+        11,
+        Some(10),
+        "Could not fetch".to_string(),
+        CHAIN_ID,
+    ));
+    // Terminates the stream wherever it is, the data of the failed batch left without a batch end
+    for mut state_machine in [
+        after(vec![init(10)]),
+        after(vec![init(10), data(&[10])]),
+        after(vec![init(10), data(&[10]), batch_end(10, 10), data(&[11])]),
+    ] {
+        assert_eq!(state_machine.on_message(&error), Ok(()));
+        assert_eq!(
+            state_machine.on_message(&batch_end(11, 11)),
+            Err(ProtocolViolation::MessageAfterTermination)
+        );
+        assert!(!state_machine.terminate());
+    }
+}
+
+#[test]
+fn test_empty_response() {
+    let empty = Ok(RawDatastreamResponse {
+        response: None,
+        chain_id: CHAIN_ID as u32,
+    });
+    let mut state_machine = StreamStateMachine::new();
+    assert_eq!(
+        state_machine.on_message(&empty),
+        Err(ProtocolViolation::EmptyResponse)
+    );
+    let mut state_machine = after(vec![init(10)]);
+    assert_eq!(
+        state_machine.on_message(&empty),
+        Err(ProtocolViolation::EmptyResponse)
+    );
+}
+
+#[test]
+fn test_message_after_termination() {
+    for mut state_machine in [
+        after(vec![Err(Status::internal("Failed"))]),
+        after(vec![init(10), data(&[10]), Err(Status::internal("Failed"))]),
+    ] {
+        for message in [
+            init(10),
+            data(&[11]),
+            batch_end(10, 11),
+            Err(Status::internal("Failed again")),
+        ] {
+            assert_eq!(
+                state_machine.on_message(&message),
+                Err(ProtocolViolation::MessageAfterTermination)
+            );
+        }
+    }
+
+    // Terminating the stream on a violation
+    let mut state_machine = after(vec![init(10)]);
+    assert!(state_machine.terminate());
+    assert!(!state_machine.terminate());
+    assert_eq!(
+        state_machine.on_message(&data(&[10])),
+        Err(ProtocolViolation::MessageAfterTermination)
+    );
+}
+
+/// Versions of the data and batch ends of the messages, checking that they follow the protocol
+fn check_stream(messages: &[Message]) -> (Vec<u64>, Vec<(u64, u64)>) {
+    let mut state_machine = StreamStateMachine::new();
+    let mut versions = vec![];
+    let mut batch_ends = vec![];
+    for message in messages {
+        state_machine.on_message(message).unwrap();
+        match message.as_ref().unwrap().response.as_ref().unwrap() {
+            ResponsePB::Data(data) => {
+                versions.extend(data.transactions.iter().map(|txn| txn.version))
+            },
+            ResponsePB::Status(status) if status.r#type() == StatusType::BatchEnd => {
+                batch_ends.push((status.start_version, status.end_version.unwrap()))
+            },
+            ResponsePB::Status(_) => {},
+        }
+    }
+    (versions, batch_ends)
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_coordinator_enforces_protocol() {
+    let mut context =
+        super_new_test_context("test_coordinator_enforces_protocol".to_string(), false);
+    let mut root_account = context.root_account();
+    for _ in 0..3 {
+        let account = context.gen_account();
+        let txn = context.create_user_account_by(&mut root_account, &account);
+        context.commit_block(&[txn]).await;
+    }
+    let chain_id = context.context.chain_id().id();
+    let readers = Arc::new(ReaderFailover::new(
+        vec![Arc::new(context.context.clone())],
+        FailoverOptions::default(),
+    ));
+
+    // Many small parallel fetches, whose data is sent in order regardless of which completes
+    // first.
+    let (tx, mut rx) = mpsc::channel(1000);
+    let mut coordinator = IndexerStreamCoordinator::new(
+        readers,
+        0,
+        None,
+        5,
+        2,
+        1,
+        false,
+        TRANSACTION_SCHEMA_VERSION,
+        Arc::new(Redactor::default()),
+        Arc::new(TransactionFilter::default()),
+        tx,
+        Duration::from_secs(60),
+    );
+    coordinator
+        .send(Ok(IndexerStreamService::get_init_status(
+            0,
+            TRANSACTION_SCHEMA_VERSION,
+            vec![],
+            chain_id,
+        )))
+        .await
+        .unwrap();
+    let results = coordinator.process_next_batch().await;
+    let max_version = IndexerStreamCoordinator::get_max_batch_version(results).unwrap();
+    assert_eq!(max_version, 9);
+    coordinator
+        .send(Ok(IndexerStreamService::get_batch_end_status(
+            0,
+            max_version,
+            chain_id,
+        )))
+        .await
+        .unwrap();
+
+    // Then a protocol bug, a second init.
+    let duplicate_inits = STREAM_PROTOCOL_VIOLATIONS
+        .with_label_values(&["duplicate_init"])
+        .get();
+    let status = coordinator
+        .send(Ok(IndexerStreamService::get_init_status(
+            10,
+            TRANSACTION_SCHEMA_VERSION,
+            vec![],
+            chain_id,
+        )))
+        .await
+        .unwrap_err();
+    assert_eq!(status.code(), Code::Internal);
+    assert!(
+        STREAM_PROTOCOL_VIOLATIONS
+            .with_label_values(&["duplicate_init"])
+            .get()
+            > duplicate_inits
+    );
+    // Nothing goes out once the stream is terminated.
+    let status = coordinator
+        .send(Ok(IndexerStreamService::get_batch_end_status(
+            10, 10, chain_id,
+        )))
+        .await
+        .unwrap_err();
+    assert_eq!(status.code(), Code::Internal);
+    drop(coordinator);
+
+    let mut messages = vec![];
+    while let Some(message) = rx.recv().await {
+        messages.push(message);
+    }
+    // The client sees the data in order, then the internal error instead of the second init.
+    let termination = messages.pop().unwrap().unwrap_err();
+    assert_eq!(termination.code(), Code::Internal);
+    let (versions, batch_ends) = check_stream(&messages);
+    assert_eq!(versions, (0..=9).collect::<Vec<_>>());
+    assert_eq!(batch_ends, vec![(0, 9)]);
+}
@@ -0,0 +1,185 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::runtime::{server_builder, IndexerStreamService};
+use aptos_config::{
+    config::{IndexerGrpcConfig, IndexerGrpcTlsConfig},
+    utils::get_available_port,
+};
+use aptos_protos::datastream::v1::{
+    indexer_stream_client::IndexerStreamClient,
+    indexer_stream_server::{IndexerStream, IndexerStreamServer},
+    raw_datastream_response::Response as ResponsePB,
+    stream_status::StatusType,
+    RawDatastreamRequest, RawDatastreamResponse, WatchChainStatusRequest, WatchChainStatusResponse,
+};
+use futures::{Stream, StreamExt};
+use std::{net::SocketAddr, path::PathBuf, pin::Pin, time::Duration};
+use tonic::{
+    transport::{Certificate, Channel, ClientTlsConfig, Identity},
+    Request, Response, Status,
+};
+
+fn test_data(file: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("src/tests/test_data/tls")
+        .join(file)
+}
+
+fn tls_config(client_ca: bool) -> IndexerGrpcTlsConfig {
+    IndexerGrpcTlsConfig {
+        cert_path: test_data("server.pem"),
+        key_path: test_data("server.key"),
+        client_ca_cert_path: client_ca.then(|| test_data("ca.pem")),
+    }
+}
+
+/// Sends the init status, then ends the stream.
+struct InitOnlyService;
+
+#[tonic::async_trait]
+impl IndexerStream for InitOnlyService {
+    type RawDatastreamStream =
+        Pin<Box<dyn Stream<Item = Result<RawDatastreamResponse, Status>> + Send>>;
+
+    async fn raw_datastream(
+        &self,
+        req: Request<RawDatastreamRequest>,
+    ) -> Result<Response<Self::RawDatastreamStream>, Status> {
+        let init = IndexerStreamService::get_status(
+            StatusType::Init,
+            req.into_inner().starting_version,
+            None,
+            4,
+        );
+        Ok(Response::new(Box::pin(futures::stream::iter([Ok(init)]))))
+    }
+
+    type WatchChainStatusStream =
+        Pin<Box<dyn Stream<Item = Result<WatchChainStatusResponse, Status>> + Send>>;
+
+    async fn watch_chain_status(
+        &self,
+        _req: Request<WatchChainStatusRequest>,
+    ) -> Result<Response<Self::WatchChainStatusStream>, Status> {
+        Err(Status::unimplemented("Not needed by the TLS tests"))
+    }
+}
+
+async fn start_server(tls: IndexerGrpcTlsConfig) -> SocketAddr {
+    let config = IndexerGrpcConfig {
+        tls: Some(tls),
+        ..Default::default()
+    };
+    let address: SocketAddr = format!("127.0.0.1:{}", get_available_port())
+        .parse()
+        .unwrap();
+    tokio::spawn(
+        server_builder(&config)
+            .unwrap()
+            .add_service(IndexerStreamServer::new(InitOnlyService))
+            .serve(address),
+    );
+    // Give the server a moment to start listening.
+    tokio::time::sleep(Duration::from_millis(200)).await;
+    address
+}
+
+/// Whether the init status made it to the client, which trusts the test CA
+async fn receives_init(address: SocketAddr, tls: bool, client_cert: bool) -> bool {
+    let scheme = if tls { "https" } else { "http" };
+    let mut endpoint =
+        Channel::from_shared(format!("{}://localhost:{}", scheme, address.port())).unwrap();
+    if tls {
+        let mut tls_config = ClientTlsConfig::new()
+            .ca_certificate(Certificate::from_pem(
+                std::fs::read(test_data("ca.pem")).unwrap(),
+            ))
+            .domain_name("localhost");
+        if client_cert {
+            tls_config = tls_config.identity(Identity::from_pem(
+                std::fs::read(test_data("client.pem")).unwrap(),
+                std::fs::read(test_data("client.key")).unwrap(),
+            ));
+        }
+        endpoint = endpoint.tls_config(tls_config).unwrap();
+    }
+    // The handshake may only fail on the first request
+    let channel = match endpoint.connect().await {
+        Ok(channel) => channel,
+        Err(_) => return false,
+    };
+    let request = RawDatastreamRequest {
+        starting_version: 10,
+        max_supported_schema_version: None,
+        resume_token: None,
+        strict_resume: false,
+        ending_version: None,
+        transaction_types: vec![],
+        entry_function_address_prefixes: vec![],
+    };
+    let mut stream = match IndexerStreamClient::new(channel)
+        .raw_datastream(request)
+        .await
+    {
+        Ok(response) => response.into_inner(),
+        Err(_) => return false,
+    };
+    matches!(
+        stream.next().await,
+        Some(Ok(RawDatastreamResponse {
+            response: Some(ResponsePB::Status(status)),
+            ..
+        })) if status.r#type() == StatusType::Init && status.start_version == 10
+    )
+}
+
+#[tokio::test]
+async fn test_tls_handshake() {
+    let address = start_server(tls_config(false)).await;
+    assert!(receives_init(address, true, false).await);
+    // A client certificate isn't asked for
+    assert!(receives_init(address, true, true).await);
+    // No plaintext on the same port
+    assert!(!receives_init(address, false, false).await);
+}
+
+#[tokio::test]
+async fn test_mutual_tls() {
+    let address = start_server(tls_config(true)).await;
+    assert!(receives_init(address, true, true).await);
+    // Clients without a certificate are rejected during the handshake
+    assert!(!receives_init(address, true, false).await);
+}
+
+#[test]
+fn test_invalid_tls_config_fails() {
+    let builder = |tls: IndexerGrpcTlsConfig| {
+        server_builder(&IndexerGrpcConfig {
+            tls: Some(tls),
+            ..Default::default()
+        })
+    };
+    assert!(builder(tls_config(false)).is_ok());
+    assert!(builder(tls_config(true)).is_ok());
+
+    let missing = IndexerGrpcTlsConfig {
+        key_path: test_data("missing.key"),
+        ..tls_config(false)
+    };
+    let error = builder(missing).unwrap_err().to_string();
+    assert!(error.contains("missing.key"), "{}", error);
+
+    // A key in place of the certificate, and the other way around
+    let swapped = IndexerGrpcTlsConfig {
+        cert_path: test_data("server.key"),
+        key_path: test_data("server.pem"),
+        client_ca_cert_path: None,
+    };
+    assert!(builder(swapped).is_err());
+    let key_as_client_ca = IndexerGrpcTlsConfig {
+        client_ca_cert_path: Some(test_data("client.key")),
+        ..tls_config(false)
+    };
+    assert!(builder(key_as_client_ca).is_err());
+}
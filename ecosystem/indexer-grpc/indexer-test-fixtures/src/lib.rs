@@ -0,0 +1,118 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+//! A curated corpus of transactions, as served by the API, shared by the tests of the fullnode
+//! proto conversion and of the indexer models. When a new transaction feature lands, add it to
+//! the corpus once and both sides get coverage.
+
+use aptos_api_types::{Transaction, U64};
+use serde_json::{json, Value};
+
+pub const GENESIS_TRANSACTION: &str = "genesis_transaction";
+pub const BLOCK_METADATA_TRANSACTION: &str = "block_metadata_transaction";
+/// A successful user transaction emitting events
+pub const USER_TRANSACTION: &str = "user_transaction";
+pub const STATE_CHECKPOINT_TRANSACTION: &str = "state_checkpoint_transaction";
+/// A failed user transaction signed with a multi ed25519 signature
+pub const MULTISIG_TRANSACTION: &str = "multisig_transaction";
+/// A user transaction writing `HUGE_WRITE_SET_SIZE` table items
+pub const HUGE_WRITE_SET_TRANSACTION: &str = "huge_write_set_transaction";
+
+pub const HUGE_WRITE_SET_SIZE: usize = 10_000;
+
+/// All the transactions in the corpus, in version order
+pub const CORPUS: &[&str] = &[
+    GENESIS_TRANSACTION,
+    BLOCK_METADATA_TRANSACTION,
+    USER_TRANSACTION,
+    STATE_CHECKPOINT_TRANSACTION,
+    MULTISIG_TRANSACTION,
+    HUGE_WRITE_SET_TRANSACTION,
+];
+
+/// Returns the JSON of a fixture, useful to tweak it before deserializing
+pub fn load_json(name: &str) -> Value {
+    let raw = match name {
+        GENESIS_TRANSACTION => include_str!("../fixtures/genesis_transaction.json"),
+        BLOCK_METADATA_TRANSACTION => include_str!("../fixtures/block_metadata_transaction.json"),
+        USER_TRANSACTION => include_str!("../fixtures/user_transaction.json"),
+        STATE_CHECKPOINT_TRANSACTION => {
+            include_str!("../fixtures/state_checkpoint_transaction.json")
+        },
+        MULTISIG_TRANSACTION => include_str!("../fixtures/multisig_transaction.json"),
+        HUGE_WRITE_SET_TRANSACTION => return huge_write_set_json(),
+        _ => panic!("Unknown fixture {}", name),
+    };
+    serde_json::from_str(raw).unwrap_or_else(|e| panic!("Invalid fixture {}: {:?}", name, e))
+}
+
+/// Derived from the user transaction fixture to keep the checked in files small
+fn huge_write_set_json() -> Value {
+    let mut txn = load_json(USER_TRANSACTION);
+    txn["version"] = json!("691598");
+    txn["hash"] = json!("0x8e0b7a2f4c6d1e3a5b7c9d0e2f4a6b8c0d1e3f5a7b9c0d2e4f6a8b0c1d3e5f70");
+    txn["events"] = json!([]);
+    txn["changes"] = (0..HUGE_WRITE_SET_SIZE)
+        .map(|i| {
+            json!({
+                "type": "write_table_item",
+                "state_key_hash": format!("0x{:064x}", i),
+                "handle": "0x1b854694ae746cdbd8d44186ca4929b2b337df21d1c74633be19b2710552fdca",
+                "key": format!("0x{:016x}", i),
+                "value": format!("0x{:032x}", i * 7),
+                "data": {
+                    "key": i.to_string(),
+                    "key_type": "u64",
+                    "value": (i * 7).to_string(),
+                    "value_type": "u128",
+                },
+            })
+        })
+        .collect();
+    txn
+}
+
+pub fn load_transaction(name: &str) -> Transaction {
+    let mut txn: Transaction = serde_json::from_value(load_json(name))
+        .unwrap_or_else(|e| panic!("Fixture {} is not a transaction: {:?}", name, e));
+    // The block metadata `epoch` field shadows the one of the flattened transaction info, so
+    // the deserializer never fills the latter
+    if let Transaction::BlockMetadataTransaction(ref mut bmt) = txn {
+        bmt.info.epoch = Some(U64::from(bmt.epoch.0));
+    }
+    txn
+}
+
+/// Loads the whole corpus, in version order
+pub fn load_corpus() -> Vec<(&'static str, Transaction)> {
+    CORPUS
+        .iter()
+        .map(|name| (*name, load_transaction(name)))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_corpus_loads() {
+        let corpus = load_corpus();
+        let versions: Vec<u64> = corpus
+            .iter()
+            .map(|(_, txn)| txn.version().unwrap())
+            .collect();
+        assert!(versions.windows(2).all(|pair| pair[0] < pair[1]));
+        for (name, txn) in &corpus {
+            let info = txn.transaction_info().unwrap();
+            assert!(info.block_height.is_some(), "{} has no block height", name);
+            assert!(info.epoch.is_some(), "{} has no epoch", name);
+        }
+        match &corpus.last().unwrap().1 {
+            Transaction::UserTransaction(txn) => {
+                assert_eq!(txn.info.changes.len(), HUGE_WRITE_SET_SIZE)
+            },
+            _ => panic!("The huge write set fixture should be a user transaction"),
+        }
+    }
+}